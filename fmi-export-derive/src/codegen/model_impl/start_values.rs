@@ -63,8 +63,8 @@ impl ToTokens for SetStartValuesGen<'_> {
                                     self.#field_name = #start_expr.to_string();
                                 });
                             }
-                            schema::VariableType::FmiBinary => {
-                                // Skip binary for now
+                            schema::VariableType::FmiBinary | schema::VariableType::FmiClock => {
+                                // Skip binary and clocks for now
                             }
                         }
                     }
@@ -167,6 +167,9 @@ fn create_and_add_variable(
             use fmi::schema::fmi3::AppendToModelVariables;
             variable.append_to_variables(model_variables);
         }
+        schema::VariableType::FmiClock => {
+            // Clocks are not derived from struct fields
+        }
     }
 }
 
@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use fmi::{
+    Error,
+    fmi3::{Fmi3Status, binding, log_to_facade, mock::MockImport, schema},
+};
+
+use super::ModelInstance;
+use crate::fmi3::Model;
+
+impl<M> ModelInstance<M>
+where
+    M: Model<ValueRef = binding::fmi3ValueReference> + 'static,
+{
+    /// An import creating in-process instances of the model, without building the FMU.
+    ///
+    /// Co-Simulation instances are integrated by [`fmi::fmi3::mock::MockCoSimulation`]. Messages
+    /// logged by the instances are forwarded to the `log` facade, see [`fmi::fmi3::log_to_facade`].
+    pub fn mock_import() -> Result<MockImport<Self>, Error> {
+        let model_description = schema::Fmi3ModelDescription {
+            fmi_version: "3.0".to_owned(),
+            model_name: M::MODEL_NAME.to_owned(),
+            instantiation_token: M::INSTANTIATION_TOKEN.to_owned(),
+            model_variables: fmi::schema::deserialize(M::MODEL_VARIABLES_XML)?,
+            model_structure: fmi::schema::deserialize(M::MODEL_STRUCTURE_XML)?,
+            model_exchange: Some(schema::Fmi3ModelExchange {
+                model_identifier: M::MODEL_NAME.to_owned(),
+                ..Default::default()
+            }),
+            co_simulation: Some(schema::Fmi3CoSimulation {
+                model_identifier: M::MODEL_NAME.to_owned(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        MockImport::new(model_description, |name, logging_on| {
+            let instance_name = name.to_owned();
+            let log_message =
+                move |status: Fmi3Status, category: &str, args: std::fmt::Arguments<'_>| {
+                    log_to_facade(&status, category, &format!("{instance_name}: {args}"));
+                };
+            ModelInstance::new(
+                name.to_owned(),
+                PathBuf::new(),
+                logging_on,
+                Box::new(log_message),
+                M::INSTANTIATION_TOKEN,
+            )
+            .map_err(Error::from)
+        })
+    }
+}
@@ -12,6 +12,8 @@ use crate::fmi3::{
 
 mod common;
 mod get_set;
+#[cfg(feature = "mock")]
+mod mock;
 mod model_exchange;
 
 /// An exportable FMU instance
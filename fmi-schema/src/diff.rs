@@ -0,0 +1,718 @@
+//! Differences between two versions of a model description.
+//!
+//! [`Diff::diff`] compares an old and a new model description of the same FMI version. Variables
+//! are matched by name. A removed and an added variable with the same data type and value
+//! reference are reported as renamed. [`ModelDiff::compatibility`] checks whether the variables
+//! used by connections or parameter sets still work with the new version:
+//!
+//! ```rust
+//! # #[cfg(feature = "fmi3")] {
+//! use fmi_schema::{diff::Diff, fmi3::Fmi3ModelDescription};
+//!
+//! let old: Fmi3ModelDescription = fmi_schema::deserialize(
+//!     r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="">
+//!     <ModelVariables>
+//!         <Float64 name="u" valueReference="1" causality="input" start="0"/>
+//!         <Float64 name="k" valueReference="2" causality="parameter" variability="fixed" start="1"/>
+//!     </ModelVariables>
+//!     <ModelStructure/>
+//! </fmiModelDescription>"#,
+//! )
+//! .unwrap();
+//! let new: Fmi3ModelDescription = fmi_schema::deserialize(
+//!     r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="">
+//!     <ModelVariables>
+//!         <Float64 name="u" valueReference="1" causality="input" start="0"/>
+//!         <Float64 name="gain" valueReference="2" causality="parameter" variability="fixed" start="2"/>
+//!     </ModelVariables>
+//!     <ModelStructure/>
+//! </fmiModelDescription>"#,
+//! )
+//! .unwrap();
+//!
+//! let diff = old.diff(&new);
+//! assert_eq!(
+//!     diff.to_string(),
+//!     "renamed variable 'k' to 'gain'\nvariable 'gain': start: 1 -> 2\n"
+//! );
+//! assert!(diff.compatibility(["u"]).is_compatible());
+//! assert!(!diff.compatibility(["u", "k"]).is_compatible());
+//! # }
+//! ```
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    traits::{DataType, DefaultExperiment, FmiInterfaceType, FmiModelVariables, FmiVariable},
+    units::FmiUnits,
+    validation::{Finding, Location, Severity},
+};
+
+/// An attribute of a variable that can change between versions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attribute {
+    ValueReference,
+    Causality,
+    Variability,
+    DataType,
+    DeclaredType,
+    /// The unit of the variable, declared on the variable itself or else on its declared type
+    Unit,
+    Start,
+}
+
+impl Display for Attribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Attribute::ValueReference => write!(f, "valueReference"),
+            Attribute::Causality => write!(f, "causality"),
+            Attribute::Variability => write!(f, "variability"),
+            Attribute::DataType => write!(f, "type"),
+            Attribute::DeclaredType => write!(f, "declaredType"),
+            Attribute::Unit => write!(f, "unit"),
+            Attribute::Start => write!(f, "start"),
+        }
+    }
+}
+
+/// A changed value, `None` if the value is not defined
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change<A> {
+    pub attribute: A,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+impl<A> Change<A> {
+    fn new(attribute: A, old: Option<String>, new: Option<String>) -> Option<Self> {
+        (old != new).then_some(Self {
+            attribute,
+            old,
+            new,
+        })
+    }
+}
+
+impl<A: Display> Display for Change<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "(none)".to_owned());
+        write!(
+            f,
+            "{}: {} -> {}",
+            self.attribute,
+            value(&self.old),
+            value(&self.new)
+        )
+    }
+}
+
+/// A variable that was renamed
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rename {
+    pub old: String,
+    pub new: String,
+}
+
+/// The changed attributes of a variable that exists in both versions
+#[derive(Clone, Debug, PartialEq)]
+pub struct VariableDiff {
+    /// The name of the variable in the new version
+    pub name: String,
+    pub changes: Vec<Change<Attribute>>,
+}
+
+/// The differences between two model descriptions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModelDiff {
+    /// The names of the added variables, in the order of the new model description
+    pub added: Vec<String>,
+    /// The names of the removed variables, in the order of the old model description
+    pub removed: Vec<String>,
+    pub renamed: Vec<Rename>,
+    /// The changed variables, in the order of the new model description
+    pub changed: Vec<VariableDiff>,
+    /// The names of the variables that are neither renamed nor changed, in the order of the new
+    /// model description
+    pub unchanged: Vec<String>,
+    /// Changes of the attributes of the `DefaultExperiment`, such as `stopTime`
+    pub default_experiment: Vec<Change<&'static str>>,
+    /// Changes of the interface types and their capability flags, such as `CoSimulation` or
+    /// `CoSimulation.canGetAndSetFMUState`. The value of an interface type is its model
+    /// identifier.
+    pub capabilities: Vec<Change<String>>,
+}
+
+/// Compare two versions of a model description
+pub trait Diff {
+    /// The differences from `self` to the `new` version
+    fn diff(&self, new: &Self) -> ModelDiff;
+}
+
+/// Whether the used variables of a model still work with a new version, see
+/// [`ModelDiff::compatibility`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Compatibility {
+    pub findings: Vec<Finding>,
+}
+
+impl Compatibility {
+    /// True if none of the findings is an error
+    pub fn is_compatible(&self) -> bool {
+        self.findings.iter().all(|f| f.severity < Severity::Error)
+    }
+}
+
+impl ModelDiff {
+    /// True if the model descriptions do not differ
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.changed.is_empty()
+            && self.default_experiment.is_empty()
+            && self.capabilities.is_empty()
+    }
+
+    /// Check the variables named `used` in the old version against the new version.
+    ///
+    /// Removed and renamed variables, changes of the causality, type or unit, and names that do
+    /// not exist in the old version are errors. Changes of the value reference, variability,
+    /// declared type and start value are warnings. So are removed interface types and
+    /// capabilities.
+    pub fn compatibility<'a>(&self, used: impl IntoIterator<Item = &'a str>) -> Compatibility {
+        let mut findings = Vec::new();
+
+        for name in used {
+            let location = || Location::Variable(name.to_owned());
+            if self.removed.iter().any(|removed| removed == name) {
+                findings.push(Finding::error(location(), "the variable was removed"));
+                continue;
+            }
+            let new_name = match self.renamed.iter().find(|rename| rename.old == name) {
+                Some(rename) => {
+                    findings.push(Finding::error(
+                        location(),
+                        format!("the variable was renamed to '{}'", rename.new),
+                    ));
+                    rename.new.as_str()
+                }
+                None if self.unchanged.iter().any(|unchanged| unchanged == name) => continue,
+                None if !self.changed.iter().any(|diff| diff.name == name) => {
+                    findings.push(Finding::error(
+                        location(),
+                        "the variable does not exist in the old version",
+                    ));
+                    continue;
+                }
+                None => name,
+            };
+            let changes = self
+                .changed
+                .iter()
+                .filter(|diff| diff.name == new_name)
+                .flat_map(|diff| diff.changes.iter());
+            for change in changes {
+                let severity = match change.attribute {
+                    Attribute::Causality | Attribute::DataType | Attribute::Unit => Severity::Error,
+                    Attribute::ValueReference
+                    | Attribute::Variability
+                    | Attribute::DeclaredType
+                    | Attribute::Start => Severity::Warning,
+                };
+                findings.push(Finding {
+                    severity,
+                    location: location(),
+                    message: format!("changed {change}"),
+                });
+            }
+        }
+
+        for change in &self.capabilities {
+            match (&change.old, &change.new) {
+                (Some(_), None) if !change.attribute.contains('.') => {
+                    findings.push(Finding::warning(
+                        Location::ModelDescription,
+                        format!("the interface type {} was removed", change.attribute),
+                    ))
+                }
+                (Some(old), new) if old == "true" => findings.push(Finding::warning(
+                    Location::ModelDescription,
+                    format!(
+                        "the capability {} is no longer supported ({})",
+                        change.attribute,
+                        new.as_deref().unwrap_or("(none)")
+                    ),
+                )),
+                _ => {}
+            }
+        }
+
+        Compatibility { findings }
+    }
+}
+
+impl Display for ModelDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for name in &self.added {
+            writeln!(f, "added variable '{name}'")?;
+        }
+        for name in &self.removed {
+            writeln!(f, "removed variable '{name}'")?;
+        }
+        for rename in &self.renamed {
+            writeln!(f, "renamed variable '{}' to '{}'", rename.old, rename.new)?;
+        }
+        for diff in &self.changed {
+            for change in &diff.changes {
+                writeln!(f, "variable '{}': {change}", diff.name)?;
+            }
+        }
+        for change in &self.default_experiment {
+            writeln!(f, "DefaultExperiment.{change}")?;
+        }
+        for change in &self.capabilities {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The names of the variables of `md` that are not in `other`, by data type and value reference
+fn unmatched<'a, MD: FmiModelVariables>(
+    md: &'a MD,
+    other: &HashMap<&str, &MD::Variable>,
+) -> HashMap<(DataType, u32), Vec<&'a str>> {
+    let mut unmatched = HashMap::<_, Vec<_>>::new();
+    for var in md.variables().filter(|var| !other.contains_key(var.name())) {
+        unmatched
+            .entry((var.data_type(), var.value_reference()))
+            .or_default()
+            .push(var.name());
+    }
+    unmatched
+}
+
+/// Match the variables of `old` and `new` by name, or else as renamed by their data type and
+/// value reference, and compare their attributes
+fn diff_variables<MD: FmiUnits>(old: &MD, new: &MD, diff: &mut ModelDiff) {
+    let old_by_name = old
+        .variables()
+        .map(|var| (var.name(), var))
+        .collect::<HashMap<_, _>>();
+    let new_by_name = new
+        .variables()
+        .map(|var| (var.name(), var))
+        .collect::<HashMap<_, _>>();
+
+    // A rename is only detected if it is unambiguous, which it may not be for the aliases of
+    // FMI 2.0
+    let unmatched_old = unmatched(old, &new_by_name);
+    let unmatched_new = unmatched(new, &old_by_name);
+
+    // The old variable of each new variable
+    let mut pairs = old_by_name
+        .keys()
+        .filter(|name| new_by_name.contains_key(*name))
+        .map(|name| (*name, *name))
+        .collect::<HashMap<_, _>>();
+
+    for var in old.variables() {
+        if new_by_name.contains_key(var.name()) {
+            continue;
+        }
+        let key = (var.data_type(), var.value_reference());
+        match (unmatched_old[&key].as_slice(), unmatched_new.get(&key)) {
+            ([_], Some(new_names)) if new_names.len() == 1 => {
+                pairs.insert(new_names[0], var.name());
+                diff.renamed.push(Rename {
+                    old: var.name().to_owned(),
+                    new: new_names[0].to_owned(),
+                });
+            }
+            _ => diff.removed.push(var.name().to_owned()),
+        }
+    }
+
+    for new_var in new.variables() {
+        let Some(old_var) = pairs.get(new_var.name()).map(|name| old_by_name[name]) else {
+            diff.added.push(new_var.name().to_owned());
+            continue;
+        };
+        let start = |var: &MD::Variable| {
+            let start = var.start();
+            (!start.is_empty()).then(|| {
+                start
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+        };
+        let changes = [
+            Change::new(
+                Attribute::ValueReference,
+                Some(old_var.value_reference().to_string()),
+                Some(new_var.value_reference().to_string()),
+            ),
+            Change::new(
+                Attribute::Causality,
+                Some(format!("{:?}", old_var.causality())),
+                Some(format!("{:?}", new_var.causality())),
+            ),
+            Change::new(
+                Attribute::Variability,
+                Some(format!("{:?}", old_var.variability())),
+                Some(format!("{:?}", new_var.variability())),
+            ),
+            Change::new(
+                Attribute::DataType,
+                Some(format!("{:?}", old_var.data_type())),
+                Some(format!("{:?}", new_var.data_type())),
+            ),
+            Change::new(
+                Attribute::DeclaredType,
+                old_var.declared_type().map(str::to_owned),
+                new_var.declared_type().map(str::to_owned),
+            ),
+            Change::new(
+                Attribute::Unit,
+                old.variable_unit(old_var).map(str::to_owned),
+                new.variable_unit(new_var).map(str::to_owned),
+            ),
+            Change::new(Attribute::Start, start(old_var), start(new_var)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        if !changes.is_empty() {
+            diff.changed.push(VariableDiff {
+                name: new_var.name().to_owned(),
+                changes,
+            });
+        } else if old_var.name() == new_var.name() {
+            diff.unchanged.push(new_var.name().to_owned());
+        }
+    }
+}
+
+fn diff_default_experiment<D: DefaultExperiment>(old: &D, new: &D, diff: &mut ModelDiff) {
+    let attributes = [
+        ("startTime", old.start_time(), new.start_time()),
+        ("stopTime", old.stop_time(), new.stop_time()),
+        ("tolerance", old.tolerance(), new.tolerance()),
+        ("stepSize", old.step_size(), new.step_size()),
+    ];
+    for (attribute, old, new) in attributes {
+        let value = |value: Option<f64>| value.map(|value| value.to_string());
+        diff.default_experiment
+            .extend(Change::new(attribute, value(old), value(new)));
+    }
+}
+
+/// Compare the presence and model identifier of an interface type, and its capability flags if
+/// both versions have it
+fn diff_interface_type<I: FmiInterfaceType>(
+    name: &str,
+    old: Option<&I>,
+    new: Option<&I>,
+    diff: &mut ModelDiff,
+) {
+    let model_identifier = |it: Option<&I>| it.map(|it| it.model_identifier().to_owned());
+    diff.capabilities.extend(Change::new(
+        name.to_owned(),
+        model_identifier(old),
+        model_identifier(new),
+    ));
+
+    let (Some(old), Some(new)) = (old, new) else {
+        return;
+    };
+    let flags = [
+        (
+            "needsExecutionTool",
+            old.needs_execution_tool(),
+            new.needs_execution_tool(),
+        ),
+        (
+            "canBeInstantiatedOnlyOncePerProcess",
+            old.can_be_instantiated_only_once_per_process(),
+            new.can_be_instantiated_only_once_per_process(),
+        ),
+        (
+            "canGetAndSetFMUState",
+            old.can_get_and_set_fmu_state(),
+            new.can_get_and_set_fmu_state(),
+        ),
+        (
+            "canSerializeFMUState",
+            old.can_serialize_fmu_state(),
+            new.can_serialize_fmu_state(),
+        ),
+        (
+            "providesDirectionalDerivatives",
+            old.provides_directional_derivatives(),
+            new.provides_directional_derivatives(),
+        ),
+        (
+            "providesAdjointDerivatives",
+            old.provides_adjoint_derivatives(),
+            new.provides_adjoint_derivatives(),
+        ),
+        (
+            "providesPerElementDependencies",
+            old.provides_per_element_dependencies(),
+            new.provides_per_element_dependencies(),
+        ),
+    ];
+    for (flag, old, new) in flags {
+        let value = |value: Option<bool>| value.map(|value| value.to_string());
+        diff.capabilities.extend(Change::new(
+            format!("{name}.{flag}"),
+            value(old),
+            value(new),
+        ));
+    }
+}
+
+#[cfg(feature = "fmi2")]
+impl Diff for crate::fmi2::Fmi2ModelDescription {
+    fn diff(&self, new: &Self) -> ModelDiff {
+        let mut diff = ModelDiff::default();
+        diff_variables(self, new, &mut diff);
+        diff_default_experiment(self, new, &mut diff);
+        diff_interface_type(
+            "ModelExchange",
+            self.model_exchange.as_ref(),
+            new.model_exchange.as_ref(),
+            &mut diff,
+        );
+        diff_interface_type(
+            "CoSimulation",
+            self.co_simulation.as_ref(),
+            new.co_simulation.as_ref(),
+            &mut diff,
+        );
+        diff
+    }
+}
+
+#[cfg(feature = "fmi3")]
+impl Diff for crate::fmi3::Fmi3ModelDescription {
+    fn diff(&self, new: &Self) -> ModelDiff {
+        let mut diff = ModelDiff::default();
+        diff_variables(self, new, &mut diff);
+        diff_default_experiment(self, new, &mut diff);
+        diff_interface_type(
+            "ModelExchange",
+            self.model_exchange.as_ref(),
+            new.model_exchange.as_ref(),
+            &mut diff,
+        );
+        diff_interface_type(
+            "CoSimulation",
+            self.co_simulation.as_ref(),
+            new.co_simulation.as_ref(),
+            &mut diff,
+        );
+        diff_interface_type(
+            "ScheduledExecution",
+            self.scheduled_execution.as_ref(),
+            new.scheduled_execution.as_ref(),
+            &mut diff,
+        );
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "fmi3")]
+    #[test]
+    fn test_fmi3() {
+        use crate::fmi3::Fmi3ModelDescription;
+
+        let old: Fmi3ModelDescription = crate::deserialize(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="">
+    <CoSimulation modelIdentifier="Model" canGetAndSetFMUState="true"/>
+    <ModelExchange modelIdentifier="Model"/>
+    <UnitDefinitions>
+        <Unit name="m"/>
+        <Unit name="mm"/>
+    </UnitDefinitions>
+    <TypeDefinitions>
+        <Float64Type name="Length" unit="m"/>
+    </TypeDefinitions>
+    <DefaultExperiment stopTime="1"/>
+    <ModelVariables>
+        <Float64 name="x" valueReference="1" causality="output" declaredType="Length"/>
+        <Float64 name="u" valueReference="2" causality="input" start="0"/>
+        <Int32 name="n" valueReference="3" causality="parameter" start="1"/>
+        <Boolean name="b" valueReference="4" causality="output"/>
+        <Float64 name="k" valueReference="5" causality="parameter" variability="fixed" start="1"/>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+        let new: Fmi3ModelDescription = crate::deserialize(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="">
+    <CoSimulation modelIdentifier="Model" canGetAndSetFMUState="false"/>
+    <UnitDefinitions>
+        <Unit name="m"/>
+        <Unit name="mm"/>
+    </UnitDefinitions>
+    <DefaultExperiment stopTime="2" stepSize="0.1"/>
+    <ModelVariables>
+        <Float64 name="x" valueReference="1" causality="output" unit="mm"/>
+        <Float64 name="u" valueReference="12" causality="input" start="0"/>
+        <Int32 name="count" valueReference="3" causality="parameter" start="1"/>
+        <Float64 name="y" valueReference="6" causality="output"/>
+        <Float64 name="k" valueReference="5" causality="parameter" variability="tunable" start="2"/>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert!(old.diff(&old).is_empty());
+
+        assert_eq!(diff.added, vec!["y"]);
+        assert_eq!(diff.removed, vec!["b"]);
+        assert_eq!(
+            diff.renamed,
+            vec![Rename {
+                old: "n".to_owned(),
+                new: "count".to_owned()
+            }]
+        );
+        assert_eq!(
+            diff.to_string(),
+            "added variable 'y'
+removed variable 'b'
+renamed variable 'n' to 'count'
+variable 'x': declaredType: Length -> (none)
+variable 'x': unit: m -> mm
+variable 'u': valueReference: 2 -> 12
+variable 'k': variability: Fixed -> Tunable
+variable 'k': start: 1 -> 2
+DefaultExperiment.stopTime: 1 -> 2
+DefaultExperiment.stepSize: (none) -> 0.1
+ModelExchange: Model -> (none)
+CoSimulation.canGetAndSetFMUState: true -> false
+"
+        );
+
+        let compatibility = diff.compatibility(["u", "k"]);
+        assert!(compatibility.is_compatible());
+        assert_eq!(
+            compatibility.findings,
+            vec![
+                Finding::warning(
+                    Location::Variable("u".to_owned()),
+                    "changed valueReference: 2 -> 12"
+                ),
+                Finding::warning(
+                    Location::Variable("k".to_owned()),
+                    "changed variability: Fixed -> Tunable"
+                ),
+                Finding::warning(Location::Variable("k".to_owned()), "changed start: 1 -> 2"),
+                Finding::warning(
+                    Location::ModelDescription,
+                    "the interface type ModelExchange was removed"
+                ),
+                Finding::warning(
+                    Location::ModelDescription,
+                    "the capability CoSimulation.canGetAndSetFMUState is no longer supported \
+                     (false)"
+                ),
+            ]
+        );
+
+        let errors = |used: &[&str]| {
+            diff.compatibility(used.iter().copied())
+                .findings
+                .into_iter()
+                .filter(|f| f.severity == Severity::Error)
+                .map(|f| f.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            errors(&["x", "n", "b", "nope"]),
+            vec![
+                "error: variable 'x': changed unit: m -> mm",
+                "error: variable 'n': the variable was renamed to 'count'",
+                "error: variable 'b': the variable was removed",
+                "error: variable 'nope': the variable does not exist in the old version",
+            ]
+        );
+    }
+
+    #[cfg(feature = "fmi2")]
+    #[test]
+    fn test_fmi2() {
+        use crate::fmi2::Fmi2ModelDescription;
+
+        let xml = |causality: &str, aliases: [&str; 2]| {
+            format!(
+                r#"<fmiModelDescription fmiVersion="2.0" modelName="Model" guid="{{}}">
+    <CoSimulation modelIdentifier="Model"/>
+    <ModelVariables>
+        <ScalarVariable name="u" valueReference="1" causality="{causality}">
+            <Real start="0"/>
+        </ScalarVariable>
+        <ScalarVariable name="{}" valueReference="1" causality="{causality}">
+            <Real/>
+        </ScalarVariable>
+        <ScalarVariable name="{}" valueReference="1" causality="{causality}">
+            <Real/>
+        </ScalarVariable>
+        <ScalarVariable name="y" valueReference="2" causality="output">
+            <Real/>
+        </ScalarVariable>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#,
+                aliases[0], aliases[1]
+            )
+        };
+        let old: Fmi2ModelDescription = crate::deserialize(&xml("input", ["v", "w"])).unwrap();
+        let new: Fmi2ModelDescription = crate::deserialize(&xml("output", ["v", "x"])).unwrap();
+
+        // The rename of the alias is unambiguous
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.renamed,
+            vec![Rename {
+                old: "w".to_owned(),
+                new: "x".to_owned()
+            }]
+        );
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 3);
+        assert_eq!(
+            diff.changed[0],
+            VariableDiff {
+                name: "u".to_owned(),
+                changes: vec![Change {
+                    attribute: Attribute::Causality,
+                    old: Some("Input".to_owned()),
+                    new: Some("Output".to_owned()),
+                }],
+            }
+        );
+        assert!(!diff.compatibility(["u"]).is_compatible());
+        assert!(diff.compatibility(["y"]).is_compatible());
+
+        // The renames of two aliases of the same value reference are ambiguous
+        let new: Fmi2ModelDescription = crate::deserialize(&xml("input", ["a", "b"])).unwrap();
+        let diff = old.diff(&new);
+        assert!(diff.renamed.is_empty());
+        assert_eq!(diff.added, vec!["a", "b"]);
+        assert_eq!(diff.removed, vec!["v", "w"]);
+    }
+}
@@ -0,0 +1,179 @@
+//! Enumeration types and the mapping between their values and item names, independent of the FMI
+//! version.
+//!
+//! The values of enumeration variables are exchanged as integers, `Int32` in FMI 2.0 and `Int64`
+//! in FMI 3.0. Their declared type lists the valid values, each with the name of an item. The
+//! [`Enumeration`] of a variable is resolved through its declared type by [`FmiEnumerations`],
+//! which is implemented for the FMI 2.0 and 3.0 model descriptions:
+//!
+//! ```rust
+//! # #[cfg(feature = "fmi3")] {
+//! use fmi_schema::{
+//!     enumerations::FmiEnumerations, fmi3::Fmi3ModelDescription, traits::FmiModelVariables,
+//! };
+//!
+//! let xml = r#"<fmiModelDescription fmiVersion="3.0" modelName="Gear" instantiationToken="">
+//!     <TypeDefinitions>
+//!         <EnumerationType name="Direction">
+//!             <Item name="forward" value="1"/>
+//!             <Item name="reverse" value="-1"/>
+//!         </EnumerationType>
+//!     </TypeDefinitions>
+//!     <ModelVariables>
+//!         <Enumeration name="direction" valueReference="1" declaredType="Direction" start="1"/>
+//!     </ModelVariables>
+//!     <ModelStructure/>
+//! </fmiModelDescription>"#;
+//! let md: Fmi3ModelDescription = fmi_schema::deserialize(xml).unwrap();
+//!
+//! let direction = md.variable_by_name("direction").unwrap();
+//! let enumeration = md.variable_enumeration(direction).unwrap().unwrap();
+//! assert_eq!(enumeration.item_name(-1), Some("reverse"));
+//! assert_eq!(enumeration.parse("forward").unwrap(), 1);
+//! assert!(enumeration.validate(0).is_err());
+//! # }
+//! ```
+
+use crate::{
+    Error,
+    traits::{DataType, FmiModelVariables, FmiVariable},
+};
+
+/// An item of an enumeration type
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnumerationItem {
+    pub name: String,
+    pub value: i64,
+    pub description: Option<String>,
+}
+
+/// An enumeration type of a model description
+#[derive(Clone, Debug, PartialEq)]
+pub struct Enumeration {
+    /// The name of the type definition
+    pub name: String,
+    pub quantity: Option<String>,
+    pub items: Vec<EnumerationItem>,
+}
+
+impl Enumeration {
+    /// Find the item with `value`
+    pub fn item(&self, value: i64) -> Option<&EnumerationItem> {
+        self.items.iter().find(|item| item.value == value)
+    }
+
+    /// The name of the item with `value`
+    pub fn item_name(&self, value: i64) -> Option<&str> {
+        self.item(value).map(|item| item.name.as_str())
+    }
+
+    /// The value of the item named `name`
+    pub fn value_of(&self, name: &str) -> Option<i64> {
+        self.items
+            .iter()
+            .find(|item| item.name == name)
+            .map(|item| item.value)
+    }
+
+    /// Check that `value` is the value of one of the items
+    pub fn validate(&self, value: i64) -> Result<i64, Error> {
+        match self.item(value) {
+            Some(_) => Ok(value),
+            None => Err(Error::EnumerationItemNotFound(
+                value.to_string(),
+                self.name.clone(),
+            )),
+        }
+    }
+
+    /// Parse `s` as the name of an item, or else as the integer value of an item
+    pub fn parse(&self, s: &str) -> Result<i64, Error> {
+        if let Some(value) = self.value_of(s) {
+            return Ok(value);
+        }
+        s.trim()
+            .parse()
+            .map_err(|_| Error::EnumerationItemNotFound(s.to_owned(), self.name.clone()))
+            .and_then(|value| self.validate(value))
+    }
+}
+
+/// Enumeration types of the variables of a model description, resolved through their declared
+/// types
+pub trait FmiEnumerations: FmiModelVariables {
+    /// The enumeration type definition named `name`
+    fn enumeration_type(&self, name: &str) -> Option<Enumeration>;
+
+    /// The enumeration type of `variable`, or `None` if it is not an enumeration variable.
+    fn variable_enumeration(
+        &self,
+        variable: &Self::Variable,
+    ) -> Result<Option<Enumeration>, Error> {
+        if variable.data_type() != DataType::Enumeration {
+            return Ok(None);
+        }
+        let declared_type = variable.declared_type().ok_or_else(|| {
+            Error::Model(format!(
+                "Enumeration variable {} has no declared type",
+                variable.name()
+            ))
+        })?;
+        self.enumeration_type(declared_type)
+            .map(Some)
+            .ok_or_else(|| {
+                Error::Model(format!(
+                    "Enumeration type {declared_type} of variable {} not found",
+                    variable.name()
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enumeration() -> Enumeration {
+        Enumeration {
+            name: "Direction".to_owned(),
+            quantity: None,
+            items: vec![
+                EnumerationItem {
+                    name: "forward".to_owned(),
+                    value: 1,
+                    description: None,
+                },
+                EnumerationItem {
+                    name: "reverse".to_owned(),
+                    value: -1,
+                    description: Some("Backwards".to_owned()),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_item_names() {
+        let enumeration = enumeration();
+        assert_eq!(enumeration.item_name(1), Some("forward"));
+        assert_eq!(enumeration.item_name(-1), Some("reverse"));
+        assert_eq!(enumeration.item_name(0), None);
+        assert_eq!(enumeration.value_of("reverse"), Some(-1));
+        assert_eq!(enumeration.value_of("sideways"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let enumeration = enumeration();
+        assert_eq!(enumeration.parse("reverse").unwrap(), -1);
+        assert_eq!(enumeration.parse("1").unwrap(), 1);
+        assert_eq!(enumeration.parse(" -1").unwrap(), -1);
+        assert!(matches!(
+            enumeration.parse("2"),
+            Err(Error::EnumerationItemNotFound(value, name)) if value == "2" && name == "Direction"
+        ));
+        assert!(enumeration.parse("sideways").is_err());
+        assert!(enumeration.validate(1).is_ok());
+        assert!(enumeration.validate(0).is_err());
+    }
+}
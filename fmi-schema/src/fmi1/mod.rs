@@ -0,0 +1,97 @@
+//! FMI1.0 schema definitions
+//!
+//! This module contains the definitions of the FMI1.0 XML schema, as far as needed to import FMUs
+//! for Model Exchange and Co-Simulation.
+
+mod model_description;
+mod scalar_variable;
+
+use std::str::FromStr;
+
+pub use model_description::*;
+pub use scalar_variable::*;
+
+use crate::{
+    Error,
+    variable_counts::{Counts, VariableCounts},
+};
+
+impl FromStr for Fmi1ModelDescription {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        hard_xml::XmlRead::from_str(s).map_err(Error::XmlParse)
+    }
+}
+
+impl crate::traits::DefaultExperiment for Fmi1ModelDescription {
+    fn start_time(&self) -> Option<f64> {
+        self.default_experiment
+            .as_ref()
+            .and_then(|de| de.start_time)
+    }
+
+    fn stop_time(&self) -> Option<f64> {
+        self.default_experiment.as_ref().and_then(|de| de.stop_time)
+    }
+
+    fn tolerance(&self) -> Option<f64> {
+        self.default_experiment.as_ref().and_then(|de| de.tolerance)
+    }
+
+    fn step_size(&self) -> Option<f64> {
+        None
+    }
+}
+
+impl VariableCounts for ModelVariables {
+    fn model_counts(&self) -> Counts {
+        self.variables
+            .iter()
+            .fold(Counts::default(), |mut cts, sv| {
+                match sv.variability() {
+                    Variability::Constant => {
+                        cts.num_constants += 1;
+                    }
+                    Variability::Parameter => {
+                        cts.num_parameters += 1;
+                    }
+                    Variability::Discrete => {
+                        cts.num_discrete += 1;
+                    }
+                    Variability::Continuous => {
+                        cts.num_continuous += 1;
+                    }
+                }
+                match sv.causality() {
+                    Causality::Input => {
+                        cts.num_inputs += 1;
+                    }
+                    Causality::Output => {
+                        cts.num_outputs += 1;
+                    }
+                    Causality::Internal | Causality::None => {
+                        cts.num_local += 1;
+                    }
+                }
+                match sv.elem {
+                    ScalarVariableElement::Real(_) => {
+                        cts.num_real_vars += 1;
+                    }
+                    ScalarVariableElement::Integer(_) => {
+                        cts.num_integer_vars += 1;
+                    }
+                    ScalarVariableElement::Enumeration(_) => {
+                        cts.num_enum_vars += 1;
+                    }
+                    ScalarVariableElement::Boolean(_) => {
+                        cts.num_bool_vars += 1;
+                    }
+                    ScalarVariableElement::String(_) => {
+                        cts.num_string_vars += 1;
+                    }
+                }
+                cts
+            })
+    }
+}
@@ -0,0 +1,280 @@
+use crate::{Error, traits::FmiModelDescription};
+
+use super::ScalarVariable;
+
+/// FMI 1.0 model description.
+///
+/// FMI 1.0 uses the same `fmiModelDescription` root element for Model Exchange and Co-Simulation.
+/// An FMU for Co-Simulation is recognized by the presence of the `Implementation` element.
+///
+/// Elements not needed to import an FMU (`TypeDefinitions`, `UnitDefinitions`,
+/// `VendorAnnotations`) are ignored when reading.
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "fmiModelDescription")]
+pub struct Fmi1ModelDescription {
+    /// Version of FMI, "1.0"
+    #[xml(attr = "fmiVersion")]
+    pub fmi_version: String,
+
+    /// The name of the model as used in the modeling environment that generated the XML file.
+    #[xml(attr = "modelName")]
+    pub model_name: String,
+
+    /// Short class name according to C-syntax. Prefix of all C functions exported by the FMU.
+    #[xml(attr = "modelIdentifier")]
+    pub model_identifier: String,
+
+    /// Fingerprint of xml-file content to verify that xml-file and C-functions are compatible to
+    /// each other
+    #[xml(attr = "guid")]
+    pub guid: String,
+
+    #[xml(attr = "description")]
+    pub description: Option<String>,
+
+    #[xml(attr = "author")]
+    pub author: Option<String>,
+
+    /// Version of FMU, e.g., "1.4.1"
+    #[xml(attr = "version")]
+    pub version: Option<String>,
+
+    /// Name of the tool that generated the XML file.
+    #[xml(attr = "generationTool")]
+    pub generation_tool: Option<String>,
+
+    /// Date and time when the XML file was generated.
+    #[xml(attr = "generationDateAndTime")]
+    pub generation_date_and_time: Option<String>,
+
+    /// Defines whether the variable names in ModelVariables follow a particular convention.
+    #[xml(attr = "variableNamingConvention")]
+    pub variable_naming_convention: Option<String>,
+
+    #[xml(attr = "numberOfContinuousStates")]
+    pub number_of_continuous_states: u32,
+
+    #[xml(attr = "numberOfEventIndicators")]
+    pub number_of_event_indicators: u32,
+
+    #[xml(child = "DefaultExperiment")]
+    pub default_experiment: Option<DefaultExperiment>,
+
+    #[xml(child = "ModelVariables", default)]
+    pub model_variables: ModelVariables,
+
+    /// If present, the FMU is based on FMI for Co-Simulation
+    #[xml(child = "Implementation")]
+    pub implementation: Option<Implementation>,
+}
+
+impl Fmi1ModelDescription {
+    /// Total number of variables
+    pub fn num_variables(&self) -> usize {
+        self.model_variables.variables.len()
+    }
+
+    /// Get the number of continuous states (and derivatives)
+    pub fn num_states(&self) -> usize {
+        self.number_of_continuous_states as usize
+    }
+
+    pub fn num_event_indicators(&self) -> usize {
+        self.number_of_event_indicators as usize
+    }
+
+    /// Get a iterator of the SalarVariables
+    pub fn get_model_variables(&self) -> impl Iterator<Item = &ScalarVariable> {
+        self.model_variables.variables.iter()
+    }
+
+    /// Get a reference to the model variable with the given name
+    pub fn model_variable_by_name(&self, name: &str) -> Result<&ScalarVariable, Error> {
+        self.model_variables
+            .variables
+            .iter()
+            .find(|var| var.name == name)
+            .ok_or_else(|| Error::VariableNotFound(name.to_owned()))
+    }
+
+    /// Returns true if the FMU is for Co-Simulation
+    pub fn is_co_simulation(&self) -> bool {
+        self.implementation.is_some()
+    }
+}
+
+impl FmiModelDescription for Fmi1ModelDescription {
+    fn model_name(&self) -> &str {
+        &self.model_name
+    }
+
+    fn version_string(&self) -> &str {
+        &self.fmi_version
+    }
+
+    fn deserialize(xml: &str) -> Result<Self, crate::Error> {
+        hard_xml::XmlRead::from_str(xml).map_err(crate::Error::XmlParse)
+    }
+
+    fn serialize(&self) -> Result<String, crate::Error> {
+        hard_xml::XmlWrite::to_string(self).map_err(crate::Error::XmlParse)
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "DefaultExperiment")]
+pub struct DefaultExperiment {
+    /// Default start time of simulation
+    #[xml(attr = "startTime")]
+    pub start_time: Option<f64>,
+    /// Default stop time of simulation
+    #[xml(attr = "stopTime")]
+    pub stop_time: Option<f64>,
+    /// Default relative integration tolerance
+    #[xml(attr = "tolerance")]
+    pub tolerance: Option<f64>,
+}
+
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "ModelVariables")]
+pub struct ModelVariables {
+    #[xml(child = "ScalarVariable")]
+    pub variables: Vec<ScalarVariable>,
+}
+
+/// Co-Simulation specific information, either a stand-alone slave or a slave coupled to a
+/// simulation tool.
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "Implementation")]
+pub struct Implementation {
+    #[xml(child = "CoSimulation_StandAlone")]
+    pub co_simulation_stand_alone: Option<CoSimulationStandAlone>,
+
+    #[xml(child = "CoSimulation_Tool")]
+    pub co_simulation_tool: Option<CoSimulationTool>,
+}
+
+impl Implementation {
+    /// The capabilities of the slave
+    pub fn capabilities(&self) -> Option<&Capabilities> {
+        self.co_simulation_stand_alone
+            .as_ref()
+            .map(|cs| &cs.capabilities)
+            .or_else(|| self.co_simulation_tool.as_ref().map(|cs| &cs.capabilities))
+    }
+}
+
+/// The slave contains the model and the solver.
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "CoSimulation_StandAlone")]
+pub struct CoSimulationStandAlone {
+    #[xml(child = "Capabilities", default)]
+    pub capabilities: Capabilities,
+}
+
+/// The slave is a wrapper around a simulation tool. The `Model` element is ignored when reading.
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "CoSimulation_Tool")]
+pub struct CoSimulationTool {
+    #[xml(child = "Capabilities", default)]
+    pub capabilities: Capabilities,
+}
+
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "Capabilities")]
+pub struct Capabilities {
+    #[xml(attr = "canHandleVariableCommunicationStepSize")]
+    pub can_handle_variable_communication_step_size: Option<bool>,
+
+    #[xml(attr = "canHandleEvents")]
+    pub can_handle_events: Option<bool>,
+
+    #[xml(attr = "canRejectSteps")]
+    pub can_reject_steps: Option<bool>,
+
+    #[xml(attr = "canInterpolateInputs")]
+    pub can_interpolate_inputs: Option<bool>,
+
+    #[xml(attr = "maxOutputDerivativeOrder")]
+    pub max_output_derivative_order: Option<u32>,
+
+    #[xml(attr = "canRunAsynchronuously")]
+    pub can_run_asynchronuously: Option<bool>,
+
+    #[xml(attr = "canSignalEvents")]
+    pub can_signal_events: Option<bool>,
+
+    #[xml(attr = "canBeInstantiatedOnlyOncePerProcess")]
+    pub can_be_instantiated_only_once_per_process: Option<bool>,
+
+    #[xml(attr = "canNotUseMemoryManagementFunctions")]
+    pub can_not_use_memory_management_functions: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use hard_xml::XmlRead;
+
+    use super::*;
+    use crate::fmi1::{Causality, ScalarVariableElement, Variability};
+
+    #[test]
+    fn test_model_description() {
+        let s = r##"<?xml version="1.0" encoding="UTF-8"?>
+<fmiModelDescription
+  fmiVersion="1.0"
+  modelName="bouncingBall"
+  modelIdentifier="bouncingBall"
+  guid="{8c4e810f-3df3-4a00-8276-176fa3c9f003}"
+  numberOfContinuousStates="2"
+  numberOfEventIndicators="1">
+<TypeDefinitions>
+  <Type name="Modelica.SIunits.Height"><RealType quantity="Height" unit="m"/></Type>
+</TypeDefinitions>
+<DefaultExperiment startTime="0.0" stopTime="3.0" tolerance="1e-6"/>
+<ModelVariables>
+  <ScalarVariable name="h" valueReference="0" description="height, used as state">
+    <Real start="1" fixed="true"/>
+  </ScalarVariable>
+  <ScalarVariable name="der(h)" valueReference="1" description="velocity of ball">
+    <Real/>
+  </ScalarVariable>
+  <ScalarVariable name="g" valueReference="4" variability="parameter" causality="none">
+    <Real start="9.81"/>
+  </ScalarVariable>
+</ModelVariables>
+</fmiModelDescription>"##;
+        let md = Fmi1ModelDescription::from_str(s).unwrap();
+        assert_eq!(md.fmi_version, "1.0");
+        assert_eq!(md.model_identifier, "bouncingBall");
+        assert_eq!(md.num_states(), 2);
+        assert_eq!(md.num_event_indicators(), 1);
+        assert!(!md.is_co_simulation());
+        assert_eq!(md.default_experiment.as_ref().unwrap().stop_time, Some(3.0));
+        assert_eq!(md.num_variables(), 3);
+
+        let g = md.model_variable_by_name("g").unwrap();
+        assert_eq!(g.causality(), Causality::None);
+        assert_eq!(g.variability(), Variability::Parameter);
+        assert!(matches!(
+            g.elem,
+            ScalarVariableElement::Real(ref r) if r.start == Some(9.81)
+        ));
+    }
+
+    #[test]
+    fn test_implementation() {
+        let s = r##"<Implementation>
+  <CoSimulation_StandAlone>
+    <Capabilities canHandleVariableCommunicationStepSize="true" canRejectSteps="false"/>
+  </CoSimulation_StandAlone>
+</Implementation>"##;
+        let implementation = Implementation::from_str(s).unwrap();
+        let capabilities = implementation.capabilities().unwrap();
+        assert_eq!(
+            capabilities.can_handle_variable_communication_step_size,
+            Some(true)
+        );
+        assert_eq!(capabilities.can_reject_steps, Some(false));
+    }
+}
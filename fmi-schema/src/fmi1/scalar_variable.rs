@@ -0,0 +1,349 @@
+use std::{fmt::Display, str::FromStr};
+
+/// Enumeration that defines the causality of the variable.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Causality {
+    /// The value of the variable is provided by the environment.
+    Input,
+    /// The value of the variable can be used by the environment.
+    Output,
+    /// Local variable of the model, may not be used by the environment.
+    #[default]
+    Internal,
+    /// The variable has no causal meaning, such as a parameter.
+    None,
+}
+
+impl FromStr for Causality {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "input" => Ok(Causality::Input),
+            "output" => Ok(Causality::Output),
+            "internal" => Ok(Causality::Internal),
+            "none" => Ok(Causality::None),
+            _ => Err(format!("Invalid Causality: {}", s)),
+        }
+    }
+}
+
+impl Display for Causality {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Causality::Input => "input",
+            Causality::Output => "output",
+            Causality::Internal => "internal",
+            Causality::None => "none",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Enumeration that defines the time dependency of the variable.
+///
+/// The default is [`Variability::Continuous`].
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Variability {
+    /// The value of the variable never changes.
+    Constant,
+    /// The value of the variable is fixed after initialization, in other words after
+    /// `fmiInitialize` was called the variable value does not change anymore.
+    Parameter,
+    /// The value of the variable changes only at events.
+    Discrete,
+    /// No restrictions on value changes, only allowed for variables of type Real.
+    #[default]
+    Continuous,
+}
+
+impl FromStr for Variability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "constant" => Ok(Variability::Constant),
+            "parameter" => Ok(Variability::Parameter),
+            "discrete" => Ok(Variability::Discrete),
+            "continuous" => Ok(Variability::Continuous),
+            _ => Err(format!("Invalid Variability: {}", s)),
+        }
+    }
+}
+
+impl Display for Variability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Variability::Constant => "constant",
+            Variability::Parameter => "parameter",
+            Variability::Discrete => "discrete",
+            Variability::Continuous => "continuous",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Defines whether the variable is an alias of another variable with the same value reference.
+#[derive(Clone, Copy, Default, PartialEq, Debug)]
+pub enum Alias {
+    #[default]
+    NoAlias,
+    Alias,
+    /// The value of the variable is the negated value of the variable it aliases.
+    NegatedAlias,
+}
+
+impl FromStr for Alias {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "noAlias" => Ok(Alias::NoAlias),
+            "alias" => Ok(Alias::Alias),
+            "negatedAlias" => Ok(Alias::NegatedAlias),
+            _ => Err(format!("Invalid Alias: {}", s)),
+        }
+    }
+}
+
+impl Display for Alias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Alias::NoAlias => "noAlias",
+            Alias::Alias => "alias",
+            Alias::NegatedAlias => "negatedAlias",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "Real")]
+pub struct Real {
+    /// If present, name of type defined with TypeDefinitions / Type providing defaults.
+    #[xml(attr = "declaredType")]
+    pub declared_type: Option<String>,
+
+    #[xml(attr = "quantity")]
+    pub quantity: Option<String>,
+
+    #[xml(attr = "unit")]
+    pub unit: Option<String>,
+
+    #[xml(attr = "displayUnit")]
+    pub display_unit: Option<String>,
+
+    #[xml(attr = "min")]
+    pub min: Option<f64>,
+
+    #[xml(attr = "max")]
+    pub max: Option<f64>,
+
+    #[xml(attr = "nominal")]
+    pub nominal: Option<f64>,
+
+    /// Initial or guess value of the variable, before `fmiInitialize` is called.
+    #[xml(attr = "start")]
+    pub start: Option<f64>,
+
+    /// If true, `start` is the initial value of the variable, otherwise it is a guess value.
+    #[xml(attr = "fixed")]
+    pub fixed: Option<bool>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "Integer")]
+pub struct Integer {
+    /// If present, name of type defined with TypeDefinitions / Type providing defaults.
+    #[xml(attr = "declaredType")]
+    pub declared_type: Option<String>,
+
+    #[xml(attr = "quantity")]
+    pub quantity: Option<String>,
+
+    #[xml(attr = "min")]
+    pub min: Option<i32>,
+
+    #[xml(attr = "max")]
+    pub max: Option<i32>,
+
+    /// Initial or guess value of the variable, before `fmiInitialize` is called.
+    #[xml(attr = "start")]
+    pub start: Option<i32>,
+
+    /// If true, `start` is the initial value of the variable, otherwise it is a guess value.
+    #[xml(attr = "fixed")]
+    pub fixed: Option<bool>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "Boolean")]
+pub struct Boolean {
+    /// If present, name of type defined with TypeDefinitions / Type providing defaults.
+    #[xml(attr = "declaredType")]
+    pub declared_type: Option<String>,
+
+    /// Initial or guess value of the variable, before `fmiInitialize` is called.
+    #[xml(attr = "start")]
+    pub start: Option<bool>,
+
+    /// If true, `start` is the initial value of the variable, otherwise it is a guess value.
+    #[xml(attr = "fixed")]
+    pub fixed: Option<bool>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "String")]
+pub struct FmiString {
+    /// If present, name of type defined with TypeDefinitions / Type providing defaults.
+    #[xml(attr = "declaredType")]
+    pub declared_type: Option<String>,
+
+    /// Initial or guess value of the variable, before `fmiInitialize` is called.
+    #[xml(attr = "start")]
+    pub start: Option<String>,
+
+    /// If true, `start` is the initial value of the variable, otherwise it is a guess value.
+    #[xml(attr = "fixed")]
+    pub fixed: Option<bool>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "Enumeration")]
+pub struct Enumeration {
+    /// Name of the enumeration type defined with TypeDefinitions / Type.
+    #[xml(attr = "declaredType")]
+    pub declared_type: String,
+
+    #[xml(attr = "min")]
+    pub min: Option<i32>,
+
+    #[xml(attr = "max")]
+    pub max: Option<i32>,
+
+    /// Initial or guess value of the variable, before `fmiInitialize` is called.
+    #[xml(attr = "start")]
+    pub start: Option<i32>,
+
+    /// If true, `start` is the initial value of the variable, otherwise it is a guess value.
+    #[xml(attr = "fixed")]
+    pub fixed: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+pub enum ScalarVariableElement {
+    #[xml(tag = "Real")]
+    Real(Real),
+    #[xml(tag = "Integer")]
+    Integer(Integer),
+    #[xml(tag = "Boolean")]
+    Boolean(Boolean),
+    #[xml(tag = "String")]
+    String(FmiString),
+    #[xml(tag = "Enumeration")]
+    Enumeration(Enumeration),
+}
+
+impl Default for ScalarVariableElement {
+    fn default() -> Self {
+        Self::Real(Real::default())
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl ScalarVariableElement {
+    pub fn data_type(&self) -> arrow::datatypes::DataType {
+        match self {
+            ScalarVariableElement::Real(_) => arrow::datatypes::DataType::Float64,
+            ScalarVariableElement::Integer(_) => arrow::datatypes::DataType::Int32,
+            ScalarVariableElement::Boolean(_) => arrow::datatypes::DataType::Boolean,
+            ScalarVariableElement::String(_) => arrow::datatypes::DataType::Utf8,
+            ScalarVariableElement::Enumeration(_) => arrow::datatypes::DataType::Int32,
+        }
+    }
+}
+
+/// FMI 1.0 ScalarVariable.
+///
+/// The `DirectDependency` child element is not represented and ignored when reading.
+#[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[xml(tag = "ScalarVariable")]
+pub struct ScalarVariable {
+    /// The full, unique name of the variable.
+    #[xml(attr = "name")]
+    pub name: String,
+
+    /// A handle of the variable to efficiently identify the variable value in the model interface.
+    #[xml(attr = "valueReference")]
+    pub value_reference: u32,
+
+    /// An optional description string describing the meaning of the variable.
+    #[xml(attr = "description")]
+    pub description: Option<String>,
+
+    /// Enumeration that defines the time dependency of the variable.
+    #[xml(attr = "variability")]
+    pub variability: Option<Variability>,
+
+    /// Enumeration that defines the causality of the variable.
+    #[xml(attr = "causality")]
+    pub causality: Option<Causality>,
+
+    /// Whether the variable is an alias of another variable.
+    #[xml(attr = "alias")]
+    pub alias: Option<Alias>,
+
+    #[xml(
+        child = "Real",
+        child = "Integer",
+        child = "Boolean",
+        child = "String",
+        child = "Enumeration"
+    )]
+    pub elem: ScalarVariableElement,
+}
+
+impl ScalarVariable {
+    pub fn causality(&self) -> Causality {
+        self.causality.unwrap_or_default()
+    }
+
+    pub fn variability(&self) -> Variability {
+        self.variability.unwrap_or_default()
+    }
+
+    pub fn alias(&self) -> Alias {
+        self.alias.unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hard_xml::XmlRead;
+
+    use super::*;
+
+    #[test]
+    fn test_scalar_variable() {
+        let s = r#"
+        <ScalarVariable name="u" valueReference="3" description="input" causality="input" variability="discrete">
+            <Integer start="2" fixed="true"/>
+            <DirectDependency/>
+        </ScalarVariable>
+        "#;
+        let sv = ScalarVariable::from_str(s).unwrap();
+        assert_eq!(sv.name, "u");
+        assert_eq!(sv.value_reference, 3);
+        assert_eq!(sv.causality(), Causality::Input);
+        assert_eq!(sv.variability(), Variability::Discrete);
+        assert_eq!(sv.alias(), Alias::NoAlias);
+        assert_eq!(
+            sv.elem,
+            ScalarVariableElement::Integer(Integer {
+                start: Some(2),
+                fixed: Some(true),
+                ..Default::default()
+            })
+        );
+    }
+}
@@ -1,4 +1,5 @@
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Real")]
 pub struct RealAttributes {
     #[xml(attr = "quantity")]
@@ -34,6 +35,7 @@ pub struct RealAttributes {
 }
 
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Integer")]
 pub struct IntegerAttributes {
     #[xml(attr = "quantity")]
@@ -1,6 +1,7 @@
 use crate::traits::FmiInterfaceType;
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "File", strict(unknown_attribute, unknown_element))]
 pub struct File {
     /// Name of the file including the path relative to the sources directory, using the forward
@@ -10,6 +11,7 @@ pub struct File {
 }
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "SourceFiles", strict(unknown_attribute, unknown_element))]
 pub struct SourceFiles {
     #[xml(child = "File")]
@@ -19,6 +21,7 @@ pub struct SourceFiles {
 /// The FMU includes a model or the communication to a tool that provides a model. The environment
 /// provides the simulation engine for the model.
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ModelExchange", strict(unknown_attribute, unknown_element))]
 pub struct ModelExchange {
     /// Short class name according to C-syntax
@@ -58,6 +61,7 @@ pub struct ModelExchange {
 }
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "CoSimulation", strict(unknown_attribute, unknown_element))]
 pub struct CoSimulation {
     /// Short class name according to C-syntax
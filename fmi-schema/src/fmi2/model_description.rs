@@ -1,10 +1,19 @@
-use crate::{Error, traits::FmiModelDescription};
+use crate::{
+    Error,
+    enumerations::{Enumeration, EnumerationItem, FmiEnumerations},
+    traits::{
+        FmiModelDescription, FmiModelStructure, FmiModelVariables, ModelUnknown, UnknownKind,
+    },
+    units::{FmiUnits, Units},
+};
 
 use super::{
-    CoSimulation, Fmi2Unit, Fmi2VariableDependency, ModelExchange, ScalarVariable, SimpleType,
+    CoSimulation, Fmi2Unit, Fmi2VariableDependency, ModelExchange, Real, RealAttributes,
+    ScalarVariable, ScalarVariableElement, SimpleType, SimpleTypeElement,
 };
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(
     tag = "fmiModelDescription",
     strict(unknown_attribute, unknown_element)
@@ -257,7 +266,145 @@ impl FmiModelDescription for Fmi2ModelDescription {
     }
 }
 
+impl FmiModelVariables for Fmi2ModelDescription {
+    type Variable = ScalarVariable;
+
+    fn variables(&self) -> impl Iterator<Item = &Self::Variable> {
+        self.model_variables.variables.iter()
+    }
+}
+
+impl FmiModelStructure for Fmi2ModelDescription {
+    /// Iterate over the outputs, derivatives and initial unknowns.
+    ///
+    /// The ScalarVariable indices of the model structure are resolved to value references.
+    /// Unknowns and dependencies referring to an index outside of the model variables are
+    /// skipped.
+    fn unknowns(&self) -> impl Iterator<Item = ModelUnknown> {
+        // Variable indices start at 1 in the modelDescription
+        let value_reference = |index: u32| {
+            (index as usize)
+                .checked_sub(1)
+                .and_then(|index| self.model_variables.variables.get(index))
+                .map(|var| var.value_reference)
+        };
+        let structure = &self.model_structure;
+        [
+            (UnknownKind::Output, &structure.outputs.unknowns),
+            (
+                UnknownKind::ContinuousStateDerivative,
+                &structure.derivatives.unknowns,
+            ),
+            (
+                UnknownKind::InitialUnknown,
+                &structure.initial_unknowns.unknowns,
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(kind, unknowns)| unknowns.iter().map(move |unknown| (kind, unknown)))
+        .filter_map(move |(kind, unknown)| {
+            Some(ModelUnknown {
+                kind,
+                value_reference: value_reference(unknown.index)?,
+                // An empty list can not be told apart from a missing one
+                dependencies: (!unknown.dependencies.is_empty()).then(|| {
+                    unknown
+                        .dependencies
+                        .iter()
+                        .filter_map(|&index| value_reference(index))
+                        .collect()
+                }),
+            })
+        })
+    }
+}
+
+impl Fmi2ModelDescription {
+    /// The Real attributes of a Real variable, and of its declared type if any
+    fn real_attributes<'a>(
+        &'a self,
+        variable: &'a ScalarVariable,
+    ) -> Option<(&'a Real, Option<&'a RealAttributes>)> {
+        let ScalarVariableElement::Real(real) = &variable.elem else {
+            return None;
+        };
+        let declared_type = real.declared_type.as_deref().and_then(|declared_type| {
+            self.type_definitions
+                .as_ref()?
+                .types
+                .iter()
+                .find(|ty| ty.name == declared_type)
+                .and_then(|ty| match &ty.elem {
+                    SimpleTypeElement::Real(attributes) => Some(attributes),
+                    _ => None,
+                })
+        });
+        Some((real, declared_type))
+    }
+}
+
+impl FmiEnumerations for Fmi2ModelDescription {
+    fn enumeration_type(&self, name: &str) -> Option<Enumeration> {
+        self.type_definitions
+            .as_ref()?
+            .types
+            .iter()
+            .find_map(|ty| match &ty.elem {
+                SimpleTypeElement::Enumeration(enumeration) if ty.name == name => {
+                    Some(Enumeration {
+                        name: ty.name.clone(),
+                        quantity: enumeration.quantity.clone(),
+                        items: enumeration
+                            .items
+                            .iter()
+                            .map(|item| EnumerationItem {
+                                name: item.name.clone(),
+                                value: item.value.into(),
+                                description: item.description.clone(),
+                            })
+                            .collect(),
+                    })
+                }
+                _ => None,
+            })
+    }
+}
+
+impl FmiUnits for Fmi2ModelDescription {
+    fn units(&self) -> Units {
+        self.unit_definitions
+            .iter()
+            .flat_map(|defs| defs.units.iter())
+            .map(Into::into)
+            .collect()
+    }
+
+    fn variable_unit<'a>(&'a self, variable: &'a Self::Variable) -> Option<&'a str> {
+        let (real, declared_type) = self.real_attributes(variable)?;
+        real.unit
+            .as_deref()
+            .or_else(|| declared_type?.unit.as_deref())
+    }
+
+    fn variable_display_unit<'a>(&'a self, variable: &'a Self::Variable) -> Option<&'a str> {
+        let (real, declared_type) = self.real_attributes(variable)?;
+        real.display_unit
+            .as_deref()
+            .or_else(|| declared_type?.display_unit.as_deref())
+    }
+
+    fn is_relative_quantity(&self, variable: &Self::Variable) -> bool {
+        self.real_attributes(variable)
+            .and_then(|(real, declared_type)| {
+                real.relative_quantity
+                    .or_else(|| declared_type?.relative_quantity)
+            })
+            .unwrap_or(false)
+    }
+}
+
 #[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "LogCategories", strict(unknown_attribute, unknown_element))]
 pub struct LogCategories {
     #[xml(child = "Category")]
@@ -265,6 +412,7 @@ pub struct LogCategories {
 }
 
 #[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Category", strict(unknown_attribute, unknown_element))]
 pub struct Category {
     #[xml(attr = "name")]
@@ -274,6 +422,7 @@ pub struct Category {
 }
 
 #[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "DefaultExperiment")]
 pub struct DefaultExperiment {
     /// Default start time of simulation
@@ -310,6 +459,7 @@ impl DefaultExperiment {
 }
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "UnitDefinitions", strict(unknown_attribute, unknown_element))]
 pub struct UnitDefinitions {
     #[xml(child = "Unit")]
@@ -317,6 +467,7 @@ pub struct UnitDefinitions {
 }
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "TypeDefinitions", strict(unknown_attribute, unknown_element))]
 pub struct TypeDefinitions {
     #[xml(child = "SimpleType")]
@@ -324,6 +475,7 @@ pub struct TypeDefinitions {
 }
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ModelVariables", strict(unknown_attribute, unknown_element))]
 pub struct ModelVariables {
     #[xml(child = "ScalarVariable")]
@@ -331,6 +483,7 @@ pub struct ModelVariables {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ModelStructure", strict(unknown_attribute, unknown_element))]
 pub struct ModelStructure {
     #[xml(child = "Outputs", default)]
@@ -344,6 +497,7 @@ pub struct ModelStructure {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Outputs")]
 pub struct Outputs {
     #[xml(child = "Unknown")]
@@ -351,6 +505,7 @@ pub struct Outputs {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Derivatives")]
 pub struct Derivatives {
     #[xml(child = "Unknown")]
@@ -358,6 +513,7 @@ pub struct Derivatives {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "InitialUnknowns")]
 pub struct InitialUnknowns {
     #[xml(child = "Unknown")]
@@ -424,4 +580,41 @@ mod tests {
         assert_eq!(derivatives[0].index, 4);
         assert_eq!(derivatives[0].dependencies, vec![1, 2]);
     }
+
+    #[test]
+    fn test_enumerations() {
+        use crate::traits::FmiModelVariables;
+
+        let s = r##"<?xml version="1.0" encoding="UTF8"?>
+<fmiModelDescription fmiVersion="2.0" modelName="Gear" guid="{gear}">
+ <TypeDefinitions>
+    <SimpleType name="Direction">
+        <Enumeration>
+            <Item name="forward" value="1"/>
+            <Item name="reverse" value="-1"/>
+        </Enumeration>
+    </SimpleType>
+ </TypeDefinitions>
+ <ModelVariables>
+    <ScalarVariable name="direction" valueReference="1" causality="input">
+        <Enumeration declaredType="Direction" start="1"/>
+    </ScalarVariable>
+    <ScalarVariable name="n" valueReference="2" causality="input">
+        <Integer start="1"/>
+    </ScalarVariable>
+ </ModelVariables>
+ <ModelStructure/>
+</fmiModelDescription>"##;
+        let md = Fmi2ModelDescription::from_str(s).unwrap();
+
+        let direction = md.variable_by_name("direction").unwrap();
+        let enumeration = md.variable_enumeration(direction).unwrap().unwrap();
+        assert_eq!(enumeration.name, "Direction");
+        assert_eq!(enumeration.item_name(-1), Some("reverse"));
+        assert_eq!(enumeration.parse("forward").unwrap(), 1);
+
+        let n = md.variable_by_name("n").unwrap();
+        assert_eq!(md.variable_enumeration(n).unwrap(), None);
+        assert!(md.enumeration_type("Other").is_none());
+    }
 }
@@ -1,7 +1,10 @@
 use std::{fmt::Display, str::FromStr};
 
+use crate::traits::{self, FmiVariable, StartValue};
+
 /// Enumeration that defines the causality of the variable.
 #[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Causality {
     Parameter,
     CalculatedParameter,
@@ -46,6 +49,7 @@ impl Display for Causality {
 ///
 /// The default is [`Variability::Continuous`].
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Variability {
     /// The value of the variable never changes.
     Constant,
@@ -92,6 +96,7 @@ impl Display for Variability {
 }
 
 #[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Initial {
     #[default]
     Exact,
@@ -124,12 +129,26 @@ impl Display for Initial {
 }
 
 #[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Real")]
 pub struct Real {
     /// If present, name of type defined with TypeDefinitions / SimpleType providing defaults.
     #[xml(attr = "declaredType")]
     pub declared_type: Option<String>,
 
+    #[xml(attr = "quantity")]
+    pub quantity: Option<String>,
+
+    #[xml(attr = "unit")]
+    pub unit: Option<String>,
+
+    #[xml(attr = "displayUnit")]
+    pub display_unit: Option<String>,
+
+    /// If relativeQuantity=true, offset for displayUnit must be ignored.
+    #[xml(attr = "relativeQuantity")]
+    pub relative_quantity: Option<bool>,
+
     /// Value before initialization, if initial=exact or approx.
     /// max >= start >= min required
     #[xml(attr = "start")]
@@ -148,6 +167,7 @@ pub struct Real {
 }
 
 #[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Integer")]
 pub struct Integer {
     /// If present, name of type defined with TypeDefinitions / SimpleType providing defaults.
@@ -161,6 +181,7 @@ pub struct Integer {
 }
 
 #[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Boolean")]
 pub struct Boolean {
     /// If present, name of type defined with TypeDefinitions / SimpleType providing defaults.
@@ -172,7 +193,44 @@ pub struct Boolean {
     pub start: Option<bool>,
 }
 
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "String")]
+pub struct FmiString {
+    /// If present, name of type defined with TypeDefinitions / SimpleType providing defaults.
+    #[xml(attr = "declaredType")]
+    pub declared_type: Option<String>,
+
+    /// Value before initialization, if initial=exact or approx.
+    #[xml(attr = "start")]
+    pub start: Option<String>,
+}
+
+#[derive(Clone, Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Enumeration")]
+pub struct Enumeration {
+    /// Name of the enumeration type defined with TypeDefinitions / SimpleType.
+    #[xml(attr = "declaredType")]
+    pub declared_type: String,
+
+    #[xml(attr = "quantity")]
+    pub quantity: Option<String>,
+
+    #[xml(attr = "min")]
+    pub min: Option<i32>,
+
+    #[xml(attr = "max")]
+    pub max: Option<i32>,
+
+    /// Value before initialization, if initial=exact or approx.
+    /// max >= start >= min required
+    #[xml(attr = "start")]
+    pub start: Option<i32>,
+}
+
 #[derive(Clone, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ScalarVariableElement {
     #[xml(tag = "Real")]
     Real(Real),
@@ -181,9 +239,9 @@ pub enum ScalarVariableElement {
     #[xml(tag = "Boolean")]
     Boolean(Boolean),
     #[xml(tag = "String")]
-    String,
+    String(FmiString),
     #[xml(tag = "Enumeration")]
-    Enumeration,
+    Enumeration(Enumeration),
 }
 
 impl Default for ScalarVariableElement {
@@ -199,13 +257,14 @@ impl ScalarVariableElement {
             ScalarVariableElement::Real(_) => arrow::datatypes::DataType::Float64,
             ScalarVariableElement::Integer(_) => arrow::datatypes::DataType::Int32,
             ScalarVariableElement::Boolean(_) => arrow::datatypes::DataType::Boolean,
-            ScalarVariableElement::String => arrow::datatypes::DataType::Utf8,
-            ScalarVariableElement::Enumeration => arrow::datatypes::DataType::Int32,
+            ScalarVariableElement::String(_) => arrow::datatypes::DataType::Utf8,
+            ScalarVariableElement::Enumeration(_) => arrow::datatypes::DataType::Int32,
         }
     }
 }
 
 #[derive(Default, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ScalarVariable", strict(unknown_attribute, unknown_element))]
 pub struct ScalarVariable {
     /// The full, unique name of the variable.
@@ -253,6 +312,121 @@ impl ScalarVariable {
     }
 }
 
+impl From<&Causality> for traits::Causality {
+    fn from(causality: &Causality) -> Self {
+        match causality {
+            Causality::Parameter => traits::Causality::Parameter,
+            Causality::CalculatedParameter => traits::Causality::CalculatedParameter,
+            Causality::Input => traits::Causality::Input,
+            Causality::Output => traits::Causality::Output,
+            Causality::Local => traits::Causality::Local,
+            Causality::Independent => traits::Causality::Independent,
+        }
+    }
+}
+
+impl From<Variability> for traits::Variability {
+    fn from(variability: Variability) -> Self {
+        match variability {
+            Variability::Constant => traits::Variability::Constant,
+            Variability::Fixed => traits::Variability::Fixed,
+            Variability::Tunable => traits::Variability::Tunable,
+            Variability::Discrete => traits::Variability::Discrete,
+            Variability::Continuous => traits::Variability::Continuous,
+        }
+    }
+}
+
+impl From<&Initial> for traits::Initial {
+    fn from(initial: &Initial) -> Self {
+        match initial {
+            Initial::Exact => traits::Initial::Exact,
+            Initial::Approx => traits::Initial::Approx,
+            Initial::Calculated => traits::Initial::Calculated,
+        }
+    }
+}
+
+impl FmiVariable for ScalarVariable {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn value_reference(&self) -> u32 {
+        self.value_reference
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    fn causality(&self) -> traits::Causality {
+        (&self.causality).into()
+    }
+
+    /// The declared variability, defaulting to continuous for Real and discrete for all other
+    /// variables
+    fn variability(&self) -> traits::Variability {
+        match (self.variability, &self.elem) {
+            (Some(variability), _) => variability.into(),
+            (None, ScalarVariableElement::Real(_)) => traits::Variability::Continuous,
+            (None, _) => traits::Variability::Discrete,
+        }
+    }
+
+    fn declared_variability(&self) -> Option<traits::Variability> {
+        self.variability.map(Into::into)
+    }
+
+    fn initial(&self) -> Option<traits::Initial> {
+        self.initial.as_ref().map(Into::into)
+    }
+
+    fn data_type(&self) -> traits::DataType {
+        match self.elem {
+            ScalarVariableElement::Real(_) => traits::DataType::Float64,
+            ScalarVariableElement::Integer(_) => traits::DataType::Int32,
+            ScalarVariableElement::Boolean(_) => traits::DataType::Boolean,
+            ScalarVariableElement::String(_) => traits::DataType::String,
+            ScalarVariableElement::Enumeration(_) => traits::DataType::Enumeration,
+        }
+    }
+
+    fn declared_type(&self) -> Option<&str> {
+        match &self.elem {
+            ScalarVariableElement::Real(real) => real.declared_type.as_deref(),
+            ScalarVariableElement::Integer(integer) => integer.declared_type.as_deref(),
+            ScalarVariableElement::Boolean(boolean) => boolean.declared_type.as_deref(),
+            ScalarVariableElement::String(string) => string.declared_type.as_deref(),
+            ScalarVariableElement::Enumeration(enumeration) => Some(&enumeration.declared_type),
+        }
+    }
+
+    fn unit(&self) -> Option<&str> {
+        match &self.elem {
+            ScalarVariableElement::Real(real) => real.unit.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn start(&self) -> Vec<StartValue> {
+        let start = match &self.elem {
+            ScalarVariableElement::Real(real) => real.start.map(StartValue::from),
+            ScalarVariableElement::Integer(integer) => integer.start.map(StartValue::from),
+            ScalarVariableElement::Boolean(boolean) => boolean.start.map(StartValue::from),
+            ScalarVariableElement::String(string) => string.start.clone().map(StartValue::String),
+            ScalarVariableElement::Enumeration(enumeration) => {
+                enumeration.start.map(StartValue::from)
+            }
+        };
+        start.into_iter().collect()
+    }
+
+    fn dimensions(&self) -> Vec<traits::Dimension> {
+        Vec::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use hard_xml::XmlRead;
@@ -281,6 +455,10 @@ mod tests {
             sv.elem,
             ScalarVariableElement::Real(Real {
                 declared_type: Some("Modelica.SIunits.Inertia".to_string()),
+                quantity: None,
+                unit: None,
+                display_unit: None,
+                relative_quantity: None,
                 start: Some(1.0),
                 derivative: None,
                 reinit: None
@@ -1,6 +1,7 @@
 use super::attribute_groups::{IntegerAttributes, RealAttributes};
 
 #[derive(Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SimpleTypeElement {
     #[xml(tag = "Real")]
     Real(RealAttributes),
@@ -11,7 +12,7 @@ pub enum SimpleTypeElement {
     #[xml(tag = "String")]
     String,
     #[xml(tag = "Enumeration")]
-    Enumeration,
+    Enumeration(EnumerationType),
 }
 
 impl Default for SimpleTypeElement {
@@ -20,7 +21,36 @@ impl Default for SimpleTypeElement {
     }
 }
 
+/// An item of an enumeration type
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Item", strict(unknown_attribute, unknown_element))]
+pub struct EnumerationItem {
+    #[xml(attr = "name")]
+    pub name: String,
+
+    /// Must be unique within the same enumeration
+    #[xml(attr = "value")]
+    pub value: i32,
+
+    #[xml(attr = "description")]
+    pub description: Option<String>,
+}
+
+/// Type attributes of an enumeration, with the names and values of its items
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Enumeration", strict(unknown_attribute, unknown_element))]
+pub struct EnumerationType {
+    #[xml(attr = "quantity")]
+    pub quantity: Option<String>,
+
+    #[xml(child = "Item")]
+    pub items: Vec<EnumerationItem>,
+}
+
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "SimpleType", strict(unknown_attribute, unknown_element))]
 /// Type attributes of a scalar variable
 pub struct SimpleType {
@@ -48,7 +78,7 @@ pub struct SimpleType {
 mod tests {
     use hard_xml::XmlRead;
 
-    use crate::fmi2::{RealAttributes, SimpleTypeElement};
+    use crate::fmi2::{EnumerationItem, EnumerationType, RealAttributes, SimpleTypeElement};
 
     use super::SimpleType;
 
@@ -71,4 +101,35 @@ mod tests {
             })
         );
     }
+
+    #[test]
+    fn test_enumeration_type() {
+        let xml = r#"
+        <SimpleType name="Options">
+            <Enumeration>
+                <Item name="Option 1" value="1" description="First option"/>
+                <Item name="Option 2" value="2"/>
+            </Enumeration>
+        </SimpleType>"#;
+
+        let simple_type = SimpleType::from_str(xml).unwrap();
+        assert_eq!(
+            simple_type.elem,
+            SimpleTypeElement::Enumeration(EnumerationType {
+                quantity: None,
+                items: vec![
+                    EnumerationItem {
+                        name: "Option 1".to_owned(),
+                        value: 1,
+                        description: Some("First option".to_owned()),
+                    },
+                    EnumerationItem {
+                        name: "Option 2".to_owned(),
+                        value: 2,
+                        description: None,
+                    },
+                ],
+            })
+        );
+    }
 }
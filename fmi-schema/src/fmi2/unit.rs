@@ -1,4 +1,7 @@
+use crate::units;
+
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Unit", strict(unknown_attribute, unknown_element))]
 /// Unit definition (with respect to SI base units) and default display units
 pub struct Fmi2Unit {
@@ -12,6 +15,7 @@ pub struct Fmi2Unit {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "BaseUnit", strict(unknown_attribute, unknown_element))]
 pub struct BaseUnit {
     /// Exponent of SI base unit "kg"
@@ -39,6 +43,7 @@ pub struct BaseUnit {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "DisplayUnit", strict(unknown_attribute, unknown_element))]
 pub struct DisplayUnit {
     #[xml(attr = "name")]
@@ -51,6 +56,46 @@ pub struct DisplayUnit {
     pub inverse: Option<bool>,
 }
 
+impl From<&BaseUnit> for units::BaseUnit {
+    fn from(base_unit: &BaseUnit) -> Self {
+        Self {
+            exponents: units::SiExponents {
+                kg: base_unit.kg.unwrap_or(0),
+                m: base_unit.m.unwrap_or(0),
+                s: base_unit.s.unwrap_or(0),
+                a: base_unit.a.unwrap_or(0),
+                k: base_unit.k.unwrap_or(0),
+                mol: base_unit.mol.unwrap_or(0),
+                cd: base_unit.cd.unwrap_or(0),
+                rad: base_unit.rad.unwrap_or(0),
+            },
+            factor: base_unit.factor.unwrap_or(1.0),
+            offset: base_unit.offset.unwrap_or(0.0),
+        }
+    }
+}
+
+impl From<&DisplayUnit> for units::DisplayUnit {
+    fn from(display_unit: &DisplayUnit) -> Self {
+        Self {
+            name: display_unit.name.clone(),
+            factor: display_unit.factor.unwrap_or(1.0),
+            offset: display_unit.offset.unwrap_or(0.0),
+            inverse: display_unit.inverse.unwrap_or(false),
+        }
+    }
+}
+
+impl From<&Fmi2Unit> for units::Unit {
+    fn from(unit: &Fmi2Unit) -> Self {
+        Self {
+            name: unit.name.clone(),
+            base_unit: unit.base_unit.as_ref().map(Into::into),
+            display_units: unit.display_unit.iter().map(Into::into).collect(),
+        }
+    }
+}
+
 #[test]
 fn test_dependencies_kind() {
     use hard_xml::XmlRead;
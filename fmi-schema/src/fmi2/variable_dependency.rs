@@ -6,6 +6,7 @@ use crate::utils::AttrList;
 /// at Communication Points (CoSimulation): Unknown=f(Known_1, Known_2, ...).
 /// The Knowns are "inputs", "continuous states" and "independent variable" (usually time)".
 #[derive(Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fmi2VariableDependency {
     /// ScalarVariable index of Unknown
     pub index: u32,
@@ -138,6 +139,7 @@ impl ::hard_xml::XmlWrite for Fmi2VariableDependency {
 }
 
 #[derive(Clone, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DependenciesKind {
     #[default]
     Dependent,
@@ -1,5 +1,5 @@
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Annotations", strict(unknown_attribute, unknown_element))]
 pub struct Fmi3Annotations {
     #[xml(child = "Annotation")]
@@ -7,7 +7,7 @@ pub struct Fmi3Annotations {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Annotation", strict(unknown_attribute, unknown_element))]
 pub struct Annotation {
     #[xml(attr = "type")]
@@ -35,6 +35,7 @@ use super::Annotations;
 
 /// Root element for FMI 3.0 build description XML
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(
     tag = "fmiBuildDescription",
     strict(unknown_attribute, unknown_element)
@@ -56,6 +57,7 @@ pub struct Fmi3BuildDescription {
 
 /// Build configuration for a specific platform and model identifier
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "BuildConfiguration", strict(unknown_attribute, unknown_element))]
 pub struct BuildConfiguration {
     /// Model identifier that this build configuration applies to
@@ -85,6 +87,7 @@ pub struct BuildConfiguration {
 
 /// Set of source files with compilation settings
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "SourceFileSet", strict(unknown_attribute, unknown_element))]
 pub struct SourceFileSet {
     /// Optional name for this source file set
@@ -122,6 +125,7 @@ pub struct SourceFileSet {
 
 /// Individual source file
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "SourceFile", strict(unknown_attribute, unknown_element))]
 pub struct SourceFile {
     /// Name/path of the source file
@@ -135,6 +139,7 @@ pub struct SourceFile {
 
 /// Preprocessor definition/macro
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(
     tag = "PreprocessorDefinition",
     strict(unknown_attribute, unknown_element)
@@ -167,6 +172,7 @@ pub struct PreprocessorDefinition {
 
 /// Option for a preprocessor definition
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Option", strict(unknown_attribute, unknown_element))]
 pub struct PreprocessorOption {
     /// Value of the option
@@ -180,6 +186,7 @@ pub struct PreprocessorOption {
 
 /// Include directory for compilation
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "IncludeDirectory", strict(unknown_attribute, unknown_element))]
 pub struct IncludeDirectory {
     /// Name/path of the include directory
@@ -193,6 +200,7 @@ pub struct IncludeDirectory {
 
 /// External library dependency
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Library", strict(unknown_attribute, unknown_element))]
 pub struct Library {
     /// Name of the library
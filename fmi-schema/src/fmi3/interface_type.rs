@@ -3,6 +3,7 @@ use crate::traits::FmiInterfaceType;
 use super::Annotations;
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ModelExchange", strict(unknown_attribute, unknown_element))]
 pub struct Fmi3ModelExchange {
     #[xml(child = "Annotations")]
@@ -37,6 +38,7 @@ pub struct Fmi3ModelExchange {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "CoSimulation", strict(unknown_attribute, unknown_element))]
 pub struct Fmi3CoSimulation {
     #[xml(child = "Annotations")]
@@ -85,6 +87,7 @@ pub struct Fmi3CoSimulation {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ScheduledExecution", strict(unknown_attribute, unknown_element))]
 pub struct Fmi3ScheduledExecution {
     #[xml(child = "Annotations")]
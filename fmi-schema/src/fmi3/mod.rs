@@ -6,6 +6,7 @@ mod annotation;
 mod build_description;
 mod interface_type;
 mod model_description;
+mod terminals_and_icons;
 mod r#type;
 mod unit;
 mod variable;
@@ -15,6 +16,7 @@ pub use annotation::{Annotation, Fmi3Annotations as Annotations};
 pub use build_description::*;
 pub use interface_type::*;
 pub use model_description::*;
+pub use terminals_and_icons::*;
 pub use r#type::*;
 pub use unit::*;
 pub use variable::*;
@@ -51,6 +53,7 @@ impl VariableCounts for ModelVariables {
         let mut num_bool_vars = 0;
         let mut num_integer_vars = 0;
         let mut num_string_vars = 0;
+        let mut num_enum_vars = 0;
 
         for var in &self.variables {
             match var {
@@ -65,7 +68,8 @@ impl VariableCounts for ModelVariables {
                 | Variable::Int64(_)
                 | Variable::UInt64(_) => num_integer_vars += 1,
                 Variable::String(_) => num_string_vars += 1,
-                Variable::Binary(_) => {}
+                Variable::Enumeration(_) => num_enum_vars += 1,
+                Variable::Binary(_) | Variable::Clock(_) => {}
             }
         }
 
@@ -74,7 +78,7 @@ impl VariableCounts for ModelVariables {
             num_bool_vars,
             num_integer_vars,
             num_string_vars,
-            num_enum_vars: 0,
+            num_enum_vars,
             ..Default::default()
         };
 
@@ -1,11 +1,21 @@
-use crate::{Error, fmi3::Fmi3Unknown, traits::FmiModelDescription};
+use crate::{
+    Error,
+    enumerations::{Enumeration, FmiEnumerations},
+    fmi3::Fmi3Unknown,
+    traits::{
+        FmiModelDescription, FmiModelStructure, FmiModelVariables, ModelUnknown, UnknownKind,
+    },
+    units::{FmiUnits, Units},
+};
 
 use super::{
     Annotations, Fmi3CoSimulation, Fmi3ModelExchange, Fmi3ScheduledExecution, Fmi3Unit,
-    ModelVariables, TypeDefinitions, VariableDependency,
+    ModelVariables, TypeDefinition, TypeDefinitions, TypedArrayableVariableTrait, Variable,
+    VariableDependency,
 };
 
 #[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(
     tag = "fmiModelDescription",
     strict(unknown_attribute, unknown_element)
@@ -121,7 +131,125 @@ impl FmiModelDescription for Fmi3ModelDescription {
     }
 }
 
+impl FmiModelVariables for Fmi3ModelDescription {
+    type Variable = Variable;
+
+    fn variables(&self) -> impl Iterator<Item = &Self::Variable> {
+        self.model_variables.variables.iter()
+    }
+}
+
+impl FmiModelStructure for Fmi3ModelDescription {
+    fn unknowns(&self) -> impl Iterator<Item = ModelUnknown> {
+        self.model_structure.unknowns.iter().map(|dep| {
+            let (kind, unknown) = match dep {
+                VariableDependency::Output(unknown) => (UnknownKind::Output, unknown),
+                VariableDependency::ContinuousStateDerivative(unknown) => {
+                    (UnknownKind::ContinuousStateDerivative, unknown)
+                }
+                VariableDependency::ClockedState(unknown) => (UnknownKind::ClockedState, unknown),
+                VariableDependency::InitialUnknown(unknown) => {
+                    (UnknownKind::InitialUnknown, unknown)
+                }
+                VariableDependency::EventIndicator(unknown) => {
+                    (UnknownKind::EventIndicator, unknown)
+                }
+            };
+            ModelUnknown {
+                kind,
+                value_reference: unknown.value_reference,
+                dependencies: unknown.dependencies.as_ref().map(|deps| deps.0.clone()),
+            }
+        })
+    }
+}
+
+impl Fmi3ModelDescription {
+    /// The type definition named by the `declaredType` of a Float32 or Float64 variable
+    fn float_type_definition(&self, variable: &Variable) -> Option<&TypeDefinition> {
+        let declared_type = match variable {
+            Variable::Float32(var) => var.declared_type()?,
+            Variable::Float64(var) => var.declared_type()?,
+            _ => return None,
+        };
+        self.type_definitions
+            .as_ref()?
+            .type_definitions
+            .iter()
+            .find(|ty| match ty {
+                TypeDefinition::Float32(ty) => ty.name == declared_type,
+                TypeDefinition::Float64(ty) => ty.name == declared_type,
+                _ => false,
+            })
+    }
+}
+
+impl FmiEnumerations for Fmi3ModelDescription {
+    fn enumeration_type(&self, name: &str) -> Option<Enumeration> {
+        self.type_definitions
+            .as_ref()?
+            .type_definitions
+            .iter()
+            .find_map(|ty| match ty {
+                TypeDefinition::Enumeration(ty) if ty.name == name => Some(ty.into()),
+                _ => None,
+            })
+    }
+}
+
+impl FmiUnits for Fmi3ModelDescription {
+    fn units(&self) -> Units {
+        self.unit_definitions
+            .iter()
+            .flat_map(|defs| defs.units.iter())
+            .map(Into::into)
+            .collect()
+    }
+
+    fn variable_unit<'a>(&'a self, variable: &'a Self::Variable) -> Option<&'a str> {
+        let unit = match variable {
+            Variable::Float32(var) => var.unit.as_deref(),
+            Variable::Float64(var) => var.unit.as_deref(),
+            _ => return None,
+        };
+        unit.or_else(|| match self.float_type_definition(variable)? {
+            TypeDefinition::Float32(ty) => ty.unit.as_deref(),
+            TypeDefinition::Float64(ty) => ty.unit.as_deref(),
+            _ => None,
+        })
+    }
+
+    fn variable_display_unit<'a>(&'a self, variable: &'a Self::Variable) -> Option<&'a str> {
+        let display_unit = match variable {
+            Variable::Float32(var) => var.display_unit.as_deref(),
+            Variable::Float64(var) => var.display_unit.as_deref(),
+            _ => return None,
+        };
+        display_unit.or_else(|| match self.float_type_definition(variable)? {
+            TypeDefinition::Float32(ty) => ty.display_unit.as_deref(),
+            TypeDefinition::Float64(ty) => ty.display_unit.as_deref(),
+            _ => None,
+        })
+    }
+
+    fn is_relative_quantity(&self, variable: &Self::Variable) -> bool {
+        let relative_quantity = match variable {
+            Variable::Float32(var) => var.relative_quantity,
+            Variable::Float64(var) => var.relative_quantity,
+            _ => return false,
+        };
+        relative_quantity
+            .or_else(|| match self.float_type_definition(variable)? {
+                TypeDefinition::Float32(ty) => ty.relative_quantity,
+                TypeDefinition::Float64(ty) => ty.relative_quantity,
+                _ => None,
+            })
+            .unwrap_or(false)
+    }
+}
+
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "UnitDefinitions", strict(unknown_attribute, unknown_element))]
 pub struct UnitDefinitions {
     #[xml(child = "Unit")]
@@ -129,6 +257,7 @@ pub struct UnitDefinitions {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "LogCategories", strict(unknown_attribute, unknown_element))]
 pub struct LogCategories {
     #[xml(child = "Category")]
@@ -136,6 +265,7 @@ pub struct LogCategories {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Category", strict(unknown_attribute, unknown_element))]
 pub struct Category {
     #[xml(child = "Annotations")]
@@ -147,7 +277,7 @@ pub struct Category {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
-#[cfg_attr(feature = "serde", derive(serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", serde(default))]
 #[xml(tag = "DefaultExperiment", strict(unknown_attribute, unknown_element))]
 pub struct DefaultExperiment {
@@ -180,6 +310,7 @@ pub struct DefaultExperiment {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ModelStructure", strict(unknown_attribute, unknown_element))]
 pub struct ModelStructure {
     #[xml(
@@ -0,0 +1,294 @@
+//! FMI 3.0 Terminals and Icons XML Schema
+//!
+//! This module provides Rust data structures for parsing and generating the optional
+//! `terminalsAndIcons/terminalsAndIcons.xml` file of an FMU.
+//!
+//! The file declares:
+//! - Terminals, named groups of variables that are connected together, such as the pins of a plug
+//!   or the signals of a bus
+//! - The graphical representation of the FMU and its terminals
+//!
+//! # Example
+//!
+//! ```rust
+//! use fmi_schema::fmi3::{Fmi3TerminalsAndIcons, TerminalMemberKind};
+//!
+//! let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <fmiTerminalsAndIcons fmiVersion="3.0">
+//!     <Terminals>
+//!         <Terminal name="flange" matchingRule="plug">
+//!             <TerminalMemberVariable variableName="phi" memberName="phi" variableKind="signal"/>
+//!             <TerminalMemberVariable variableName="tau" memberName="tau" variableKind="inflow"/>
+//!         </Terminal>
+//!     </Terminals>
+//! </fmiTerminalsAndIcons>"#;
+//!
+//! let terminals: Fmi3TerminalsAndIcons = fmi_schema::deserialize(xml).unwrap();
+//! let flange = terminals.terminal("flange").unwrap();
+//! assert_eq!(flange.member_variables[1].variable_kind, TerminalMemberKind::Inflow);
+//! ```
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::utils::AttrList;
+
+use super::Annotations;
+
+/// Root element for FMI 3.0 terminals and icons XML
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(
+    tag = "fmiTerminalsAndIcons",
+    strict(unknown_attribute, unknown_element)
+)]
+pub struct Fmi3TerminalsAndIcons {
+    /// Version of FMI that was used to generate the XML file.
+    #[xml(attr = "fmiVersion")]
+    pub fmi_version: String,
+
+    /// Graphical representation of the FMU
+    #[xml(child = "GraphicalRepresentation")]
+    pub graphical_representation: Option<GraphicalRepresentation>,
+
+    /// The terminals of the FMU
+    #[xml(child = "Terminals")]
+    pub terminals: Option<Terminals>,
+
+    /// Optional annotations
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+}
+
+impl Fmi3TerminalsAndIcons {
+    /// The top-level terminal named `name`
+    pub fn terminal(&self, name: &str) -> Option<&Terminal> {
+        self.terminals
+            .iter()
+            .flat_map(|terminals| terminals.terminals.iter())
+            .find(|terminal| terminal.name == name)
+    }
+}
+
+/// Graphical representation of the FMU as a whole
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(
+    tag = "GraphicalRepresentation",
+    strict(unknown_attribute, unknown_element)
+)]
+pub struct GraphicalRepresentation {
+    /// The coordinate system of the icons
+    #[xml(child = "CoordinateSystem")]
+    pub coordinate_system: Option<CoordinateSystem>,
+
+    /// The extent of the icon of the FMU
+    #[xml(child = "Icon")]
+    pub icon: Option<Icon>,
+
+    /// Optional annotations
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+}
+
+/// Coordinate system of the icons, defined by its lower left and upper right corners
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "CoordinateSystem", strict(unknown_attribute, unknown_element))]
+pub struct CoordinateSystem {
+    #[xml(attr = "x1")]
+    pub x1: f64,
+    #[xml(attr = "y1")]
+    pub y1: f64,
+    #[xml(attr = "x2")]
+    pub x2: f64,
+    #[xml(attr = "y2")]
+    pub y2: f64,
+
+    /// Suggested size of one unit of the coordinate system in millimeters
+    #[xml(attr = "suggestedScalingFactorTo_mm")]
+    pub suggested_scaling_factor_to_mm: f64,
+}
+
+/// Extent of an icon in the coordinate system
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Icon", strict(unknown_attribute, unknown_element))]
+pub struct Icon {
+    #[xml(attr = "x1")]
+    pub x1: f64,
+    #[xml(attr = "y1")]
+    pub y1: f64,
+    #[xml(attr = "x2")]
+    pub x2: f64,
+    #[xml(attr = "y2")]
+    pub y2: f64,
+}
+
+/// List of the top-level terminals
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Terminals", strict(unknown_attribute, unknown_element))]
+pub struct Terminals {
+    #[xml(child = "Terminal")]
+    pub terminals: Vec<Terminal>,
+}
+
+/// A named group of variables that are connected together
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Terminal", strict(unknown_attribute, unknown_element))]
+pub struct Terminal {
+    /// Name of the terminal, unique among its siblings
+    #[xml(attr = "name")]
+    pub name: String,
+
+    /// How the members of connected terminals are matched: `plug` and `bus` match by member name,
+    /// `sequence` by order. Other rules may be defined by tools.
+    #[xml(attr = "matchingRule")]
+    pub matching_rule: String,
+
+    /// Kind of the terminal, for example to check that only compatible terminals are connected
+    #[xml(attr = "terminalKind")]
+    pub terminal_kind: Option<String>,
+
+    /// Optional description of the terminal
+    #[xml(attr = "description")]
+    pub description: Option<String>,
+
+    /// Variables that are members of the terminal
+    #[xml(child = "TerminalMemberVariable")]
+    pub member_variables: Vec<TerminalMemberVariable>,
+
+    /// Pairs of stream variables that are members of the terminal
+    #[xml(child = "TerminalStreamMemberVariable")]
+    pub stream_member_variables: Vec<TerminalStreamMemberVariable>,
+
+    /// Nested terminals
+    #[xml(child = "Terminal")]
+    pub terminals: Vec<Terminal>,
+
+    /// Graphical representation of the terminal
+    #[xml(child = "TerminalGraphicalRepresentation")]
+    pub graphical_representation: Option<TerminalGraphicalRepresentation>,
+
+    /// Optional annotations
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+}
+
+/// How a member variable is connected
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TerminalMemberKind {
+    /// Connected variables are equal
+    #[default]
+    Signal,
+    /// Connected variables sum up to zero, positive if flowing into the FMU
+    Inflow,
+    /// Connected variables sum up to zero, positive if flowing out of the FMU
+    Outflow,
+}
+
+impl FromStr for TerminalMemberKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "signal" => Ok(TerminalMemberKind::Signal),
+            "inflow" => Ok(TerminalMemberKind::Inflow),
+            "outflow" => Ok(TerminalMemberKind::Outflow),
+            _ => Err(format!("Invalid TerminalMemberKind: {}", s)),
+        }
+    }
+}
+
+impl Display for TerminalMemberKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            TerminalMemberKind::Signal => "signal",
+            TerminalMemberKind::Inflow => "inflow",
+            TerminalMemberKind::Outflow => "outflow",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A variable that is a member of a terminal
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(
+    tag = "TerminalMemberVariable",
+    strict(unknown_attribute, unknown_element)
+)]
+pub struct TerminalMemberVariable {
+    /// Name of the variable in the model description
+    #[xml(attr = "variableName")]
+    pub variable_name: String,
+
+    /// Name of the member in the terminal, required by the `plug` and `bus` matching rules
+    #[xml(attr = "memberName")]
+    pub member_name: Option<String>,
+
+    #[xml(attr = "variableKind")]
+    pub variable_kind: TerminalMemberKind,
+
+    /// Optional annotations
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+}
+
+/// A pair of stream variables that is a member of a terminal
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(
+    tag = "TerminalStreamMemberVariable",
+    strict(unknown_attribute, unknown_element)
+)]
+pub struct TerminalStreamMemberVariable {
+    #[xml(attr = "inStreamMemberName")]
+    pub in_stream_member_name: String,
+
+    #[xml(attr = "outStreamMemberName")]
+    pub out_stream_member_name: String,
+
+    /// Name of the variable of the inflowing stream in the model description
+    #[xml(attr = "inStreamVariableName")]
+    pub in_stream_variable_name: String,
+
+    /// Name of the variable of the outflowing stream in the model description
+    #[xml(attr = "outStreamVariableName")]
+    pub out_stream_variable_name: String,
+
+    /// Optional annotations
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+}
+
+/// Graphical representation of a terminal
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(
+    tag = "TerminalGraphicalRepresentation",
+    strict(unknown_attribute, unknown_element)
+)]
+pub struct TerminalGraphicalRepresentation {
+    /// Default color of connections as red, green and blue components
+    #[xml(attr = "defaultConnectionColor")]
+    pub default_connection_color: Option<AttrList<u8>>,
+
+    /// Default stroke size of connections
+    #[xml(attr = "defaultConnectionStrokeSize")]
+    pub default_connection_stroke_size: Option<f64>,
+
+    /// Base name of the icon files of the terminal in the `terminalsAndIcons` directory
+    #[xml(attr = "iconBaseName")]
+    pub icon_base_name: Option<String>,
+
+    /// The extent of the icon of the terminal
+    #[xml(child = "Icon")]
+    pub icon: Option<Icon>,
+
+    /// Optional annotations
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+}
@@ -1,4 +1,6 @@
-use super::annotation::Fmi3Annotations;
+use crate::{enumerations, traits::DataType};
+
+use super::{IntervalVariability, annotation::Fmi3Annotations};
 
 pub trait BaseTypeTrait {
     fn name(&self) -> &str;
@@ -8,6 +10,7 @@ pub trait BaseTypeTrait {
 macro_rules! declare_float_type {
     ($name: ident, $tag: expr, $type: ty) => {
         #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[xml(tag = $tag, strict(unknown_attribute, unknown_element))]
         pub struct $name {
             // TypeDefinitionBase
@@ -52,6 +55,7 @@ macro_rules! declare_float_type {
 macro_rules! declare_int_type {
     ($name: ident, $tag: expr, $type: ty) => {
         #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[xml(tag = $tag, strict(unknown_attribute, unknown_element))]
         pub struct $name {
             // TypeDefinitionBase
@@ -85,6 +89,7 @@ declare_int_type!(Int64Type, "Int64Type", i64);
 declare_int_type!(UInt64Type, "UInt64Type", u64);
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "BooleanType", strict(unknown_attribute, unknown_element))]
 pub struct BooleanType {
     // TypeDefinitionBase
@@ -97,6 +102,7 @@ pub struct BooleanType {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "StringType", strict(unknown_attribute, unknown_element))]
 pub struct StringType {
     // TypeDefinitionBase
@@ -109,6 +115,7 @@ pub struct StringType {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "BinaryType", strict(unknown_attribute, unknown_element))]
 pub struct BinaryType {
     // TypeDefinitionBase
@@ -126,6 +133,7 @@ pub struct BinaryType {
 }
 
 #[derive(PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Item", strict(unknown_attribute, unknown_element))]
 pub struct EnumerationItem {
     #[xml(attr = "name")]
@@ -139,6 +147,7 @@ pub struct EnumerationItem {
 }
 
 #[derive(PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "EnumerationType", strict(unknown_attribute, unknown_element))]
 pub struct EnumerationType {
     // TypeDefinitionBase
@@ -156,7 +165,26 @@ pub struct EnumerationType {
     pub items: Vec<EnumerationItem>,
 }
 
+impl From<&EnumerationType> for enumerations::Enumeration {
+    fn from(enumeration: &EnumerationType) -> Self {
+        Self {
+            name: enumeration.name.clone(),
+            quantity: enumeration.quantity.clone(),
+            items: enumeration
+                .items
+                .iter()
+                .map(|item| enumerations::EnumerationItem {
+                    name: item.name.clone(),
+                    value: item.value,
+                    description: item.description.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ClockType", strict(unknown_attribute, unknown_element))]
 pub struct ClockType {
     // TypeDefinitionBase
@@ -172,7 +200,7 @@ pub struct ClockType {
     #[xml(attr = "priority")]
     pub priority: Option<u32>,
     #[xml(attr = "intervalVariability")]
-    pub interval_variability: Option<String>,
+    pub interval_variability: Option<IntervalVariability>,
     #[xml(attr = "intervalDecimal")]
     pub interval_decimal: Option<f64>,
     #[xml(attr = "shiftDecimal")]
@@ -188,6 +216,7 @@ pub struct ClockType {
 }
 
 #[derive(PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TypeDefinition {
     #[xml(tag = "Float32Type")]
     Float32(Float32Type),
@@ -221,7 +250,52 @@ pub enum TypeDefinition {
     Clock(ClockType),
 }
 
+impl TypeDefinition {
+    /// The name of the type definition, referred to by the `declaredType` of variables
+    pub fn name(&self) -> &str {
+        match self {
+            TypeDefinition::Float32(ty) => &ty.name,
+            TypeDefinition::Float64(ty) => &ty.name,
+            TypeDefinition::Int8(ty) => &ty.name,
+            TypeDefinition::UInt8(ty) => &ty.name,
+            TypeDefinition::Int16(ty) => &ty.name,
+            TypeDefinition::UInt16(ty) => &ty.name,
+            TypeDefinition::Int32(ty) => &ty.name,
+            TypeDefinition::UInt32(ty) => &ty.name,
+            TypeDefinition::Int64(ty) => &ty.name,
+            TypeDefinition::UInt64(ty) => &ty.name,
+            TypeDefinition::Boolean(ty) => &ty.name,
+            TypeDefinition::String(ty) => &ty.name,
+            TypeDefinition::Binary(ty) => &ty.name,
+            TypeDefinition::Enumeration(ty) => &ty.name,
+            TypeDefinition::Clock(ty) => &ty.name,
+        }
+    }
+
+    /// The data type of the variables that can declare this type
+    pub fn data_type(&self) -> DataType {
+        match self {
+            TypeDefinition::Float32(_) => DataType::Float32,
+            TypeDefinition::Float64(_) => DataType::Float64,
+            TypeDefinition::Int8(_) => DataType::Int8,
+            TypeDefinition::UInt8(_) => DataType::UInt8,
+            TypeDefinition::Int16(_) => DataType::Int16,
+            TypeDefinition::UInt16(_) => DataType::UInt16,
+            TypeDefinition::Int32(_) => DataType::Int32,
+            TypeDefinition::UInt32(_) => DataType::UInt32,
+            TypeDefinition::Int64(_) => DataType::Int64,
+            TypeDefinition::UInt64(_) => DataType::UInt64,
+            TypeDefinition::Boolean(_) => DataType::Boolean,
+            TypeDefinition::String(_) => DataType::String,
+            TypeDefinition::Binary(_) => DataType::Binary,
+            TypeDefinition::Enumeration(_) => DataType::Enumeration,
+            TypeDefinition::Clock(_) => DataType::Clock,
+        }
+    }
+}
+
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "TypeDefinitions", strict(unknown_attribute, unknown_element))]
 pub struct TypeDefinitions {
     #[xml(
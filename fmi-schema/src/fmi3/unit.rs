@@ -1,6 +1,9 @@
+use crate::units;
+
 use super::Annotations;
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Unit", strict(unknown_attribute, unknown_element))]
 pub struct Fmi3Unit {
     #[xml(attr = "name")]
@@ -14,6 +17,7 @@ pub struct Fmi3Unit {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "BaseUnit", strict(unknown_attribute, unknown_element))]
 pub struct BaseUnit {
     #[xml(attr = "kg")]
@@ -22,9 +26,9 @@ pub struct BaseUnit {
     pub m: Option<i32>,
     #[xml(attr = "s")]
     pub s: Option<i32>,
-    #[xml(attr = "a")]
+    #[xml(attr = "A")]
     pub a: Option<i32>,
-    #[xml(attr = "k")]
+    #[xml(attr = "K")]
     pub k: Option<i32>,
     #[xml(attr = "mol")]
     pub mol: Option<i32>,
@@ -39,6 +43,7 @@ pub struct BaseUnit {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "DisplayUnit", strict(unknown_attribute, unknown_element))]
 pub struct DisplayUnit {
     #[xml(child = "Annotations")]
@@ -53,6 +58,46 @@ pub struct DisplayUnit {
     pub inverse: Option<bool>,
 }
 
+impl From<&BaseUnit> for units::BaseUnit {
+    fn from(base_unit: &BaseUnit) -> Self {
+        Self {
+            exponents: units::SiExponents {
+                kg: base_unit.kg.unwrap_or(0),
+                m: base_unit.m.unwrap_or(0),
+                s: base_unit.s.unwrap_or(0),
+                a: base_unit.a.unwrap_or(0),
+                k: base_unit.k.unwrap_or(0),
+                mol: base_unit.mol.unwrap_or(0),
+                cd: base_unit.cd.unwrap_or(0),
+                rad: base_unit.rad.unwrap_or(0),
+            },
+            factor: base_unit.factor.unwrap_or(1.0),
+            offset: base_unit.offset.unwrap_or(0.0),
+        }
+    }
+}
+
+impl From<&DisplayUnit> for units::DisplayUnit {
+    fn from(display_unit: &DisplayUnit) -> Self {
+        Self {
+            name: display_unit.name.clone(),
+            factor: display_unit.factor.unwrap_or(1.0),
+            offset: display_unit.offset.unwrap_or(0.0),
+            inverse: display_unit.inverse.unwrap_or(false),
+        }
+    }
+}
+
+impl From<&Fmi3Unit> for units::Unit {
+    fn from(unit: &Fmi3Unit) -> Self {
+        Self {
+            name: unit.name.clone(),
+            base_unit: unit.base_unit.as_ref().map(Into::into),
+            display_units: unit.display_unit.iter().map(Into::into).collect(),
+        }
+    }
+}
+
 #[test]
 fn test_dependencies_kind() {
     use hard_xml::{XmlRead, XmlWrite};
@@ -86,3 +131,30 @@ fn test_display_unit() {
     assert_eq!(display_unit.factor, Some(0.2777777777777778));
     assert_eq!(display_unit.offset, Some(0.0));
 }
+
+#[test]
+fn test_base_unit_conversion() {
+    use hard_xml::XmlRead;
+
+    let xml = r#"<Unit name="degC">
+        <BaseUnit K="1" offset="273.15"/>
+        <DisplayUnit name="degF" factor="1.8" offset="32"/>
+    </Unit>"#;
+    let unit = units::Unit::from(&Fmi3Unit::from_str(xml).unwrap());
+
+    assert_eq!(
+        unit.base_unit,
+        Some(units::BaseUnit {
+            exponents: units::SiExponents {
+                k: 1,
+                ..Default::default()
+            },
+            factor: 1.0,
+            offset: 273.15,
+        })
+    );
+    assert_eq!(
+        unit.display_unit("degF").unwrap().from_unit(100.0, false),
+        212.0
+    );
+}
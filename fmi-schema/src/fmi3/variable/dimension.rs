@@ -2,6 +2,7 @@
 use hard_xml::{XmlRead, XmlWrite};
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Dimension {
     /// Defines a constant unsigned 64-bit integer size for this dimension. The variability of the
     /// dimension size is constant in this case.
@@ -32,6 +33,17 @@ impl Dimension {
     }
 }
 
+impl From<&Dimension> for crate::traits::Dimension {
+    fn from(dimension: &Dimension) -> Self {
+        match dimension {
+            Dimension::Fixed(size) => crate::traits::Dimension::Fixed(*size),
+            Dimension::Variable(value_reference) => {
+                crate::traits::Dimension::Variable(*value_reference)
+            }
+        }
+    }
+}
+
 impl Default for Dimension {
     fn default() -> Self {
         Self::Fixed(1)
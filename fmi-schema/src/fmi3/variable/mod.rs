@@ -1,6 +1,6 @@
 use super::Annotations;
 
-use crate::utils::AttrList;
+use crate::{traits, utils::AttrList};
 
 use std::{
     fmt::{Debug, Display},
@@ -17,6 +17,7 @@ pub use model_variables::{AppendToModelVariables, ModelVariables, Variable};
 
 /// Base type for variable aliases
 #[derive(PartialEq, Debug, Default, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Alias")]
 pub struct VariableAlias {
     #[xml(attr = "name")]
@@ -27,6 +28,7 @@ pub struct VariableAlias {
 
 /// Alias for float variables (Float32 and Float64) with additional displayUnit attribute
 #[derive(PartialEq, Debug, Default, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Alias")]
 pub struct FloatVariableAlias {
     #[xml(attr = "name")]
@@ -39,6 +41,7 @@ pub struct FloatVariableAlias {
 
 /// An enumeration that defines the type of a variable.
 #[derive(Debug, PartialEq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VariableType {
     FmiFloat32,
     FmiFloat64,
@@ -53,6 +56,7 @@ pub enum VariableType {
     FmiBoolean,
     FmiString,
     FmiBinary,
+    FmiClock,
 }
 
 #[cfg(feature = "arrow")]
@@ -72,6 +76,7 @@ impl From<VariableType> for arrow::datatypes::DataType {
             VariableType::FmiBoolean => arrow::datatypes::DataType::Boolean,
             VariableType::FmiString => arrow::datatypes::DataType::Utf8,
             VariableType::FmiBinary => arrow::datatypes::DataType::Binary,
+            VariableType::FmiClock => arrow::datatypes::DataType::Boolean,
         }
     }
 }
@@ -87,9 +92,13 @@ pub trait AbstractVariableTrait {
     /// Enumeration that defines the causality of the variable.
     fn causality(&self) -> Causality;
     fn variability(&self) -> Variability;
+    /// The variability as declared, `None` if the default applies.
+    fn declared_variability(&self) -> Option<Variability>;
     fn can_handle_multiple_set_per_time_instant(&self) -> Option<bool>;
     fn data_type(&self) -> VariableType;
     fn annotations(&self) -> Option<&Annotations>;
+    /// The value references of the clocks the variable belongs to, if any.
+    fn clocks(&self) -> Option<&[u32]>;
 }
 
 pub trait ArrayableVariableTrait: AbstractVariableTrait {
@@ -132,6 +141,9 @@ macro_rules! impl_abstract_variable {
             fn variability(&self) -> Variability {
                 self.variability.unwrap_or($default_variability)
             }
+            fn declared_variability(&self) -> Option<Variability> {
+                self.variability
+            }
             fn can_handle_multiple_set_per_time_instant(&self) -> Option<bool> {
                 self.can_handle_multiple_set_per_time_instant
             }
@@ -141,6 +153,9 @@ macro_rules! impl_abstract_variable {
             fn annotations(&self) -> Option<&Annotations> {
                 self.annotations.as_ref()
             }
+            fn clocks(&self) -> Option<&[u32]> {
+                self.clocks.as_ref().map(|clocks| clocks.0.as_slice())
+            }
         }
     };
 }
@@ -194,6 +209,7 @@ macro_rules! impl_float_type {
     // Implementation for float types using hard_xml derives
     ($name:ident, $tag:expr, $type:ty, $variable_type:expr) => {
         #[derive(PartialEq, Debug, Default, hard_xml::XmlRead, hard_xml::XmlWrite)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[xml(tag = $tag, strict(unknown_attribute, unknown_element))]
         pub struct $name {
             #[xml(attr = "name")]
@@ -212,6 +228,16 @@ macro_rules! impl_float_type {
             pub clocks: Option<AttrList<u32>>,
             #[xml(attr = "declaredType")]
             pub declared_type: Option<String>,
+            #[xml(attr = "quantity")]
+            pub quantity: Option<String>,
+            #[xml(attr = "unit")]
+            pub unit: Option<String>,
+            #[xml(attr = "displayUnit")]
+            pub display_unit: Option<String>,
+            #[xml(attr = "relativeQuantity")]
+            pub relative_quantity: Option<bool>,
+            #[xml(attr = "unbounded")]
+            pub unbounded: Option<bool>,
             #[xml(child = "Dimension")]
             pub dimensions: Vec<Dimension>,
             #[xml(attr = "intermediateUpdate")]
@@ -227,6 +253,8 @@ macro_rules! impl_float_type {
             pub min: Option<$type>,
             #[xml(attr = "max")]
             pub max: Option<$type>,
+            #[xml(attr = "nominal")]
+            pub nominal: Option<$type>,
             #[xml(attr = "derivative")]
             pub derivative: Option<u32>,
             #[xml(attr = "reinit")]
@@ -279,6 +307,7 @@ macro_rules! impl_integer_type {
     // Implementation for integer types using hard_xml derives
     ($name:ident, $tag:expr, $type:ty, $variable_type:expr) => {
         #[derive(PartialEq, Debug, Default, hard_xml::XmlRead, hard_xml::XmlWrite)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[xml(tag = $tag, strict(unknown_attribute, unknown_element))]
         pub struct $name {
             #[xml(attr = "name")]
@@ -352,6 +381,7 @@ macro_rules! impl_integer_type {
 
 /// Enumeration that defines the causality of the variable.
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Causality {
     /// A data value that is constant during the simulation
     Parameter,
@@ -411,6 +441,7 @@ impl Display for Causality {
 ///
 /// See [https://fmi-standard.org/docs/3.0.1/#variability]
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Variability {
     /// The value of the variable never changes.
     Constant,
@@ -468,6 +499,7 @@ impl Display for Variability {
 }
 
 #[derive(Clone, Copy, Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Initial {
     #[default]
     Exact,
@@ -498,6 +530,134 @@ impl Display for Initial {
     }
 }
 
+impl From<Causality> for traits::Causality {
+    fn from(causality: Causality) -> Self {
+        match causality {
+            Causality::Parameter => traits::Causality::Parameter,
+            Causality::CalculatedParameter => traits::Causality::CalculatedParameter,
+            Causality::Input => traits::Causality::Input,
+            Causality::Output => traits::Causality::Output,
+            // `dependent` is reserved in FMI 3.0 and treated like a local variable
+            Causality::Local | Causality::Dependent => traits::Causality::Local,
+            Causality::Independent => traits::Causality::Independent,
+            Causality::StructuralParameter => traits::Causality::StructuralParameter,
+        }
+    }
+}
+
+impl From<Variability> for traits::Variability {
+    fn from(variability: Variability) -> Self {
+        match variability {
+            Variability::Constant => traits::Variability::Constant,
+            Variability::Fixed => traits::Variability::Fixed,
+            Variability::Tunable => traits::Variability::Tunable,
+            Variability::Discrete => traits::Variability::Discrete,
+            Variability::Continuous => traits::Variability::Continuous,
+        }
+    }
+}
+
+impl From<Initial> for traits::Initial {
+    fn from(initial: Initial) -> Self {
+        match initial {
+            Initial::Exact => traits::Initial::Exact,
+            Initial::Approx => traits::Initial::Approx,
+            Initial::Calculated => traits::Initial::Calculated,
+        }
+    }
+}
+
+impl From<VariableType> for traits::DataType {
+    fn from(v: VariableType) -> Self {
+        match v {
+            VariableType::FmiFloat32 => traits::DataType::Float32,
+            VariableType::FmiFloat64 => traits::DataType::Float64,
+            VariableType::FmiInt8 => traits::DataType::Int8,
+            VariableType::FmiUInt8 => traits::DataType::UInt8,
+            VariableType::FmiInt16 => traits::DataType::Int16,
+            VariableType::FmiUInt16 => traits::DataType::UInt16,
+            VariableType::FmiInt32 => traits::DataType::Int32,
+            VariableType::FmiUInt32 => traits::DataType::UInt32,
+            VariableType::FmiInt64 => traits::DataType::Int64,
+            VariableType::FmiUInt64 => traits::DataType::UInt64,
+            VariableType::FmiBoolean => traits::DataType::Boolean,
+            VariableType::FmiString => traits::DataType::String,
+            VariableType::FmiBinary => traits::DataType::Binary,
+            VariableType::FmiClock => traits::DataType::Clock,
+        }
+    }
+}
+
+/// Enumeration that defines how the interval of a Clock may change.
+///
+/// See <https://fmi-standard.org/docs/3.0.1/#intervalVariability>
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IntervalVariability {
+    /// Periodic Clock with an interval that never changes.
+    Constant,
+    /// Periodic Clock with an interval that only changes during initialization.
+    Fixed,
+    /// Periodic Clock with an interval that can be changed in Event Mode.
+    Tunable,
+    /// Aperiodic Clock where the FMU provides a new interval after each tick.
+    Changing,
+    /// Aperiodic Clock where the FMU provides the interval until the next tick only when it is
+    /// known.
+    Countdown,
+    /// Clock without an interval, ticks are triggered by the importer (input Clocks) or the FMU
+    /// (output Clocks).
+    Triggered,
+}
+
+impl IntervalVariability {
+    /// Returns `true` for time-based Clocks with a periodic interval (`constant`, `fixed` or
+    /// `tunable`).
+    pub fn is_periodic(&self) -> bool {
+        matches!(self, Self::Constant | Self::Fixed | Self::Tunable)
+    }
+
+    /// Returns `true` for time-based Clocks with an aperiodic interval (`changing` or
+    /// `countdown`).
+    pub fn is_aperiodic(&self) -> bool {
+        matches!(self, Self::Changing | Self::Countdown)
+    }
+
+    /// Returns `true` for triggered Clocks, which have no interval.
+    pub fn is_triggered(&self) -> bool {
+        matches!(self, Self::Triggered)
+    }
+}
+
+impl FromStr for IntervalVariability {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "constant" => Ok(IntervalVariability::Constant),
+            "fixed" => Ok(IntervalVariability::Fixed),
+            "tunable" => Ok(IntervalVariability::Tunable),
+            "changing" => Ok(IntervalVariability::Changing),
+            "countdown" => Ok(IntervalVariability::Countdown),
+            "triggered" => Ok(IntervalVariability::Triggered),
+            _ => Err(format!("Invalid IntervalVariability: {}", s)),
+        }
+    }
+}
+
+impl Display for IntervalVariability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalVariability::Constant => write!(f, "constant"),
+            IntervalVariability::Fixed => write!(f, "fixed"),
+            IntervalVariability::Tunable => write!(f, "tunable"),
+            IntervalVariability::Changing => write!(f, "changing"),
+            IntervalVariability::Countdown => write!(f, "countdown"),
+            IntervalVariability::Triggered => write!(f, "triggered"),
+        }
+    }
+}
+
 impl_float_type!(FmiFloat32, "Float32", f32, VariableType::FmiFloat32);
 impl_float_type!(FmiFloat64, "Float64", f64, VariableType::FmiFloat64);
 impl_integer_type!(FmiInt8, "Int8", i8, VariableType::FmiInt8);
@@ -508,8 +668,11 @@ impl_integer_type!(FmiInt32, "Int32", i32, VariableType::FmiInt32);
 impl_integer_type!(FmiUInt32, "UInt32", u32, VariableType::FmiUInt32);
 impl_integer_type!(FmiInt64, "Int64", i64, VariableType::FmiInt64);
 impl_integer_type!(FmiUInt64, "UInt64", u64, VariableType::FmiUInt64);
+// The values of enumerations are exchanged with the Int64 functions of the FMI 3.0 API
+impl_integer_type!(FmiEnumeration, "Enumeration", i64, VariableType::FmiInt64);
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Boolean", strict(unknown_attribute, unknown_element))]
 pub struct FmiBoolean {
     #[xml(attr = "name")]
@@ -582,6 +745,7 @@ impl FmiBoolean {
 
 /// A String start value element
 #[derive(PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Start")]
 pub struct StringStart {
     #[xml(attr = "value")]
@@ -589,6 +753,7 @@ pub struct StringStart {
 }
 
 #[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "String", strict(unknown_attribute, unknown_element))]
 pub struct FmiString {
     #[xml(attr = "name")]
@@ -679,6 +844,7 @@ impl InitializableVariableTrait for FmiString {
 }
 
 #[derive(PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Start")]
 pub struct BinaryStart {
     #[xml(attr = "value")]
@@ -686,6 +852,7 @@ pub struct BinaryStart {
 }
 
 #[derive(PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "Binary")]
 pub struct FmiBinary {
     #[xml(attr = "name")]
@@ -801,3 +968,61 @@ impl InitializableVariableTrait for FmiBinary {
         }
     }
 }
+
+#[derive(Default, PartialEq, Debug, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "Clock", strict(unknown_attribute, unknown_element))]
+pub struct FmiClock {
+    #[xml(attr = "name")]
+    pub name: String,
+    #[xml(attr = "valueReference")]
+    pub value_reference: u32,
+    #[xml(attr = "description")]
+    pub description: Option<String>,
+    #[xml(attr = "causality")]
+    pub causality: Option<Causality>,
+    #[xml(attr = "variability")]
+    pub variability: Option<Variability>,
+    #[xml(attr = "canHandleMultipleSetPerTimeInstant")]
+    pub can_handle_multiple_set_per_time_instant: Option<bool>,
+    #[xml(attr = "clocks")]
+    pub clocks: Option<AttrList<u32>>,
+    #[xml(attr = "declaredType")]
+    pub declared_type: Option<String>,
+    #[xml(attr = "intermediateUpdate")]
+    pub intermediate_update: Option<bool>,
+    #[xml(attr = "previous")]
+    pub previous: Option<u32>,
+    // ClockAttributes
+    #[xml(attr = "canBeDeactivated")]
+    pub can_be_deactivated: Option<bool>,
+    #[xml(attr = "priority")]
+    pub priority: Option<u32>,
+    #[xml(attr = "intervalVariability")]
+    pub interval_variability: Option<IntervalVariability>,
+    #[xml(attr = "intervalDecimal")]
+    pub interval_decimal: Option<f64>,
+    #[xml(attr = "shiftDecimal")]
+    pub shift_decimal: Option<f64>,
+    #[xml(attr = "supportsFraction")]
+    pub supports_fraction: Option<bool>,
+    #[xml(attr = "resolution")]
+    pub resolution: Option<u64>,
+    #[xml(attr = "intervalCounter")]
+    pub interval_counter: Option<u64>,
+    #[xml(attr = "shiftCounter")]
+    pub shift_counter: Option<u64>,
+    #[xml(child = "Annotations")]
+    pub annotations: Option<Annotations>,
+    #[xml(child = "Alias")]
+    pub aliases: Vec<VariableAlias>,
+}
+
+impl_abstract_variable!(FmiClock, Variability::Discrete, VariableType::FmiClock);
+
+impl FmiClock {
+    /// The declared type of the Clock, if any.
+    pub fn declared_type(&self) -> Option<&str> {
+        self.declared_type.as_deref()
+    }
+}
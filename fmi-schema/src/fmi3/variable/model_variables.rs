@@ -1,10 +1,13 @@
+use crate::traits::{self, FmiVariable, StartValue};
+
 use super::{
-    AbstractVariableTrait, FmiBinary, FmiBoolean, FmiFloat32, FmiFloat64, FmiInt8, FmiInt16,
-    FmiInt32, FmiInt64, FmiString, FmiUInt8, FmiUInt16, FmiUInt32, FmiUInt64,
-    TypedArrayableVariableTrait,
+    AbstractVariableTrait, ArrayableVariableTrait, FmiBinary, FmiBoolean, FmiClock, FmiEnumeration,
+    FmiFloat32, FmiFloat64, FmiInt8, FmiInt16, FmiInt32, FmiInt64, FmiString, FmiUInt8, FmiUInt16,
+    FmiUInt32, FmiUInt64, InitializableVariableTrait, TypedArrayableVariableTrait,
 };
 
 #[derive(hard_xml::XmlRead, hard_xml::XmlWrite, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Variable {
     #[xml(tag = "Int8")]
     Int8(FmiInt8),
@@ -32,9 +35,192 @@ pub enum Variable {
     String(FmiString),
     #[xml(tag = "Binary")]
     Binary(FmiBinary),
+    #[xml(tag = "Enumeration")]
+    Enumeration(FmiEnumeration),
+    #[xml(tag = "Clock")]
+    Clock(FmiClock),
+}
+
+impl Variable {
+    /// The variable as an [`AbstractVariableTrait`] object
+    pub fn as_abstract(&self) -> &dyn AbstractVariableTrait {
+        match self {
+            Variable::Int8(var) => var,
+            Variable::UInt8(var) => var,
+            Variable::Int16(var) => var,
+            Variable::UInt16(var) => var,
+            Variable::Int32(var) => var,
+            Variable::UInt32(var) => var,
+            Variable::Int64(var) => var,
+            Variable::UInt64(var) => var,
+            Variable::Float32(var) => var,
+            Variable::Float64(var) => var,
+            Variable::Boolean(var) => var,
+            Variable::String(var) => var,
+            Variable::Binary(var) => var,
+            Variable::Enumeration(var) => var,
+            Variable::Clock(var) => var,
+        }
+    }
+
+    /// The variable as an [`ArrayableVariableTrait`] object, `None` for Clock variables
+    pub fn as_arrayable(&self) -> Option<&dyn ArrayableVariableTrait> {
+        match self {
+            Variable::Int8(var) => Some(var),
+            Variable::UInt8(var) => Some(var),
+            Variable::Int16(var) => Some(var),
+            Variable::UInt16(var) => Some(var),
+            Variable::Int32(var) => Some(var),
+            Variable::UInt32(var) => Some(var),
+            Variable::Int64(var) => Some(var),
+            Variable::UInt64(var) => Some(var),
+            Variable::Float32(var) => Some(var),
+            Variable::Float64(var) => Some(var),
+            Variable::Boolean(var) => Some(var),
+            Variable::String(var) => Some(var),
+            Variable::Binary(var) => Some(var),
+            Variable::Enumeration(var) => Some(var),
+            Variable::Clock(_) => None,
+        }
+    }
+}
+
+/// Collect the start values of an initializable variable
+fn start_values<V>(var: &V) -> Vec<StartValue>
+where
+    V: InitializableVariableTrait,
+    V::StartType: Copy + Into<StartValue>,
+{
+    var.start()
+        .unwrap_or_default()
+        .iter()
+        .map(|&value| value.into())
+        .collect()
+}
+
+impl FmiVariable for Variable {
+    fn name(&self) -> &str {
+        self.as_abstract().name()
+    }
+
+    fn value_reference(&self) -> u32 {
+        self.as_abstract().value_reference()
+    }
+
+    fn description(&self) -> Option<&str> {
+        self.as_abstract().description()
+    }
+
+    fn causality(&self) -> traits::Causality {
+        self.as_abstract().causality().into()
+    }
+
+    fn variability(&self) -> traits::Variability {
+        self.as_abstract().variability().into()
+    }
+
+    fn declared_variability(&self) -> Option<traits::Variability> {
+        self.as_abstract().declared_variability().map(Into::into)
+    }
+
+    fn initial(&self) -> Option<traits::Initial> {
+        let initial = match self {
+            Variable::Int8(var) => var.initial(),
+            Variable::UInt8(var) => var.initial(),
+            Variable::Int16(var) => var.initial(),
+            Variable::UInt16(var) => var.initial(),
+            Variable::Int32(var) => var.initial(),
+            Variable::UInt32(var) => var.initial(),
+            Variable::Int64(var) => var.initial(),
+            Variable::UInt64(var) => var.initial(),
+            Variable::Float32(var) => var.initial(),
+            Variable::Float64(var) => var.initial(),
+            Variable::Boolean(var) => var.initial(),
+            Variable::String(var) => var.initial(),
+            Variable::Binary(var) => var.initial(),
+            Variable::Enumeration(var) => var.initial(),
+            Variable::Clock(_) => None,
+        };
+        initial.map(Into::into)
+    }
+
+    fn data_type(&self) -> traits::DataType {
+        match self {
+            Variable::Enumeration(_) => traits::DataType::Enumeration,
+            _ => self.as_abstract().data_type().into(),
+        }
+    }
+
+    #[cfg(feature = "arrow")]
+    fn arrow_data_type(&self) -> arrow::datatypes::DataType {
+        self.as_abstract().data_type().into()
+    }
+
+    fn declared_type(&self) -> Option<&str> {
+        match self {
+            Variable::Int8(var) => var.declared_type(),
+            Variable::UInt8(var) => var.declared_type(),
+            Variable::Int16(var) => var.declared_type(),
+            Variable::UInt16(var) => var.declared_type(),
+            Variable::Int32(var) => var.declared_type(),
+            Variable::UInt32(var) => var.declared_type(),
+            Variable::Int64(var) => var.declared_type(),
+            Variable::UInt64(var) => var.declared_type(),
+            Variable::Float32(var) => var.declared_type(),
+            Variable::Float64(var) => var.declared_type(),
+            Variable::Boolean(var) => var.declared_type(),
+            Variable::String(var) => var.declared_type(),
+            Variable::Binary(var) => var.declared_type(),
+            Variable::Enumeration(var) => var.declared_type(),
+            Variable::Clock(var) => var.declared_type(),
+        }
+    }
+
+    fn unit(&self) -> Option<&str> {
+        match self {
+            Variable::Float32(var) => var.unit.as_deref(),
+            Variable::Float64(var) => var.unit.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn start(&self) -> Vec<StartValue> {
+        match self {
+            Variable::Int8(var) => start_values(var),
+            Variable::UInt8(var) => start_values(var),
+            Variable::Int16(var) => start_values(var),
+            Variable::UInt16(var) => start_values(var),
+            Variable::Int32(var) => start_values(var),
+            Variable::UInt32(var) => start_values(var),
+            Variable::Int64(var) => start_values(var),
+            Variable::UInt64(var) => start_values(var),
+            Variable::Float32(var) => start_values(var),
+            Variable::Float64(var) => start_values(var),
+            Variable::Boolean(var) => start_values(var),
+            Variable::String(var) => var
+                .start
+                .iter()
+                .map(|start| StartValue::String(start.value.clone()))
+                .collect(),
+            Variable::Binary(var) => var
+                .start
+                .iter()
+                .map(|start| StartValue::Binary(start.value.clone()))
+                .collect(),
+            Variable::Enumeration(var) => start_values(var),
+            Variable::Clock(_) => Vec::new(),
+        }
+    }
+
+    fn dimensions(&self) -> Vec<traits::Dimension> {
+        self.as_arrayable()
+            .map(|var| var.dimensions().iter().map(Into::into).collect())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, PartialEq, Default, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[xml(tag = "ModelVariables")]
 pub struct ModelVariables {
     #[xml(
@@ -50,7 +236,9 @@ pub struct ModelVariables {
         child = "Float64",
         child = "Boolean",
         child = "String",
-        child = "Binary"
+        child = "Binary",
+        child = "Enumeration",
+        child = "Clock"
     )]
     pub variables: Vec<Variable>,
 }
@@ -67,21 +255,7 @@ impl ModelVariables {
 
     /// Returns an iterator over all the AbstractVariables in the model description
     pub fn iter_abstract(&self) -> impl Iterator<Item = &dyn AbstractVariableTrait> {
-        self.variables.iter().map(|v| match v {
-            Variable::Int8(var) => var as &dyn AbstractVariableTrait,
-            Variable::UInt8(var) => var as &dyn AbstractVariableTrait,
-            Variable::Int16(var) => var as &dyn AbstractVariableTrait,
-            Variable::UInt16(var) => var as &dyn AbstractVariableTrait,
-            Variable::Int32(var) => var as &dyn AbstractVariableTrait,
-            Variable::UInt32(var) => var as &dyn AbstractVariableTrait,
-            Variable::Int64(var) => var as &dyn AbstractVariableTrait,
-            Variable::UInt64(var) => var as &dyn AbstractVariableTrait,
-            Variable::Float32(var) => var as &dyn AbstractVariableTrait,
-            Variable::Float64(var) => var as &dyn AbstractVariableTrait,
-            Variable::Boolean(var) => var as &dyn AbstractVariableTrait,
-            Variable::String(var) => var as &dyn AbstractVariableTrait,
-            Variable::Binary(var) => var as &dyn AbstractVariableTrait,
-        })
+        self.variables.iter().map(Variable::as_abstract)
     }
 
     /// Returns an iterator over all the float32 and float64 variables in the model description
@@ -240,6 +414,28 @@ impl ModelVariables {
             })
             .collect()
     }
+
+    /// Returns a vector of all Enumeration variables
+    pub fn enumeration(&self) -> Vec<&FmiEnumeration> {
+        self.variables
+            .iter()
+            .filter_map(|v| match v {
+                Variable::Enumeration(var) => Some(var),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Returns a vector of all Clock variables
+    pub fn clock(&self) -> Vec<&FmiClock> {
+        self.variables
+            .iter()
+            .filter_map(|v| match v {
+                Variable::Clock(var) => Some(var),
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 /// Append a variable to the given `ModelVariables` struct
@@ -324,3 +520,15 @@ impl AppendToModelVariables for FmiBinary {
         variables.variables.push(Variable::Binary(self));
     }
 }
+
+impl AppendToModelVariables for FmiEnumeration {
+    fn append_to_variables(self, variables: &mut ModelVariables) {
+        variables.variables.push(Variable::Enumeration(self));
+    }
+}
+
+impl AppendToModelVariables for FmiClock {
+    fn append_to_variables(self, variables: &mut ModelVariables) {
+        variables.variables.push(Variable::Clock(self));
+    }
+}
@@ -120,6 +120,20 @@ fn test_float32() {
     assert_eq!(var.reinit(), None);
 }
 
+#[test]
+fn test_parameter_variability() {
+    // The default variability does not depend on the causality
+    let xml = r#"<Float32 name="k" valueReference="11" causality="parameter" start="1"/>"#;
+    let var = FmiFloat32::from_str(xml).unwrap();
+    assert_eq!(var.variability(), Variability::Continuous);
+    assert_eq!(var.declared_variability(), None);
+
+    let xml = r#"<Float32 name="c" valueReference="12" causality="calculatedParameter" variability="fixed"/>"#;
+    let var = FmiFloat32::from_str(xml).unwrap();
+    assert_eq!(var.variability(), Variability::Fixed);
+    assert_eq!(var.declared_variability(), Some(Variability::Fixed));
+}
+
 #[test]
 fn test_int8() {
     let xml = r#"<Int8 name="int8_var" valueReference="20" causality="parameter" variability="fixed" start="-128"/>"#;
@@ -213,6 +227,31 @@ fn test_boolean() {
     assert_eq!(var.variability(), Variability::Discrete); // Default for boolean
 }
 
+#[test]
+fn test_clock() {
+    let xml = r#"<Clock name="inClock1" valueReference="1001" causality="input" intervalVariability="constant" intervalDecimal="0.1" supportsFraction="true" resolution="10" intervalCounter="1"/>"#;
+    let var: FmiClock = FmiClock::from_str(xml).unwrap();
+
+    assert_eq!(var.name(), "inClock1");
+    assert_eq!(var.value_reference(), 1001);
+    assert_eq!(var.causality(), Causality::Input);
+    assert_eq!(var.variability(), Variability::Discrete);
+    assert_eq!(var.data_type(), VariableType::FmiClock);
+    assert_eq!(
+        var.interval_variability,
+        Some(IntervalVariability::Constant)
+    );
+    assert!(var.interval_variability.unwrap().is_periodic());
+    assert_eq!(var.interval_decimal, Some(0.1));
+    assert_eq!(var.supports_fraction, Some(true));
+    assert_eq!(var.resolution, Some(10));
+    assert_eq!(var.interval_counter, Some(1));
+
+    let xml = r#"<Clock name="outClock" valueReference="1003" causality="output" intervalVariability="triggered"/>"#;
+    let var: FmiClock = FmiClock::from_str(xml).unwrap();
+    assert!(var.interval_variability.unwrap().is_triggered());
+}
+
 #[test]
 fn test_variable_with_all_attributes() {
     let xml = r#"<Float64
@@ -646,6 +685,21 @@ fn test_model_variables() {
                 annotations: None,
                 aliases: vec![],
             }),
+            Variable::Enumeration(FmiEnumeration {
+                name: "Enumeration_input".to_string(),
+                value_reference: 32,
+                causality: Some(Causality::Input),
+                declared_type: Some("Option".to_string()),
+                start: Some(AttrList(vec![1])),
+                ..Default::default()
+            }),
+            Variable::Enumeration(FmiEnumeration {
+                name: "Enumeration_output".to_string(),
+                value_reference: 33,
+                causality: Some(Causality::Output),
+                declared_type: Some("Option".to_string()),
+                ..Default::default()
+            }),
         ]
     );
 }
@@ -5,6 +5,7 @@ use crate::utils::AttrList;
 use super::Annotations;
 
 #[derive(Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DependenciesKind {
     #[default]
     Dependent,
@@ -43,6 +44,7 @@ impl Display for DependenciesKind {
 }
 
 #[derive(Default, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fmi3Unknown {
     pub annotations: Option<Annotations>,
     pub value_reference: u32,
@@ -224,6 +226,7 @@ impl Fmi3Unknown {
 }
 
 #[derive(PartialEq, Debug, hard_xml::XmlRead)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VariableDependency {
     #[xml(tag = "Output")]
     Output(Fmi3Unknown),
@@ -0,0 +1,50 @@
+//! Manifests of FMI layered standards
+//!
+//! A layered standard, such as LS-BUS or LS-XCP, places its files in a directory
+//! `extra/<reverse-domain-name>/` of the FMU and identifies itself with a manifest
+//! `fmi-ls-manifest.xml` in that directory. The manifest may have further attributes and elements
+//! defined by the layered standard, which are ignored.
+//!
+//! The attributes of the manifest are matched by their qualified names with the `fmi-ls` prefix,
+//! which the standard uses for the namespace `http://fmi-standard.org/fmi-ls-manifest`. A manifest
+//! that binds this namespace to another prefix is not recognized.
+//!
+//! # Example
+//!
+//! ```rust
+//! use fmi_schema::layered_standard::LayeredStandardManifest;
+//!
+//! let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <fmiLayeredStandardManifest
+//!     xmlns:fmi-ls="http://fmi-standard.org/fmi-ls-manifest"
+//!     fmi-ls:fmi-ls-name="org.fmi-standard.fmi-ls-bus"
+//!     fmi-ls:fmi-ls-version="1.0.0"
+//!     fmi-ls:fmi-ls-description="Layered standard for network communication"
+//!     isBusSimulationFMU="false"/>"#;
+//!
+//! let manifest: LayeredStandardManifest = fmi_schema::deserialize(xml).unwrap();
+//! assert_eq!(manifest.name, "org.fmi-standard.fmi-ls-bus");
+//! assert_eq!(manifest.version, "1.0.0");
+//! ```
+
+/// File name of the manifest in the directory of a layered standard
+pub const MANIFEST: &str = "fmi-ls-manifest.xml";
+
+/// The manifest `fmi-ls-manifest.xml` of a layered standard
+#[derive(Default, Debug, PartialEq, hard_xml::XmlRead, hard_xml::XmlWrite)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[xml(tag = "fmiLayeredStandardManifest")]
+pub struct LayeredStandardManifest {
+    /// Name of the layered standard in reverse domain notation, such as
+    /// `org.fmi-standard.fmi-ls-bus`
+    #[xml(attr = "fmi-ls:fmi-ls-name")]
+    pub name: String,
+
+    /// Version of the layered standard
+    #[xml(attr = "fmi-ls:fmi-ls-version")]
+    pub version: String,
+
+    /// Optional description of the layered standard
+    #[xml(attr = "fmi-ls:fmi-ls-description")]
+    pub description: Option<String>,
+}
@@ -9,13 +9,22 @@ use std::fmt::Display;
 use thiserror::Error;
 
 pub mod date_time;
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub mod diff;
+pub mod enumerations;
+#[cfg(feature = "fmi1")]
+pub mod fmi1;
 #[cfg(feature = "fmi2")]
 pub mod fmi2;
 #[cfg(feature = "fmi3")]
 pub mod fmi3;
+pub mod layered_standard;
 pub mod minimal;
 pub mod traits;
+pub mod units;
 pub mod utils;
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub mod validation;
 pub mod variable_counts;
 
 /// The major version of the FMI standard
@@ -49,6 +58,18 @@ pub enum Error {
 
     #[error("Error in model: {0}")]
     Model(String),
+
+    #[error("Unit {0} not found")]
+    UnitNotFound(String),
+
+    #[error("Display unit {0} not found for unit {1}")]
+    DisplayUnitNotFound(String, String),
+
+    #[error("Units {0} and {1} are not compatible")]
+    IncompatibleUnits(String, String),
+
+    #[error("{0} is not an item of enumeration {1}")]
+    EnumerationItemNotFound(String, String),
 }
 
 /// Serialize a value to XML string. If `fragment` is true, the XML declaration is omitted.
@@ -1,5 +1,7 @@
 //! Common traits for FMI schema
 
+use std::fmt::Display;
+
 use crate::MajorVersion;
 
 pub trait DefaultExperiment {
@@ -60,3 +62,261 @@ pub trait FmiInterfaceType: Sized {
     /// (only FMI 3.0)
     fn provides_per_element_dependencies(&self) -> Option<bool>;
 }
+
+/// Causality of a variable, independent of the FMI version
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Causality {
+    Parameter,
+    CalculatedParameter,
+    Input,
+    Output,
+    Local,
+    Independent,
+    /// Only FMI 3.0
+    StructuralParameter,
+}
+
+/// Variability of a variable, independent of the FMI version
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variability {
+    Constant,
+    Fixed,
+    Tunable,
+    Discrete,
+    Continuous,
+}
+
+/// How a variable is initialized, independent of the FMI version
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Initial {
+    Exact,
+    Approx,
+    Calculated,
+}
+
+/// The data type of a variable, independent of the FMI version.
+///
+/// The FMI 2.0 types `Real` and `Integer` are reported as [`DataType::Float64`] and
+/// [`DataType::Int32`]. The values of enumerations are `Int32` in FMI 2.0 and `Int64` in FMI 3.0,
+/// see [`FmiVariable::arrow_data_type`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataType {
+    Float32,
+    Float64,
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Int64,
+    UInt64,
+    Boolean,
+    String,
+    Binary,
+    Enumeration,
+    Clock,
+}
+
+#[cfg(feature = "arrow")]
+impl From<DataType> for arrow::datatypes::DataType {
+    fn from(data_type: DataType) -> Self {
+        match data_type {
+            DataType::Float32 => arrow::datatypes::DataType::Float32,
+            DataType::Float64 => arrow::datatypes::DataType::Float64,
+            DataType::Int8 => arrow::datatypes::DataType::Int8,
+            DataType::UInt8 => arrow::datatypes::DataType::UInt8,
+            DataType::Int16 => arrow::datatypes::DataType::Int16,
+            DataType::UInt16 => arrow::datatypes::DataType::UInt16,
+            DataType::Int32 => arrow::datatypes::DataType::Int32,
+            DataType::UInt32 => arrow::datatypes::DataType::UInt32,
+            DataType::Int64 => arrow::datatypes::DataType::Int64,
+            DataType::UInt64 => arrow::datatypes::DataType::UInt64,
+            DataType::Boolean => arrow::datatypes::DataType::Boolean,
+            DataType::String => arrow::datatypes::DataType::Utf8,
+            DataType::Binary => arrow::datatypes::DataType::Binary,
+            DataType::Enumeration => arrow::datatypes::DataType::Int32,
+            DataType::Clock => arrow::datatypes::DataType::Boolean,
+        }
+    }
+}
+
+/// A start value of a variable, independent of the FMI version
+#[derive(Clone, Debug, PartialEq)]
+pub enum StartValue {
+    /// Start value of a float variable
+    Float(f64),
+    /// Start value of a signed integer or enumeration variable
+    Int(i64),
+    /// Start value of an unsigned integer variable
+    UInt(u64),
+    Boolean(bool),
+    String(String),
+    /// Start value of a binary variable, hex-encoded as in the model description
+    Binary(String),
+}
+
+impl Display for StartValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartValue::Float(value) => write!(f, "{value}"),
+            StartValue::Int(value) => write!(f, "{value}"),
+            StartValue::UInt(value) => write!(f, "{value}"),
+            StartValue::Boolean(value) => write!(f, "{value}"),
+            StartValue::String(value) | StartValue::Binary(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+macro_rules! impl_start_value_from {
+    ($variant:ident, $target:ty, $($type:ty),+) => {
+        $(
+            impl From<$type> for StartValue {
+                fn from(value: $type) -> Self {
+                    StartValue::$variant(<$target>::from(value))
+                }
+            }
+        )+
+    };
+}
+
+impl_start_value_from!(Float, f64, f32, f64);
+impl_start_value_from!(Int, i64, i8, i16, i32, i64);
+impl_start_value_from!(UInt, u64, u8, u16, u32, u64);
+impl_start_value_from!(Boolean, bool, bool);
+
+/// The size of one dimension of an array variable, independent of the FMI version.
+///
+/// Only FMI 3.0 has array variables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Dimension {
+    /// A constant size
+    Fixed(u64),
+    /// The size is the value of the variable with this value reference
+    Variable(u32),
+}
+
+/// A variable of a model description, independent of the FMI version
+pub trait FmiVariable {
+    /// The full, unique name of the variable
+    fn name(&self) -> &str;
+
+    /// The handle of the variable in the model interface
+    fn value_reference(&self) -> u32;
+
+    /// An optional description of the meaning of the variable
+    fn description(&self) -> Option<&str>;
+
+    /// The causality of the variable, or its default if not declared
+    fn causality(&self) -> Causality;
+
+    /// The variability of the variable, or its default if not declared
+    fn variability(&self) -> Variability;
+
+    /// The variability of the variable, if declared
+    fn declared_variability(&self) -> Option<Variability>;
+
+    /// How the variable is initialized, if declared
+    fn initial(&self) -> Option<Initial>;
+
+    /// The data type of the variable
+    fn data_type(&self) -> DataType;
+
+    /// The Arrow data type of the values of the variable
+    #[cfg(feature = "arrow")]
+    fn arrow_data_type(&self) -> arrow::datatypes::DataType {
+        self.data_type().into()
+    }
+
+    /// The name of the type definition providing defaults for the variable, if any
+    fn declared_type(&self) -> Option<&str>;
+
+    /// The unit declared on the variable itself, if any.
+    ///
+    /// The unit may also be inherited from the [`FmiVariable::declared_type`].
+    fn unit(&self) -> Option<&str>;
+
+    /// The start values of the variable, in row-major order for array variables. Empty if no
+    /// start value is declared.
+    fn start(&self) -> Vec<StartValue>;
+
+    /// The dimensions of the variable, empty for scalar variables
+    fn dimensions(&self) -> Vec<Dimension>;
+}
+
+/// Access to the variables of a model description, independent of the FMI version
+pub trait FmiModelVariables {
+    /// The type of the variables
+    type Variable: FmiVariable;
+
+    /// Iterate over all variables, in the order of the model description
+    fn variables(&self) -> impl Iterator<Item = &Self::Variable>;
+
+    /// Find a variable by its name
+    fn variable_by_name(&self, name: &str) -> Option<&Self::Variable> {
+        self.variables().find(|v| v.name() == name)
+    }
+}
+
+/// The kind of an unknown in the model structure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnknownKind {
+    Output,
+    ContinuousStateDerivative,
+    /// Only FMI 3.0
+    ClockedState,
+    InitialUnknown,
+    /// Only FMI 3.0
+    EventIndicator,
+}
+
+/// An unknown of the model structure, independent of the FMI version
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModelUnknown {
+    /// Where the unknown is listed in the model structure
+    pub kind: UnknownKind,
+    /// The value reference of the unknown
+    pub value_reference: u32,
+    /// Value references of the knowns the unknown depends on. If `None`, the unknown depends on
+    /// all knowns.
+    ///
+    /// FMI 2.0 model descriptions do not distinguish an empty list of dependencies from a missing
+    /// one, both are reported as `None`.
+    pub dependencies: Option<Vec<u32>>,
+}
+
+/// Access to the model structure of a model description, independent of the FMI version
+pub trait FmiModelStructure {
+    /// Iterate over all unknowns of the model structure, grouped by their kind
+    fn unknowns(&self) -> impl Iterator<Item = ModelUnknown>;
+
+    /// Iterate over the outputs
+    fn outputs(&self) -> impl Iterator<Item = ModelUnknown> {
+        self.unknowns()
+            .filter(|unknown| unknown.kind == UnknownKind::Output)
+    }
+
+    /// Iterate over the derivatives of the continuous states
+    fn continuous_state_derivatives(&self) -> impl Iterator<Item = ModelUnknown> {
+        self.unknowns()
+            .filter(|unknown| unknown.kind == UnknownKind::ContinuousStateDerivative)
+    }
+
+    /// Iterate over the clocked states
+    fn clocked_states(&self) -> impl Iterator<Item = ModelUnknown> {
+        self.unknowns()
+            .filter(|unknown| unknown.kind == UnknownKind::ClockedState)
+    }
+
+    /// Iterate over the initial unknowns
+    fn initial_unknowns(&self) -> impl Iterator<Item = ModelUnknown> {
+        self.unknowns()
+            .filter(|unknown| unknown.kind == UnknownKind::InitialUnknown)
+    }
+
+    /// Iterate over the event indicators
+    fn event_indicators(&self) -> impl Iterator<Item = ModelUnknown> {
+        self.unknowns()
+            .filter(|unknown| unknown.kind == UnknownKind::EventIndicator)
+    }
+}
@@ -0,0 +1,418 @@
+//! Physical units and their conversion, independent of the FMI version.
+//!
+//! A [`Unit`] is defined with respect to the SI base units by its [`BaseUnit`]:
+//!
+//! `value_BaseUnit = factor * value_Unit + offset`
+//!
+//! and may have any number of [`DisplayUnit`]s, defined with respect to the unit:
+//!
+//! `value_DisplayUnit = factor * value_Unit + offset`, or
+//! `value_DisplayUnit = 1 / (factor * value_Unit + offset)` if the display unit is `inverse`.
+//!
+//! For variables that are relative quantities (such as temperature differences), all offsets are
+//! ignored.
+//!
+//! The unit of a variable is resolved through its declared type by [`FmiUnits`], which is
+//! implemented for the FMI 2.0 and 3.0 model descriptions:
+//!
+//! ```rust
+//! # #[cfg(feature = "fmi3")] {
+//! use fmi_schema::{fmi3::Fmi3ModelDescription, traits::FmiModelVariables, units::FmiUnits};
+//!
+//! let xml = r#"<fmiModelDescription fmiVersion="3.0" modelName="Car" instantiationToken="">
+//!     <UnitDefinitions>
+//!         <Unit name="m/s">
+//!             <BaseUnit m="1" s="-1"/>
+//!             <DisplayUnit name="km/h" factor="3.6"/>
+//!         </Unit>
+//!     </UnitDefinitions>
+//!     <TypeDefinitions>
+//!         <Float64Type name="Speed" unit="m/s" displayUnit="km/h"/>
+//!     </TypeDefinitions>
+//!     <ModelVariables>
+//!         <Float64 name="v" valueReference="1" declaredType="Speed"/>
+//!     </ModelVariables>
+//!     <ModelStructure/>
+//! </fmiModelDescription>"#;
+//! let md: Fmi3ModelDescription = fmi_schema::deserialize(xml).unwrap();
+//!
+//! let v = md.variable_by_name("v").unwrap();
+//! assert_eq!(md.variable_unit(v), Some("m/s"));
+//! let conversion = md.display_unit_conversion(v).unwrap().unwrap();
+//! assert_eq!(conversion.to_display(10.0), 36.0);
+//! assert_eq!(conversion.from_display(36.0), 10.0);
+//! # }
+//! ```
+
+use crate::{Error, traits::FmiModelVariables};
+
+/// Exponents of the SI base units of a unit
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct SiExponents {
+    pub kg: i32,
+    pub m: i32,
+    pub s: i32,
+    pub a: i32,
+    pub k: i32,
+    pub mol: i32,
+    pub cd: i32,
+    pub rad: i32,
+}
+
+impl SiExponents {
+    /// Whether all exponents are zero
+    pub fn is_dimensionless(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Definition of a unit with respect to the SI base units
+#[derive(Clone, Debug, PartialEq)]
+pub struct BaseUnit {
+    pub exponents: SiExponents,
+    pub factor: f64,
+    pub offset: f64,
+}
+
+impl Default for BaseUnit {
+    fn default() -> Self {
+        Self {
+            exponents: SiExponents::default(),
+            factor: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+/// A display unit, defined with respect to the unit it belongs to
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayUnit {
+    pub name: String,
+    pub factor: f64,
+    pub offset: f64,
+    /// Only FMI 3.0
+    pub inverse: bool,
+}
+
+impl DisplayUnit {
+    /// The identity display unit of the unit `name`
+    pub fn identity(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            factor: 1.0,
+            offset: 0.0,
+            inverse: false,
+        }
+    }
+
+    /// Convert `value` from the unit to this display unit
+    pub fn from_unit(&self, value: f64, relative_quantity: bool) -> f64 {
+        let offset = if relative_quantity { 0.0 } else { self.offset };
+        let value = self.factor * value + offset;
+        if self.inverse { 1.0 / value } else { value }
+    }
+
+    /// Convert `value` from this display unit to the unit
+    pub fn to_unit(&self, value: f64, relative_quantity: bool) -> f64 {
+        let offset = if relative_quantity { 0.0 } else { self.offset };
+        let value = if self.inverse { 1.0 / value } else { value };
+        (value - offset) / self.factor
+    }
+}
+
+/// A unit of a model description
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    /// The definition of the unit in SI base units. If `None`, the unit can only be compared by
+    /// name.
+    pub base_unit: Option<BaseUnit>,
+    pub display_units: Vec<DisplayUnit>,
+}
+
+impl Unit {
+    /// Find a display unit of this unit by name
+    pub fn display_unit(&self, name: &str) -> Option<&DisplayUnit> {
+        self.display_units.iter().find(|du| du.name == name)
+    }
+
+    /// Whether values of this unit and `other` measure the same physical dimension.
+    ///
+    /// Units are compatible if they have the same name, or if both are defined in SI base units
+    /// with the same exponents.
+    pub fn is_compatible(&self, other: &Unit) -> bool {
+        if self.name == other.name {
+            return true;
+        }
+        match (&self.base_unit, &other.base_unit) {
+            (Some(a), Some(b)) => a.exponents == b.exponents,
+            _ => false,
+        }
+    }
+
+    /// Convert `value` from this unit to `target`.
+    pub fn convert_to(
+        &self,
+        value: f64,
+        target: &Unit,
+        relative_quantity: bool,
+    ) -> Result<f64, Error> {
+        if self.name == target.name {
+            return Ok(value);
+        }
+        match (&self.base_unit, &target.base_unit) {
+            (Some(from), Some(to)) if from.exponents == to.exponents => {
+                let (from_offset, to_offset) = if relative_quantity {
+                    (0.0, 0.0)
+                } else {
+                    (from.offset, to.offset)
+                };
+                let base_value = from.factor * value + from_offset;
+                Ok((base_value - to_offset) / to.factor)
+            }
+            _ => Err(Error::IncompatibleUnits(
+                self.name.clone(),
+                target.name.clone(),
+            )),
+        }
+    }
+}
+
+/// The unit definitions of a model description
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct Units {
+    units: Vec<Unit>,
+}
+
+impl FromIterator<Unit> for Units {
+    fn from_iter<T: IntoIterator<Item = Unit>>(iter: T) -> Self {
+        Self {
+            units: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Units {
+    /// Iterate over all units
+    pub fn iter(&self) -> impl Iterator<Item = &Unit> {
+        self.units.iter()
+    }
+
+    /// Find a unit by name
+    pub fn unit(&self, name: &str) -> Result<&Unit, Error> {
+        self.units
+            .iter()
+            .find(|unit| unit.name == name)
+            .ok_or_else(|| Error::UnitNotFound(name.to_owned()))
+    }
+
+    /// Check that the units named `a` and `b` measure the same physical dimension
+    pub fn check_compatible(&self, a: &str, b: &str) -> Result<(), Error> {
+        if self.unit(a)?.is_compatible(self.unit(b)?) {
+            Ok(())
+        } else {
+            Err(Error::IncompatibleUnits(a.to_owned(), b.to_owned()))
+        }
+    }
+
+    /// Convert `value` from the unit named `from` to the unit named `to`
+    pub fn convert(
+        &self,
+        value: f64,
+        from: &str,
+        to: &str,
+        relative_quantity: bool,
+    ) -> Result<f64, Error> {
+        self.unit(from)?
+            .convert_to(value, self.unit(to)?, relative_quantity)
+    }
+
+    /// Find the display unit `display_unit` of the unit named `unit`. A display unit with the
+    /// name of the unit itself is the identity.
+    pub fn display_unit(&self, unit: &str, display_unit: &str) -> Result<DisplayUnit, Error> {
+        if unit == display_unit {
+            return Ok(DisplayUnit::identity(unit));
+        }
+        self.unit(unit)?
+            .display_unit(display_unit)
+            .cloned()
+            .ok_or_else(|| Error::DisplayUnitNotFound(display_unit.to_owned(), unit.to_owned()))
+    }
+}
+
+/// Conversion of the values of a variable between its unit and its display unit
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayUnitConversion {
+    pub display_unit: DisplayUnit,
+    pub relative_quantity: bool,
+}
+
+impl DisplayUnitConversion {
+    /// Convert `value` from the unit of the variable to its display unit
+    pub fn to_display(&self, value: f64) -> f64 {
+        self.display_unit.from_unit(value, self.relative_quantity)
+    }
+
+    /// Convert `value` from the display unit of the variable to its unit
+    pub fn from_display(&self, value: f64) -> f64 {
+        self.display_unit.to_unit(value, self.relative_quantity)
+    }
+}
+
+/// Units of the variables of a model description, resolved through their declared types
+pub trait FmiUnits: FmiModelVariables {
+    /// The unit definitions of the model description
+    fn units(&self) -> Units;
+
+    /// The unit of `variable`, declared on the variable itself or else on its declared type
+    fn variable_unit<'a>(&'a self, variable: &'a Self::Variable) -> Option<&'a str>;
+
+    /// The display unit of `variable`, declared on the variable itself or else on its declared
+    /// type
+    fn variable_display_unit<'a>(&'a self, variable: &'a Self::Variable) -> Option<&'a str>;
+
+    /// Whether `variable` is a relative quantity, for which offsets are ignored in conversions
+    fn is_relative_quantity(&self, variable: &Self::Variable) -> bool;
+
+    /// The conversion between the unit and the display unit of `variable`, or `None` if it has
+    /// no display unit.
+    fn display_unit_conversion(
+        &self,
+        variable: &Self::Variable,
+    ) -> Result<Option<DisplayUnitConversion>, Error> {
+        use crate::traits::FmiVariable;
+
+        let Some(display_unit) = self.variable_display_unit(variable) else {
+            return Ok(None);
+        };
+        let unit = self.variable_unit(variable).ok_or_else(|| {
+            Error::Model(format!(
+                "Variable {} has display unit {display_unit} but no unit",
+                variable.name()
+            ))
+        })?;
+        Ok(Some(DisplayUnitConversion {
+            display_unit: self.units().display_unit(unit, display_unit)?,
+            relative_quantity: self.is_relative_quantity(variable),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn units() -> Units {
+        let si = |exponents: SiExponents, factor: f64, offset: f64| {
+            Some(BaseUnit {
+                exponents,
+                factor,
+                offset,
+            })
+        };
+        let temperature = SiExponents {
+            k: 1,
+            ..Default::default()
+        };
+        [
+            Unit {
+                name: "K".to_owned(),
+                base_unit: si(temperature, 1.0, 0.0),
+                display_units: vec![DisplayUnit {
+                    name: "degF".to_owned(),
+                    factor: 1.8,
+                    offset: -459.67,
+                    inverse: false,
+                }],
+            },
+            Unit {
+                name: "degC".to_owned(),
+                base_unit: si(temperature, 1.0, 273.15),
+                display_units: vec![],
+            },
+            Unit {
+                name: "m3/m".to_owned(),
+                base_unit: si(
+                    SiExponents {
+                        m: 2,
+                        ..Default::default()
+                    },
+                    1.0,
+                    0.0,
+                ),
+                display_units: vec![DisplayUnit {
+                    name: "mpg".to_owned(),
+                    factor: 4.25143707e5,
+                    offset: 0.0,
+                    inverse: true,
+                }],
+            },
+            Unit {
+                name: "count".to_owned(),
+                base_unit: None,
+                display_units: vec![],
+            },
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    #[test]
+    fn test_convert() {
+        let units = units();
+        let approx = |a: f64, b: f64| assert!((a - b).abs() < 1e-9, "{a} != {b}");
+
+        approx(units.convert(20.0, "degC", "K", false).unwrap(), 293.15);
+        approx(units.convert(293.15, "K", "degC", false).unwrap(), 20.0);
+        // Temperature differences ignore the offset
+        approx(units.convert(20.0, "degC", "K", true).unwrap(), 20.0);
+        approx(units.convert(3.0, "count", "count", false).unwrap(), 3.0);
+
+        assert!(matches!(
+            units.convert(1.0, "K", "m3/m", false),
+            Err(Error::IncompatibleUnits(..))
+        ));
+        assert!(matches!(
+            units.convert(1.0, "K", "V", false),
+            Err(Error::UnitNotFound(name)) if name == "V"
+        ));
+    }
+
+    #[test]
+    fn test_compatible() {
+        let units = units();
+        assert!(units.check_compatible("K", "degC").is_ok());
+        assert!(units.check_compatible("count", "count").is_ok());
+        assert!(matches!(
+            units.check_compatible("K", "m3/m"),
+            Err(Error::IncompatibleUnits(..))
+        ));
+        // Without a base unit, only the name can be compared
+        assert!(units.check_compatible("count", "K").is_err());
+    }
+
+    #[test]
+    fn test_display_units() {
+        let units = units();
+        let approx = |a: f64, b: f64| assert!((a - b).abs() < 1e-9, "{a} != {b}");
+
+        let degf = units.display_unit("K", "degF").unwrap();
+        approx(degf.from_unit(273.15, false), 32.0);
+        approx(degf.to_unit(32.0, false), 273.15);
+        approx(degf.from_unit(10.0, true), 18.0);
+
+        // 1 l/100km is about 235.2 mpg
+        let mpg = units.display_unit("m3/m", "mpg").unwrap();
+        approx((mpg.from_unit(1e-8, false) * 10.0).round() / 10.0, 235.2);
+        approx(mpg.to_unit(mpg.from_unit(1e-8, false), false), 1e-8);
+
+        assert_eq!(
+            units.display_unit("K", "K").unwrap(),
+            DisplayUnit::identity("K")
+        );
+        assert!(matches!(
+            units.display_unit("K", "degC"),
+            Err(Error::DisplayUnitNotFound(..))
+        ));
+    }
+}
@@ -34,6 +34,7 @@ where
 
 /// Newtype for space-separated lists in XML attributes
 #[derive(PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttrList<T>(pub Vec<T>);
 
 impl<T> Deref for AttrList<T> {
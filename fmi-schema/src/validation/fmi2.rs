@@ -0,0 +1,310 @@
+//! Checks specific to FMI 2.0 model descriptions
+
+use std::collections::HashMap;
+
+use crate::{
+    MajorVersion,
+    fmi2::{
+        Fmi2ModelDescription, Fmi2VariableDependency, ScalarVariable, ScalarVariableElement,
+        SimpleTypeElement,
+    },
+    traits::{Causality, DataType, FmiVariable},
+};
+
+use super::{Finding, Location, Validate, check_variables};
+
+impl Validate for Fmi2ModelDescription {
+    fn validate(&self) -> Vec<Finding> {
+        let mut findings = vec![];
+        check_aliases(self, &mut findings);
+        check_variables(self, MajorVersion::FMI2, &mut findings);
+        check_declared_types(self, &mut findings);
+        check_derivatives(self, &mut findings);
+        check_model_structure(self, &mut findings);
+        findings
+    }
+}
+
+/// The variable at the 1-based `index` of the model variables
+fn variable_at(md: &Fmi2ModelDescription, index: u32) -> Option<&ScalarVariable> {
+    (index as usize)
+        .checked_sub(1)
+        .and_then(|index| md.model_variables.variables.get(index))
+}
+
+/// Variables of the same type with the same value reference are aliases. At most one variable of
+/// an alias set may have a start value.
+fn check_aliases(md: &Fmi2ModelDescription, findings: &mut Vec<Finding>) {
+    let mut aliases = HashMap::<(DataType, u32), &ScalarVariable>::new();
+    for var in &md.model_variables.variables {
+        if var.start().is_empty() {
+            continue;
+        }
+        let key = (var.data_type(), var.value_reference);
+        if let Some(other) = aliases.insert(key, var) {
+            findings.push(Finding::error(
+                Location::Variable(var.name.clone()),
+                format!(
+                    "the start value is also defined by alias '{}' of value reference {}",
+                    other.name, var.value_reference
+                ),
+            ));
+        }
+    }
+}
+
+/// Declared types exist and are of the same kind as the variable
+fn check_declared_types(md: &Fmi2ModelDescription, findings: &mut Vec<Finding>) {
+    let types = md
+        .type_definitions
+        .iter()
+        .flat_map(|defs| defs.types.iter())
+        .collect::<Vec<_>>();
+
+    for var in &md.model_variables.variables {
+        let Some(declared_type) = var.declared_type() else {
+            continue;
+        };
+        let location = Location::Variable(var.name.clone());
+        let Some(ty) = types.iter().find(|ty| ty.name == declared_type) else {
+            findings.push(Finding::error(
+                location,
+                format!("declared type {declared_type} is not defined"),
+            ));
+            continue;
+        };
+        let matches = matches!(
+            (&ty.elem, &var.elem),
+            (SimpleTypeElement::Real(_), ScalarVariableElement::Real(_))
+                | (
+                    SimpleTypeElement::Integer(_),
+                    ScalarVariableElement::Integer(_)
+                )
+                | (
+                    SimpleTypeElement::Boolean,
+                    ScalarVariableElement::Boolean(_)
+                )
+                | (SimpleTypeElement::String, ScalarVariableElement::String(_))
+                | (
+                    SimpleTypeElement::Enumeration(_),
+                    ScalarVariableElement::Enumeration(_)
+                )
+        );
+        if !matches {
+            findings.push(Finding::error(
+                location,
+                format!(
+                    "declared type {declared_type} does not match the {:?} variable",
+                    var.data_type()
+                ),
+            ));
+        }
+    }
+}
+
+/// The `derivative` attributes are indices of Real variables
+fn check_derivatives(md: &Fmi2ModelDescription, findings: &mut Vec<Finding>) {
+    for var in &md.model_variables.variables {
+        let ScalarVariableElement::Real(real) = &var.elem else {
+            continue;
+        };
+        let Some(derivative) = real.derivative else {
+            continue;
+        };
+        let location = Location::Variable(var.name.clone());
+        match variable_at(md, derivative) {
+            None => findings.push(Finding::error(
+                location,
+                format!("derivative {derivative} is not the index of a variable"),
+            )),
+            Some(state) if !matches!(state.elem, ScalarVariableElement::Real(_)) => {
+                findings.push(Finding::error(
+                    location,
+                    format!(
+                        "derivative {derivative} refers to {:?} variable '{}'",
+                        state.data_type(),
+                        state.name
+                    ),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// The unknowns and their dependencies are indices of variables, every output is listed, and the
+/// derivatives are derivatives
+fn check_model_structure(md: &Fmi2ModelDescription, findings: &mut Vec<Finding>) {
+    let structure = &md.model_structure;
+    let lists = [
+        ("Outputs", &structure.outputs.unknowns),
+        ("Derivatives", &structure.derivatives.unknowns),
+        ("InitialUnknowns", &structure.initial_unknowns.unknowns),
+    ];
+    for (list, unknowns) in lists {
+        for (i, unknown) in unknowns.iter().enumerate() {
+            let location = Location::ModelStructure(format!("{list}/Unknown[{}]", i + 1));
+            let mut error =
+                |message: String| findings.push(Finding::error(location.clone(), message));
+
+            check_dependencies(md, unknown, &mut error);
+
+            let Some(var) = variable_at(md, unknown.index) else {
+                error(format!("{} is not the index of a variable", unknown.index));
+                continue;
+            };
+            match list {
+                "Outputs" if var.causality() != Causality::Output => {
+                    error(format!("variable '{}' is not an output", var.name))
+                }
+                "Derivatives" if !matches!(&var.elem, ScalarVariableElement::Real(real) if real.derivative.is_some()) => {
+                    error(format!("variable '{}' is not a derivative", var.name))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for (i, var) in md.model_variables.variables.iter().enumerate() {
+        let index = i as u32 + 1;
+        if var.causality() == Causality::Output
+            && !structure
+                .outputs
+                .unknowns
+                .iter()
+                .any(|output| output.index == index)
+        {
+            findings.push(Finding::error(
+                Location::Variable(var.name.clone()),
+                "the output is not listed in ModelStructure",
+            ));
+        }
+    }
+}
+
+fn check_dependencies(
+    md: &Fmi2ModelDescription,
+    unknown: &Fmi2VariableDependency,
+    error: &mut impl FnMut(String),
+) {
+    for &dependency in &unknown.dependencies {
+        if variable_at(md, dependency).is_none() {
+            error(format!(
+                "dependency {dependency} is not the index of a variable"
+            ));
+        }
+    }
+    let kinds = unknown.dependencies_kind.len();
+    if kinds > 0 && kinds != unknown.dependencies.len() {
+        error(format!(
+            "{} dependencies but {kinds} dependenciesKind",
+            unknown.dependencies.len()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validation::Severity;
+
+    use super::*;
+
+    #[test]
+    fn test_invalid() {
+        let md: Fmi2ModelDescription = crate::deserialize(
+            r#"<fmiModelDescription fmiVersion="2.0" modelName="Broken" guid="{}">
+    <UnitDefinitions>
+        <Unit name="m">
+            <DisplayUnit name="mm" factor="1000"/>
+        </Unit>
+    </UnitDefinitions>
+    <TypeDefinitions>
+        <SimpleType name="Count">
+            <Integer/>
+        </SimpleType>
+    </TypeDefinitions>
+    <ModelVariables>
+        <ScalarVariable name="x" valueReference="1" causality="output">
+            <Real unit="m" displayUnit="cm"/>
+        </ScalarVariable>
+        <ScalarVariable name="der(x)" valueReference="2">
+            <Real derivative="5"/>
+        </ScalarVariable>
+        <ScalarVariable name="p" valueReference="3" causality="parameter" variability="fixed">
+            <Real start="1" declaredType="Count"/>
+        </ScalarVariable>
+        <ScalarVariable name="q" valueReference="3" causality="parameter" variability="fixed">
+            <Real start="2"/>
+        </ScalarVariable>
+        <ScalarVariable name="u" valueReference="4" causality="input" initial="exact">
+            <Integer start="0"/>
+        </ScalarVariable>
+        <ScalarVariable name="v" valueReference="5" causality="input">
+            <Real/>
+        </ScalarVariable>
+    </ModelVariables>
+    <ModelStructure>
+        <Outputs>
+            <Unknown index="5"/>
+        </Outputs>
+        <Derivatives>
+            <Unknown index="1" dependencies="3 7" dependenciesKind="fixed"/>
+        </Derivatives>
+    </ModelStructure>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+        let findings = md.validate();
+        assert!(findings.iter().all(|f| f.severity == Severity::Error));
+
+        let messages = |location: Location| {
+            findings
+                .iter()
+                .filter(|f| f.location == location)
+                .map(|f| f.message.as_str())
+                .collect::<Vec<_>>()
+        };
+        let variable = |name: &str| messages(Location::Variable(name.to_owned()));
+        let element = |element: &str| messages(Location::ModelStructure(element.to_owned()));
+
+        assert_eq!(
+            variable("x"),
+            vec![
+                "Display unit cm not found for unit m",
+                "the output is not listed in ModelStructure",
+            ]
+        );
+        assert_eq!(
+            variable("der(x)"),
+            vec!["derivative 5 refers to Int32 variable 'u'"]
+        );
+        assert_eq!(
+            variable("p"),
+            vec!["declared type Count does not match the Float64 variable"]
+        );
+        assert_eq!(
+            variable("q"),
+            vec!["the start value is also defined by alias 'p' of value reference 3"]
+        );
+        assert_eq!(
+            variable("u"),
+            vec!["initial Exact is not allowed for causality Input and variability Discrete"]
+        );
+        assert_eq!(
+            variable("v"),
+            vec!["causality Input requires a start value"]
+        );
+        assert_eq!(
+            element("Outputs/Unknown[1]"),
+            vec!["variable 'u' is not an output"]
+        );
+        assert_eq!(
+            element("Derivatives/Unknown[1]"),
+            vec![
+                "dependency 7 is not the index of a variable",
+                "2 dependencies but 1 dependenciesKind",
+                "variable 'x' is not a derivative",
+            ]
+        );
+    }
+}
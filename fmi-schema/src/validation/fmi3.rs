@@ -0,0 +1,466 @@
+//! Checks specific to FMI 3.0 model descriptions
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    MajorVersion,
+    fmi3::{
+        Fmi3ModelDescription, Fmi3TerminalsAndIcons, Fmi3Unknown, Terminal, Variable,
+        VariableDependency,
+    },
+    traits::{Causality, DataType, FmiModelVariables, FmiVariable},
+};
+
+use super::{Finding, Location, Validate, check_variables};
+
+impl Validate for Fmi3ModelDescription {
+    fn validate(&self) -> Vec<Finding> {
+        let mut findings = vec![];
+        let variables = check_value_references(self, &mut findings);
+        check_variables(self, MajorVersion::FMI3, &mut findings);
+        check_declared_types(self, &mut findings);
+        check_derivatives(self, &variables, &mut findings);
+        check_model_structure(self, &variables, &mut findings);
+        findings
+    }
+}
+
+/// Value references are unique across all variables. Returns the variables by value reference.
+fn check_value_references<'a>(
+    md: &'a Fmi3ModelDescription,
+    findings: &mut Vec<Finding>,
+) -> HashMap<u32, &'a Variable> {
+    let mut variables = HashMap::new();
+    for var in md.variables() {
+        if let Some(other) = variables.insert(var.value_reference(), var) {
+            findings.push(Finding::error(
+                Location::Variable(var.name().to_owned()),
+                format!(
+                    "value reference {} is also used by variable '{}'",
+                    var.value_reference(),
+                    other.name()
+                ),
+            ));
+            // Keep the first variable for the lookups of the following checks
+            variables.insert(var.value_reference(), other);
+        }
+    }
+    variables
+}
+
+/// Declared types exist and are of the same kind as the variable
+fn check_declared_types(md: &Fmi3ModelDescription, findings: &mut Vec<Finding>) {
+    let type_definitions = md
+        .type_definitions
+        .iter()
+        .flat_map(|defs| defs.type_definitions.iter())
+        .collect::<Vec<_>>();
+
+    for var in md.variables() {
+        let Some(declared_type) = var.declared_type() else {
+            continue;
+        };
+        let location = Location::Variable(var.name().to_owned());
+        match type_definitions
+            .iter()
+            .find(|ty| ty.name() == declared_type)
+        {
+            None => findings.push(Finding::error(
+                location,
+                format!("declared type {declared_type} is not defined"),
+            )),
+            Some(ty) if ty.data_type() != var.data_type() => findings.push(Finding::error(
+                location,
+                format!(
+                    "declared type {declared_type} is a {:?} type, not {:?}",
+                    ty.data_type(),
+                    var.data_type()
+                ),
+            )),
+            Some(_) => {}
+        }
+    }
+}
+
+/// The `derivative` attributes refer to float variables
+fn check_derivatives(
+    md: &Fmi3ModelDescription,
+    variables: &HashMap<u32, &Variable>,
+    findings: &mut Vec<Finding>,
+) {
+    for var in md.variables() {
+        let derivative = match var {
+            Variable::Float32(var) => var.derivative(),
+            Variable::Float64(var) => var.derivative(),
+            _ => None,
+        };
+        let Some(derivative) = derivative else {
+            continue;
+        };
+        let location = Location::Variable(var.name().to_owned());
+        match variables.get(&derivative) {
+            None => findings.push(Finding::error(
+                location,
+                format!("derivative {derivative} is not the value reference of a variable"),
+            )),
+            Some(state) if !matches!(state.data_type(), DataType::Float32 | DataType::Float64) => {
+                findings.push(Finding::error(
+                    location,
+                    format!(
+                        "derivative {derivative} refers to {:?} variable '{}'",
+                        state.data_type(),
+                        state.name()
+                    ),
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+}
+
+/// The unknowns and their dependencies refer to existing variables, every output is listed, and
+/// the continuous state derivatives are derivatives
+fn check_model_structure(
+    md: &Fmi3ModelDescription,
+    variables: &HashMap<u32, &Variable>,
+    findings: &mut Vec<Finding>,
+) {
+    let mut counts = HashMap::<&str, usize>::new();
+    for dependency in &md.model_structure.unknowns {
+        let (element, unknown) = match dependency {
+            VariableDependency::Output(unknown) => ("Output", unknown),
+            VariableDependency::ContinuousStateDerivative(unknown) => {
+                ("ContinuousStateDerivative", unknown)
+            }
+            VariableDependency::ClockedState(unknown) => ("ClockedState", unknown),
+            VariableDependency::InitialUnknown(unknown) => ("InitialUnknown", unknown),
+            VariableDependency::EventIndicator(unknown) => ("EventIndicator", unknown),
+        };
+        let count = counts.entry(element).or_default();
+        *count += 1;
+        let location = Location::ModelStructure(format!("{element}[{count}]"));
+        let mut error = |message: String| findings.push(Finding::error(location.clone(), message));
+
+        check_dependencies(unknown, variables, &mut error);
+
+        let Some(var) = variables.get(&unknown.value_reference) else {
+            error(format!(
+                "value reference {} is not the value reference of a variable",
+                unknown.value_reference
+            ));
+            continue;
+        };
+        match dependency {
+            VariableDependency::Output(_) if var.causality() != Causality::Output => {
+                error(format!("variable '{}' is not an output", var.name()))
+            }
+            VariableDependency::ContinuousStateDerivative(_)
+                if !matches!(var, Variable::Float32(v) if v.derivative().is_some())
+                    && !matches!(var, Variable::Float64(v) if v.derivative().is_some()) =>
+            {
+                error(format!("variable '{}' is not a derivative", var.name()))
+            }
+            _ => {}
+        }
+    }
+
+    for var in md.variables() {
+        if var.causality() == Causality::Output
+            && !md
+                .model_structure
+                .outputs()
+                .any(|output| output.value_reference == var.value_reference())
+        {
+            findings.push(Finding::error(
+                Location::Variable(var.name().to_owned()),
+                "the output is not listed in ModelStructure",
+            ));
+        }
+    }
+}
+
+fn check_dependencies(
+    unknown: &Fmi3Unknown,
+    variables: &HashMap<u32, &Variable>,
+    error: &mut impl FnMut(String),
+) {
+    let dependencies = unknown
+        .dependencies
+        .as_ref()
+        .map(|deps| deps.0.as_slice())
+        .unwrap_or_default();
+    for dependency in dependencies {
+        if !variables.contains_key(dependency) {
+            error(format!(
+                "dependency {dependency} is not the value reference of a variable"
+            ));
+        }
+    }
+    match &unknown.dependencies_kind {
+        Some(kinds) if kinds.0.len() != dependencies.len() => error(format!(
+            "{} dependencies but {} dependenciesKind",
+            dependencies.len(),
+            kinds.0.len()
+        )),
+        _ => {}
+    }
+}
+
+/// Check the terminals of `terminalsAndIcons.xml` against the model description `md`: the member
+/// variables exist, and the members of terminals matched by name have unique member names.
+pub fn validate_terminals(
+    md: &Fmi3ModelDescription,
+    terminals_and_icons: &Fmi3TerminalsAndIcons,
+) -> Vec<Finding> {
+    let mut findings = vec![];
+    let variables = md.variables().map(|var| var.name()).collect::<HashSet<_>>();
+    let terminals = terminals_and_icons
+        .terminals
+        .iter()
+        .flat_map(|terminals| terminals.terminals.iter());
+    check_terminals(terminals, "", &variables, &mut findings);
+    findings
+}
+
+fn check_terminals<'a>(
+    terminals: impl Iterator<Item = &'a Terminal>,
+    prefix: &str,
+    variables: &HashSet<&str>,
+    findings: &mut Vec<Finding>,
+) {
+    let mut names = HashSet::new();
+    for terminal in terminals {
+        let path = format!("{prefix}{}", terminal.name);
+        let location = Location::Terminal(path.clone());
+        let mut error = |message: String| findings.push(Finding::error(location.clone(), message));
+
+        if !names.insert(&terminal.name) {
+            error("the name is not unique".to_owned());
+        }
+
+        let matched_by_name = matches!(terminal.matching_rule.as_str(), "plug" | "bus");
+        let mut member_names = HashSet::new();
+        for member in &terminal.member_variables {
+            let variable = &member.variable_name;
+            if !variables.contains(variable.as_str()) {
+                error(format!("variable '{variable}' is not defined"));
+            }
+            match &member.member_name {
+                Some(member_name) if !member_names.insert(member_name.as_str()) => {
+                    error(format!("member name '{member_name}' is not unique"))
+                }
+                None if matched_by_name => error(format!(
+                    "member variable '{variable}' has no memberName, which matching rule {} \
+                     requires",
+                    terminal.matching_rule
+                )),
+                _ => {}
+            }
+        }
+        for stream in &terminal.stream_member_variables {
+            for variable in [
+                &stream.in_stream_variable_name,
+                &stream.out_stream_variable_name,
+            ] {
+                if !variables.contains(variable.as_str()) {
+                    error(format!("stream variable '{variable}' is not defined"));
+                }
+            }
+        }
+
+        check_terminals(
+            terminal.terminals.iter(),
+            &format!("{path}."),
+            variables,
+            findings,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::validation::Severity;
+
+    use super::*;
+
+    fn validate(xml: &str) -> Vec<Finding> {
+        let md: Fmi3ModelDescription = crate::deserialize(xml).unwrap();
+        md.validate()
+    }
+
+    #[test]
+    fn test_valid() {
+        let findings = validate(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="BouncingBall" instantiationToken="">
+    <UnitDefinitions>
+        <Unit name="m"/>
+        <Unit name="m/s"/>
+    </UnitDefinitions>
+    <TypeDefinitions>
+        <Float64Type name="Height" quantity="Length" unit="m"/>
+    </TypeDefinitions>
+    <ModelVariables>
+        <Float64 name="time" valueReference="0" causality="independent" variability="continuous"/>
+        <Float64 name="h" valueReference="1" causality="output" initial="exact" declaredType="Height" start="1"/>
+        <Float64 name="der(h)" valueReference="2" derivative="1"/>
+        <Float64 name="v" valueReference="3" causality="output" initial="exact" unit="m/s" start="0"/>
+        <Float64 name="der(v)" valueReference="4" derivative="3"/>
+        <Float64 name="g" valueReference="5" causality="parameter" variability="fixed" start="-9.81"/>
+        <Int32 name="n" valueReference="6" causality="input" start="0"/>
+    </ModelVariables>
+    <ModelStructure>
+        <Output valueReference="1"/>
+        <Output valueReference="3"/>
+        <ContinuousStateDerivative valueReference="2"/>
+        <ContinuousStateDerivative valueReference="4" dependencies="5" dependenciesKind="fixed"/>
+        <InitialUnknown valueReference="2"/>
+        <InitialUnknown valueReference="4"/>
+    </ModelStructure>
+</fmiModelDescription>"#,
+        );
+        assert_eq!(findings, vec![]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let findings = validate(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Broken" instantiationToken="">
+    <TypeDefinitions>
+        <Int32Type name="Count"/>
+    </TypeDefinitions>
+    <ModelVariables>
+        <Float64 name="x" valueReference="1" causality="output" unit="m"/>
+        <Float64 name="x" valueReference="1" causality="parameter" variability="continuous"/>
+        <Float64 name="der(x)" valueReference="2" derivative="3"/>
+        <Boolean name="b" valueReference="3" variability="continuous" initial="exact" start="true"/>
+        <Float64 name="y" valueReference="4" causality="calculatedParameter" variability="fixed" initial="exact" start="1"/>
+        <Int32 name="u" valueReference="5" causality="input"/>
+        <Float64 name="c" valueReference="6" declaredType="Count"/>
+        <Float64 name="z" valueReference="7" causality="output"/>
+        <Float64 name="k" valueReference="9" causality="parameter" start="1"/>
+    </ModelVariables>
+    <ModelStructure>
+        <Output valueReference="1" dependencies="5 8" dependenciesKind="dependent"/>
+        <Output valueReference="5"/>
+        <ContinuousStateDerivative valueReference="1"/>
+    </ModelStructure>
+</fmiModelDescription>"#,
+        );
+        assert!(findings.iter().all(|f| f.severity == Severity::Error));
+
+        let messages = |location: Location| {
+            findings
+                .iter()
+                .filter(|f| f.location == location)
+                .map(|f| f.message.as_str())
+                .collect::<Vec<_>>()
+        };
+        let variable = |name: &str| messages(Location::Variable(name.to_owned()));
+        let element = |element: &str| messages(Location::ModelStructure(element.to_owned()));
+
+        assert_eq!(
+            variable("x"),
+            vec![
+                "value reference 1 is also used by variable 'x'",
+                "unit m is not defined",
+                "the name is not unique",
+                "causality Parameter can not be Continuous",
+                "initial Exact requires a start value",
+            ]
+        );
+        assert_eq!(
+            variable("der(x)"),
+            vec!["derivative 3 refers to Boolean variable 'b'"]
+        );
+        assert_eq!(
+            variable("b"),
+            vec!["only float variables can be continuous, not Boolean"]
+        );
+        assert_eq!(
+            variable("y"),
+            vec![
+                "initial Exact is not allowed for causality CalculatedParameter and variability \
+                 Fixed"
+            ]
+        );
+        assert_eq!(variable("u"), vec!["initial Exact requires a start value"]);
+        assert_eq!(
+            variable("k"),
+            vec!["causality Parameter requires a declared variability of Fixed or Tunable"]
+        );
+        assert_eq!(
+            variable("c"),
+            vec!["declared type Count is a Int32 type, not Float64"]
+        );
+        assert_eq!(
+            variable("z"),
+            vec!["the output is not listed in ModelStructure"]
+        );
+        assert_eq!(
+            element("Output[1]"),
+            vec![
+                "dependency 8 is not the value reference of a variable",
+                "2 dependencies but 1 dependenciesKind",
+            ]
+        );
+        assert_eq!(element("Output[2]"), vec!["variable 'u' is not an output"]);
+        assert_eq!(
+            element("ContinuousStateDerivative[1]"),
+            vec!["variable 'x' is not a derivative"]
+        );
+    }
+
+    #[test]
+    fn test_terminals() {
+        let md: Fmi3ModelDescription = crate::deserialize(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="">
+    <ModelVariables>
+        <Float64 name="phi" valueReference="1" causality="output"/>
+        <Float64 name="tau" valueReference="2" causality="input" start="0"/>
+    </ModelVariables>
+    <ModelStructure>
+        <Output valueReference="1"/>
+    </ModelStructure>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+        let terminals: Fmi3TerminalsAndIcons = crate::deserialize(
+            r#"<fmiTerminalsAndIcons fmiVersion="3.0">
+    <Terminals>
+        <Terminal name="flange" matchingRule="plug">
+            <TerminalMemberVariable variableName="phi" memberName="phi" variableKind="signal"/>
+            <TerminalMemberVariable variableName="tau" memberName="tau" variableKind="inflow"/>
+        </Terminal>
+        <Terminal name="bus" matchingRule="bus">
+            <TerminalMemberVariable variableName="phi" variableKind="signal"/>
+            <Terminal name="sub" matchingRule="sequence">
+                <TerminalMemberVariable variableName="w" variableKind="signal"/>
+                <TerminalStreamMemberVariable inStreamMemberName="h" outStreamMemberName="h"
+                    inStreamVariableName="h_in" outStreamVariableName="phi"/>
+            </Terminal>
+        </Terminal>
+        <Terminal name="flange" matchingRule="plug">
+            <TerminalMemberVariable variableName="phi" memberName="x" variableKind="signal"/>
+            <TerminalMemberVariable variableName="tau" memberName="x" variableKind="signal"/>
+        </Terminal>
+    </Terminals>
+</fmiTerminalsAndIcons>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            validate_terminals(&md, &terminals)
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "error: terminal 'bus': member variable 'phi' has no memberName, which matching \
+                 rule bus requires",
+                "error: terminal 'bus.sub': variable 'w' is not defined",
+                "error: terminal 'bus.sub': stream variable 'h_in' is not defined",
+                "error: terminal 'flange': the name is not unique",
+                "error: terminal 'flange': member name 'x' is not unique",
+            ]
+        );
+    }
+}
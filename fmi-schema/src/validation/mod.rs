@@ -0,0 +1,295 @@
+//! Checks of model descriptions against the rules of the FMI standard.
+//!
+//! Parsing a model description only ensures that it matches the XML schema. [`Validate`] checks
+//! the rules the schema can not express, such as references between variables, the combinations
+//! of causality, variability and initial, and the type and unit definitions that variables refer
+//! to. Each violation is reported as a [`Finding`] with a [`Severity`] and a [`Location`]:
+//!
+//! ```rust
+//! # #[cfg(feature = "fmi3")] {
+//! use fmi_schema::{fmi3::Fmi3ModelDescription, validation::{Location, Severity, Validate}};
+//!
+//! let xml = r#"<fmiModelDescription fmiVersion="3.0" modelName="Broken" instantiationToken="">
+//!     <ModelVariables>
+//!         <Float64 name="x" valueReference="1" causality="output" unit="m"/>
+//!         <Float64 name="k" valueReference="1" causality="parameter" start="1"/>
+//!     </ModelVariables>
+//!     <ModelStructure>
+//!         <Output valueReference="1"/>
+//!     </ModelStructure>
+//! </fmiModelDescription>"#;
+//! let md: Fmi3ModelDescription = fmi_schema::deserialize(xml).unwrap();
+//!
+//! let findings = md.validate();
+//! assert!(findings.iter().all(|f| f.severity == Severity::Error));
+//! assert!(findings.iter().any(|f| f.location == Location::Variable("k".to_owned())));
+//! # }
+//! ```
+
+use std::{collections::HashSet, fmt::Display};
+
+use crate::{
+    MajorVersion,
+    enumerations::FmiEnumerations,
+    traits::{Causality, DataType, FmiVariable, Initial, Variability},
+    units::FmiUnits,
+};
+
+#[cfg(feature = "fmi2")]
+mod fmi2;
+#[cfg(feature = "fmi3")]
+mod fmi3;
+
+#[cfg(feature = "fmi3")]
+pub use fmi3::validate_terminals;
+
+/// How severe a violation of the standard is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The FMU is likely to work, but does not follow a recommendation of the standard
+    Warning,
+    /// The FMU violates a rule of the standard
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// Where in the FMU a finding was made
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Location {
+    /// The model description as a whole
+    ModelDescription,
+    /// The variable with the given name
+    Variable(String),
+    /// The type definition with the given name
+    TypeDefinition(String),
+    /// An element of the model structure, such as `Outputs/Unknown[1]` or `Output[1]`
+    ModelStructure(String),
+    /// A terminal of `terminalsAndIcons.xml`, with the names of nested terminals separated by `.`
+    Terminal(String),
+    /// A file or directory of the FMU archive
+    Archive(String),
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::ModelDescription => write!(f, "modelDescription.xml"),
+            Location::Variable(name) => write!(f, "variable '{name}'"),
+            Location::TypeDefinition(name) => write!(f, "type '{name}'"),
+            Location::ModelStructure(element) => write!(f, "ModelStructure/{element}"),
+            Location::Terminal(name) => write!(f, "terminal '{name}'"),
+            Location::Archive(path) => write!(f, "{path}"),
+        }
+    }
+}
+
+/// A violation of the standard
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub severity: Severity,
+    pub location: Location,
+    pub message: String,
+}
+
+impl Finding {
+    /// A finding with [`Severity::Error`]
+    pub fn error(location: Location, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            location,
+            message: message.into(),
+        }
+    }
+
+    /// A finding with [`Severity::Warning`]
+    pub fn warning(location: Location, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            location,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for Finding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.location, self.message)
+    }
+}
+
+/// Check a model description against the rules of the standard
+pub trait Validate {
+    /// All violations of the standard, in the order of the model description. Empty if the model
+    /// description is valid.
+    fn validate(&self) -> Vec<Finding>;
+}
+
+/// The checks of the variables that are common to FMI 2.0 and 3.0: unique names, valid
+/// combinations of causality, variability and initial, the presence of start values, and the
+/// units and enumeration types the variables refer to.
+fn check_variables<MD>(md: &MD, version: MajorVersion, findings: &mut Vec<Finding>)
+where
+    MD: FmiUnits + FmiEnumerations,
+{
+    let units = md.units();
+    let mut names = HashSet::new();
+
+    for var in md.variables() {
+        let location = || Location::Variable(var.name().to_owned());
+        let mut error = |message: String| findings.push(Finding::error(location(), message));
+
+        if !names.insert(var.name()) {
+            error("the name is not unique".to_owned());
+        }
+
+        let causality = var.causality();
+        let variability = var.variability();
+        // The default variability is never valid for parameters
+        if var.declared_variability().is_none()
+            && matches!(
+                causality,
+                Causality::Parameter
+                    | Causality::CalculatedParameter
+                    | Causality::StructuralParameter
+            )
+        {
+            error(format!(
+                "causality {causality:?} requires a declared variability of Fixed or Tunable"
+            ));
+        } else if let Some(message) = check_causality_variability(causality, variability) {
+            error(message);
+        }
+        if variability == Variability::Continuous
+            && !matches!(var.data_type(), DataType::Float32 | DataType::Float64)
+        {
+            error(format!(
+                "only float variables can be continuous, not {:?}",
+                var.data_type()
+            ));
+        }
+
+        if var.data_type() != DataType::Clock {
+            check_initial(var, &version, &mut error);
+        }
+
+        if let Some(unit) = md.variable_unit(var) {
+            if units.unit(unit).is_err() {
+                error(format!("unit {unit} is not defined"));
+            } else if let Err(err) = md.display_unit_conversion(var) {
+                error(err.to_string());
+            }
+        }
+
+        match md.variable_enumeration(var) {
+            Ok(Some(enumeration)) => {
+                for start in var.start() {
+                    if let Err(err) = enumeration.parse(&start.to_string()) {
+                        error(format!("start value: {err}"));
+                    }
+                }
+            }
+            Ok(None) => {}
+            Err(err) => error(err.to_string()),
+        }
+    }
+}
+
+/// The reason why `causality` and `variability` can not be combined, if they can't
+fn check_causality_variability(causality: Causality, variability: Variability) -> Option<String> {
+    let valid = match causality {
+        Causality::Parameter | Causality::CalculatedParameter | Causality::StructuralParameter => {
+            matches!(variability, Variability::Fixed | Variability::Tunable)
+        }
+        Causality::Input => matches!(variability, Variability::Discrete | Variability::Continuous),
+        Causality::Output => !matches!(variability, Variability::Fixed | Variability::Tunable),
+        Causality::Local => true,
+        Causality::Independent => variability == Variability::Continuous,
+    };
+    (!valid).then(|| format!("causality {causality:?} can not be {variability:?}"))
+}
+
+/// Check the `initial` attribute of `var`, and that it has start values if and only if required
+fn check_initial<V: FmiVariable>(var: &V, version: &MajorVersion, error: &mut impl FnMut(String)) {
+    use Initial::{Approx, Calculated, Exact};
+
+    let causality = var.causality();
+    let variability = var.variability();
+    // The allowed values of initial, the first being the default
+    let allowed: &[Initial] = match (causality, variability) {
+        (Causality::Independent, _) => &[],
+        (Causality::Input, _) if *version == MajorVersion::FMI2 => &[],
+        (Causality::Input, _) => &[Exact],
+        (Causality::Parameter | Causality::StructuralParameter, _) => &[Exact],
+        (_, Variability::Constant) => &[Exact],
+        (Causality::CalculatedParameter, _) => &[Calculated, Approx],
+        (Causality::Local, Variability::Fixed | Variability::Tunable) => &[Calculated, Approx],
+        _ => &[Calculated, Exact, Approx],
+    };
+
+    let initial = match var.initial() {
+        Some(initial) if !allowed.contains(&initial) => {
+            error(format!(
+                "initial {initial:?} is not allowed for causality {causality:?} and variability \
+                 {variability:?}"
+            ));
+            return;
+        }
+        initial => initial.or_else(|| allowed.first().copied()),
+    };
+
+    let has_start = !var.start().is_empty();
+    let start_required = causality == Causality::Input || matches!(initial, Some(Exact | Approx));
+    if start_required && !has_start {
+        match initial {
+            Some(initial) => error(format!("initial {initial:?} requires a start value")),
+            None => error(format!("causality {causality:?} requires a start value")),
+        }
+    } else if !start_required && has_start {
+        match initial {
+            Some(initial) => error(format!("initial {initial:?} does not allow a start value")),
+            None => error(format!(
+                "causality {causality:?} does not allow a start value"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_causality_variability() {
+        assert!(check_causality_variability(Causality::Parameter, Variability::Fixed).is_none());
+        assert!(check_causality_variability(Causality::Input, Variability::Discrete).is_none());
+        assert!(check_causality_variability(Causality::Output, Variability::Constant).is_none());
+        assert!(
+            check_causality_variability(Causality::Independent, Variability::Continuous).is_none()
+        );
+        assert!(check_causality_variability(Causality::Parameter, Variability::Constant).is_some());
+        assert!(check_causality_variability(Causality::Input, Variability::Tunable).is_some());
+        assert!(check_causality_variability(Causality::Output, Variability::Fixed).is_some());
+        assert!(
+            check_causality_variability(Causality::Independent, Variability::Discrete).is_some()
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let finding = Finding::error(Location::Variable("x".to_owned()), "the name is not unique");
+        assert_eq!(
+            finding.to_string(),
+            "error: variable 'x': the name is not unique"
+        );
+        let finding = Finding::warning(Location::Archive("binaries/os2".to_owned()), "unknown");
+        assert_eq!(finding.to_string(), "warning: binaries/os2: unknown");
+    }
+}
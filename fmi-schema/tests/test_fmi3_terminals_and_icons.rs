@@ -0,0 +1,81 @@
+//! Test FMI 3.0 terminals and icons schema by parsing the FMI3TerminalsAndIcons.xml file.
+
+#[test]
+#[cfg(feature = "fmi3")]
+fn test_fmi3_terminals_and_icons() {
+    use fmi_schema::fmi3::{Fmi3TerminalsAndIcons, TerminalMemberKind};
+
+    let test_file = std::env::current_dir()
+        .map(|path| path.join("tests/FMI3TerminalsAndIcons.xml"))
+        .unwrap();
+    let xml_content = std::fs::read_to_string(test_file).unwrap();
+    let terminals: Fmi3TerminalsAndIcons = fmi_schema::deserialize(&xml_content).unwrap();
+
+    assert_eq!(terminals.fmi_version, "3.0");
+
+    // Test graphical representation
+    let graphics = terminals.graphical_representation.as_ref().unwrap();
+    let coordinate_system = graphics.coordinate_system.as_ref().unwrap();
+    assert_eq!(coordinate_system.x1, -100.0);
+    assert_eq!(coordinate_system.suggested_scaling_factor_to_mm, 0.1);
+    assert_eq!(graphics.icon.as_ref().unwrap().y2, 100.0);
+
+    assert_eq!(terminals.terminals.as_ref().unwrap().terminals.len(), 3);
+
+    // Test plug terminal
+    let flange = terminals.terminal("flange_a").unwrap();
+    assert_eq!(flange.matching_rule, "plug");
+    assert_eq!(
+        flange.terminal_kind.as_deref(),
+        Some("org.modelica.Mechanics.Rotational.Interfaces.Flange")
+    );
+    assert_eq!(flange.description.as_deref(), Some("Left flange"));
+    assert_eq!(flange.member_variables.len(), 2);
+    assert_eq!(flange.member_variables[0].variable_name, "phi_a");
+    assert_eq!(
+        flange.member_variables[0].member_name.as_deref(),
+        Some("phi")
+    );
+    assert_eq!(
+        flange.member_variables[0].variable_kind,
+        TerminalMemberKind::Signal
+    );
+    assert_eq!(
+        flange.member_variables[1].variable_kind,
+        TerminalMemberKind::Inflow
+    );
+
+    let flange_graphics = flange.graphical_representation.as_ref().unwrap();
+    assert_eq!(
+        flange_graphics.default_connection_color.as_deref(),
+        Some(&[0, 0, 0][..])
+    );
+    assert_eq!(flange_graphics.default_connection_stroke_size, Some(0.25));
+    assert_eq!(flange_graphics.icon_base_name.as_deref(), Some("flange_a"));
+    assert_eq!(flange_graphics.icon.as_ref().unwrap().x1, -110.0);
+
+    // Test nested bus terminals
+    let bus = terminals.terminal("controlBus").unwrap();
+    assert_eq!(bus.matching_rule, "bus");
+    assert_eq!(bus.terminals.len(), 1);
+    assert_eq!(bus.terminals[0].name, "engine");
+    assert_eq!(
+        bus.terminals[0].member_variables[0].variable_name,
+        "engine.torque"
+    );
+
+    // Test stream member variables
+    let port = terminals.terminal("fluidPort").unwrap();
+    assert_eq!(port.stream_member_variables.len(), 1);
+    let stream = &port.stream_member_variables[0];
+    assert_eq!(stream.in_stream_member_name, "h_outflow");
+    assert_eq!(stream.in_stream_variable_name, "h_in");
+    assert_eq!(stream.out_stream_variable_name, "h_out");
+
+    assert!(terminals.terminal("missing").is_none());
+
+    // Test round trip
+    let xml = fmi_schema::serialize(&terminals, true).unwrap();
+    let round_trip: Fmi3TerminalsAndIcons = fmi_schema::deserialize(&xml).unwrap();
+    assert_eq!(terminals, round_trip);
+}
@@ -0,0 +1,59 @@
+//! Tests for the JSON representation of the schema types with the `serde` feature.
+#![cfg(feature = "serde")]
+
+fn read_test_file(name: &str) -> String {
+    let test_file = std::env::current_dir()
+        .map(|path| path.join("tests").join(name))
+        .unwrap();
+    std::fs::read_to_string(test_file).unwrap()
+}
+
+#[test]
+#[cfg(feature = "fmi2")]
+fn test_fmi2_model_description() {
+    use fmi_schema::fmi2::Fmi2ModelDescription;
+
+    let md: Fmi2ModelDescription = fmi_schema::deserialize(&read_test_file("FMI2.xml")).unwrap();
+    let json = serde_json::to_value(&md).unwrap();
+    assert_eq!(json["model_name"], "BouncingBall");
+    assert_eq!(json["default_experiment"]["stop_time"], 3.0);
+
+    // `Fmi2ModelDescription` does not implement `PartialEq`, compare the JSON instead
+    let round_trip: Fmi2ModelDescription = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&round_trip).unwrap(), json);
+}
+
+#[test]
+#[cfg(feature = "fmi3")]
+fn test_fmi3_model_description() {
+    use fmi_schema::fmi3::Fmi3ModelDescription;
+
+    let md: Fmi3ModelDescription = fmi_schema::deserialize(&read_test_file("FMI3.xml")).unwrap();
+    let json = serde_json::to_string(&md).unwrap();
+    let round_trip: Fmi3ModelDescription = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, md);
+}
+
+#[test]
+#[cfg(feature = "fmi3")]
+fn test_fmi3_build_description() {
+    use fmi_schema::fmi3::Fmi3BuildDescription;
+
+    let bd: Fmi3BuildDescription =
+        fmi_schema::deserialize(&read_test_file("FMI3BuildDescription.xml")).unwrap();
+    let json = serde_json::to_string(&bd).unwrap();
+    let round_trip: Fmi3BuildDescription = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, bd);
+}
+
+#[test]
+#[cfg(feature = "fmi3")]
+fn test_fmi3_terminals_and_icons() {
+    use fmi_schema::fmi3::Fmi3TerminalsAndIcons;
+
+    let terminals: Fmi3TerminalsAndIcons =
+        fmi_schema::deserialize(&read_test_file("FMI3TerminalsAndIcons.xml")).unwrap();
+    let json = serde_json::to_string(&terminals).unwrap();
+    let round_trip: Fmi3TerminalsAndIcons = serde_json::from_str(&json).unwrap();
+    assert_eq!(round_trip, terminals);
+}
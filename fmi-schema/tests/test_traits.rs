@@ -0,0 +1,112 @@
+//! Test the version-agnostic traits on the FMI2.xml and FMI3.xml files, which describe the same
+//! model.
+
+#[cfg(all(feature = "fmi2", feature = "fmi3"))]
+mod both {
+    use fmi_schema::{
+        Error,
+        traits::{
+            Causality, DataType, FmiModelDescription, FmiModelStructure, FmiModelVariables,
+            FmiVariable, Initial, ModelUnknown, StartValue, UnknownKind, Variability,
+        },
+        units::FmiUnits,
+    };
+
+    fn read<MD: FmiModelDescription>(file: &str) -> MD {
+        let test_file = std::env::current_dir()
+            .map(|path| path.join("tests").join(file))
+            .unwrap();
+        MD::deserialize(&std::fs::read_to_string(test_file).unwrap()).unwrap()
+    }
+
+    /// Written once, checked against both versions
+    fn check_bouncing_ball<MD: FmiModelVariables + FmiModelStructure + FmiUnits>(md: &MD) {
+        let h = md.variable_by_name("h").unwrap();
+        assert_eq!(h.value_reference(), 1);
+        assert_eq!(h.description(), Some("Position of the ball"));
+        assert_eq!(h.causality(), Causality::Output);
+        assert_eq!(h.variability(), Variability::Continuous);
+        assert_eq!(h.initial(), Some(Initial::Exact));
+        assert_eq!(h.data_type(), DataType::Float64);
+        assert_eq!(h.declared_type(), Some("Position"));
+        assert_eq!(h.unit(), None);
+        // The unit is inherited from the declared type
+        assert_eq!(md.variable_unit(h), Some("m"));
+        assert_eq!(md.variable_display_unit(h), None);
+        assert_eq!(md.display_unit_conversion(h).unwrap(), None);
+        assert_eq!(h.start(), vec![StartValue::Float(1.0)]);
+        assert!(h.dimensions().is_empty());
+
+        let parameters = md
+            .variables()
+            .filter(|v| v.causality() == Causality::Parameter)
+            .map(|v| (v.name(), v.variability(), v.start()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parameters,
+            vec![
+                ("g", Variability::Fixed, vec![StartValue::Float(-9.81)]),
+                ("e", Variability::Tunable, vec![StartValue::Float(0.7)]),
+            ]
+        );
+        assert!(md.variable_by_name("x").is_none());
+
+        let units = md.units();
+        assert_eq!(units.iter().count(), 3);
+        assert!(units.check_compatible("m/s", "m/s").is_ok());
+        assert!(matches!(
+            units.check_compatible("m/s", "m/s2"),
+            Err(Error::IncompatibleUnits(..))
+        ));
+        let der_v = md.variable_by_name("der(v)").unwrap();
+        assert_eq!(md.variable_unit(der_v), Some("m/s2"));
+
+        let outputs = md.outputs().map(|u| u.value_reference).collect::<Vec<_>>();
+        assert_eq!(outputs, vec![1, 3]);
+        let derivatives = md
+            .continuous_state_derivatives()
+            .map(|u| u.value_reference)
+            .collect::<Vec<_>>();
+        assert_eq!(derivatives, vec![2, 4]);
+        assert_eq!(
+            md.initial_unknowns().collect::<Vec<_>>(),
+            vec![
+                ModelUnknown {
+                    kind: UnknownKind::InitialUnknown,
+                    value_reference: 2,
+                    dependencies: Some(vec![3]),
+                },
+                ModelUnknown {
+                    kind: UnknownKind::InitialUnknown,
+                    value_reference: 4,
+                    dependencies: Some(vec![5]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fmi2_traits() {
+        let md: fmi_schema::fmi2::Fmi2ModelDescription = read("FMI2.xml");
+        check_bouncing_ball(&md);
+        assert_eq!(md.event_indicators().count(), 0);
+
+        // Only FMI 2.0 has the constant v_min, without a causality
+        let v_min = md.variable_by_name("v_min").unwrap();
+        assert_eq!(v_min.causality(), Causality::Local);
+        assert_eq!(v_min.variability(), Variability::Constant);
+        assert_eq!(v_min.initial(), None);
+    }
+
+    #[test]
+    fn test_fmi3_traits() {
+        let md: fmi_schema::fmi3::Fmi3ModelDescription = read("FMI3.xml");
+        check_bouncing_ball(&md);
+        assert_eq!(
+            md.event_indicators()
+                .map(|u| u.value_reference)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+    }
+}
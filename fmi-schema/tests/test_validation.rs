@@ -0,0 +1,25 @@
+//! The FMI2.xml and FMI3.xml files follow the rules of the standard
+
+#[cfg(all(feature = "fmi2", feature = "fmi3"))]
+mod both {
+    use fmi_schema::{traits::FmiModelDescription, validation::Validate};
+
+    fn read<MD: FmiModelDescription>(file: &str) -> MD {
+        let test_file = std::env::current_dir()
+            .map(|path| path.join("tests").join(file))
+            .unwrap();
+        MD::deserialize(&std::fs::read_to_string(test_file).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_fmi2_valid() {
+        let md: fmi_schema::fmi2::Fmi2ModelDescription = read("FMI2.xml");
+        assert_eq!(md.validate(), vec![]);
+    }
+
+    #[test]
+    fn test_fmi3_valid() {
+        let md: fmi_schema::fmi3::Fmi3ModelDescription = read("FMI3.xml");
+        assert_eq!(md.validate(), vec![]);
+    }
+}
@@ -0,0 +1,52 @@
+//! Check FMUs for compliance with the FMI standard, see [`fmi::validation`].
+//!
+//! ```text
+//! fmi-check <model.fmu|directory>...
+//! ```
+//!
+//! Prints the findings for each FMU. Exits with status 1 if any FMU violates the standard, or can
+//! not be read.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use fmi::validation::{Severity, validate_path};
+
+#[derive(Debug, Parser)]
+#[command(version)]
+/// Check FMUs for compliance with the FMI standard
+struct Args {
+    /// The FMU archives or directories of extracted FMUs to check
+    #[arg(required = true)]
+    paths: Vec<PathBuf>,
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let mut failed = false;
+    for path in &args.paths {
+        let path_str = path.display();
+        match validate_path(path) {
+            Ok(findings) => {
+                for finding in &findings {
+                    println!("{path_str}: {finding}");
+                }
+                let errors = findings
+                    .iter()
+                    .filter(|f| f.severity == Severity::Error)
+                    .count();
+                let warnings = findings.len() - errors;
+                println!("{path_str}: {errors} errors, {warnings} warnings");
+                failed |= errors > 0;
+            }
+            Err(e) => {
+                eprintln!("{path_str}: {e}");
+                failed = true;
+            }
+        }
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
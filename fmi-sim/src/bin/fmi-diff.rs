@@ -0,0 +1,108 @@
+//! Compare two versions of an FMU, see [`fmi::schema::diff`].
+//!
+//! ```text
+//! fmi-diff <old> <new> [<variable>...]
+//! ```
+//!
+//! `<old>` and `<new>` are FMU archives, directories of extracted FMUs, or model description XML
+//! files. Prints the differences of the model descriptions. If variables are given, also prints
+//! whether they are still compatible, and exits with status 1 if they are not.
+
+use std::{io::Read, path::Path, path::PathBuf};
+
+use clap::Parser;
+use fmi::schema::{
+    MajorVersion,
+    diff::{Diff, ModelDiff},
+    minimal::MinModelDescription,
+    traits::FmiModelDescription,
+};
+
+const MODEL_DESCRIPTION: &str = "modelDescription.xml";
+
+#[derive(Debug, Parser)]
+#[command(version)]
+/// Compare two versions of an FMU
+struct Args {
+    /// The old FMU archive, directory of an extracted FMU, or model description XML file
+    old: PathBuf,
+
+    /// The new FMU archive, directory of an extracted FMU, or model description XML file
+    new: PathBuf,
+
+    /// The variables used from the old version, to check for compatibility
+    used: Vec<String>,
+}
+
+/// The model description XML of the FMU at `path`
+fn read_model_description(path: &Path) -> Result<String, fmi::Error> {
+    if path.is_dir() {
+        Ok(std::fs::read_to_string(path.join(MODEL_DESCRIPTION))?)
+    } else if path.extension().is_some_and(|ext| ext == "xml") {
+        Ok(std::fs::read_to_string(path)?)
+    } else {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?)?;
+        let mut xml = String::new();
+        archive
+            .by_name(MODEL_DESCRIPTION)?
+            .read_to_string(&mut xml)?;
+        Ok(xml)
+    }
+}
+
+fn diff_as<MD: FmiModelDescription + Diff>(old: &str, new: &str) -> Result<ModelDiff, String> {
+    let old = MD::deserialize(old).map_err(|e| e.to_string())?;
+    let new = MD::deserialize(new).map_err(|e| e.to_string())?;
+    Ok(old.diff(&new))
+}
+
+fn diff(old: &str, new: &str) -> Result<ModelDiff, String> {
+    let version = |xml: &str| {
+        MinModelDescription::deserialize(xml)
+            .and_then(|md| md.major_version())
+            .map_err(|e| e.to_string())
+    };
+    match (version(old)?, version(new)?) {
+        (MajorVersion::FMI2, MajorVersion::FMI2) => {
+            diff_as::<fmi::schema::fmi2::Fmi2ModelDescription>(old, new)
+        }
+        (MajorVersion::FMI3, MajorVersion::FMI3) => {
+            diff_as::<fmi::schema::fmi3::Fmi3ModelDescription>(old, new)
+        }
+        (old, new) if old != new => Err(format!("can not compare FMI {old} with FMI {new}")),
+        (version, _) => Err(format!("FMI {version} is not supported")),
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let read = |path: &Path| {
+        read_model_description(path).unwrap_or_else(|e| {
+            eprintln!("{}: {e}", path.display());
+            std::process::exit(1);
+        })
+    };
+    let diff = match diff(&read(&args.old), &read(&args.new)) {
+        Ok(diff) => diff,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(1);
+        }
+    };
+    print!("{diff}");
+
+    if args.used.is_empty() {
+        return;
+    }
+    let compatibility = diff.compatibility(args.used.iter().map(String::as_str));
+    for finding in &compatibility.findings {
+        println!("{finding}");
+    }
+    if compatibility.is_compatible() {
+        println!("compatible");
+    } else {
+        println!("incompatible");
+        std::process::exit(1);
+    }
+}
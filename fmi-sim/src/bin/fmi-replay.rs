@@ -0,0 +1,141 @@
+//! Replay a trace recorded with [`fmi::fmi2::trace::TracedInstance`] or
+//! [`fmi::fmi3::trace::TracedInstance`] against an FMU, and report where the results differ.
+//!
+//! ```text
+//! fmi-replay <model.fmu> <trace.jsonl> [--tolerance <tolerance>]
+//! ```
+//!
+//! Exits with status 1 if any result differs, or the trace can not be replayed. Logging is
+//! configured with the `RUST_LOG` environment variable, and defaults to warnings.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use fmi::{
+    Error,
+    fmi2::import::Fmi2Import,
+    fmi3::{Fmi3Model, import::Fmi3Import},
+    trace::{Mismatch, TraceCall, TraceHeader, read_trace},
+};
+
+#[derive(Debug, Parser)]
+#[command(version)]
+/// Replay a trace against an FMU, and report where the results differ
+struct Args {
+    /// The FMU to replay the trace against
+    fmu: PathBuf,
+
+    /// The trace recorded by a `TracedInstance`
+    trace: PathBuf,
+
+    /// The tolerance for comparing real results
+    #[arg(long, default_value_t = 0.0)]
+    tolerance: f64,
+}
+
+fn replay(args: &Args) -> Result<Vec<Mismatch>, Error> {
+    let file = std::fs::File::open(&args.trace)?;
+    let (header, calls) = read_trace(std::io::BufReader::new(file))?;
+    log::info!(
+        "Replaying {} calls of {} instance '{}'",
+        calls.len(),
+        header.interface,
+        header.instance_name
+    );
+    match header.fmi_version.as_str() {
+        "2.0" => replay_fmi2(args, &header, &calls),
+        "3.0" => replay_fmi3(args, &header, &calls),
+        version => Err(Error::Trace(format!("unsupported FMI version {version}"))),
+    }
+}
+
+fn replay_fmi2(
+    args: &Args,
+    header: &TraceHeader,
+    calls: &[TraceCall],
+) -> Result<Vec<Mismatch>, Error> {
+    use fmi::fmi2::trace::{replay_cs, replay_me};
+
+    let import: Fmi2Import = fmi::import::from_path(&args.fmu)?;
+    match header.interface.as_str() {
+        "ModelExchange" => {
+            let instance = import.instantiate_me(&header.instance_name, false, true)?;
+            replay_me(instance, calls, args.tolerance)
+        }
+        "CoSimulation" => {
+            let instance = import.instantiate_cs(&header.instance_name, false, true)?;
+            replay_cs(instance, calls, args.tolerance)
+        }
+        interface => Err(Error::Trace(format!("unsupported interface {interface}"))),
+    }
+}
+
+fn replay_fmi3(
+    args: &Args,
+    header: &TraceHeader,
+    calls: &[TraceCall],
+) -> Result<Vec<Mismatch>, Error> {
+    use fmi::fmi3::trace::{replay_cs, replay_me, replay_se};
+
+    let import: Fmi3Import = fmi::import::from_path(&args.fmu)?;
+    match header.interface.as_str() {
+        "ModelExchange" => {
+            let instance = import.instantiate_me(&header.instance_name, false, true)?;
+            replay_me(instance, calls, args.tolerance)
+        }
+        "CoSimulation" => {
+            // The instantiation arguments are not part of the trace, infer them from the calls
+            let event_mode_used = calls.iter().any(|call| {
+                matches!(
+                    call.function.as_str(),
+                    "fmi3EnterEventMode" | "fmi3EnterStepMode"
+                )
+            });
+            let early_return_allowed = calls.iter().any(|call| {
+                call.function == "fmi3DoStep"
+                    && call.args.get("early_return") == Some(&serde_json::Value::Bool(true))
+            });
+            let instance = import.instantiate_cs(
+                &header.instance_name,
+                false,
+                true,
+                event_mode_used,
+                early_return_allowed,
+                &[],
+            )?;
+            replay_cs(instance, calls, args.tolerance)
+        }
+        "ScheduledExecution" => {
+            let instance = import.instantiate_se(&header.instance_name, false, true)?;
+            replay_se(instance, calls, args.tolerance)
+        }
+        interface => Err(Error::Trace(format!("unsupported interface {interface}"))),
+    }
+}
+
+fn main() {
+    let _logger = flexi_logger::Logger::try_with_env_or_str("warn")
+        .and_then(|logger| logger.start())
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            std::process::exit(2);
+        });
+
+    let args = Args::parse();
+    match replay(&args) {
+        Ok(mismatches) if mismatches.is_empty() => {
+            println!("Replay matches the trace");
+        }
+        Ok(mismatches) => {
+            for mismatch in &mismatches {
+                println!("{mismatch}");
+            }
+            println!("{} mismatches", mismatches.len());
+            std::process::exit(1);
+        }
+        Err(e) => {
+            log::error!("{e}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -37,6 +37,11 @@ pub fn simulate(options: &options::FmiSimOptions) -> Result<(RecordBatch, SimSta
 
     log::debug!("Loaded {mini_descr:?}");
 
+    #[cfg(feature = "remote")]
+    if options.remote && version != MajorVersion::FMI3 {
+        log::warn!("Remote workers are only supported for FMI 3.0, simulating in process");
+    }
+
     // Read optional input data
     let input_data = options
         .input_file
@@ -46,7 +51,17 @@ pub fn simulate(options: &options::FmiSimOptions) -> Result<(RecordBatch, SimSta
         .transpose()?;
 
     match version {
-        MajorVersion::FMI1 => Err(fmi::Error::UnsupportedFmiVersion(version).into()),
+        #[cfg(feature = "fmi1")]
+        MajorVersion::FMI1 => {
+            let import: fmi::fmi1::import::Fmi1Import = fmi::import::from_path(&options.model)?;
+            if options.display_units {
+                log::warn!("Display units are not supported for FMI 1.0, using units");
+            }
+            if options.item_names {
+                log::warn!("Item names are not supported for FMI 1.0, using values");
+            }
+            sim::simulate_with(input_data, &options.interface, import)
+        }
 
         #[cfg(feature = "fmi2")]
         MajorVersion::FMI2 => {
@@ -60,13 +75,31 @@ pub fn simulate(options: &options::FmiSimOptions) -> Result<(RecordBatch, SimSta
             //    crate::logging::register_fmu_log_categories(category_names);
             //}
 
-            sim::simulate_with(input_data, &options.interface, import)
+            sim::simulate_with_options(input_data, options, import)
         }
 
         #[cfg(feature = "fmi3")]
         MajorVersion::FMI3 => {
             let import: fmi::fmi3::import::Fmi3Import = fmi::import::from_path(&options.model)?;
 
+            #[cfg(feature = "remote")]
+            if options.remote {
+                let mut remote_options = fmi::fmi3::remote::RemoteOptions {
+                    timeout: options.remote_timeout,
+                    ..Default::default()
+                };
+                if let Some(worker) = &options.remote_worker {
+                    remote_options.worker = worker.clone();
+                }
+                let import = fmi::fmi3::remote::RemoteImport::new(import, remote_options);
+                return sim::simulate_described(
+                    input_data,
+                    options,
+                    import.model_description(),
+                    &import,
+                );
+            }
+
             // Register FMU log categories for proper filtering
             //if let Some(log_categories) = &import.model_description().log_categories {
             //    let category_names = log_categories.categories.iter()
@@ -75,7 +108,10 @@ pub fn simulate(options: &options::FmiSimOptions) -> Result<(RecordBatch, SimSta
             //    crate::logging::register_fmu_log_categories(category_names);
             //}
 
-            sim::simulate_with(input_data, &options.interface, import)
+            sim::simulate_with_options(input_data, options, import)
         }
+
+        #[allow(unreachable_patterns)]
+        _ => Err(fmi::Error::UnsupportedFmiVersion(version).into()),
     }
 }
@@ -96,8 +96,9 @@ pub struct CommonOptions {
 
     /// List of initial values to set before simulation starts. The format is
     /// "variableName=value", where variableName is the name of the variable and value is the
-    /// value to set. The value must be of the same type as the variable. The variable name must
-    /// be a valid FMI variable name, i.e. it must be a valid identifier and it must be unique.
+    /// value to set. The value must be of the same type as the variable, or the name of an item
+    /// for enumeration variables. The variable name must be a valid FMI variable name, i.e. it
+    /// must be a valid identifier and it must be unique.
     #[arg(long = "init-values")]
     pub initial_values: Vec<String>,
 
@@ -165,7 +166,55 @@ pub struct FmiSimOptions {
     /// according to the CrossCheck rules).
     #[arg(short = 'm')]
     pub mangle_names: bool,
+    /// Read the input data and write the results in the display units of the variables, instead
+    /// of their units. Only supported for FMI 2.0 and 3.0.
+    #[arg(long)]
+    pub display_units: bool,
+    /// Write the values of enumeration outputs as the names of their items, instead of integers.
+    /// Only supported for FMI 2.0 and 3.0.
+    #[arg(long)]
+    pub item_names: bool,
+    /// Run the FMU in a separate `fmi-worker` process, so that a crash or hang of the FMU is
+    /// reported as an error. Only supported for FMI 3.0.
+    #[cfg(feature = "remote")]
+    #[arg(long)]
+    pub remote: bool,
+    /// Path of the `fmi-worker` binary. Default is the `FMI_WORKER` environment variable, then
+    /// `fmi-worker` next to `fmi-sim`, then on the `PATH`.
+    #[cfg(feature = "remote")]
+    #[arg(long, requires = "remote")]
+    pub remote_worker: Option<std::path::PathBuf>,
+    /// Time in seconds to wait for the worker to reply to a single call before killing it.
+    /// Default is to wait indefinitely.
+    #[cfg(feature = "remote")]
+    #[arg(long, requires = "remote", value_parser = parse_seconds)]
+    pub remote_timeout: Option<std::time::Duration>,
     /// Verbosity level. Use -v for info, -vv for debug, -vvv for trace. Also controls FMU log messages.
     #[command(flatten)]
     pub verbose: clap_verbosity_flag::Verbosity,
 }
+
+/// Parse a duration given as a non-negative, finite number of seconds.
+#[cfg(feature = "remote")]
+fn parse_seconds(arg: &str) -> Result<std::time::Duration, String> {
+    let seconds: f64 = arg.parse().map_err(|e| format!("{e}"))?;
+    std::time::Duration::try_from_secs_f64(seconds)
+        .map_err(|_| format!("expected a non-negative, finite number of seconds, got {arg}"))
+}
+
+#[cfg(all(test, feature = "remote"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_seconds() {
+        assert_eq!(
+            parse_seconds("1.5"),
+            Ok(std::time::Duration::from_millis(1500))
+        );
+        assert_eq!(parse_seconds("0"), Ok(std::time::Duration::ZERO));
+        for arg in ["-1", "inf", "NaN", "1e300", "soon"] {
+            assert!(parse_seconds(arg).is_err(), "{arg}");
+        }
+    }
+}
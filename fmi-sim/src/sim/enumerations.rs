@@ -0,0 +1,193 @@
+//! Conversion of input and output data between the values and the item names of enumeration
+//! variables.
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        Array, ArrayRef, AsArray, DictionaryArray, Int32Array, Int64Array, RecordBatch, StringArray,
+    },
+    datatypes::{DataType, Field, Int64Type, Schema},
+};
+use fmi::schema::{enumerations::FmiEnumerations, traits::FmiVariable};
+
+use crate::Error;
+
+/// Convert the string columns of `batch` that are named after an enumeration variable from item
+/// names to the values of the variable. Integer strings are accepted as values. Other columns are
+/// passed through.
+pub fn from_item_names<MD: FmiEnumerations>(
+    md: &MD,
+    batch: &RecordBatch,
+) -> Result<RecordBatch, Error> {
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let enumeration = md
+            .variable_by_name(field.name())
+            .filter(|_| field.data_type() == &DataType::Utf8)
+            .map(|var| md.variable_enumeration(var).map(|e| (var, e)))
+            .transpose()
+            .map_err(fmi::Error::from)?;
+        let Some((var, Some(enumeration))) = enumeration else {
+            fields.push(field.clone());
+            columns.push(column.clone());
+            continue;
+        };
+
+        let values = column
+            .as_string::<i32>()
+            .iter()
+            .map(|name| name.map(|name| enumeration.parse(name)).transpose())
+            .collect::<Result<Int64Array, _>>()
+            .map_err(fmi::Error::from)?;
+        let values = arrow::compute::cast(&values, &var.arrow_data_type())?;
+        fields.push(Arc::new(
+            field
+                .as_ref()
+                .clone()
+                .with_data_type(values.data_type().clone()),
+        ));
+        columns.push(values);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+/// Convert the integer columns of `batch` that are named after an enumeration variable to
+/// dictionary arrays of the item names. Other columns are passed through.
+pub fn to_item_names<MD: FmiEnumerations>(
+    md: &MD,
+    batch: &RecordBatch,
+) -> Result<RecordBatch, Error> {
+    let mut fields = Vec::with_capacity(batch.num_columns());
+    let mut columns = Vec::with_capacity(batch.num_columns());
+
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        let enumeration = md
+            .variable_by_name(field.name())
+            .filter(|_| field.data_type().is_integer())
+            .map(|var| md.variable_enumeration(var))
+            .transpose()
+            .map_err(fmi::Error::from)?
+            .flatten();
+        let Some(enumeration) = enumeration else {
+            fields.push(field.clone());
+            columns.push(column.clone());
+            continue;
+        };
+
+        // The keys of the dictionary are the indices of the items
+        let key = |value: i64| {
+            enumeration.validate(value)?;
+            let index = enumeration
+                .items
+                .iter()
+                .position(|item| item.value == value);
+            Ok::<_, fmi::schema::Error>(index.unwrap_or_default() as i32)
+        };
+        let values = arrow::compute::cast(column, &DataType::Int64)?;
+        let keys = values
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|value| value.map(key).transpose())
+            .collect::<Result<Int32Array, _>>()
+            .map_err(fmi::Error::from)?;
+        let names = StringArray::from_iter_values(enumeration.items.iter().map(|i| &i.name));
+        let names: ArrayRef = Arc::new(DictionaryArray::try_new(keys, Arc::new(names))?);
+
+        fields.push(Arc::new(Field::new(
+            field.name(),
+            names.data_type().clone(),
+            field.is_nullable(),
+        )));
+        columns.push(names);
+    }
+
+    Ok(RecordBatch::try_new(
+        Arc::new(Schema::new(fields)),
+        columns,
+    )?)
+}
+
+#[cfg(all(test, feature = "fmi3"))]
+mod tests {
+    use arrow::{array::Float64Array, datatypes::Int32Type};
+    use fmi::fmi3::schema::Fmi3ModelDescription;
+
+    use super::*;
+
+    #[test]
+    fn test_item_names() {
+        let md: Fmi3ModelDescription = fmi::schema::deserialize(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Gear" instantiationToken="">
+    <TypeDefinitions>
+        <EnumerationType name="Direction">
+            <Item name="forward" value="1"/>
+            <Item name="reverse" value="-1"/>
+        </EnumerationType>
+    </TypeDefinitions>
+    <ModelVariables>
+        <Enumeration name="direction" valueReference="1" declaredType="Direction"/>
+        <String name="label" valueReference="2"/>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("time", DataType::Float64, false),
+            Field::new("direction", DataType::Utf8, false),
+            Field::new("label", DataType::Utf8, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Float64Array::from(vec![0.0, 1.0, 2.0])),
+                Arc::new(StringArray::from(vec!["forward", "reverse", "1"])),
+                Arc::new(StringArray::from(vec!["a", "b", "c"])),
+            ],
+        )
+        .unwrap();
+
+        let values = from_item_names(&md, &batch).unwrap();
+        assert_eq!(values.column(0), batch.column(0));
+        assert_eq!(
+            values.column(1).as_primitive::<Int64Type>().values(),
+            &[1, -1, 1]
+        );
+        assert_eq!(values.column(2), batch.column(2));
+
+        let names = to_item_names(&md, &values).unwrap();
+        let direction = names.column(1).as_dictionary::<Int32Type>();
+        let direction = direction.downcast_dict::<StringArray>().unwrap();
+        assert_eq!(
+            direction.into_iter().collect::<Vec<_>>(),
+            vec![Some("forward"), Some("reverse"), Some("forward")]
+        );
+        assert_eq!(names.column(2), batch.column(2));
+
+        let mut csv = vec![];
+        arrow::csv::Writer::new(&mut csv).write(&names).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "time,direction,label\n0.0,forward,a\n1.0,reverse,b\n2.0,forward,c\n"
+        );
+
+        let invalid = RecordBatch::try_new(
+            batch.schema(),
+            vec![
+                batch.column(0).clone(),
+                Arc::new(StringArray::from(vec!["forward", "sideways", "0"])),
+                batch.column(2).clone(),
+            ],
+        )
+        .unwrap();
+        assert!(from_item_names(&md, &invalid).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+//! FMI1-specific input and output implementation
+
+use fmi::fmi1::binding;
+
+use crate::sim::io::{impl_scalar_record_values, impl_scalar_set_values};
+
+#[cfg(feature = "cs")]
+impl_scalar_set_values!(fmi::fmi1::instance::InstanceCS, binding::fmiBoolean);
+#[cfg(feature = "cs")]
+impl_scalar_record_values!(fmi::fmi1::instance::InstanceCS);
+
+#[cfg(feature = "me")]
+impl_scalar_set_values!(fmi::fmi1::instance::InstanceME, binding::fmiBoolean);
+#[cfg(feature = "me")]
+impl_scalar_record_values!(fmi::fmi1::instance::InstanceME);
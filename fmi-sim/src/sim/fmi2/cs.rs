@@ -43,9 +43,9 @@ impl SimApplyStartValues<InstanceCS> for SimState<InstanceCS> {
         &mut self,
         start_values: &StartValues<<InstanceCS as FmiInstance>::ValueRef>,
     ) -> Result<(), Error> {
-        start_values.variables.iter().for_each(|(vr, ary)| {
-            self.inst.set_array(&[*vr], ary);
-        });
+        for (vr, ary) in &start_values.variables {
+            self.inst.set_array(&[*vr], ary)?;
+        }
         Ok(())
     }
 }
@@ -38,9 +38,9 @@ impl SimApplyStartValues<InstanceME> for SimState<InstanceME> {
         &mut self,
         start_values: &StartValues<<InstanceME as FmiInstance>::ValueRef>,
     ) -> Result<(), Error> {
-        start_values.variables.iter().for_each(|(vr, ary)| {
-            self.inst.set_array(&[*vr], ary);
-        });
+        for (vr, ary) in &start_values.variables {
+            self.inst.set_array(&[*vr], ary)?;
+        }
         Ok(())
     }
 }
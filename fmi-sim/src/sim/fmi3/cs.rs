@@ -1,8 +1,8 @@
 use anyhow::Context;
 use fmi::{
     EventFlags,
-    fmi3::{CoSimulation, Fmi3Model, import::Fmi3Import, instance::InstanceCS},
-    traits::FmiInstance,
+    fmi3::{CoSimulation, Common, Fmi3Model},
+    traits::FmiEventHandler,
 };
 
 use crate::{
@@ -11,38 +11,59 @@ use crate::{
         InputState, RecorderState, SimState, SimStateTrait, SimStats,
         interpolation::Linear,
         params::SimParams,
-        traits::{InstRecordValues, SimHandleEvents},
+        traits::{InstRecordValues, InstSetValues, SimCs, SimHandleEvents},
     },
 };
 
-impl SimStateTrait<InstanceCS, Fmi3Import> for SimState<InstanceCS> {
-    fn new(
-        import: &Fmi3Import,
-        sim_params: SimParams,
-        input_state: InputState<InstanceCS>,
-        output_state: RecorderState<InstanceCS>,
-    ) -> Result<Self, Error> {
-        let inst = import.instantiate_cs(
-            "inst1",
-            true,
-            true,
-            sim_params.event_mode_used,
-            sim_params.early_return_allowed,
-            &[],
-        )?;
-        Ok(Self {
-            sim_params,
-            input_state,
-            recorder_state: output_state,
-            inst,
-            event_flags: EventFlags::default(),
-        })
-    }
+macro_rules! impl_sim_state_cs {
+    ($import:ty, $inst:ty) => {
+        impl SimStateTrait<$inst, $import> for SimState<$inst> {
+            fn new(
+                import: &$import,
+                sim_params: SimParams,
+                input_state: InputState<$inst>,
+                output_state: RecorderState<$inst>,
+            ) -> Result<Self, Error> {
+                let inst = import.instantiate_cs(
+                    "inst1",
+                    true,
+                    true,
+                    sim_params.event_mode_used,
+                    sim_params.early_return_allowed,
+                    &[],
+                )?;
+                Ok(Self {
+                    sim_params,
+                    input_state,
+                    recorder_state: output_state,
+                    inst,
+                    event_flags: EventFlags::default(),
+                })
+            }
+        }
+    };
 }
 
-impl SimState<InstanceCS> {
-    /// Main loop of the co-simulation
-    pub fn main_loop(&mut self) -> Result<SimStats, Error> {
+impl_sim_state_cs!(
+    fmi::fmi3::import::Fmi3Import,
+    fmi::fmi3::instance::InstanceCS
+);
+#[cfg(feature = "mock")]
+impl_sim_state_cs!(
+    fmi::fmi3::mock::MockImport<fmi::fmi3::mock::OdeInstance>,
+    fmi::fmi3::mock::MockCoSimulation<fmi::fmi3::mock::OdeInstance>
+);
+#[cfg(feature = "remote")]
+impl_sim_state_cs!(
+    fmi::fmi3::remote::RemoteImport,
+    fmi::fmi3::remote::RemoteInstance<fmi::CS>
+);
+
+impl<Inst> SimCs for SimState<Inst>
+where
+    Inst: CoSimulation + FmiEventHandler + InstSetValues + InstRecordValues,
+{
+    fn main_loop(&mut self) -> Result<SimStats, Error> {
         let mut stats = SimStats::default();
 
         if self.sim_params.event_mode_used {
@@ -127,7 +148,9 @@ impl SimState<InstanceCS> {
             }
         }
 
-        self.inst.terminate().ok().context("terminate")?;
+        Common::terminate(&mut self.inst)
+            .ok()
+            .context("terminate")?;
 
         stats.end_time = time;
         Ok(stats)
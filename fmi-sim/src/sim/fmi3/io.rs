@@ -122,44 +122,44 @@ macro_rules! impl_record_values {
 macro_rules! impl_set_values {
     ($t:ty) => {
         impl InstSetValues for $t {
-            fn set_array(&mut self, vrs: &[Self::ValueRef], values: &ArrayRef) {
+            fn set_array(&mut self, vrs: &[Self::ValueRef], values: &ArrayRef) -> anyhow::Result<()> {
                 match values.data_type() {
                     DataType::Boolean => {
                         let values = values.as_boolean().iter().map(|x| x.unwrap()).collect_vec();
-                        self.set_boolean(vrs, &values).unwrap();
+                        self.set_boolean(vrs, &values)?;
                     }
                     DataType::Int8 => {
-                        self.set_int8(vrs, values.as_primitive::<Int8Type>().values()).unwrap();
+                        self.set_int8(vrs, values.as_primitive::<Int8Type>().values())?;
                     }
                     DataType::Int16 => {
-                        self.set_int16(vrs, values.as_primitive::<Int16Type>().values()).unwrap();
+                        self.set_int16(vrs, values.as_primitive::<Int16Type>().values())?;
                     }
                     DataType::Int32 => {
-                        self.set_int32(vrs, values.as_primitive::<Int32Type>().values()).unwrap();
+                        self.set_int32(vrs, values.as_primitive::<Int32Type>().values())?;
                     }
                     DataType::Int64 => {
-                        self.set_int64(vrs, values.as_primitive::<Int64Type>().values()).unwrap();
+                        self.set_int64(vrs, values.as_primitive::<Int64Type>().values())?;
                     }
                     DataType::UInt8 => {
-                        self.set_uint8(vrs, values.as_primitive::<UInt8Type>().values()).unwrap();
+                        self.set_uint8(vrs, values.as_primitive::<UInt8Type>().values())?;
                     }
                     DataType::UInt16 => {
-                        self.set_uint16(vrs, values.as_primitive::<UInt16Type>().values()).unwrap();
+                        self.set_uint16(vrs, values.as_primitive::<UInt16Type>().values())?;
                     }
                     DataType::UInt32 => {
-                        self.set_uint32(vrs, values.as_primitive::<UInt32Type>().values()).unwrap();
+                        self.set_uint32(vrs, values.as_primitive::<UInt32Type>().values())?;
                     }
                     DataType::UInt64 => {
-                        self.set_uint64(vrs, values.as_primitive::<UInt64Type>().values()).unwrap();
+                        self.set_uint64(vrs, values.as_primitive::<UInt64Type>().values())?;
                     }
                     DataType::Float16 => {
                         unimplemented!()
                     }
                     DataType::Float32 => {
-                        self.set_float32(vrs, values.as_primitive::<Float32Type>().values()).unwrap();
+                        self.set_float32(vrs, values.as_primitive::<Float32Type>().values())?;
                     }
                     DataType::Float64 => {
-                        self.set_float64(vrs, values.as_primitive::<Float64Type>().values()).unwrap();
+                        self.set_float64(vrs, values.as_primitive::<Float64Type>().values())?;
                     }
                     DataType::Binary => {
                         let binary_refs: Vec<&[u8]> = values
@@ -183,6 +183,7 @@ macro_rules! impl_set_values {
                     DataType::LargeUtf8 => todo!(),
                     _ => unimplemented!("Unsupported data type"),
                 }
+                Ok(())
             }
 
             fn set_interpolated<I: Interpolate>(
@@ -270,3 +271,23 @@ impl_record_values!(fmi::fmi3::instance::InstanceCS);
 impl_set_values!(fmi::fmi3::instance::InstanceME);
 #[cfg(feature = "me")]
 impl_record_values!(fmi::fmi3::instance::InstanceME);
+
+#[cfg(all(feature = "mock", feature = "cs"))]
+impl_set_values!(fmi::fmi3::mock::MockCoSimulation<fmi::fmi3::mock::OdeInstance>);
+#[cfg(all(feature = "mock", feature = "cs"))]
+impl_record_values!(fmi::fmi3::mock::MockCoSimulation<fmi::fmi3::mock::OdeInstance>);
+
+#[cfg(all(feature = "mock", feature = "me"))]
+impl_set_values!(fmi::fmi3::mock::OdeInstance);
+#[cfg(all(feature = "mock", feature = "me"))]
+impl_record_values!(fmi::fmi3::mock::OdeInstance);
+
+#[cfg(all(feature = "remote", feature = "cs"))]
+impl_set_values!(fmi::fmi3::remote::RemoteInstance<fmi::CS>);
+#[cfg(all(feature = "remote", feature = "cs"))]
+impl_record_values!(fmi::fmi3::remote::RemoteInstance<fmi::CS>);
+
+#[cfg(all(feature = "remote", feature = "me"))]
+impl_set_values!(fmi::fmi3::remote::RemoteInstance<fmi::ME>);
+#[cfg(all(feature = "remote", feature = "me"))]
+impl_record_values!(fmi::fmi3::remote::RemoteInstance<fmi::ME>);
@@ -1,27 +1,43 @@
-use fmi::{
-    EventFlags,
-    fmi3::{Fmi3Model, import::Fmi3Import, instance::InstanceME},
-};
+use fmi::{EventFlags, fmi3::Fmi3Model};
 
 use crate::{
     Error,
     sim::{InputState, RecorderState, SimState, SimStateTrait, params::SimParams},
 };
 
-impl SimStateTrait<InstanceME, Fmi3Import> for SimState<InstanceME> {
-    fn new(
-        import: &Fmi3Import,
-        sim_params: SimParams,
-        input_state: InputState<InstanceME>,
-        recorder_state: RecorderState<InstanceME>,
-    ) -> Result<Self, Error> {
-        let inst = import.instantiate_me("inst1", true, true)?;
-        Ok(Self {
-            sim_params,
-            input_state,
-            recorder_state,
-            inst,
-            event_flags: EventFlags::default(),
-        })
-    }
+macro_rules! impl_sim_state_me {
+    ($import:ty, $inst:ty) => {
+        impl SimStateTrait<$inst, $import> for SimState<$inst> {
+            fn new(
+                import: &$import,
+                sim_params: SimParams,
+                input_state: InputState<$inst>,
+                recorder_state: RecorderState<$inst>,
+            ) -> Result<Self, Error> {
+                let inst = import.instantiate_me("inst1", true, true)?;
+                Ok(Self {
+                    sim_params,
+                    input_state,
+                    recorder_state,
+                    inst,
+                    event_flags: EventFlags::default(),
+                })
+            }
+        }
+    };
 }
+
+impl_sim_state_me!(
+    fmi::fmi3::import::Fmi3Import,
+    fmi::fmi3::instance::InstanceME
+);
+#[cfg(feature = "mock")]
+impl_sim_state_me!(
+    fmi::fmi3::mock::MockImport<fmi::fmi3::mock::OdeInstance>,
+    fmi::fmi3::mock::OdeInstance
+);
+#[cfg(feature = "remote")]
+impl_sim_state_me!(
+    fmi::fmi3::remote::RemoteImport,
+    fmi::fmi3::remote::RemoteInstance<fmi::ME>
+);
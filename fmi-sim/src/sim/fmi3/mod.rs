@@ -1,7 +1,7 @@
 use arrow::array::RecordBatch;
 
 use fmi::{
-    fmi3::{Common, import::Fmi3Import},
+    fmi3::{Common, Fmi3Model, import::Fmi3Import},
     traits::{FmiImport, FmiInstance},
 };
 
@@ -41,16 +41,16 @@ macro_rules! impl_sim_apply_start_values {
                         .map_err(fmi::Error::from)?;
                     for (vr, ary) in &start_values.structural_parameters {
                         //log::trace!("Setting structural parameter `{}`", (*vr).into());
-                        self.inst.set_array(&[(*vr)], ary);
+                        InstSetValues::set_array(&mut self.inst, &[*vr], ary)?;
                     }
                     self.inst
                         .exit_configuration_mode()
                         .map_err(fmi::Error::from)?;
                 }
 
-                start_values.variables.iter().for_each(|(vr, ary)| {
-                    self.inst.set_array(&[*vr], ary);
-                });
+                for (vr, ary) in &start_values.variables {
+                    InstSetValues::set_array(&mut self.inst, &[*vr], ary)?;
+                }
 
                 Ok(())
             }
@@ -62,71 +62,100 @@ macro_rules! impl_sim_apply_start_values {
 impl_sim_apply_start_values!(fmi::fmi3::instance::InstanceME);
 #[cfg(feature = "cs")]
 impl_sim_apply_start_values!(fmi::fmi3::instance::InstanceCS);
+#[cfg(all(feature = "mock", feature = "me"))]
+impl_sim_apply_start_values!(fmi::fmi3::mock::OdeInstance);
+#[cfg(all(feature = "mock", feature = "cs"))]
+impl_sim_apply_start_values!(fmi::fmi3::mock::MockCoSimulation<fmi::fmi3::mock::OdeInstance>);
+#[cfg(all(feature = "remote", feature = "me"))]
+impl_sim_apply_start_values!(fmi::fmi3::remote::RemoteInstance<fmi::ME>);
+#[cfg(all(feature = "remote", feature = "cs"))]
+impl_sim_apply_start_values!(fmi::fmi3::remote::RemoteInstance<fmi::CS>);
+
+macro_rules! impl_fmi_sim {
+    ($import:ty) => {
+        impl FmiSim for $import {
+            #[cfg(feature = "me")]
+            fn simulate_me(
+                &self,
+                options: &ModelExchangeOptions,
+                input_data: Option<RecordBatch>,
+            ) -> Result<(RecordBatch, SimStats), Error> {
+                use crate::sim::{solver, traits::SimMe};
+                use fmi::fmi3::ModelExchange;
+
+                type InstanceME = <$import as Fmi3Model>::InstanceME;
+
+                let sim_params = SimParams::new_from_options(
+                    &options.common,
+                    self.model_description(),
+                    true,
+                    false,
+                );
+
+                let start_values = self.parse_start_values(&options.common.initial_values)?;
+                let input_state = InputState::new(self, input_data)?;
+                let recorder_state = RecorderState::new(self, &sim_params);
+
+                let start_time = sim_params.start_time;
+                let tol = sim_params.tolerance.unwrap_or_default();
+
+                let mut sim_state =
+                    SimState::<InstanceME>::new(self, sim_params, input_state, recorder_state)?;
+
+                let nx = sim_state
+                    .inst
+                    .get_number_of_continuous_states()
+                    .map_err(|e| Error::from(fmi::Error::from(e)))?;
+                let nz = sim_state
+                    .inst
+                    .get_number_of_event_indicators()
+                    .map_err(|e| Error::from(fmi::Error::from(e)))?;
+
+                let solver: solver::Euler =
+                    solver::Solver::<InstanceME>::new(start_time, tol, nx, nz, ());
+
+                sim_state
+                    .initialize(start_values, options.common.initial_fmu_state_file.as_ref())?;
+                let stats = sim_state.main_loop(solver)?;
+
+                Ok((sim_state.recorder_state.finish(), stats))
+            }
 
-impl FmiSim for Fmi3Import {
-    #[cfg(feature = "me")]
-    fn simulate_me(
-        &self,
-        options: &ModelExchangeOptions,
-        input_data: Option<RecordBatch>,
-    ) -> Result<(RecordBatch, SimStats), Error> {
-        use crate::sim::{solver, traits::SimMe};
-        use fmi::fmi3::{ModelExchange, instance::InstanceME};
-
-        let sim_params =
-            SimParams::new_from_options(&options.common, self.model_description(), true, false);
-
-        let start_values = self.parse_start_values(&options.common.initial_values)?;
-        let input_state = InputState::new(self, input_data)?;
-        let recorder_state = RecorderState::new(self, &sim_params);
-
-        let start_time = sim_params.start_time;
-        let tol = sim_params.tolerance.unwrap_or_default();
-
-        let mut sim_state =
-            SimState::<InstanceME>::new(self, sim_params, input_state, recorder_state)?;
-
-        let nx = sim_state
-            .inst
-            .get_number_of_continuous_states()
-            .map_err(|e| Error::from(fmi::Error::from(e)))?;
-        let nz = sim_state
-            .inst
-            .get_number_of_event_indicators()
-            .map_err(|e| Error::from(fmi::Error::from(e)))?;
-
-        let solver: solver::Euler = solver::Solver::<InstanceME>::new(start_time, tol, nx, nz, ());
-
-        sim_state.initialize(start_values, options.common.initial_fmu_state_file.as_ref())?;
-        let stats = sim_state.main_loop(solver)?;
-
-        Ok((sim_state.recorder_state.finish(), stats))
-    }
-
-    #[cfg(feature = "cs")]
-    fn simulate_cs(
-        &self,
-        options: &CoSimulationOptions,
-        input_data: Option<RecordBatch>,
-    ) -> Result<(RecordBatch, SimStats), Error> {
-        use fmi::fmi3::instance::InstanceCS;
-
-        let sim_params = SimParams::new_from_options(
-            &options.common,
-            self.model_description(),
-            options.event_mode_used,
-            options.early_return_allowed,
-        );
-
-        let start_values = self.parse_start_values(&options.common.initial_values)?;
-        let input_state = InputState::new(self, input_data)?;
-        let output_state = RecorderState::new(self, &sim_params);
-
-        let mut sim_state =
-            SimState::<InstanceCS>::new(self, sim_params, input_state, output_state)?;
-        sim_state.initialize(start_values, options.common.initial_fmu_state_file.as_ref())?;
-        let stats = sim_state.main_loop()?;
-
-        Ok((sim_state.recorder_state.finish(), stats))
-    }
+            #[cfg(feature = "cs")]
+            fn simulate_cs(
+                &self,
+                options: &CoSimulationOptions,
+                input_data: Option<RecordBatch>,
+            ) -> Result<(RecordBatch, SimStats), Error> {
+                use crate::sim::traits::SimCs;
+
+                type InstanceCS = <$import as Fmi3Model>::InstanceCS;
+
+                let sim_params = SimParams::new_from_options(
+                    &options.common,
+                    self.model_description(),
+                    options.event_mode_used,
+                    options.early_return_allowed,
+                );
+
+                let start_values = self.parse_start_values(&options.common.initial_values)?;
+                let input_state = InputState::new(self, input_data)?;
+                let output_state = RecorderState::new(self, &sim_params);
+
+                let mut sim_state =
+                    SimState::<InstanceCS>::new(self, sim_params, input_state, output_state)?;
+                sim_state
+                    .initialize(start_values, options.common.initial_fmu_state_file.as_ref())?;
+                let stats = sim_state.main_loop()?;
+
+                Ok((sim_state.recorder_state.finish(), stats))
+            }
+        }
+    };
 }
+
+impl_fmi_sim!(Fmi3Import);
+#[cfg(feature = "mock")]
+impl_fmi_sim!(fmi::fmi3::mock::MockImport<fmi::fmi3::mock::OdeInstance>);
+#[cfg(feature = "remote")]
+impl_fmi_sim!(fmi::fmi3::remote::RemoteImport);
@@ -138,7 +138,7 @@ where
 
                         //log::trace!( "Applying discrete input {}={values:#?} at time {time:.2}", field.name());
 
-                        inst.set_array(&[*vr], values);
+                        inst.set_array(&[*vr], values)?;
                     }
                 }
             }
@@ -250,3 +250,153 @@ where
         RecordBatch::try_new(schema, columns).unwrap()
     }
 }
+
+/// Implements [`InstSetValues`] for an FMI 1.0 or FMI 2.0 instance type.
+///
+/// Both versions have the same `Real`, `Integer`, `Boolean` and `String` variable types, they only
+/// differ in the integer type used for `Boolean` values. The `get_*`/`set_*` methods of the
+/// instance must be in scope where the macro is invoked.
+#[cfg(any(feature = "fmi1", feature = "fmi2"))]
+macro_rules! impl_scalar_set_values {
+    ($inst:ty, $boolean:ty) => {
+        impl $crate::sim::traits::InstSetValues for $inst {
+            fn set_array(
+                &mut self,
+                vrs: &[Self::ValueRef],
+                values: &::arrow::array::ArrayRef,
+            ) -> anyhow::Result<()> {
+                use ::arrow::{array::AsArray, datatypes};
+
+                match values.data_type() {
+                    datatypes::DataType::Boolean => {
+                        let values = values
+                            .as_boolean()
+                            .iter()
+                            .map(|x| x.unwrap_or_default() as $boolean)
+                            .collect::<Vec<_>>();
+                        self.set_boolean(vrs, &values)?;
+                    }
+                    datatypes::DataType::Int32 => {
+                        self.set_integer(
+                            vrs,
+                            values.as_primitive::<datatypes::Int32Type>().values(),
+                        )?;
+                    }
+                    datatypes::DataType::Float64 => {
+                        self.set_real(
+                            vrs,
+                            values.as_primitive::<datatypes::Float64Type>().values(),
+                        )?;
+                    }
+                    datatypes::DataType::Utf8 => {
+                        let cstrings = values
+                            .as_string::<i32>()
+                            .iter()
+                            .flatten()
+                            .map(std::ffi::CString::new)
+                            .collect::<Result<Vec<_>, _>>()?;
+                        self.set_string(vrs, &cstrings)?;
+                    }
+                    data_type => anyhow::bail!("Unsupported data type: {data_type:?}"),
+                }
+                Ok(())
+            }
+
+            fn set_interpolated<I: $crate::sim::interpolation::Interpolate>(
+                &mut self,
+                vr: <Self as ::fmi::traits::FmiInstance>::ValueRef,
+                pl: &$crate::sim::interpolation::PreLookup,
+                array: &::arrow::array::ArrayRef,
+            ) -> anyhow::Result<()> {
+                use ::arrow::{array::AsArray, datatypes};
+
+                match array.data_type() {
+                    datatypes::DataType::Int32 => {
+                        let array = array.as_primitive::<datatypes::Int32Type>();
+                        let value = I::interpolate(pl, array);
+                        self.set_integer(&[vr], &[value])?;
+                    }
+                    datatypes::DataType::Float64 => {
+                        let array = array.as_primitive::<datatypes::Float64Type>();
+                        let value = I::interpolate(pl, array);
+                        self.set_real(&[vr], &[value])?;
+                    }
+                    data_type => {
+                        anyhow::bail!("Interpolation of {data_type:?} inputs is not supported")
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+/// Implements [`InstRecordValues`](super::traits::InstRecordValues) for an FMI 1.0 or FMI 2.0
+/// instance type, see [`impl_scalar_set_values`].
+#[cfg(any(feature = "fmi1", feature = "fmi2"))]
+macro_rules! impl_scalar_record_values {
+    ($inst:ty) => {
+        impl $crate::sim::traits::InstRecordValues for $inst {
+            fn record_outputs(
+                &mut self,
+                time: f64,
+                recorder: &mut $crate::sim::RecorderState<Self>,
+            ) -> anyhow::Result<()> {
+                use ::arrow::{
+                    array::{BooleanBuilder, Float64Builder, Int32Builder, StringBuilder},
+                    datatypes::DataType,
+                };
+
+                /// Downcasts the builder of a column to the builder type of its data type
+                fn builder<B: 'static>(builder: &mut dyn ::arrow::array::ArrayBuilder) -> &mut B {
+                    builder
+                        .as_any_mut()
+                        .downcast_mut::<B>()
+                        .expect("column builder does not match the data type")
+                }
+
+                log::trace!("Recording variables at time {}", time);
+
+                recorder.time.append_value(time);
+                for $crate::sim::io::Recorder {
+                    field,
+                    value_reference: vr,
+                    builder: column,
+                } in &mut recorder.recorders
+                {
+                    match field.data_type() {
+                        DataType::Boolean => {
+                            let mut value = [Default::default()];
+                            self.get_boolean(&[*vr], &mut value)?;
+                            builder::<BooleanBuilder>(column.as_mut()).append_value(value[0] != 0);
+                        }
+                        DataType::Int32 => {
+                            let mut value = [Default::default()];
+                            self.get_integer(&[*vr], &mut value)?;
+                            builder::<Int32Builder>(column.as_mut()).append_value(value[0]);
+                        }
+                        DataType::Float64 => {
+                            let mut value = [Default::default()];
+                            self.get_real(&[*vr], &mut value)?;
+                            builder::<Float64Builder>(column.as_mut()).append_value(value[0]);
+                        }
+                        DataType::Utf8 => {
+                            let mut value = [std::ffi::CString::default()];
+                            // Record an empty string if the FMU fails to return one
+                            let value = match self.get_string(&[*vr], &mut value) {
+                                Ok(_) => value[0].to_str().unwrap_or(""),
+                                Err(_) => "",
+                            };
+                            builder::<StringBuilder>(column.as_mut()).append_value(value);
+                        }
+                        data_type => anyhow::bail!("Unsupported data type: {data_type:?}"),
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "fmi1", feature = "fmi2"))]
+pub(crate) use {impl_scalar_record_values, impl_scalar_set_values};
@@ -3,7 +3,7 @@ use std::path::Path;
 
 use fmi::{
     EventFlags,
-    traits::{FmiEventHandler, FmiImport, FmiInstance},
+    traits::{FmiEventHandler, FmiInstance},
 };
 
 pub use io::{InputState, RecorderState};
@@ -16,6 +16,10 @@ use self::{
     traits::{FmiSim, InstRecordValues, InstSetValues, SimDefaultInitialize, SimHandleEvents},
 };
 
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub mod enumerations;
+#[cfg(feature = "fmi1")]
+pub mod fmi1;
 #[cfg(feature = "fmi2")]
 pub mod fmi2;
 #[cfg(feature = "fmi3")]
@@ -24,8 +28,12 @@ mod interpolation;
 mod io;
 mod me;
 pub mod params;
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+mod schema;
 pub mod solver;
 pub mod traits;
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub mod units;
 pub mod util;
 
 pub struct SimState<Inst>
@@ -46,7 +54,7 @@ impl<Inst: FmiInstance> SimState<Inst> {
     }
 }
 
-pub trait SimStateTrait<Inst: FmiInstance, Import: FmiImport> {
+pub trait SimStateTrait<Inst: FmiInstance, Import> {
     fn new(
         import: &Import,
         sim_params: SimParams,
@@ -100,6 +108,67 @@ pub fn simulate_with<Imp: FmiSim>(
     input_data: Option<RecordBatch>,
     interface: &options::Interface,
     import: Imp,
+) -> Result<(RecordBatch, SimStats), Error> {
+    simulate_import(input_data, interface, &import)
+}
+
+/// Simulate like [`simulate_with`], converting the input data and the results as selected in
+/// `options`.
+///
+/// Enumeration inputs are accepted as item names. With `options.display_units`, the input data
+/// and the results are in the display units of the variables, and with `options.item_names`,
+/// enumeration outputs are written as item names.
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub fn simulate_with_options<Imp>(
+    input_data: Option<RecordBatch>,
+    options: &options::FmiSimOptions,
+    import: Imp,
+) -> Result<(RecordBatch, SimStats), Error>
+where
+    Imp: FmiSim + fmi::traits::FmiImport,
+    Imp::ModelDescription:
+        fmi::schema::units::FmiUnits + fmi::schema::enumerations::FmiEnumerations,
+{
+    simulate_described(input_data, options, import.model_description(), &import)
+}
+
+/// [`simulate_with_options`] for imports that are described by `md` without being an
+/// [`FmiImport`](fmi::traits::FmiImport), such as a remote import.
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub(crate) fn simulate_described<Imp, MD>(
+    input_data: Option<RecordBatch>,
+    options: &options::FmiSimOptions,
+    md: &MD,
+    import: &Imp,
+) -> Result<(RecordBatch, SimStats), Error>
+where
+    Imp: FmiSim,
+    MD: fmi::schema::units::FmiUnits + fmi::schema::enumerations::FmiEnumerations,
+{
+    let mut input_data = input_data
+        .map(|batch| enumerations::from_item_names(md, &batch))
+        .transpose()?;
+    if options.display_units {
+        input_data = input_data
+            .map(|batch| units::from_display_units(md, &batch))
+            .transpose()?;
+    }
+
+    let (mut outputs, stats) = simulate_import(input_data, &options.interface, import)?;
+
+    if options.display_units {
+        outputs = units::to_display_units(md, &outputs)?;
+    }
+    if options.item_names {
+        outputs = enumerations::to_item_names(md, &outputs)?;
+    }
+    Ok((outputs, stats))
+}
+
+fn simulate_import<Imp: FmiSim>(
+    input_data: Option<RecordBatch>,
+    interface: &options::Interface,
+    import: &Imp,
 ) -> Result<(RecordBatch, SimStats), Error> {
     match interface {
         #[cfg(feature = "me")]
@@ -150,9 +219,30 @@ macro_rules! impl_sim_default_initialize {
     };
 }
 
-#[cfg(feature = "me")]
+// The first `update_discrete_states` of an FMI 1.0 instance reports the event info of `fmiInitialize`
+#[cfg(all(feature = "fmi1", feature = "me"))]
+impl_sim_default_initialize!(fmi::fmi1::instance::InstanceME);
+#[cfg(all(feature = "fmi1", feature = "cs"))]
+impl SimDefaultInitialize for SimState<fmi::fmi1::instance::InstanceCS> {
+    fn default_initialize(&mut self) -> Result<(), Error> {
+        self.inst
+            .enter_initialization_mode(
+                self.sim_params.tolerance,
+                self.sim_params.start_time,
+                Some(self.sim_params.stop_time),
+            )
+            .map_err(fmi::Error::from)?;
+        self.inst
+            .exit_initialization_mode()
+            .map_err(fmi::Error::from)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "fmi2", feature = "me"))]
 impl_sim_default_initialize!(fmi::fmi2::instance::InstanceME);
-#[cfg(feature = "cs")]
+#[cfg(all(feature = "fmi2", feature = "cs"))]
 impl SimDefaultInitialize for SimState<fmi::fmi2::instance::InstanceCS> {
     fn default_initialize(&mut self) -> Result<(), Error> {
         self.inst
@@ -170,10 +260,18 @@ impl SimDefaultInitialize for SimState<fmi::fmi2::instance::InstanceCS> {
     }
 }
 
-#[cfg(feature = "me")]
+#[cfg(all(feature = "fmi3", feature = "me"))]
 impl_sim_default_initialize!(fmi::fmi3::instance::InstanceME);
-#[cfg(feature = "cs")]
+#[cfg(all(feature = "fmi3", feature = "cs"))]
 impl_sim_default_initialize!(fmi::fmi3::instance::InstanceCS);
+#[cfg(all(feature = "mock", feature = "me"))]
+impl_sim_default_initialize!(fmi::fmi3::mock::OdeInstance);
+#[cfg(all(feature = "mock", feature = "cs"))]
+impl_sim_default_initialize!(fmi::fmi3::mock::MockCoSimulation<fmi::fmi3::mock::OdeInstance>);
+#[cfg(all(feature = "remote", feature = "me"))]
+impl_sim_default_initialize!(fmi::fmi3::remote::RemoteInstance<fmi::ME>);
+#[cfg(all(feature = "remote", feature = "cs"))]
+impl_sim_default_initialize!(fmi::fmi3::remote::RemoteInstance<fmi::CS>);
 
 macro_rules! impl_sim_initialize {
     ($inst:ty) => {
@@ -210,11 +308,23 @@ macro_rules! impl_sim_initialize {
     };
 }
 
-#[cfg(feature = "me")]
+#[cfg(all(feature = "fmi1", feature = "me"))]
+impl_sim_initialize!(fmi::fmi1::instance::InstanceME);
+#[cfg(all(feature = "fmi1", feature = "cs"))]
+impl_sim_initialize!(fmi::fmi1::instance::InstanceCS);
+#[cfg(all(feature = "fmi2", feature = "me"))]
 impl_sim_initialize!(fmi::fmi2::instance::InstanceME);
-#[cfg(feature = "me")]
+#[cfg(all(feature = "fmi3", feature = "me"))]
 impl_sim_initialize!(fmi::fmi3::instance::InstanceME);
-#[cfg(feature = "cs")]
+#[cfg(all(feature = "fmi2", feature = "cs"))]
 impl_sim_initialize!(fmi::fmi2::instance::InstanceCS);
-#[cfg(feature = "cs")]
+#[cfg(all(feature = "fmi3", feature = "cs"))]
 impl_sim_initialize!(fmi::fmi3::instance::InstanceCS);
+#[cfg(all(feature = "mock", feature = "me"))]
+impl_sim_initialize!(fmi::fmi3::mock::OdeInstance);
+#[cfg(all(feature = "mock", feature = "cs"))]
+impl_sim_initialize!(fmi::fmi3::mock::MockCoSimulation<fmi::fmi3::mock::OdeInstance>);
+#[cfg(all(feature = "remote", feature = "me"))]
+impl_sim_initialize!(fmi::fmi3::remote::RemoteInstance<fmi::ME>);
+#[cfg(all(feature = "remote", feature = "cs"))]
+impl_sim_initialize!(fmi::fmi3::remote::RemoteInstance<fmi::CS>);
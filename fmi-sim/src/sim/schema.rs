@@ -0,0 +1,136 @@
+//! Building the Arrow schema of an FMU from its model variables, shared between FMI 2.0 and 3.0.
+
+use arrow::{
+    array::{ArrayRef, StringArray},
+    datatypes::{DataType, Field, Fields, Schema},
+};
+use fmi::schema::{
+    enumerations::FmiEnumerations,
+    traits::{self, FmiModelVariables, FmiVariable},
+};
+
+use super::io::StartValues;
+
+/// Whether `var` with `causality` is exchanged with the importer. Clocks are not.
+fn is_exchanged<V: FmiVariable>(var: &V, causality: traits::Causality) -> bool {
+    var.causality() == causality && var.data_type() != traits::DataType::Clock
+}
+
+fn field<V: FmiVariable>(var: &V) -> Field {
+    Field::new(var.name(), var.arrow_data_type(), false)
+}
+
+pub fn inputs_schema<MV: FmiModelVariables>(model_variables: &MV) -> Schema {
+    let input_fields = model_variables
+        .variables()
+        .filter(|v| is_exchanged(*v, traits::Causality::Input))
+        .map(field)
+        .collect::<Fields>();
+
+    Schema::new(input_fields)
+}
+
+pub fn outputs_schema<MV: FmiModelVariables>(model_variables: &MV) -> Schema {
+    let time = Field::new("time", DataType::Float64, false);
+    let output_fields = model_variables
+        .variables()
+        .filter(|v| is_exchanged(*v, traits::Causality::Output))
+        .map(field)
+        .chain(std::iter::once(time))
+        .collect::<Fields>();
+
+    Schema::new(output_fields)
+}
+
+pub fn continuous_inputs<MV, VR>(model_variables: &MV) -> impl Iterator<Item = (Field, VR)> + '_
+where
+    MV: FmiModelVariables,
+    VR: From<u32>,
+{
+    model_variables
+        .variables()
+        .filter(|v| {
+            is_exchanged(*v, traits::Causality::Input)
+                && v.variability() == traits::Variability::Continuous
+        })
+        .map(|v| (field(v), v.value_reference().into()))
+}
+
+pub fn discrete_inputs<MV, VR>(model_variables: &MV) -> impl Iterator<Item = (Field, VR)> + '_
+where
+    MV: FmiModelVariables,
+    VR: From<u32>,
+{
+    model_variables
+        .variables()
+        .filter(|v| {
+            is_exchanged(*v, traits::Causality::Input)
+                && matches!(
+                    v.variability(),
+                    traits::Variability::Discrete | traits::Variability::Tunable
+                )
+        })
+        .map(|v| (field(v), v.value_reference().into()))
+}
+
+pub fn outputs<MV, VR>(model_variables: &MV) -> impl Iterator<Item = (Field, VR)> + '_
+where
+    MV: FmiModelVariables,
+    VR: From<u32>,
+{
+    model_variables
+        .variables()
+        .filter(|v| is_exchanged(*v, traits::Causality::Output))
+        .map(|v| (field(v), v.value_reference().into()))
+}
+
+/// Parse "var=value" strings into start values. The values of enumeration variables may also be
+/// given as item names.
+pub fn parse_start_values<MV, VR>(
+    model_variables: &MV,
+    start_values: &[String],
+) -> anyhow::Result<StartValues<VR>>
+where
+    MV: FmiEnumerations,
+    VR: From<u32>,
+{
+    let mut structural_parameters: Vec<(VR, ArrayRef)> = vec![];
+    let mut variables: Vec<(VR, ArrayRef)> = vec![];
+
+    for start_value in start_values {
+        let (name, value) = start_value
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid start value: {}", start_value))?;
+
+        let var = model_variables.variable_by_name(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid variable name: {name}. Valid variables are: {valid:?}",
+                valid = model_variables
+                    .variables()
+                    .map(|v| v.name())
+                    .collect::<Vec<_>>()
+            )
+        })?;
+
+        let value = match model_variables.variable_enumeration(var)? {
+            Some(enumeration) => enumeration.parse(value)?.to_string(),
+            None => value.to_string(),
+        };
+
+        let dt = var.arrow_data_type();
+        let ary = StringArray::from(vec![value]);
+        let ary = arrow::compute::cast(&ary, &dt)
+            .map_err(|e| anyhow::anyhow!("Error casting type: {e}"))?;
+
+        if var.causality() == traits::Causality::StructuralParameter {
+            structural_parameters.push((var.value_reference().into(), ary));
+        } else {
+            variables.push((var.value_reference().into(), ary));
+        }
+    }
+
+    Ok(StartValues {
+        structural_parameters,
+        variables,
+    })
+}
@@ -4,7 +4,7 @@ use arrow::{
     array::{ArrayRef, RecordBatch},
     datatypes::{Field, Schema},
 };
-use fmi::traits::{FmiImport, FmiInstance};
+use fmi::traits::FmiInstance;
 
 use crate::{
     Error,
@@ -19,7 +19,10 @@ use super::{
 };
 
 /// Interface for building the Arrow schema for the inputs and outputs of an FMU.
-pub trait ImportSchemaBuilder: FmiImport {
+pub trait ImportSchemaBuilder {
+    /// The type of the value reference used by the instances of the FMU.
+    type ValueRef;
+
     /// Build the schema for the inputs of the model.
     fn inputs_schema(&self) -> Schema;
     /// Build the schema for the outputs of the model.
@@ -46,7 +49,7 @@ pub trait InstSetValues: FmiInstance {
         &mut self,
         vrs: &[<Self as FmiInstance>::ValueRef],
         values: &arrow::array::ArrayRef,
-    );
+    ) -> anyhow::Result<()>;
     fn set_interpolated<I: Interpolate>(
         &mut self,
         vr: <Self as FmiInstance>::ValueRef,
@@ -80,6 +83,13 @@ pub trait SimMe<Inst> {
         S: Solver<Inst>;
 }
 
+/// Interface for the Co-Simulation main loop.
+/// Implemented by CS in fmi3, fmi1 and fmi2 have inherent main loops.
+pub trait SimCs {
+    /// Main loop of the co-simulation
+    fn main_loop(&mut self) -> Result<SimStats, Error>;
+}
+
 pub trait SimDefaultInitialize {
     fn default_initialize(&mut self) -> Result<(), Error>;
 }
@@ -99,7 +109,7 @@ pub trait SimInitialize<Inst: FmiInstance>: SimDefaultInitialize {
     ) -> Result<(), Error>;
 }
 
-pub trait FmiSim: FmiImport + ImportSchemaBuilder {
+pub trait FmiSim: ImportSchemaBuilder {
     /// Simulate the model using Model Exchange.
     #[cfg(feature = "me")]
     fn simulate_me(
@@ -0,0 +1,122 @@
+//! Conversion of input and output data between the units and display units of the variables.
+
+use arrow::{
+    array::{ArrayRef, AsArray, RecordBatch},
+    datatypes::{DataType, Float64Type},
+};
+use fmi::schema::units::{DisplayUnitConversion, FmiUnits};
+
+use crate::Error;
+
+/// Convert the columns of `batch` from the display units to the units of their variables.
+pub fn from_display_units<MD: FmiUnits>(
+    md: &MD,
+    batch: &RecordBatch,
+) -> Result<RecordBatch, Error> {
+    convert_columns(md, batch, DisplayUnitConversion::from_display)
+}
+
+/// Convert the columns of `batch` from the units to the display units of their variables.
+pub fn to_display_units<MD: FmiUnits>(md: &MD, batch: &RecordBatch) -> Result<RecordBatch, Error> {
+    convert_columns(md, batch, DisplayUnitConversion::to_display)
+}
+
+/// Apply `convert` to the values of all float columns that are named after a variable with a
+/// display unit. Other columns are passed through.
+fn convert_columns<MD: FmiUnits>(
+    md: &MD,
+    batch: &RecordBatch,
+    convert: fn(&DisplayUnitConversion, f64) -> f64,
+) -> Result<RecordBatch, Error> {
+    let schema = batch.schema();
+    let columns = schema
+        .fields()
+        .iter()
+        .zip(batch.columns())
+        .map(|(field, column)| {
+            if !field.data_type().is_floating() {
+                return Ok(column.clone());
+            }
+            let Some(var) = md.variable_by_name(field.name()) else {
+                return Ok(column.clone());
+            };
+            let Some(conversion) = md.display_unit_conversion(var).map_err(fmi::Error::from)?
+            else {
+                return Ok(column.clone());
+            };
+            let values = arrow::compute::cast(column, &DataType::Float64)?;
+            let values = values
+                .as_primitive::<Float64Type>()
+                .unary::<_, Float64Type>(|value| convert(&conversion, value));
+            Ok(arrow::compute::cast(&values, field.data_type())?)
+        })
+        .collect::<Result<Vec<ArrayRef>, Error>>()?;
+
+    Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+#[cfg(all(test, feature = "fmi3"))]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Float32Array, Float64Array, Int32Array},
+        datatypes::{Field, Schema},
+    };
+    use fmi::fmi3::schema::Fmi3ModelDescription;
+
+    use super::*;
+
+    #[test]
+    fn test_display_units() {
+        let md: Fmi3ModelDescription = fmi::schema::deserialize(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Car" instantiationToken="">
+    <UnitDefinitions>
+        <Unit name="m/s">
+            <BaseUnit m="1" s="-1"/>
+            <DisplayUnit name="km/h" factor="3.6"/>
+        </Unit>
+    </UnitDefinitions>
+    <ModelVariables>
+        <Float64 name="v" valueReference="1" unit="m/s" displayUnit="km/h"/>
+        <Float32 name="w" valueReference="2" unit="m/s" displayUnit="km/h"/>
+        <Float64 name="x" valueReference="3" unit="m/s"/>
+        <Int32 name="n" valueReference="4"/>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("time", DataType::Float64, false),
+            Field::new("v", DataType::Float64, false),
+            Field::new("w", DataType::Float32, false),
+            Field::new("x", DataType::Float64, false),
+            Field::new("n", DataType::Int32, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Float64Array::from(vec![0.0, 1.0])),
+                Arc::new(Float64Array::from(vec![10.0, 20.0])),
+                Arc::new(Float32Array::from(vec![10.0, 20.0])),
+                Arc::new(Float64Array::from(vec![10.0, 20.0])),
+                Arc::new(Int32Array::from(vec![10, 20])),
+            ],
+        )
+        .unwrap();
+
+        let display = to_display_units(&md, &batch).unwrap();
+        assert_eq!(display.column(0), batch.column(0));
+        assert_eq!(
+            display.column(1).as_primitive::<Float64Type>().values(),
+            &[36.0, 72.0]
+        );
+        assert_eq!(display.column(2).data_type(), &DataType::Float32);
+        assert_eq!(display.column(3), batch.column(3));
+        assert_eq!(display.column(4), batch.column(4));
+
+        assert_eq!(from_display_units(&md, &display).unwrap(), batch);
+    }
+}
@@ -91,6 +91,84 @@ fn test_stop_time(
     assert_eq!(time.value(time.len() - 1), 0.5,);
 }
 
+#[cfg(feature = "fmi1")]
+#[rstest::rstest]
+#[case::cs(fmi::InterfaceType::CoSimulation, Interface::CoSimulation(CoSimulationOptions {common: CommonOptions { stop_time: Some(0.5), output_interval: Some(0.1), ..Default::default() }, ..Default::default()}))]
+#[case::me(fmi::InterfaceType::ModelExchange, Interface::ModelExchange(ModelExchangeOptions {common: CommonOptions { stop_time: Some(0.5), output_interval: Some(0.1), ..Default::default() }, ..Default::default()}))]
+#[trace]
+#[test]
+fn test_fmi1(
+    mut ref_fmus: fmi_test_data::ReferenceFmus,
+    #[case] interface_type: fmi::InterfaceType,
+    #[case] interface: Interface,
+) {
+    if cfg!(target_os = "macos") {
+        return;
+    }
+
+    let fmu_file = ref_fmus
+        .extract_reference_fmu_fmi1("BouncingBall", interface_type)
+        .unwrap();
+
+    let options = FmiSimOptions {
+        interface,
+        model: fmu_file.path().to_path_buf(),
+        ..Default::default()
+    };
+
+    let (output, _) = fmi_sim::simulate(&options).unwrap();
+
+    let time = output
+        .column_by_name("time")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(time.value(0), 0.0);
+    assert_eq!(time.value(time.len() - 1), 0.5);
+
+    // The ball is dropped from h = 1
+    let h = output
+        .column_by_name("h")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(h.value(0), 1.0);
+    assert!(h.value(h.len() - 1) < 1.0);
+}
+
+/// Simulate an in-process `MockImport`, with the start value of `k` overridden.
+#[cfg(feature = "mock")]
+#[rstest::rstest]
+#[case::cs(Interface::CoSimulation(CoSimulationOptions {common: CommonOptions { stop_time: Some(1.0), output_interval: Some(0.1), initial_values: vec!["k=2".to_owned()], ..Default::default() }, ..Default::default()}), 1e-3)]
+#[case::me(Interface::ModelExchange(ModelExchangeOptions {common: CommonOptions { stop_time: Some(1.0), output_interval: Some(0.1), initial_values: vec!["k=2".to_owned()], ..Default::default() }, ..Default::default()}), 5e-2)]
+#[trace]
+#[test]
+fn test_mock(#[case] interface: Interface, #[case] tolerance: f64) {
+    use fmi::fmi3::mock::OdeModel;
+
+    // der(x) = -k * x
+    let import = OdeModel::new("Dahlquist")
+        .state("x", 1.0)
+        .parameter("k", 1.0)
+        .derivatives(|_time, values, derivatives| derivatives[0] = -values[3] * values[1])
+        .import();
+
+    let (output, stats) = fmi_sim::sim::simulate_with(None, &interface, import).unwrap();
+    assert_eq!(stats.num_steps, 10);
+
+    let time = output
+        .column_by_name("time")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(time.value(0), 0.0);
+    assert!((time.value(time.len() - 1) - 1.0).abs() < 1e-9);
+
+    let x = output
+        .column_by_name("x")
+        .unwrap()
+        .as_primitive::<Float64Type>();
+    assert_eq!(x.value(0), 1.0);
+    assert!((x.value(x.len() - 1) - (-2.0f64).exp()).abs() < tolerance);
+}
+
 #[test]
 fn test_start_value_types() {
     flexi_logger::init();
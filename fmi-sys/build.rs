@@ -1,7 +1,12 @@
-use std::{env, path::PathBuf};
-
 fn main() {
-    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap());
+    #[cfg(any(feature = "fmi2", feature = "fmi3"))]
+    let out_path = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    // FMI 1.0 bindings are written by hand, see src/fmi1/mod.rs
+    #[cfg(feature = "fmi1")]
+    cc::Build::new()
+        .file("src/fmi1/logger.c")
+        .compile("libfmi1logger.a");
 
     #[cfg(feature = "fmi2")]
     {
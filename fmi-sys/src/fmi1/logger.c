@@ -0,0 +1,44 @@
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+/* Subset of fmiModelTypes.h / fmiPlatformTypes.h, which are identical for Model Exchange and
+ * Co-Simulation in FMI 1.0 */
+typedef void *fmiComponent;
+typedef const char *fmiString;
+typedef enum {
+  fmiOK,
+  fmiWarning,
+  fmiDiscard,
+  fmiError,
+  fmiFatal,
+  fmiPending
+} fmiStatus;
+
+extern void fmi1_callback_log(fmiComponent c, fmiString instanceName,
+                              fmiStatus status, fmiString category,
+                              fmiString message);
+
+void fmi1_callback_logger_handler(fmiComponent c, fmiString instanceName,
+                                  fmiStatus status, fmiString category,
+                                  fmiString message, ...) {
+  va_list args;
+
+  va_start(args, message);
+  int buffer_size = vsnprintf(NULL, 0, message, args);
+  va_end(args);
+  if (buffer_size > 0) {
+    // vsnprintf return value doesn't include the terminating null-byte
+    char *buffer = malloc(buffer_size + 1);
+
+    if (buffer) {
+      va_start(args, message);
+      vsprintf(buffer, message, args);
+      va_end(args);
+
+      fmi1_callback_log(c, instanceName, status, category, buffer);
+
+      free(buffer);
+    }
+  }
+}
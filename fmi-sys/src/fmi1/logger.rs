@@ -0,0 +1,59 @@
+use crate::fmi1 as binding;
+
+/// This function gets called from logger.c
+///
+/// FMI 1.0 has no per-component environment pointer, so messages always go to the `log` facade,
+/// with the instance name as target.
+#[unsafe(no_mangle)]
+extern "C" fn fmi1_callback_log(
+    _c: binding::fmiComponent,
+    instance_name: binding::fmiString,
+    status: binding::fmiStatus,
+    category: binding::fmiString,
+    message: binding::fmiString,
+) {
+    let instance_name = unsafe { std::ffi::CStr::from_ptr(instance_name) }
+        .to_str()
+        .unwrap_or("NULL");
+
+    let category = unsafe { std::ffi::CStr::from_ptr(category) }
+        .to_str()
+        .unwrap_or("NULL");
+
+    let message = unsafe { std::ffi::CStr::from_ptr(message) }
+        .to_str()
+        .unwrap_or("NULL");
+
+    let level = match status {
+        binding::fmiStatus_fmiOK => log::Level::Info,
+        binding::fmiStatus_fmiWarning => log::Level::Warn,
+        binding::fmiStatus_fmiDiscard => log::Level::Warn,
+        binding::fmiStatus_fmiError => log::Level::Error,
+        binding::fmiStatus_fmiFatal => log::Level::Error,
+        _ => log::Level::Info,
+    };
+
+    log::logger().log(
+        &log::Record::builder()
+            .args(format_args!("[{category}] {message}"))
+            .level(level)
+            .module_path(Some("logger"))
+            .target(instance_name)
+            .build(),
+    );
+}
+
+#[link(name = "fmi1logger", kind = "static")]
+unsafe extern "C" {
+    /// This function is implemented in logger.c
+    /// Note: This can be re-implemented in pure Rust once the `c_variadics` feature stabilizes.
+    /// See: <https://doc.rust-lang.org/beta/unstable-book/language-features/c-variadic.html>
+    pub fn fmi1_callback_logger_handler(
+        c: binding::fmiComponent,
+        instanceName: binding::fmiString,
+        status: binding::fmiStatus,
+        category: binding::fmiString,
+        message: binding::fmiString,
+        ...
+    );
+}
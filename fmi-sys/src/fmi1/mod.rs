@@ -0,0 +1,358 @@
+//! Bindings to the FMI 1.0 API for Model Exchange and Co-Simulation.
+//!
+//! Unlike FMI 2.0 and 3.0, the FMI 1.0 functions are exported with the `modelIdentifier` of the
+//! FMU as prefix (such as `bouncingBall_fmiGetReal`), so the bindings are written by hand instead
+//! of being generated by bindgen. [`Fmi1MeBinding`] and [`Fmi1CsBinding`] resolve the prefixed
+//! symbols when the shared library is loaded, and expose the functions under their unprefixed
+//! names.
+//!
+//! See <https://fmi-standard.org/downloads/#fmi-1-0>
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(clippy::all)]
+
+use std::os::raw::{c_char, c_double, c_int, c_uint, c_void};
+
+pub mod logger;
+
+pub type fmiComponent = *mut c_void;
+pub type fmiValueReference = c_uint;
+pub type fmiReal = c_double;
+pub type fmiInteger = c_int;
+pub type fmiBoolean = c_char;
+pub type fmiString = *const c_char;
+
+pub const fmiTrue: fmiBoolean = 1;
+pub const fmiFalse: fmiBoolean = 0;
+pub const fmiUndefinedValueReference: fmiValueReference = c_uint::MAX;
+
+pub type fmiStatus = c_uint;
+pub const fmiStatus_fmiOK: fmiStatus = 0;
+pub const fmiStatus_fmiWarning: fmiStatus = 1;
+pub const fmiStatus_fmiDiscard: fmiStatus = 2;
+pub const fmiStatus_fmiError: fmiStatus = 3;
+pub const fmiStatus_fmiFatal: fmiStatus = 4;
+/// Only returned by the Co-Simulation API
+pub const fmiStatus_fmiPending: fmiStatus = 5;
+
+pub type fmiStatusKind = c_uint;
+pub const fmiStatusKind_fmiDoStepStatus: fmiStatusKind = 0;
+pub const fmiStatusKind_fmiPendingStatus: fmiStatusKind = 1;
+pub const fmiStatusKind_fmiLastSuccessfulTime: fmiStatusKind = 2;
+
+/// `fmiCallbackLogger`, a variadic function. See [`logger::fmi1_callback_logger_handler`].
+pub type fmiCallbackLogger = Option<
+    unsafe extern "C" fn(
+        c: fmiComponent,
+        instanceName: fmiString,
+        status: fmiStatus,
+        category: fmiString,
+        message: fmiString,
+        ...
+    ),
+>;
+pub type fmiCallbackAllocateMemory =
+    Option<unsafe extern "C" fn(nobj: usize, size: usize) -> *mut c_void>;
+pub type fmiCallbackFreeMemory = Option<unsafe extern "C" fn(obj: *mut c_void)>;
+pub type fmiStepFinished = Option<unsafe extern "C" fn(c: fmiComponent, status: fmiStatus)>;
+
+/// `fmiCallbackFunctions` of `fmiModelFunctions.h` (Model Exchange)
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct fmiCallbackFunctions {
+    pub logger: fmiCallbackLogger,
+    pub allocateMemory: fmiCallbackAllocateMemory,
+    pub freeMemory: fmiCallbackFreeMemory,
+}
+
+/// `fmiCallbackFunctions` of `fmiFunctions.h` (Co-Simulation), which adds `stepFinished`
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct fmiCoSimulationCallbackFunctions {
+    pub logger: fmiCallbackLogger,
+    pub allocateMemory: fmiCallbackAllocateMemory,
+    pub freeMemory: fmiCallbackFreeMemory,
+    pub stepFinished: fmiStepFinished,
+}
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct fmiEventInfo {
+    pub iterationConverged: fmiBoolean,
+    pub stateValueReferencesChanged: fmiBoolean,
+    pub stateValuesChanged: fmiBoolean,
+    pub terminateSimulation: fmiBoolean,
+    pub upcomingTimeEvent: fmiBoolean,
+    pub nextEventTime: fmiReal,
+}
+
+/// Generate a struct holding the functions of an FMI 1.0 shared library, resolved with the
+/// `modelIdentifier` prefix.
+macro_rules! fmi1_binding {
+    (
+        $(#[$meta:meta])*
+        pub struct $name:ident {
+            $(fn $func:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $ret:ty)?;)*
+        }
+    ) => {
+        $(#[$meta])*
+        pub struct $name {
+            __library: ::libloading::Library,
+            $($func: Result<unsafe extern "C" fn($($ty),*) $(-> $ret)?, ::libloading::Error>,)*
+        }
+
+        impl $name {
+            /// Load the shared library at `path`, resolving the functions prefixed with
+            /// `model_identifier`.
+            ///
+            /// # Safety
+            /// Loading a library runs its initialization routines, see [`libloading::Library::new`].
+            pub unsafe fn new<P>(path: P, model_identifier: &str) -> Result<Self, ::libloading::Error>
+            where
+                P: AsRef<::std::ffi::OsStr>,
+            {
+                let library = unsafe { ::libloading::Library::new(path) }?;
+                unsafe { Self::from_library(library, model_identifier) }
+            }
+
+            /// # Safety
+            /// The symbols of `library` must have the signatures of the FMI 1.0 functions.
+            pub unsafe fn from_library<L>(library: L, model_identifier: &str) -> Result<Self, ::libloading::Error>
+            where
+                L: Into<::libloading::Library>,
+            {
+                let __library = library.into();
+                $(
+                    let $func = unsafe {
+                        __library.get(format!("{model_identifier}_{}\0", stringify!($func)).as_bytes())
+                    }
+                    .map(|sym| *sym);
+                )*
+                Ok(Self { __library, $($func),* })
+            }
+
+            $(
+                pub unsafe fn $func(&self, $($arg: $ty),*) $(-> $ret)? {
+                    unsafe {
+                        (self.$func.as_ref().expect("Expected function, got error."))($($arg),*)
+                    }
+                }
+            )*
+        }
+    };
+}
+
+fmi1_binding! {
+    /// Functions of an FMU for Model Exchange, see `fmiModelFunctions.h`
+    pub struct Fmi1MeBinding {
+        fn fmiGetModelTypesPlatform() -> *const c_char;
+        fn fmiGetVersion() -> *const c_char;
+        fn fmiInstantiateModel(
+            instanceName: fmiString,
+            GUID: fmiString,
+            functions: fmiCallbackFunctions,
+            loggingOn: fmiBoolean,
+        ) -> fmiComponent;
+        fn fmiFreeModelInstance(c: fmiComponent);
+        fn fmiSetDebugLogging(c: fmiComponent, loggingOn: fmiBoolean) -> fmiStatus;
+        fn fmiSetTime(c: fmiComponent, time: fmiReal) -> fmiStatus;
+        fn fmiSetContinuousStates(c: fmiComponent, x: *const fmiReal, nx: usize) -> fmiStatus;
+        fn fmiCompletedIntegratorStep(
+            c: fmiComponent,
+            callEventUpdate: *mut fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiSetReal(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiReal,
+        ) -> fmiStatus;
+        fn fmiSetInteger(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiInteger,
+        ) -> fmiStatus;
+        fn fmiSetBoolean(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiSetString(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiString,
+        ) -> fmiStatus;
+        fn fmiInitialize(
+            c: fmiComponent,
+            toleranceControlled: fmiBoolean,
+            relativeTolerance: fmiReal,
+            eventInfo: *mut fmiEventInfo,
+        ) -> fmiStatus;
+        fn fmiGetDerivatives(c: fmiComponent, derivatives: *mut fmiReal, nx: usize) -> fmiStatus;
+        fn fmiGetEventIndicators(
+            c: fmiComponent,
+            eventIndicators: *mut fmiReal,
+            ni: usize,
+        ) -> fmiStatus;
+        fn fmiGetReal(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiReal,
+        ) -> fmiStatus;
+        fn fmiGetInteger(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiInteger,
+        ) -> fmiStatus;
+        fn fmiGetBoolean(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiGetString(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiString,
+        ) -> fmiStatus;
+        fn fmiEventUpdate(
+            c: fmiComponent,
+            intermediateResults: fmiBoolean,
+            eventInfo: *mut fmiEventInfo,
+        ) -> fmiStatus;
+        fn fmiGetContinuousStates(c: fmiComponent, states: *mut fmiReal, nx: usize) -> fmiStatus;
+        fn fmiGetNominalContinuousStates(
+            c: fmiComponent,
+            x_nominal: *mut fmiReal,
+            nx: usize,
+        ) -> fmiStatus;
+        fn fmiGetStateValueReferences(
+            c: fmiComponent,
+            vrx: *mut fmiValueReference,
+            nx: usize,
+        ) -> fmiStatus;
+        fn fmiTerminate(c: fmiComponent) -> fmiStatus;
+    }
+}
+
+fmi1_binding! {
+    /// Functions of an FMU for Co-Simulation, see `fmiFunctions.h`
+    pub struct Fmi1CsBinding {
+        fn fmiGetTypesPlatform() -> *const c_char;
+        fn fmiGetVersion() -> *const c_char;
+        fn fmiInstantiateSlave(
+            instanceName: fmiString,
+            fmuGUID: fmiString,
+            fmuLocation: fmiString,
+            mimeType: fmiString,
+            timeout: fmiReal,
+            visible: fmiBoolean,
+            interactive: fmiBoolean,
+            functions: fmiCoSimulationCallbackFunctions,
+            loggingOn: fmiBoolean,
+        ) -> fmiComponent;
+        fn fmiInitializeSlave(
+            c: fmiComponent,
+            tStart: fmiReal,
+            StopTimeDefined: fmiBoolean,
+            tStop: fmiReal,
+        ) -> fmiStatus;
+        fn fmiTerminateSlave(c: fmiComponent) -> fmiStatus;
+        fn fmiResetSlave(c: fmiComponent) -> fmiStatus;
+        fn fmiFreeSlaveInstance(c: fmiComponent);
+        fn fmiSetDebugLogging(c: fmiComponent, loggingOn: fmiBoolean) -> fmiStatus;
+        fn fmiSetReal(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiReal,
+        ) -> fmiStatus;
+        fn fmiSetInteger(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiInteger,
+        ) -> fmiStatus;
+        fn fmiSetBoolean(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiSetString(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *const fmiString,
+        ) -> fmiStatus;
+        fn fmiGetReal(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiReal,
+        ) -> fmiStatus;
+        fn fmiGetInteger(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiInteger,
+        ) -> fmiStatus;
+        fn fmiGetBoolean(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiGetString(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            value: *mut fmiString,
+        ) -> fmiStatus;
+        fn fmiSetRealInputDerivatives(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            order: *const fmiInteger,
+            value: *const fmiReal,
+        ) -> fmiStatus;
+        fn fmiGetRealOutputDerivatives(
+            c: fmiComponent,
+            vr: *const fmiValueReference,
+            nvr: usize,
+            order: *const fmiInteger,
+            value: *mut fmiReal,
+        ) -> fmiStatus;
+        fn fmiCancelStep(c: fmiComponent) -> fmiStatus;
+        fn fmiDoStep(
+            c: fmiComponent,
+            currentCommunicationPoint: fmiReal,
+            communicationStepSize: fmiReal,
+            newStep: fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiGetStatus(c: fmiComponent, s: fmiStatusKind, value: *mut fmiStatus) -> fmiStatus;
+        fn fmiGetRealStatus(c: fmiComponent, s: fmiStatusKind, value: *mut fmiReal) -> fmiStatus;
+        fn fmiGetIntegerStatus(
+            c: fmiComponent,
+            s: fmiStatusKind,
+            value: *mut fmiInteger,
+        ) -> fmiStatus;
+        fn fmiGetBooleanStatus(
+            c: fmiComponent,
+            s: fmiStatusKind,
+            value: *mut fmiBoolean,
+        ) -> fmiStatus;
+        fn fmiGetStringStatus(
+            c: fmiComponent,
+            s: fmiStatusKind,
+            value: *mut fmiString,
+        ) -> fmiStatus;
+    }
+}
@@ -1,9 +1,42 @@
+use std::sync::RwLock;
+
 use crate::fmi2 as binding;
 
+/// A message logged by an FMU through the `fmi2CallbackLogger`.
+#[derive(Debug, Clone, Copy)]
+pub struct LogMessage<'a> {
+    /// Name of the instance that logged the message
+    pub instance_name: &'a str,
+    pub status: binding::fmi2Status,
+    pub category: &'a str,
+    pub message: &'a str,
+}
+
+/// Receives the [`LogMessage`]s of one component.
+pub type LogSinkFn = Box<dyn Fn(&LogMessage<'_>) + Send + Sync>;
+
+/// Per-component state passed to the FMU as `componentEnvironment`.
+///
+/// Messages of a component without a log sink go to the `log` facade.
+#[derive(Default)]
+pub struct ComponentEnvironment {
+    log_sink: RwLock<Option<LogSinkFn>>,
+}
+
+impl ComponentEnvironment {
+    /// Route the log messages of the component to `sink`, or back to the `log` facade for `None`.
+    pub fn set_log_sink(&self, sink: Option<LogSinkFn>) {
+        *self
+            .log_sink
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = sink;
+    }
+}
+
 /// This function gets called from logger.c
 #[unsafe(no_mangle)]
 extern "C" fn callback_log(
-    _component_environment: binding::fmi2ComponentEnvironment,
+    component_environment: binding::fmi2ComponentEnvironment,
     instance_name: binding::fmi2String,
     status: binding::fmi2Status,
     category: binding::fmi2String,
@@ -13,6 +46,38 @@ extern "C" fn callback_log(
         .to_str()
         .unwrap_or("NULL");
 
+    let category = unsafe { std::ffi::CStr::from_ptr(category) }
+        .to_str()
+        .unwrap_or("NULL");
+
+    let message = unsafe { std::ffi::CStr::from_ptr(message) }
+        .to_str()
+        .unwrap_or("NULL");
+
+    // The environment is either null or points to the `ComponentEnvironment` of the component
+    let environment = unsafe { (component_environment as *const ComponentEnvironment).as_ref() };
+    if let Some(environment) = environment {
+        let log_sink = environment
+            .log_sink
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(log_sink) = log_sink.as_ref() {
+            let log_message = LogMessage {
+                instance_name,
+                status,
+                category,
+                message,
+            };
+            // Unwinding into the FMU is undefined behavior
+            if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| log_sink(&log_message)))
+                .is_err()
+            {
+                log::error!("Log sink of {instance_name} panicked");
+            }
+            return;
+        }
+    }
+
     let level = match status {
         binding::fmi2Status_fmi2OK => log::Level::Info,
         binding::fmi2Status_fmi2Warning => log::Level::Warn,
@@ -23,14 +88,6 @@ extern "C" fn callback_log(
         _ => unreachable!("Invalid status"),
     };
 
-    let _category = unsafe { std::ffi::CStr::from_ptr(category) }
-        .to_str()
-        .unwrap_or("NULL");
-
-    let message = unsafe { std::ffi::CStr::from_ptr(message) }
-        .to_str()
-        .unwrap_or("NULL");
-
     log::logger().log(
         &log::Record::builder()
             .args(format_args!("{}", message))
@@ -3,3 +3,19 @@
 #![allow(non_snake_case)]
 #![allow(clippy::all)]
 include!(concat!(env!("OUT_DIR"), "/fmi3_bindings.rs"));
+
+/// The C headers of the FMI 3.0 API as `(file name, contents)`, for compiling source code FMUs.
+pub const HEADERS: [(&str, &str); 3] = [
+    (
+        "fmi3Functions.h",
+        include_str!("../../fmi-standard3/headers/fmi3Functions.h"),
+    ),
+    (
+        "fmi3FunctionTypes.h",
+        include_str!("../../fmi-standard3/headers/fmi3FunctionTypes.h"),
+    ),
+    (
+        "fmi3PlatformTypes.h",
+        include_str!("../../fmi-standard3/headers/fmi3PlatformTypes.h"),
+    ),
+];
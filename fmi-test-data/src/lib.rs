@@ -4,7 +4,7 @@
 
 use anyhow::Context;
 use fetch_data::{FetchData, ctor};
-use fmi::{schema::MajorVersion, traits::FmiImport};
+use fmi::{InterfaceType, fmi1::import::Fmi1Import, schema::MajorVersion, traits::FmiImport};
 use std::{
     fs::File,
     io::{Cursor, Read},
@@ -152,6 +152,53 @@ impl ReferenceFmus {
         Ok(fout)
     }
 
+    /// Get an FMI 1.0 reference FMU as an import instance
+    ///
+    /// An FMI 1.0 FMU implements either Model Exchange or Co-Simulation, so the archive contains
+    /// one FMU per `interface` type.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use fmi_test_data::ReferenceFmus;
+    /// let mut reference_fmus = ReferenceFmus::new()?;
+    /// let fmu = reference_fmus.get_reference_fmu_fmi1("BouncingBall", fmi::InterfaceType::ModelExchange)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn get_reference_fmu_fmi1(
+        &mut self,
+        name: &str,
+        interface: InterfaceType,
+    ) -> anyhow::Result<Fmi1Import> {
+        let filename = fmi1_filename(name, interface)?;
+        let mut f = self
+            .archive
+            .by_name(&filename)
+            .context(format!("Open {filename}"))?;
+        let mut buf = Vec::new();
+        f.read_to_end(buf.as_mut())?;
+        Ok(fmi::import::new(Cursor::new(buf))?)
+    }
+
+    /// Extract an FMI 1.0 reference FMU for the given `interface` type into a temporary file
+    ///
+    /// See [`Self::extract_reference_fmu`] and [`Self::get_reference_fmu_fmi1`].
+    pub fn extract_reference_fmu_fmi1(
+        &mut self,
+        name: &str,
+        interface: InterfaceType,
+    ) -> anyhow::Result<NamedTempFile> {
+        let filename = fmi1_filename(name, interface)?;
+        let mut fin = self
+            .archive
+            .by_name(&filename)
+            .context(format!("Open {filename}"))?;
+        let mut fout = tempfile::NamedTempFile::new()?;
+        std::io::copy(fin.by_ref(), fout.as_file_mut())
+            .context(format!("Extracting {filename} to tempfile"))?;
+        Ok(fout)
+    }
+
     /// Get a list of all available FMU files in the archive
     ///
     /// Returns a sorted list of all FMU names available in the Reference FMUs archive.
@@ -196,6 +243,18 @@ impl ReferenceFmus {
     }
 }
 
+/// Path of an FMI 1.0 FMU in the reference archive
+fn fmi1_filename(name: &str, interface: InterfaceType) -> anyhow::Result<String> {
+    let dir = match interface {
+        InterfaceType::ModelExchange => "me",
+        InterfaceType::CoSimulation => "cs",
+        InterfaceType::ScheduledExecution => {
+            anyhow::bail!("FMI 1.0 does not support Scheduled Execution")
+        }
+    };
+    Ok(format!("{}/{dir}/{name}.fmu", MajorVersion::FMI1))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +329,28 @@ mod tests {
         assert_eq!(fmu_v3.model_description().model_name, "Feedthrough");
     }
 
+    #[test]
+    fn test_fmi1_fmu() {
+        let mut reference_fmus = ReferenceFmus::new().unwrap();
+
+        let fmu_me = reference_fmus
+            .get_reference_fmu_fmi1("BouncingBall", InterfaceType::ModelExchange)
+            .unwrap();
+        assert_eq!(fmu_me.model_description().fmi_version, "1.0");
+        assert!(!fmu_me.model_description().is_co_simulation());
+
+        let fmu_cs = reference_fmus
+            .get_reference_fmu_fmi1("BouncingBall", InterfaceType::CoSimulation)
+            .unwrap();
+        assert!(fmu_cs.model_description().is_co_simulation());
+
+        assert!(
+            reference_fmus
+                .get_reference_fmu_fmi1("BouncingBall", InterfaceType::ScheduledExecution)
+                .is_err()
+        );
+    }
+
     #[test]
     fn test_nonexistent_fmu() {
         let mut reference_fmus = ReferenceFmus::new().unwrap();
@@ -0,0 +1,66 @@
+//! Worker process hosting an FMI 3.0 instance for [`fmi::fmi3::remote::RemoteInstance`].
+//!
+//! Requests are read from stdin and responses written to stdout. Anything else the FMU writes to
+//! stdout is redirected to stderr, so that it cannot corrupt the responses.
+
+use std::io::Write;
+
+/// Writes log records to stderr, filtered by the `FMI_WORKER_LOG` environment variable.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let _ = writeln!(
+                std::io::stderr(),
+                "[fmi-worker {} {}] {}",
+                std::process::id(),
+                record.level(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Take over stdout for the protocol and point file descriptor 1 at stderr.
+#[cfg(unix)]
+fn protocol_output() -> std::io::Result<std::fs::File> {
+    use std::os::fd::FromRawFd;
+    // SAFETY: only file descriptors owned by this process are duplicated, before any other thread
+    // or the FMU is started.
+    unsafe {
+        let fd = libc::dup(libc::STDOUT_FILENO);
+        if fd < 0 || libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO) < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        Ok(std::fs::File::from_raw_fd(fd))
+    }
+}
+
+#[cfg(not(unix))]
+fn protocol_output() -> std::io::Result<std::io::Stdout> {
+    Ok(std::io::stdout())
+}
+
+fn main() {
+    let level = std::env::var("FMI_WORKER_LOG")
+        .ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(log::LevelFilter::Warn);
+    log::set_max_level(level);
+    let _ = log::set_logger(&StderrLogger);
+
+    let result = protocol_output()
+        .map_err(fmi::Error::from)
+        .and_then(|output| fmi::fmi3::remote::serve(std::io::stdin().lock(), output));
+    if let Err(e) = result {
+        log::error!("{e}");
+        std::process::exit(1);
+    }
+}
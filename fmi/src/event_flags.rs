@@ -47,4 +47,21 @@ impl EventFlags {
             None
         };
     }
+
+    #[cfg(feature = "fmi1")]
+    /// Update the event flags from the FMI1 event information.
+    pub(crate) fn update_from_fmi1_event_info(
+        &mut self,
+        event_info: crate::fmi1::binding::fmiEventInfo,
+    ) {
+        self.discrete_states_need_update = event_info.iterationConverged == 0;
+        self.terminate_simulation = event_info.terminateSimulation != 0;
+        self.nominals_of_continuous_states_changed = event_info.stateValueReferencesChanged != 0;
+        self.values_of_continuous_states_changed = event_info.stateValuesChanged != 0;
+        self.next_event_time = if event_info.upcomingTimeEvent != 0 {
+            Some(event_info.nextEventTime)
+        } else {
+            None
+        };
+    }
 }
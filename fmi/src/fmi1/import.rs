@@ -0,0 +1,188 @@
+use std::{path::PathBuf, str::FromStr};
+
+use super::{binding, instance::Instance};
+use crate::{
+    CS, Error, ME,
+    import::{FmuDir, LibraryMode},
+    library::LibraryLease,
+    traits::FmiImport,
+};
+
+use fmi_schema::{MajorVersion, fmi1 as schema};
+
+/// The loaded shared library of an FMI 1.0 FMU, which implements either the Model Exchange or the
+/// Co-Simulation API.
+pub enum Fmi1Binding {
+    ModelExchange(binding::Fmi1MeBinding),
+    CoSimulation(binding::Fmi1CsBinding),
+}
+
+#[derive(Debug)]
+pub struct Fmi1Import {
+    /// Path to the unzipped FMU on disk
+    dir: FmuDir,
+    /// Parsed raw-schema model description
+    model_description: schema::Fmi1ModelDescription,
+    /// How the shared library is loaded for new instances
+    library_mode: LibraryMode,
+}
+
+impl FmiImport for Fmi1Import {
+    const MAJOR_VERSION: MajorVersion = MajorVersion::FMI1;
+    type ModelDescription = schema::Fmi1ModelDescription;
+    type Binding = Fmi1Binding;
+    type ValueRef = binding::fmiValueReference;
+
+    fn new(dir: FmuDir, schema_xml: &str) -> Result<Self, Error> {
+        let schema = schema::Fmi1ModelDescription::from_str(schema_xml)?;
+        Ok(Self {
+            dir,
+            model_description: schema,
+            library_mode: LibraryMode::default(),
+        })
+    }
+
+    #[inline]
+    fn dir(&self) -> &FmuDir {
+        &self.dir
+    }
+
+    /// Get the path to the shared library
+    fn shared_lib_path(&self, model_identifier: &str) -> Result<PathBuf, Error> {
+        let platform_folder = match (std::env::consts::OS, std::env::consts::ARCH) {
+            ("windows", "x86_64") => "win64",
+            ("windows", "x86") => "win32",
+            ("linux", "x86_64") => "linux64",
+            ("linux", "x86") => "linux32",
+            ("macos", "x86_64") => "darwin64",
+            ("macos", "x86") => "darwin32",
+            _ => {
+                return Err(Error::UnsupportedPlatform {
+                    os: std::env::consts::OS.to_string(),
+                    arch: std::env::consts::ARCH.to_string(),
+                });
+            }
+        };
+        let fname = format!("{model_identifier}{}", std::env::consts::DLL_SUFFIX);
+        Ok(std::path::PathBuf::from("binaries")
+            .join(platform_folder)
+            .join(fname))
+    }
+
+    fn model_description(&self) -> &Self::ModelDescription {
+        &self.model_description
+    }
+
+    /// Load the plugin shared library and return the raw bindings of the API that the FMU
+    /// implements.
+    fn binding(&self, model_identifier: &str) -> Result<Self::Binding, Error> {
+        let lib_path = self
+            .dir
+            .path()
+            .join(self.shared_lib_path(model_identifier)?);
+        log::trace!("Loading shared library {lib_path:?}");
+        if self.model_description.is_co_simulation() {
+            unsafe { binding::Fmi1CsBinding::new(lib_path, model_identifier) }
+                .map(Fmi1Binding::CoSimulation)
+                .map_err(Error::from)
+        } else {
+            unsafe { binding::Fmi1MeBinding::new(lib_path, model_identifier) }
+                .map(Fmi1Binding::ModelExchange)
+                .map_err(Error::from)
+        }
+    }
+
+    fn library_mode(&self) -> LibraryMode {
+        self.library_mode
+    }
+
+    fn set_library_mode(&mut self, mode: LibraryMode) {
+        self.library_mode = mode;
+    }
+
+    /// Get a `String` representation of the resources path for this FMU
+    ///
+    /// FMI 1.0 passes the location of the unzipped FMU (not of its resources directory) as
+    /// `fmuLocation` to Co-Simulation slaves, see [`Fmi1Import::canonical_fmu_location_string`].
+    fn canonical_resource_path_string(&self) -> String {
+        file_url(self.resource_path())
+    }
+}
+
+impl Fmi1Import {
+    /// The URL of the unzipped FMU, passed as `fmuLocation` to `fmiInstantiateSlave`.
+    pub fn canonical_fmu_location_string(&self) -> String {
+        file_url(self.archive_path())
+    }
+
+    /// Prepare the shared library for a new instance according to the library mode
+    fn library_lease(&self, model_identifier: &str) -> Result<LibraryLease, Error> {
+        let lib_path = self
+            .dir
+            .path()
+            .join(self.shared_lib_path(model_identifier)?);
+        // Only Co-Simulation slaves declare `canBeInstantiatedOnlyOncePerProcess` in FMI 1.0
+        let only_once_per_process = self
+            .model_description
+            .implementation
+            .as_ref()
+            .and_then(|implementation| implementation.capabilities())
+            .and_then(|capabilities| capabilities.can_be_instantiated_only_once_per_process)
+            .unwrap_or(false);
+        LibraryLease::acquire(
+            &lib_path,
+            model_identifier,
+            self.library_mode,
+            only_once_per_process,
+        )
+    }
+
+    /// Load the shared library with the Model Exchange API for a new instance
+    pub(crate) fn me_binding(
+        &self,
+        model_identifier: &str,
+    ) -> Result<(binding::Fmi1MeBinding, LibraryLease), Error> {
+        let lease = self.library_lease(model_identifier)?;
+        log::trace!("Loading shared library {:?}", lease.path());
+        let binding = unsafe { binding::Fmi1MeBinding::new(lease.path(), model_identifier) }?;
+        Ok((binding, lease))
+    }
+
+    /// Load the shared library with the Co-Simulation API for a new instance
+    pub(crate) fn cs_binding(
+        &self,
+        model_identifier: &str,
+    ) -> Result<(binding::Fmi1CsBinding, LibraryLease), Error> {
+        let lease = self.library_lease(model_identifier)?;
+        log::trace!("Loading shared library {:?}", lease.path());
+        let binding = unsafe { binding::Fmi1CsBinding::new(lease.path(), model_identifier) }?;
+        Ok((binding, lease))
+    }
+
+    /// Create a new instance of the FMU for Model-Exchange
+    pub fn instantiate_me(
+        &self,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+    ) -> Result<Instance<ME>, Error> {
+        Instance::<ME>::new(self, instance_name, visible, logging_on)
+    }
+
+    /// Create a new instance of the FMU for Co-Simulation
+    pub fn instantiate_cs(
+        &self,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+    ) -> Result<Instance<CS>, Error> {
+        Instance::<CS>::new(self, instance_name, visible, logging_on)
+    }
+}
+
+fn file_url(path: impl AsRef<std::path::Path>) -> String {
+    let path = std::path::absolute(path).expect("Invalid path");
+    url::Url::from_file_path(path)
+        .map(|url| url.as_str().to_owned())
+        .expect("Error converting path to URL")
+}
@@ -0,0 +1,249 @@
+use std::ffi::{CStr, CString};
+
+use super::{Experiment, Instance, binding, callback_functions};
+use crate::{
+    CS, Error, InterfaceType,
+    fmi1::{Fmi1Error, Fmi1Res, Fmi1Status, import, schema},
+    traits::{FmiImport, FmiInstance, FmiStatus},
+};
+
+/// MIME type of the tool for FMUs that contain the slave as a shared library
+const MIME_TYPE_SHARED_LIBRARY: &CStr = c"application/x-fmu-sharedlibrary";
+
+impl Instance<CS> {
+    /// Initialize a new Instance from an Import
+    pub fn new(
+        import: &import::Fmi1Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+    ) -> Result<Self, Error> {
+        let schema = import.model_description();
+        if !schema.is_co_simulation() {
+            return Err(Error::UnsupportedFmuType("CoSimulation".to_owned()));
+        }
+
+        let (binding, library) = import.cs_binding(&schema.model_identifier)?;
+
+        let (logger, allocate_memory, free_memory) = callback_functions();
+        let callbacks = binding::fmiCoSimulationCallbackFunctions {
+            logger,
+            allocateMemory: allocate_memory,
+            freeMemory: free_memory,
+            stepFinished: None,
+        };
+
+        let name = instance_name.to_owned();
+
+        let instance_name = CString::new(instance_name).expect("Error building CString");
+        let guid = CString::new(schema.guid.as_bytes()).expect("Error building CString");
+        let fmu_location =
+            CString::new(import.canonical_fmu_location_string()).expect("Invalid FMU location");
+
+        let component = unsafe {
+            binding.fmiInstantiateSlave(
+                instance_name.as_ptr(),
+                guid.as_ptr(),
+                fmu_location.as_ptr(),
+                MIME_TYPE_SHARED_LIBRARY.as_ptr(),
+                0.0,                               // timeout
+                visible as binding::fmiBoolean,    // visible
+                binding::fmiFalse,                 // interactive
+                callbacks,                         // functions
+                logging_on as binding::fmiBoolean, // loggingOn
+            )
+        };
+        if component.is_null() {
+            return Err(Error::Instantiation);
+        }
+        log::trace!("Created FMI1.0 CS component {component:?}");
+
+        Ok(Self {
+            name,
+            binding,
+            component,
+            experiment: Experiment::default(),
+            initial_event_info: None,
+            _library: library,
+            _tag: std::marker::PhantomData,
+        })
+    }
+
+    /// Compute a communication step of size `communication_step_size`, starting at
+    /// `current_communication_point`.
+    pub fn do_step(
+        &mut self,
+        current_communication_point: f64,
+        communication_step_size: f64,
+        new_step: bool,
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding.fmiDoStep(
+                self.component,
+                current_communication_point,
+                communication_step_size,
+                new_step as binding::fmiBoolean,
+            )
+        })
+        .ok()
+    }
+
+    /// Stop a communication step that returned [`Fmi1Res::Pending`].
+    pub fn cancel_step(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe { self.binding.fmiCancelStep(self.component) }).ok()
+    }
+
+    /// Status of an asynchronous `fmiDoStep` call.
+    pub fn do_step_status(&mut self) -> Result<Fmi1Status, Fmi1Error> {
+        let mut ret = binding::fmiStatus_fmiOK;
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetStatus(
+                self.component,
+                binding::fmiStatusKind_fmiDoStepStatus,
+                &mut ret,
+            )
+        })
+        .ok()
+        .map(|_| Fmi1Status::from(ret))
+    }
+
+    /// Description of the state of an asynchronous `fmiDoStep` call.
+    pub fn pending_status(&mut self) -> Result<String, Fmi1Error> {
+        let mut ret: binding::fmiString = std::ptr::null();
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetStringStatus(
+                self.component,
+                binding::fmiStatusKind_fmiPendingStatus,
+                &mut ret,
+            )
+        })
+        .ok()?;
+        if ret.is_null() {
+            return Err(Fmi1Error::Error);
+        }
+        Ok(unsafe { CStr::from_ptr(ret) }
+            .to_string_lossy()
+            .into_owned())
+    }
+
+    /// End time of the last successful communication step, after `fmiDoStep` returned
+    /// [`Fmi1Error::Discard`].
+    pub fn last_successful_time(&mut self) -> Result<f64, Fmi1Error> {
+        let mut ret = 0.0;
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetRealStatus(
+                self.component,
+                binding::fmiStatusKind_fmiLastSuccessfulTime,
+                &mut ret,
+            )
+        })
+        .ok()
+        .map(|_| ret)
+    }
+
+    /// Set the `orders[i]`-th derivatives of the Real inputs `vrs[i]`.
+    pub fn set_real_input_derivatives(
+        &mut self,
+        vrs: &[binding::fmiValueReference],
+        orders: &[binding::fmiInteger],
+        values: &[binding::fmiReal],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        assert_eq!(vrs.len(), orders.len());
+        assert_eq!(vrs.len(), values.len());
+        Fmi1Status::from(unsafe {
+            self.binding.fmiSetRealInputDerivatives(
+                self.component,
+                vrs.as_ptr(),
+                vrs.len(),
+                orders.as_ptr(),
+                values.as_ptr(),
+            )
+        })
+        .ok()
+    }
+
+    /// Get the `orders[i]`-th derivatives of the Real outputs `vrs[i]`.
+    pub fn get_real_output_derivatives(
+        &mut self,
+        vrs: &[binding::fmiValueReference],
+        orders: &[binding::fmiInteger],
+        values: &mut [binding::fmiReal],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        assert_eq!(vrs.len(), orders.len());
+        assert_eq!(vrs.len(), values.len());
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetRealOutputDerivatives(
+                self.component,
+                vrs.as_ptr(),
+                vrs.len(),
+                orders.as_ptr(),
+                values.as_mut_ptr(),
+            )
+        })
+        .ok()
+    }
+}
+
+impl FmiInstance for Instance<CS> {
+    type ModelDescription = schema::Fmi1ModelDescription;
+    type ValueRef = <import::Fmi1Import as FmiImport>::ValueRef;
+    type Status = Fmi1Status;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_version(&self) -> &str {
+        Instance::<CS>::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        InterfaceType::CoSimulation
+    }
+
+    /// FMI 1.0 has no log categories, `categories` is ignored.
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        _categories: &[&str],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Instance::<CS>::set_debug_logging(self, logging_on)
+    }
+
+    /// Stores the experiment, which is passed to `fmiInitializeSlave` in
+    /// [`FmiInstance::exit_initialization_mode`]. The tolerance is not used by FMI 1.0
+    /// Co-Simulation.
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        self.experiment = Experiment {
+            tolerance,
+            start_time,
+            stop_time,
+        };
+        Ok(Fmi1Res::OK)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding.fmiInitializeSlave(
+                self.component,
+                self.experiment.start_time,
+                self.experiment.stop_time.is_some() as binding::fmiBoolean,
+                self.experiment.stop_time.unwrap_or(0.0),
+            )
+        })
+        .ok()
+    }
+
+    fn terminate(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe { self.binding.fmiTerminateSlave(self.component) }).ok()
+    }
+
+    fn reset(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe { self.binding.fmiResetSlave(self.component) }).ok()
+    }
+}
@@ -0,0 +1,274 @@
+//! FMI 1.0 instance interface
+
+use std::ffi::{CStr, CString};
+
+use crate::{
+    CS, ME,
+    library::LibraryLease,
+    traits::{FmiStatus, InstanceTag},
+};
+
+use super::{Fmi1Error, Fmi1Res, Fmi1Status, binding};
+
+mod co_simulation;
+mod model_exchange;
+
+pub type InstanceME = Instance<ME>;
+pub type InstanceCS = Instance<CS>;
+
+/// Associates an interface type with the FMI 1.0 API implementing it.
+pub trait Fmi1Tag: InstanceTag {
+    /// Raw bindings of the API
+    type Binding;
+
+    /// Free the instance, `fmiFreeModelInstance` or `fmiFreeSlaveInstance`.
+    #[doc(hidden)]
+    unsafe fn free_instance(binding: &Self::Binding, component: binding::fmiComponent);
+}
+
+impl Fmi1Tag for ME {
+    type Binding = binding::Fmi1MeBinding;
+
+    unsafe fn free_instance(binding: &Self::Binding, component: binding::fmiComponent) {
+        unsafe { binding.fmiFreeModelInstance(component) }
+    }
+}
+
+impl Fmi1Tag for CS {
+    type Binding = binding::Fmi1CsBinding;
+
+    unsafe fn free_instance(binding: &Self::Binding, component: binding::fmiComponent) {
+        unsafe { binding.fmiFreeSlaveInstance(component) }
+    }
+}
+
+/// Experiment set up by [`crate::traits::FmiInstance::enter_initialization_mode`], since FMI 1.0
+/// passes it to `fmiInitialize` or `fmiInitializeSlave` instead.
+#[derive(Debug, Default, Clone, Copy)]
+struct Experiment {
+    tolerance: Option<f64>,
+    start_time: f64,
+    stop_time: Option<f64>,
+}
+
+pub struct Instance<Tag: Fmi1Tag> {
+    /// Copy of the instance name
+    name: String,
+    /// Raw FMI 1.0 bindings
+    binding: Tag::Binding,
+    /// Pointer to the raw FMI 1.0 instance
+    component: binding::fmiComponent,
+    /// Experiment to initialize the instance with
+    experiment: Experiment,
+    /// Event info returned by `fmiInitialize`, reported by the first `update_discrete_states`
+    /// (Model Exchange only)
+    initial_event_info: Option<binding::fmiEventInfo>,
+    /// Shared library the instance is loaded from, released after the bindings are dropped
+    _library: LibraryLease,
+    _tag: std::marker::PhantomData<Tag>,
+}
+
+impl<Tag: Fmi1Tag> Drop for Instance<Tag> {
+    fn drop(&mut self) {
+        log::trace!("Freeing component {:?}", self.component);
+        unsafe { Tag::free_instance(&self.binding, self.component) };
+    }
+}
+
+impl<Tag: Fmi1Tag> std::fmt::Debug for Instance<Tag> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Instance {} {{{:?}}}", self.name, self.component,)
+    }
+}
+
+/// Callbacks shared by the Model Exchange and Co-Simulation APIs
+fn callback_functions() -> (
+    binding::fmiCallbackLogger,
+    binding::fmiCallbackAllocateMemory,
+    binding::fmiCallbackFreeMemory,
+) {
+    (
+        Some(binding::logger::fmi1_callback_logger_handler as _),
+        Some(libc::calloc),
+        Some(libc::free),
+    )
+}
+
+/// Functions with identical signatures in the Model Exchange and Co-Simulation APIs.
+macro_rules! impl_common {
+    ($tag:ty) => {
+        impl Instance<$tag> {
+            /// The FMI-standard version string, "1.0"
+            pub fn get_version(&self) -> &str {
+                // Safety: The FMI API guarantees that the pointer is valid within the lifetime of
+                // the FMU
+                unsafe { CStr::from_ptr(self.binding.fmiGetVersion()) }
+                    .to_str()
+                    .expect("Error converting string")
+            }
+
+            /// Turn debug logging of the FMU on or off.
+            pub fn set_debug_logging(&mut self, logging_on: bool) -> Result<Fmi1Res, Fmi1Error> {
+                Fmi1Status::from(unsafe {
+                    self.binding
+                        .fmiSetDebugLogging(self.component, logging_on as binding::fmiBoolean)
+                })
+                .ok()
+            }
+
+            pub fn get_real(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &mut [binding::fmiReal],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiGetReal(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        values.as_mut_ptr(),
+                    )
+                })
+                .ok()
+            }
+
+            pub fn get_integer(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &mut [binding::fmiInteger],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiGetInteger(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        values.as_mut_ptr(),
+                    )
+                })
+                .ok()
+            }
+
+            pub fn get_boolean(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &mut [binding::fmiBoolean],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiGetBoolean(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        values.as_mut_ptr(),
+                    )
+                })
+                .ok()
+            }
+
+            pub fn get_string(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &mut [CString],
+            ) -> Result<(), Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                let mut value_ptrs: Vec<binding::fmiString> = vec![std::ptr::null(); values.len()];
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiGetString(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        value_ptrs.as_mut_ptr(),
+                    )
+                })
+                .ok()?;
+
+                // Copy the C strings into the output CString values
+                for (value, ptr) in values.iter_mut().zip(value_ptrs.iter()) {
+                    if ptr.is_null() {
+                        return Err(Fmi1Error::Error);
+                    }
+                    let cstr = unsafe { CStr::from_ptr(*ptr) };
+                    *value = CString::new(cstr.to_bytes()).map_err(|_| Fmi1Error::Error)?;
+                }
+                Ok(())
+            }
+
+            pub fn set_real(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &[binding::fmiReal],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiSetReal(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        values.as_ptr(),
+                    )
+                })
+                .ok()
+            }
+
+            pub fn set_integer(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &[binding::fmiInteger],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiSetInteger(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        values.as_ptr(),
+                    )
+                })
+                .ok()
+            }
+
+            pub fn set_boolean(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &[binding::fmiBoolean],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiSetBoolean(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        values.as_ptr(),
+                    )
+                })
+                .ok()
+            }
+
+            pub fn set_string(
+                &mut self,
+                vrs: &[binding::fmiValueReference],
+                values: &[CString],
+            ) -> Result<Fmi1Res, Fmi1Error> {
+                assert_eq!(vrs.len(), values.len());
+                let ptrs = values
+                    .iter()
+                    .map(|s| s.as_c_str().as_ptr())
+                    .collect::<Vec<_>>();
+                Fmi1Status::from(unsafe {
+                    self.binding.fmiSetString(
+                        self.component,
+                        vrs.as_ptr(),
+                        vrs.len(),
+                        ptrs.as_ptr(),
+                    )
+                })
+                .ok()
+            }
+        }
+    };
+}
+
+impl_common!(ME);
+impl_common!(CS);
@@ -0,0 +1,283 @@
+use std::ffi::CString;
+
+use super::{Experiment, Instance, binding, callback_functions};
+use crate::{
+    Error, EventFlags, InterfaceType, ME,
+    fmi1::{Fmi1Error, Fmi1Res, Fmi1Status, import, schema},
+    traits::{FmiEventHandler, FmiImport, FmiInstance, FmiModelExchange, FmiStatus},
+};
+
+impl Instance<ME> {
+    /// Initialize a new Instance from an Import
+    ///
+    /// FMI 1.0 Model Exchange has no `visible` argument, it is accepted for symmetry with
+    /// [`Instance<CS>::new`] and ignored.
+    pub fn new(
+        import: &import::Fmi1Import,
+        instance_name: &str,
+        _visible: bool,
+        logging_on: bool,
+    ) -> Result<Self, Error> {
+        let schema = import.model_description();
+        if schema.is_co_simulation() {
+            return Err(Error::UnsupportedFmuType("ModelExchange".to_owned()));
+        }
+
+        let (binding, library) = import.me_binding(&schema.model_identifier)?;
+
+        let (logger, allocate_memory, free_memory) = callback_functions();
+        let callbacks = binding::fmiCallbackFunctions {
+            logger,
+            allocateMemory: allocate_memory,
+            freeMemory: free_memory,
+        };
+
+        let name = instance_name.to_owned();
+
+        let instance_name = CString::new(instance_name).expect("Error building CString");
+        let guid = CString::new(schema.guid.as_bytes()).expect("Error building CString");
+
+        let component = unsafe {
+            binding.fmiInstantiateModel(
+                instance_name.as_ptr(),
+                guid.as_ptr(),
+                callbacks,
+                logging_on as binding::fmiBoolean,
+            )
+        };
+        if component.is_null() {
+            return Err(Error::Instantiation);
+        }
+        log::trace!("Created FMI1.0 ME component {component:?}");
+
+        Ok(Self {
+            name,
+            binding,
+            component,
+            experiment: Experiment::default(),
+            initial_event_info: None,
+            _library: library,
+            _tag: std::marker::PhantomData,
+        })
+    }
+
+    /// Get the value references of the continuous states, in the order of
+    /// [`FmiModelExchange::get_continuous_states`].
+    pub fn get_state_value_references(
+        &mut self,
+        vrs: &mut [binding::fmiValueReference],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding
+                .fmiGetStateValueReferences(self.component, vrs.as_mut_ptr(), vrs.len())
+        })
+        .ok()
+    }
+}
+
+impl FmiInstance for Instance<ME> {
+    type ModelDescription = schema::Fmi1ModelDescription;
+    type ValueRef = <import::Fmi1Import as FmiImport>::ValueRef;
+    type Status = Fmi1Status;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_version(&self) -> &str {
+        Instance::<ME>::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        InterfaceType::ModelExchange
+    }
+
+    /// FMI 1.0 has no log categories, `categories` is ignored.
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        _categories: &[&str],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Instance::<ME>::set_debug_logging(self, logging_on)
+    }
+
+    /// Sets the start time. The tolerance is passed to `fmiInitialize` in
+    /// [`FmiInstance::exit_initialization_mode`].
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        self.experiment = Experiment {
+            tolerance,
+            start_time,
+            stop_time,
+        };
+        FmiModelExchange::set_time(self, start_time)
+    }
+
+    /// Calls `fmiInitialize`. The resulting event info is reported by the next call to
+    /// [`FmiModelExchange::update_discrete_states`].
+    fn exit_initialization_mode(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        let mut event_info = binding::fmiEventInfo::default();
+        let result = Fmi1Status::from(unsafe {
+            self.binding.fmiInitialize(
+                self.component,
+                self.experiment.tolerance.is_some() as binding::fmiBoolean,
+                self.experiment.tolerance.unwrap_or(0.0),
+                &mut event_info,
+            )
+        })
+        .ok()?;
+        self.initial_event_info = Some(event_info);
+        Ok(result)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe { self.binding.fmiTerminate(self.component) }).ok()
+    }
+
+    /// FMI 1.0 Model Exchange can not reset an instance, it has to be instantiated again.
+    fn reset(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        log::error!("FMI 1.0 Model Exchange instances can not be reset");
+        Err(Fmi1Error::Error)
+    }
+}
+
+impl FmiModelExchange for Instance<ME> {
+    /// FMI 1.0 has no Continuous-Time Mode, this is a no-op.
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Ok(Fmi1Res::OK)
+    }
+
+    /// FMI 1.0 has no Event Mode, this is a no-op.
+    fn enter_event_mode(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        Ok(Fmi1Res::OK)
+    }
+
+    /// Calls `fmiEventUpdate`, which iterates until the event iteration converged.
+    ///
+    /// The first call after [`FmiInstance::exit_initialization_mode`] reports the event info
+    /// returned by `fmiInitialize` instead.
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        if let Some(event_info) = self.initial_event_info.take() {
+            event_flags.update_from_fmi1_event_info(event_info);
+            return Ok(Fmi1Res::OK);
+        }
+
+        let mut event_info = binding::fmiEventInfo::default();
+        let result = Fmi1Status::from(unsafe {
+            self.binding
+                .fmiEventUpdate(self.component, binding::fmiFalse, &mut event_info)
+        })
+        .ok()?;
+        event_flags.update_from_fmi1_event_info(event_info);
+        Ok(result)
+    }
+
+    /// `no_set_fmu_state_prior` is ignored, and FMI 1.0 can not request termination here.
+    fn completed_integrator_step(
+        &mut self,
+        _no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        let mut call_event_update = binding::fmiFalse;
+        let result = Fmi1Status::from(unsafe {
+            self.binding
+                .fmiCompletedIntegratorStep(self.component, &mut call_event_update)
+        })
+        .ok();
+        *enter_event_mode = call_event_update != binding::fmiFalse;
+        *terminate_simulation = false;
+        result
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe { self.binding.fmiSetTime(self.component, time) }).ok()
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetContinuousStates(
+                self.component,
+                continuous_states.as_mut_ptr(),
+                continuous_states.len(),
+            )
+        })
+        .ok()
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding
+                .fmiSetContinuousStates(self.component, states.as_ptr(), states.len())
+        })
+        .ok()
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetDerivatives(
+                self.component,
+                derivatives.as_mut_ptr(),
+                derivatives.len(),
+            )
+        })
+        .ok()
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        Fmi1Status::from(unsafe {
+            self.binding.fmiGetNominalContinuousStates(
+                self.component,
+                nominals.as_mut_ptr(),
+                nominals.len(),
+            )
+        })
+        .ok()
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi1Error> {
+        let status = unsafe {
+            self.binding.fmiGetEventIndicators(
+                self.component,
+                event_indicators.as_mut_ptr(),
+                event_indicators.len(),
+            )
+        };
+
+        match Fmi1Status::from(status).ok() {
+            Ok(_) => Ok(true),
+            // The FMU couldn't compute the indicators, which is recoverable
+            Err(Fmi1Error::Discard) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl FmiEventHandler for Instance<ME> {
+    fn enter_event_mode(&mut self) -> Result<Fmi1Res, Fmi1Error> {
+        FmiModelExchange::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi1Res, Fmi1Error> {
+        FmiModelExchange::update_discrete_states(self, event_flags)
+    }
+}
@@ -0,0 +1,84 @@
+//! FMI 1.0 API
+//!
+//! FMI 1.0 defines separate APIs for Model Exchange and Co-Simulation, and an FMU implements
+//! exactly one of them. Both are supported, see [`import::Fmi1Import::instantiate_me`] and
+//! [`import::Fmi1Import::instantiate_cs`].
+
+pub mod import;
+pub mod instance;
+// Re-export
+pub use fmi_schema::fmi1 as schema;
+#[doc = "Hand-written bindings for the FMI 1.0 API"]
+pub use fmi_sys::fmi1 as binding;
+
+use crate::traits::FmiStatus;
+
+#[derive(Debug)]
+pub enum Fmi1Res {
+    /// All well
+    OK,
+    /// Things are not quite right, but the computation can continue. Function “logger” was called
+    /// in the model, and it is expected that this function has shown the prepared information
+    /// message to the user.
+    Warning,
+    /// This status is returned only from the co-simulation interface, if the slave executes the
+    /// function in an asynchronous way.
+    Pending,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Fmi1Error {
+    /// For “model exchange”: It is recommended to perform a smaller step size and evaluate the
+    /// model equations again. If this is not possible, the simulation has to be terminated.
+    ///
+    /// For “co-simulation”: The slave is not able to compute the communication step, or to return
+    /// the required status information.
+    #[error("Discard")]
+    Discard,
+    /// The model encountered an error. The simulation cannot be continued with this instance.
+    #[error("Error")]
+    Error,
+    /// The model computations are irreparably corrupted for all instances.
+    #[error("Fatal")]
+    Fatal,
+}
+
+#[derive(Debug)]
+pub struct Fmi1Status(binding::fmiStatus);
+
+impl FmiStatus for Fmi1Status {
+    type Res = Fmi1Res;
+
+    type Err = Fmi1Error;
+
+    /// Convert to [`Result<Fmi1Res, Fmi1Error>`]
+    #[inline]
+    fn ok(self) -> Result<Fmi1Res, Fmi1Error> {
+        self.into()
+    }
+
+    #[inline]
+    fn is_error(&self) -> bool {
+        self.0 == binding::fmiStatus_fmiError || self.0 == binding::fmiStatus_fmiFatal
+    }
+}
+
+impl From<binding::fmiStatus> for Fmi1Status {
+    fn from(status: binding::fmiStatus) -> Self {
+        Self(status)
+    }
+}
+
+impl From<Fmi1Status> for Result<Fmi1Res, Fmi1Error> {
+    fn from(Fmi1Status(status): Fmi1Status) -> Self {
+        match status {
+            binding::fmiStatus_fmiOK => Ok(Fmi1Res::OK),
+            binding::fmiStatus_fmiWarning => Ok(Fmi1Res::Warning),
+            binding::fmiStatus_fmiPending => Ok(Fmi1Res::Pending),
+            binding::fmiStatus_fmiDiscard => Err(Fmi1Error::Discard),
+            binding::fmiStatus_fmiError => Err(Fmi1Error::Error),
+            binding::fmiStatus_fmiFatal => Err(Fmi1Error::Fatal),
+            _ => unreachable!("Invalid status"),
+        }
+    }
+}
@@ -1,16 +1,23 @@
-use std::{path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr, sync::Arc};
 
 use super::{binding, instance::Instance};
-use crate::{CS, Error, ME, traits::FmiImport};
+use crate::{
+    CS, Error, ME,
+    import::{FmuDir, LibraryMode},
+    library::LibraryLease,
+    traits::FmiImport,
+};
 
-use fmi_schema::{MajorVersion, fmi2 as schema};
+use fmi_schema::{MajorVersion, fmi2 as schema, traits::FmiInterfaceType};
 
 #[derive(Debug)]
 pub struct Fmi2Import {
     /// Path to the unzipped FMU on disk
-    dir: tempfile::TempDir,
-    /// Parsed raw-schema model description
-    model_description: schema::Fmi2ModelDescription,
+    dir: FmuDir,
+    /// Parsed raw-schema model description, shared with the instances
+    model_description: Arc<schema::Fmi2ModelDescription>,
+    /// How the shared library is loaded for new instances
+    library_mode: LibraryMode,
 }
 
 impl FmiImport for Fmi2Import {
@@ -19,17 +26,18 @@ impl FmiImport for Fmi2Import {
     type Binding = binding::Fmi2Binding;
     type ValueRef = binding::fmi2ValueReference;
 
-    fn new(dir: tempfile::TempDir, schema_xml: &str) -> Result<Self, Error> {
+    fn new(dir: FmuDir, schema_xml: &str) -> Result<Self, Error> {
         let schema = schema::Fmi2ModelDescription::from_str(schema_xml)?;
         Ok(Self {
             dir,
-            model_description: schema,
+            model_description: Arc::new(schema),
+            library_mode: LibraryMode::default(),
         })
     }
 
     #[inline]
-    fn archive_path(&self) -> &std::path::Path {
-        self.dir.path()
+    fn dir(&self) -> &FmuDir {
+        &self.dir
     }
 
     /// Get the path to the shared library
@@ -68,6 +76,14 @@ impl FmiImport for Fmi2Import {
         unsafe { binding::Fmi2Binding::new(lib_path).map_err(Error::from) }
     }
 
+    fn library_mode(&self) -> LibraryMode {
+        self.library_mode
+    }
+
+    fn set_library_mode(&mut self, mode: LibraryMode) {
+        self.library_mode = mode;
+    }
+
     /// Get a `String` representation of the resources path for this FMU
     ///
     /// As per the FMI standard, the resource location is a IETF URI to the resources directory.
@@ -81,6 +97,34 @@ impl FmiImport for Fmi2Import {
 }
 
 impl Fmi2Import {
+    /// Get a shared handle to the parsed raw-schema model description
+    pub(crate) fn shared_model_description(&self) -> Arc<schema::Fmi2ModelDescription> {
+        self.model_description.clone()
+    }
+
+    /// Load the shared library of `interface` for a new instance, according to the library mode.
+    pub(crate) fn instance_binding(
+        &self,
+        interface: &impl FmiInterfaceType,
+    ) -> Result<(binding::Fmi2Binding, LibraryLease), Error> {
+        let model_identifier = interface.model_identifier();
+        let lib_path = self
+            .dir
+            .path()
+            .join(self.shared_lib_path(model_identifier)?);
+        let lease = LibraryLease::acquire(
+            &lib_path,
+            model_identifier,
+            self.library_mode,
+            interface
+                .can_be_instantiated_only_once_per_process()
+                .unwrap_or(false),
+        )?;
+        log::trace!("Loading shared library {:?}", lease.path());
+        let binding = unsafe { binding::Fmi2Binding::new(lease.path()) }?;
+        Ok((binding, lease))
+    }
+
     /// Create a new instance of the FMU for Model-Exchange
     pub fn instantiate_me(
         &self,
@@ -6,7 +6,11 @@ use crate::{
     traits::{FmiImport, FmiStatus},
 };
 
-use super::{CS, Instance, binding, traits};
+use super::{
+    CS, Instance, binding,
+    state::{STEP_STATUS, State, StateMachine},
+    traits,
+};
 
 impl Instance<CS> {
     /// Initialize a new Instance from an Import
@@ -23,9 +27,14 @@ impl Instance<CS> {
             .as_ref()
             .ok_or(Error::UnsupportedFmuType("CoSimulation".to_owned()))?;
 
-        let binding = import.binding(&co_simulation.model_identifier)?;
+        let (binding, library) = import.instance_binding(co_simulation)?;
 
-        let callbacks = Box::<CallbackFunctions>::default();
+        let environment = Box::<binding::logger::ComponentEnvironment>::default();
+        let callbacks = Box::new(CallbackFunctions {
+            component_environment: &*environment as *const binding::logger::ComponentEnvironment
+                as _,
+            ..Default::default()
+        });
 
         let name = instance_name.to_owned();
 
@@ -55,8 +64,12 @@ impl Instance<CS> {
             binding,
             component,
             callbacks,
+            environment,
             name,
             saved_states: Vec::new(),
+            model_description: import.shared_model_description(),
+            state_machine: StateMachine::co_simulation(),
+            _library: library,
             _tag: std::marker::PhantomData,
         })
     }
@@ -69,35 +82,45 @@ impl traits::CoSimulation for Instance<CS> {
         communication_step_size: f64,
         new_step: bool,
     ) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2DoStep", &[State::StepComplete])?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2DoStep(
                 self.component,
                 current_communication_point,
                 communication_step_size,
                 new_step as _,
             )
-        })
-        .ok()
+        });
+        self.state_machine.update_step(status.ok())
     }
 
     fn cancel_step(&self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2CancelStep(self.component) }).ok()
+        self.require("fmi2CancelStep", &[State::StepInProgress])?;
+        let status = Fmi2Status::from(unsafe { self.binding.fmi2CancelStep(self.component) });
+        self.state_machine
+            .update(status.ok(), Some(State::StepCanceled))
     }
 
     fn do_step_status(&mut self) -> Result<Fmi2Status, Fmi2Error> {
+        self.require("fmi2GetStatus", STEP_STATUS)?;
         let mut ret = binding::fmi2Status_fmi2OK;
-        Fmi2Status(unsafe {
+        let status = Fmi2Status(unsafe {
             self.binding.fmi2GetStatus(
                 self.component,
                 binding::fmi2StatusKind_fmi2DoStepStatus,
                 &mut ret,
             )
-        })
-        .ok()
-        .map(|_| Fmi2Status(ret))
+        });
+        self.state_machine.update(status.ok(), None)?;
+        // The asynchronous step finished
+        if self.state() == State::StepInProgress {
+            let _ = self.state_machine.update_step(Fmi2Status(ret).ok());
+        }
+        Ok(Fmi2Status(ret))
     }
 
     fn pending_status(&mut self) -> Result<&str, Fmi2Error> {
+        self.require("fmi2GetStringStatus", STEP_STATUS)?;
         let str_ret = c"";
         Fmi2Status(unsafe {
             self.binding.fmi2GetStringStatus(
@@ -111,6 +134,7 @@ impl traits::CoSimulation for Instance<CS> {
     }
 
     fn last_successful_time(&mut self) -> Result<f64, Fmi2Error> {
+        self.require("fmi2GetRealStatus", STEP_STATUS)?;
         let mut ret = 0.0;
         Fmi2Status(unsafe {
             self.binding.fmi2GetRealStatus(
@@ -124,6 +148,7 @@ impl traits::CoSimulation for Instance<CS> {
     }
 
     fn terminated(&mut self) -> Result<bool, Fmi2Error> {
+        self.require("fmi2GetBooleanStatus", STEP_STATUS)?;
         let mut ret = 0i32;
         Fmi2Status(unsafe {
             self.binding.fmi2GetBooleanStatus(
@@ -3,7 +3,10 @@ use std::ffi::CStr;
 use crate::fmi2::{Fmi2Error, Fmi2Res, Fmi2Status, binding};
 use crate::traits::{FmiStatus, InstanceTag};
 
-use super::{Common, Instance};
+use super::{
+    Common, Instance,
+    state::{ANY, GETTABLE, State},
+};
 
 impl<Tag: InstanceTag> Common for Instance<Tag> {
     fn get_version(&self) -> &str {
@@ -25,6 +28,7 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         logging_on: bool,
         categories: &[&str],
     ) -> Result<Fmi2Res, Fmi2Error> {
+        self.require("fmi2SetDebugLogging", ANY)?;
         let category_cstr = categories
             .iter()
             .map(|c| std::ffi::CString::new(*c).expect("Error building CString"))
@@ -32,15 +36,15 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
 
         let category_ptrs: Vec<_> = category_cstr.iter().map(|c| c.as_ptr()).collect();
 
-        Fmi2Status::from(unsafe {
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2SetDebugLogging(
                 self.component,
                 logging_on as binding::fmi2Boolean,
                 category_ptrs.len(),
                 category_ptrs.as_ptr(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn setup_experiment(
@@ -49,7 +53,8 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         start_time: f64,
         stop_time: Option<f64>,
     ) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2SetupExperiment", &[State::Instantiated])?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2SetupExperiment(
                 self.component,
                 tolerance.is_some() as binding::fmi2Boolean,
@@ -58,24 +63,46 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
                 stop_time.is_some() as binding::fmi2Boolean,
                 stop_time.unwrap_or(0.0),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn enter_initialization_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2EnterInitializationMode(self.component) }).ok()
+        self.require("fmi2EnterInitializationMode", &[State::Instantiated])?;
+        let status =
+            Fmi2Status::from(unsafe { self.binding.fmi2EnterInitializationMode(self.component) });
+        self.state_machine
+            .update(status.ok(), Some(State::InitializationMode))
     }
 
     fn exit_initialization_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2ExitInitializationMode(self.component) }).ok()
+        self.require("fmi2ExitInitializationMode", &[State::InitializationMode])?;
+        let status =
+            Fmi2Status::from(unsafe { self.binding.fmi2ExitInitializationMode(self.component) });
+        let next = self.state_machine.after_initialization();
+        self.state_machine.update(status.ok(), Some(next))
     }
 
     fn reset(&mut self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2Reset(self.component) }).ok()
+        self.require("fmi2Reset", ANY)?;
+        let status = Fmi2Status::from(unsafe { self.binding.fmi2Reset(self.component) });
+        self.state_machine
+            .update(status.ok(), Some(State::Instantiated))
     }
 
     fn terminate(&mut self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2Terminate(self.component) }).ok()
+        self.require(
+            "fmi2Terminate",
+            &[
+                State::EventMode,
+                State::ContinuousTimeMode,
+                State::StepComplete,
+                State::StepFailed,
+            ],
+        )?;
+        let status = Fmi2Status::from(unsafe { self.binding.fmi2Terminate(self.component) });
+        self.state_machine
+            .update(status.ok(), Some(State::Terminated))
     }
 
     fn get_real(
@@ -84,11 +111,12 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         values: &mut [binding::fmi2Real],
     ) -> Result<Fmi2Res, Fmi2Error> {
         assert_eq!(vrs.len(), values.len());
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetReal", GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2GetReal(self.component, vrs.as_ptr(), vrs.len(), values.as_mut_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_integer(
@@ -96,15 +124,16 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         vrs: &[binding::fmi2ValueReference],
         values: &mut [binding::fmi2Integer],
     ) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetInteger", GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2GetInteger(
                 self.component,
                 vrs.as_ptr(),
                 vrs.len(),
                 values.as_mut_ptr(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_boolean(
@@ -112,11 +141,12 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         sv: &[binding::fmi2ValueReference],
         v: &mut [binding::fmi2Boolean],
     ) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetBoolean", GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2GetBoolean(self.component, sv.as_ptr(), sv.len(), v.as_mut_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_string(
@@ -124,6 +154,7 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         sv: &[binding::fmi2ValueReference],
         v: &mut [std::ffi::CString],
     ) -> Result<(), Fmi2Error> {
+        self.require("fmi2GetString", GETTABLE)?;
         let n_values = v.len();
 
         // Create an array of null pointers to receive the string pointers from FMI
@@ -139,7 +170,8 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
             )
         };
 
-        Fmi2Status::from(status).ok()?;
+        self.state_machine
+            .update(Fmi2Status::from(status).ok(), None)?;
 
         // Copy the C strings into the output CString values
         for (value, ptr) in v.iter_mut().zip(value_ptrs.iter()) {
@@ -160,11 +192,12 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         values: &[binding::fmi2Real],
     ) -> Result<Fmi2Res, Fmi2Error> {
         assert_eq!(vrs.len(), values.len());
-        Fmi2Status::from(unsafe {
+        self.require_settable("fmi2SetReal", vrs)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2SetReal(self.component, vrs.as_ptr(), values.len(), values.as_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_integer(
@@ -173,11 +206,12 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         values: &[binding::fmi2Integer],
     ) -> Result<Fmi2Res, Fmi2Error> {
         assert_eq!(vrs.len(), values.len());
-        Fmi2Status::from(unsafe {
+        self.require_settable("fmi2SetInteger", vrs)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2SetInteger(self.component, vrs.as_ptr(), values.len(), values.as_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_boolean(
@@ -186,11 +220,12 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         values: &[binding::fmi2Boolean],
     ) -> Result<Fmi2Res, Fmi2Error> {
         assert_eq!(vrs.len(), values.len());
-        Fmi2Status::from(unsafe {
+        self.require_settable("fmi2SetBoolean", vrs)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2SetBoolean(self.component, vrs.as_ptr(), values.len(), values.as_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_string(
@@ -198,6 +233,7 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
         vrs: &[binding::fmi2ValueReference],
         values: &[std::ffi::CString],
     ) -> Result<(), Fmi2Error> {
+        self.require_settable("fmi2SetString", vrs)?;
         let ptrs = values
             .iter()
             .map(|s| s.as_c_str().as_ptr())
@@ -208,7 +244,8 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
                 .fmi2SetString(self.component, vrs.as_ptr(), vrs.len() as _, ptrs.as_ptr())
         };
 
-        Fmi2Status::from(status).ok()?;
+        self.state_machine
+            .update(Fmi2Status::from(status).ok(), None)?;
         Ok(())
     }
 
@@ -221,7 +258,8 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
     ) -> Result<Fmi2Res, Fmi2Error> {
         assert!(unknown_vrs.len() == dv_unknown_values.len());
         assert!(known_vrs.len() == dv_unknown_values.len());
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetDirectionalDerivative", GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2GetDirectionalDerivative(
                 self.component,
                 unknown_vrs.as_ptr(),
@@ -231,8 +269,8 @@ impl<Tag: InstanceTag> Common for Instance<Tag> {
                 dv_known_values.as_ptr(),
                 dv_unknown_values.as_mut_ptr(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     #[cfg(false)]
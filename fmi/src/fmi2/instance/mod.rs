@@ -1,20 +1,29 @@
 //! FMI 2.0 instance interface
 
+use std::sync::Arc;
+
 use crate::{
     CS, ME,
     fmi2::Fmi2Res,
+    library::LibraryLease,
     traits::{FmiImport, FmiInstance, FmiStatus, InstanceTag},
 };
 
-use super::{CallbackFunctions, Fmi2Error, Fmi2Status, binding, import::Fmi2Import, schema};
+use super::{
+    CallbackFunctions, Fmi2Error, Fmi2Status, LogMessage, binding, import::Fmi2Import, schema,
+};
 
 mod co_simulation;
 mod common;
 mod model_exchange;
+mod state;
 mod traits;
 
+pub use state::State;
 pub use traits::{CoSimulation, Common, ModelExchange};
 
+use state::StateMachine;
+
 pub type InstanceME = Instance<ME>;
 pub type InstanceCS = Instance<CS>;
 
@@ -30,8 +39,16 @@ pub struct Instance<Tag> {
     /// Callbacks struct
     #[allow(dead_code)]
     callbacks: Box<CallbackFunctions>,
+    /// Passed to the FMU as `componentEnvironment`, holds the log sink
+    environment: Box<binding::logger::ComponentEnvironment>,
     /// Allocated FMU states
     saved_states: Vec<binding::fmi2FMUstate>,
+    /// Model description of the FMU, used to check variable access in checked mode
+    model_description: Arc<schema::Fmi2ModelDescription>,
+    /// Tracked state of the FMI state machine
+    state_machine: StateMachine,
+    /// Shared library the instance is loaded from, released after the bindings are dropped
+    _library: LibraryLease,
     _tag: std::marker::PhantomData<Tag>,
 }
 
@@ -108,28 +125,62 @@ impl Default for CallbackFunctions {
     }
 }
 
+impl<Tag> Instance<Tag> {
+    /// Route the log messages of this instance to `sink` instead of the `log` facade.
+    pub fn set_log_sink<F>(&mut self, sink: F)
+    where
+        F: Fn(&LogMessage<'_>) + Send + Sync + 'static,
+    {
+        self.environment.set_log_sink(Some(Box::new(sink)));
+    }
+
+    /// Route the log messages of this instance back to the `log` facade.
+    pub fn reset_log_sink(&mut self) {
+        self.environment.set_log_sink(None);
+    }
+}
+
 impl<Tag: InstanceTag> Instance<Tag> {
     pub fn get_fmu_state(&mut self) -> Result<FmuState, Fmi2Error> {
         let mut state = std::ptr::null_mut();
-        Fmi2Status(unsafe { self.binding.fmi2GetFMUstate(self.component, &mut state) }).ok()?;
+        let status =
+            Fmi2Status(unsafe { self.binding.fmi2GetFMUstate(self.component, &mut state) });
+        self.state_machine.update(status.ok(), None)?;
 
         if state.is_null() {
             log::error!("FMU returned a null state");
             Err(Fmi2Error::Fatal)
         } else {
             self.saved_states.push(state);
+            self.state_machine.push_saved();
             Ok(FmuState(self.saved_states.len() - 1))
         }
     }
 
+    /// Restore a previously retrieved FMU state, including its [`State`].
     pub fn set_fmu_state(&mut self, state: &FmuState) -> Fmi2Status {
-        let state = self.saved_states.get(state.0).unwrap();
-        unsafe { self.binding.fmi2SetFMUstate(self.component, *state) }.into()
+        let index = state.0;
+        let state = self.saved_states.get(index).unwrap();
+        let status = unsafe { self.binding.fmi2SetFMUstate(self.component, *state) };
+        let _ = self.state_machine.update(
+            Fmi2Status(status).ok(),
+            Some(self.state_machine.saved(index)),
+        );
+        status.into()
     }
 
     pub fn update_fmu_state(&mut self, state: &FmuState) -> Fmi2Status {
-        let state = self.saved_states.get_mut(state.0).unwrap();
-        unsafe { self.binding.fmi2GetFMUstate(self.component, state) }.into()
+        let index = state.0;
+        let state = self.saved_states.get_mut(index).unwrap();
+        let status = unsafe { self.binding.fmi2GetFMUstate(self.component, state) };
+        if self
+            .state_machine
+            .update(Fmi2Status(status).ok(), None)
+            .is_ok()
+        {
+            self.state_machine.update_saved(index);
+        }
+        status.into()
     }
 
     pub fn serialize_fmu_state(&mut self, state: &FmuState) -> Result<Vec<u8>, Fmi2Error> {
@@ -155,6 +206,10 @@ impl<Tag: InstanceTag> Instance<Tag> {
         Ok(buffer)
     }
 
+    /// Deserialize an FMU state.
+    ///
+    /// The [`State`] the FMU state was retrieved in is not serialized. Applying the deserialized FMU
+    /// state enters the state the instance was in when it was deserialized.
     pub fn deserialize_fmu_state(&mut self, buffer: &[u8]) -> Result<FmuState, Fmi2Error> {
         let mut state = std::ptr::null_mut();
         Fmi2Status(unsafe {
@@ -172,6 +227,7 @@ impl<Tag: InstanceTag> Instance<Tag> {
             Err(Fmi2Error::Fatal)
         } else {
             self.saved_states.push(state);
+            self.state_machine.push_saved();
             Ok(FmuState(self.saved_states.len() - 1))
         }
     }
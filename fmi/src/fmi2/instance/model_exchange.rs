@@ -1,6 +1,10 @@
 use std::ffi::CString;
 
-use super::{CallbackFunctions, Instance, binding, traits::ModelExchange};
+use super::{
+    CallbackFunctions, Instance, binding,
+    state::{CONTINUOUS_GETTABLE, State, StateMachine},
+    traits::ModelExchange,
+};
 use crate::{
     Error, EventFlags, ME,
     fmi2::{Fmi2Error, Fmi2Res, Fmi2Status, import},
@@ -22,9 +26,14 @@ impl Instance<ME> {
             .as_ref()
             .ok_or(Error::UnsupportedFmuType("ModelExchange".to_owned()))?;
 
-        let binding = import.binding(&model_exchange.model_identifier)?;
+        let (binding, library) = import.instance_binding(model_exchange)?;
 
-        let callbacks = Box::<CallbackFunctions>::default();
+        let environment = Box::<binding::logger::ComponentEnvironment>::default();
+        let callbacks = Box::new(CallbackFunctions {
+            component_environment: &*environment as *const binding::logger::ComponentEnvironment
+                as _,
+            ..Default::default()
+        });
 
         let name = instance_name.to_owned();
 
@@ -54,8 +63,12 @@ impl Instance<ME> {
             binding,
             component,
             callbacks,
+            environment,
             name,
             saved_states: Vec::new(),
+            model_description: import.shared_model_description(),
+            state_machine: StateMachine::model_exchange(),
+            _library: library,
             _tag: std::marker::PhantomData,
         })
     }
@@ -63,20 +76,28 @@ impl Instance<ME> {
 
 impl ModelExchange for Instance<ME> {
     fn enter_continuous_time_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2EnterContinuousTimeMode(self.component) }).ok()
+        self.require("fmi2EnterContinuousTimeMode", &[State::EventMode])?;
+        let status =
+            Fmi2Status::from(unsafe { self.binding.fmi2EnterContinuousTimeMode(self.component) });
+        self.state_machine
+            .update(status.ok(), Some(State::ContinuousTimeMode))
     }
 
     fn enter_event_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2EnterEventMode(self.component) }).ok()
+        self.require("fmi2EnterEventMode", &[State::ContinuousTimeMode])?;
+        let status = Fmi2Status::from(unsafe { self.binding.fmi2EnterEventMode(self.component) });
+        self.state_machine
+            .update(status.ok(), Some(State::EventMode))
     }
 
     fn new_discrete_states(&mut self, event_flags: &mut EventFlags) -> Result<Fmi2Res, Fmi2Error> {
+        self.require("fmi2NewDiscreteStates", &[State::EventMode])?;
         let mut event_info = binding::fmi2EventInfo::default();
-        let result = Fmi2Status::from(unsafe {
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2NewDiscreteStates(self.component, &mut event_info)
-        })
-        .ok()?;
+        });
+        let result = self.state_machine.update(status.ok(), None)?;
         event_flags.update_from_fmi2_event_info(event_info);
         Ok(result)
     }
@@ -87,74 +108,82 @@ impl ModelExchange for Instance<ME> {
         enter_event_mode: &mut bool,
         terminate_simulation: &mut bool,
     ) -> Result<Fmi2Res, Fmi2Error> {
+        self.require("fmi2CompletedIntegratorStep", &[State::ContinuousTimeMode])?;
         let mut _enter_event_mode = 0;
         let mut _terminate_simulation = 0;
-        let result = Fmi2Status::from(unsafe {
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2CompletedIntegratorStep(
                 self.component,
                 no_set_fmu_state_prior as _,
                 &mut _enter_event_mode,
                 &mut _terminate_simulation,
             )
-        })
-        .ok();
+        });
+        let result = self.state_machine.update(status.ok(), None);
         *enter_event_mode = _enter_event_mode != 0;
         *terminate_simulation = _terminate_simulation != 0;
         result
     }
 
     fn set_time(&mut self, time: f64) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe { self.binding.fmi2SetTime(self.component, time) }).ok()
+        self.require("fmi2SetTime", &[State::ContinuousTimeMode])?;
+        let status = Fmi2Status::from(unsafe { self.binding.fmi2SetTime(self.component, time) });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_continuous_states(
         &mut self,
         continuous_states: &mut [f64],
     ) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetContinuousStates", CONTINUOUS_GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2GetContinuousStates(
                 self.component,
                 continuous_states.as_mut_ptr(),
                 continuous_states.len(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2SetContinuousStates", &[State::ContinuousTimeMode])?;
+        let status = Fmi2Status::from(unsafe {
             self.binding
                 .fmi2SetContinuousStates(self.component, states.as_ptr(), states.len())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_derivatives(&mut self, derivatives: &mut [f64]) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetDerivatives", CONTINUOUS_GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2GetDerivatives(
                 self.component,
                 derivatives.as_mut_ptr(),
                 derivatives.len(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_nominals_of_continuous_states(
         &mut self,
         nominals: &mut [f64],
     ) -> Result<Fmi2Res, Fmi2Error> {
-        Fmi2Status::from(unsafe {
+        self.require("fmi2GetNominalsOfContinuousStates", CONTINUOUS_GETTABLE)?;
+        let status = Fmi2Status::from(unsafe {
             self.binding.fmi2GetNominalsOfContinuousStates(
                 self.component,
                 nominals.as_mut_ptr(),
                 nominals.len(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi2Error> {
+        self.require("fmi2GetEventIndicators", CONTINUOUS_GETTABLE)?;
         let status = unsafe {
             self.binding.fmi2GetEventIndicators(
                 self.component,
@@ -164,7 +193,10 @@ impl ModelExchange for Instance<ME> {
         };
 
         // Convert status and handle Discard case
-        match Fmi2Status::from(status).ok() {
+        match self
+            .state_machine
+            .update(Fmi2Status::from(status).ok(), None)
+        {
             Ok(_) => Ok(true), // Successfully computed indicators
             Err(Fmi2Error::Discard) => {
                 // FMU couldn't compute indicators due to numerical issues
@@ -0,0 +1,365 @@
+//! Tracking of the FMI 2.0 state machine, and the checked mode of instances.
+//!
+//! Every instance tracks its state from the calls made through it. In checked mode (see
+//! [`Instance::set_checked`]), calls that the state machine does not allow in the current state
+//! are rejected with [`Fmi2Error::IllegalCall`] instead of being passed on to the FMU. Setting a
+//! variable is additionally checked against its causality, variability and initial attribute.
+//!
+//! The FMU state functions are not checked, but restoring an FMU state also restores the state it
+//! was retrieved in.
+//!
+//! See sections 3.2.3 (Model Exchange) and 4.2.4 (Co-Simulation) of the FMI 2.0 standard.
+
+use std::{cell::Cell, fmt::Display};
+
+use crate::fmi2::{
+    Fmi2Error, Fmi2Res, binding,
+    schema::{Causality, Initial, Variability},
+};
+
+use super::Instance;
+
+/// A state of the FMI 2.0 state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// After instantiation or [`Common::reset`](super::Common::reset)
+    Instantiated,
+    InitializationMode,
+    /// Model Exchange only
+    EventMode,
+    /// Model Exchange only
+    ContinuousTimeMode,
+    /// Co-Simulation: initialized, and no step is in progress
+    StepComplete,
+    /// Co-Simulation: an asynchronous step returned [`Fmi2Res::Pending`]
+    StepInProgress,
+    /// Co-Simulation: a step returned [`Fmi2Error::Discard`]
+    StepFailed,
+    /// Co-Simulation: an asynchronous step was canceled
+    StepCanceled,
+    Terminated,
+    /// A call returned [`Fmi2Error::Error`]. Only getters and
+    /// [`Common::reset`](super::Common::reset) are allowed.
+    Error,
+    /// A call returned [`Fmi2Error::Fatal`]. The instance can only be dropped.
+    Fatal,
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            State::Instantiated => "Instantiated",
+            State::InitializationMode => "Initialization Mode",
+            State::EventMode => "Event Mode",
+            State::ContinuousTimeMode => "Continuous-Time Mode",
+            State::StepComplete => "Step Complete",
+            State::StepInProgress => "Step In Progress",
+            State::StepFailed => "Step Failed",
+            State::StepCanceled => "Step Canceled",
+            State::Terminated => "Terminated",
+            State::Error => "Error",
+            State::Fatal => "Fatal",
+        })
+    }
+}
+
+/// All states that allow calls
+pub(super) const ANY: &[State] = &[
+    State::Instantiated,
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::StepComplete,
+    State::StepInProgress,
+    State::StepFailed,
+    State::StepCanceled,
+    State::Terminated,
+    State::Error,
+];
+
+/// States in which variables can be read
+pub(super) const GETTABLE: &[State] = &[
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::StepComplete,
+    State::StepFailed,
+    State::StepCanceled,
+    State::Terminated,
+    State::Error,
+];
+
+/// States in which the continuous states, their derivatives and the event indicators can be read
+pub(super) const CONTINUOUS_GETTABLE: &[State] = &[
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::Terminated,
+    State::Error,
+];
+
+/// States in which the status of a Co-Simulation step can be queried
+pub(super) const STEP_STATUS: &[State] = &[
+    State::StepComplete,
+    State::StepInProgress,
+    State::StepFailed,
+    State::StepCanceled,
+    State::Terminated,
+];
+
+/// The state machine of an instance.
+///
+/// The state is a [`Cell`], since some FMI 2.0 calls only borrow the instance.
+#[derive(Debug)]
+pub(super) struct StateMachine {
+    state: Cell<State>,
+    /// Whether calls that are not allowed in `state` are rejected
+    checked: bool,
+    /// State entered by `fmi2ExitInitializationMode`
+    after_initialization: State,
+    /// State each FMU state was retrieved in, indexed like the saved states of the instance
+    saved: Vec<State>,
+}
+
+impl StateMachine {
+    pub(super) fn model_exchange() -> Self {
+        Self::new(State::EventMode)
+    }
+
+    pub(super) fn co_simulation() -> Self {
+        Self::new(State::StepComplete)
+    }
+
+    fn new(after_initialization: State) -> Self {
+        Self {
+            state: Cell::new(State::Instantiated),
+            checked: false,
+            after_initialization,
+            saved: Vec::new(),
+        }
+    }
+
+    /// Track the result of a call that reached the FMU.
+    ///
+    /// A successful call enters `next`, if any. `fmi2Error` and `fmi2Fatal` enter the
+    /// [`State::Error`] and [`State::Fatal`] states.
+    pub(super) fn update<T>(
+        &self,
+        result: Result<T, Fmi2Error>,
+        next: Option<State>,
+    ) -> Result<T, Fmi2Error> {
+        match (&result, next) {
+            (Ok(_), Some(next)) => self.state.set(next),
+            (Err(Fmi2Error::Error), _) => self.state.set(State::Error),
+            (Err(Fmi2Error::Fatal), _) => self.state.set(State::Fatal),
+            _ => {}
+        }
+        result
+    }
+
+    /// Track the result of a Co-Simulation step, or of the asynchronous step it finished.
+    pub(super) fn update_step(
+        &self,
+        result: Result<Fmi2Res, Fmi2Error>,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        match &result {
+            Ok(Fmi2Res::Pending) => self.state.set(State::StepInProgress),
+            Ok(_) => self.state.set(State::StepComplete),
+            Err(Fmi2Error::Discard) => self.state.set(State::StepFailed),
+            _ => return self.update(result, None),
+        }
+        result
+    }
+
+    /// The state entered by `fmi2ExitInitializationMode`
+    pub(super) fn after_initialization(&self) -> State {
+        self.after_initialization
+    }
+
+    /// Remember the current state for a newly retrieved FMU state.
+    pub(super) fn push_saved(&mut self) {
+        self.saved.push(self.state.get());
+    }
+
+    /// Remember the current state for an updated FMU state.
+    pub(super) fn update_saved(&mut self, index: usize) {
+        self.saved[index] = self.state.get();
+    }
+
+    /// The state an FMU state was retrieved in
+    pub(super) fn saved(&self, index: usize) -> State {
+        self.saved[index]
+    }
+}
+
+impl<Tag> Instance<Tag> {
+    /// The current state of the instance in the FMI 2.0 state machine.
+    pub fn state(&self) -> State {
+        self.state_machine.state.get()
+    }
+
+    /// Enable or disable checked mode.
+    ///
+    /// In checked mode, calls that are not allowed in the current [`State`], and setting variables
+    /// that can not be set in it, fail with [`Fmi2Error::IllegalCall`] without reaching the FMU.
+    /// The state is tracked in either mode, so checking can be enabled at any time.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.state_machine.checked = checked;
+    }
+
+    /// Whether the instance is in checked mode, see [`Self::set_checked`].
+    pub fn is_checked(&self) -> bool {
+        self.state_machine.checked
+    }
+
+    /// In checked mode, check that `function` may be called in the current state.
+    pub(super) fn require(&self, function: &str, allowed: &[State]) -> Result<(), Fmi2Error> {
+        let state = self.state_machine.state.get();
+        if !self.state_machine.checked || allowed.contains(&state) {
+            Ok(())
+        } else {
+            Err(Fmi2Error::IllegalCall(format!(
+                "`{function}` is not allowed in {state} (instance '{}')",
+                self.name
+            )))
+        }
+    }
+
+    /// In checked mode, check that `function` may set the variables `vrs` in the current state.
+    pub(super) fn require_settable(
+        &self,
+        function: &str,
+        vrs: &[binding::fmi2ValueReference],
+    ) -> Result<(), Fmi2Error> {
+        if !self.state_machine.checked {
+            return Ok(());
+        }
+        let state = self.state_machine.state.get();
+        for vr in vrs {
+            // Aliases share the value reference, the variable can be set if any of them can
+            let mut variables = self
+                .model_description
+                .get_model_variables()
+                .filter(|v| v.value_reference == *vr)
+                .peekable();
+            let Some(variable) = variables.peek().copied() else {
+                return Err(Fmi2Error::IllegalCall(format!(
+                    "`{function}` of unknown valueReference {vr} (instance '{}')",
+                    self.name
+                )));
+            };
+            if !variables.any(|v| {
+                let variability = v.variability.unwrap_or_default();
+                let initial = v.initial.clone().or_else(|| default_initial(&v.causality));
+                settable(state, &v.causality, variability, initial)
+            }) {
+                return Err(Fmi2Error::IllegalCall(format!(
+                    "`{function}` of variable `{}` (causality {}, variability {}) is not allowed \
+                     in {state} (instance '{}')",
+                    variable.name,
+                    variable.causality,
+                    variable.variability.unwrap_or_default(),
+                    self.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The default of the `initial` attribute for `causality`
+fn default_initial(causality: &Causality) -> Option<Initial> {
+    match causality {
+        Causality::Parameter => Some(Initial::Exact),
+        Causality::CalculatedParameter | Causality::Output | Causality::Local => {
+            Some(Initial::Calculated)
+        }
+        Causality::Input | Causality::Independent => None,
+    }
+}
+
+/// Whether a variable can be set in `state`.
+fn settable(
+    state: State,
+    causality: &Causality,
+    variability: Variability,
+    initial: Option<Initial>,
+) -> bool {
+    if variability == Variability::Constant || *causality == Causality::Independent {
+        return false;
+    }
+    let input = *causality == Causality::Input;
+    let tunable_parameter =
+        *causality == Causality::Parameter && variability == Variability::Tunable;
+    match state {
+        State::Instantiated => input || matches!(initial, Some(Initial::Exact | Initial::Approx)),
+        State::InitializationMode => input || initial == Some(Initial::Exact),
+        State::EventMode => input || tunable_parameter,
+        State::ContinuousTimeMode => input && variability == Variability::Continuous,
+        State::StepComplete => input || tunable_parameter,
+        State::StepInProgress
+        | State::StepFailed
+        | State::StepCanceled
+        | State::Terminated
+        | State::Error
+        | State::Fatal => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_transitions() {
+        let machine = StateMachine::co_simulation();
+        machine
+            .update(Ok(Fmi2Res::OK), Some(machine.after_initialization()))
+            .unwrap();
+        assert_eq!(machine.state.get(), State::StepComplete);
+
+        machine.update_step(Ok(Fmi2Res::Pending)).unwrap();
+        assert_eq!(machine.state.get(), State::StepInProgress);
+        machine.update_step(Ok(Fmi2Res::OK)).unwrap();
+        assert_eq!(machine.state.get(), State::StepComplete);
+        machine.update_step(Err(Fmi2Error::Discard)).unwrap_err();
+        assert_eq!(machine.state.get(), State::StepFailed);
+        machine
+            .update(Ok(Fmi2Res::OK), Some(State::Terminated))
+            .unwrap();
+        assert_eq!(machine.state.get(), State::Terminated);
+    }
+
+    #[test]
+    fn test_settable() {
+        use Causality::*;
+        use Variability::*;
+
+        assert!(settable(State::Instantiated, &Input, Continuous, None));
+        assert!(settable(
+            State::ContinuousTimeMode,
+            &Input,
+            Continuous,
+            None
+        ));
+        assert!(!settable(State::ContinuousTimeMode, &Input, Discrete, None));
+        assert!(!settable(State::StepInProgress, &Input, Continuous, None));
+
+        const EXACT: Option<Initial> = Some(Initial::Exact);
+        assert!(settable(
+            State::InitializationMode,
+            &Parameter,
+            Fixed,
+            EXACT
+        ));
+        assert!(!settable(State::StepComplete, &Parameter, Fixed, EXACT));
+        assert!(settable(State::StepComplete, &Parameter, Tunable, EXACT));
+        assert!(!settable(
+            State::Instantiated,
+            &Output,
+            Continuous,
+            Some(Initial::Calculated)
+        ));
+        assert!(!settable(State::Instantiated, &Parameter, Constant, EXACT));
+    }
+}
@@ -2,11 +2,15 @@
 
 pub mod import;
 pub mod instance;
+#[cfg(feature = "trace")]
+pub mod trace;
 // Re-export
 pub use fmi_schema::fmi2 as schema;
 #[doc = "Autogenerated bindings for the FMI 2.0 API"]
 pub use fmi_sys::fmi2 as binding;
 
+pub use binding::logger::{LogMessage, LogSinkFn};
+
 use crate::traits::FmiStatus;
 
 #[repr(C)]
@@ -90,6 +94,10 @@ pub enum Fmi2Error {
     /// The model computations are irreparably corrupted for all FMU instances.
     #[error("Fatal")]
     Fatal,
+    /// A call was rejected by an instance in checked mode, see
+    /// [`instance::Instance::set_checked`].
+    #[error("Illegal call: {0}")]
+    IllegalCall(String),
 }
 
 #[derive(Debug)]
@@ -123,6 +131,7 @@ impl From<Fmi2Status> for Result<Fmi2Res, Fmi2Error> {
         match status {
             binding::fmi2Status_fmi2OK => Ok(Fmi2Res::OK),
             binding::fmi2Status_fmi2Warning => Ok(Fmi2Res::Warning),
+            binding::fmi2Status_fmi2Pending => Ok(Fmi2Res::Pending),
             binding::fmi2Status_fmi2Discard => Err(Fmi2Error::Discard),
             binding::fmi2Status_fmi2Error => Err(Fmi2Error::Error),
             binding::fmi2Status_fmi2Fatal => Err(Fmi2Error::Fatal),
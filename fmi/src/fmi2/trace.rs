@@ -0,0 +1,869 @@
+//! Recording and replay of FMI 2.0 call traces, see [`crate::trace`].
+//!
+//! FMU state functions, `fmi2GetVersion` and `fmi2GetTypesPlatform` are not recorded.
+
+use std::{cell::RefCell, ffi::CString, io::Write};
+
+use super::{
+    Fmi2Error, Fmi2Res, Fmi2Status, binding,
+    instance::{CoSimulation, Common, ModelExchange},
+};
+use crate::{
+    Error, EventFlags, InterfaceType,
+    trace::{
+        Mismatch, TraceCall, TraceWriter, arg, compare_calls, event_flags_value, output_len,
+        status, traced_call, value_status,
+    },
+    traits::{FmiEventHandler, FmiInstance, FmiModelExchange, FmiStatus},
+};
+
+/// An FMI 2.0 instance that records every FMI call made through it to a trace.
+///
+/// The wrapper implements the same traits as the wrapped instance, so it can be used in its
+/// place:
+///
+/// ```rust,no_run
+/// # use fmi::{fmi2::{import::Fmi2Import, trace::TracedInstance}, import};
+/// let import: Fmi2Import = import::from_path("path/to/model.fmu").unwrap();
+/// let instance = import.instantiate_cs("inst1", false, true).unwrap();
+/// let trace = std::io::BufWriter::new(std::fs::File::create("inst1.jsonl").unwrap());
+/// let mut instance = TracedInstance::new(instance, trace).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TracedInstance<I> {
+    inner: I,
+    /// Some FMI 2.0 calls only borrow the instance
+    writer: RefCell<TraceWriter>,
+}
+
+impl<I: FmiInstance> TracedInstance<I> {
+    /// Wrap `inner`, writing its trace to `writer`.
+    pub fn new(inner: I, writer: impl Write + Send + 'static) -> Result<Self, Error> {
+        let interface = format!("{:?}", inner.interface_type());
+        let writer = TraceWriter::new(writer, "2.0", &interface, inner.name())?;
+        Ok(Self {
+            inner,
+            writer: RefCell::new(writer),
+        })
+    }
+}
+
+impl<I> TracedInstance<I> {
+    /// The wrapped instance. Calls made through it are not recorded.
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// The wrapped instance. Calls made through it are not recorded.
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Stop recording and return the wrapped instance.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Flush the trace.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.get_mut().flush()
+    }
+}
+
+fn strings(values: &[CString]) -> Vec<String> {
+    values
+        .iter()
+        .map(|s| s.to_string_lossy().into_owned())
+        .collect()
+}
+
+macro_rules! traced_getter_setter {
+    ($name:ident, $ty:ty, $fmi:literal) => {
+        paste::paste! {
+            fn [<get_ $name>](
+                &mut self,
+                vrs: &[binding::fmi2ValueReference],
+                values: &mut [$ty],
+            ) -> Result<Fmi2Res, Fmi2Error> {
+                traced_call!(
+                    self.writer.get_mut(),
+                    concat!("fmi2Get", $fmi),
+                    { "vrs": vrs },
+                    self.inner.[<get_ $name>](vrs, values),
+                    |result| { "values": values },
+                    status
+                )
+            }
+
+            fn [<set_ $name>](
+                &mut self,
+                vrs: &[binding::fmi2ValueReference],
+                values: &[$ty],
+            ) -> Result<Fmi2Res, Fmi2Error> {
+                traced_call!(
+                    self.writer.get_mut(),
+                    concat!("fmi2Set", $fmi),
+                    { "vrs": vrs, "values": values },
+                    self.inner.[<set_ $name>](vrs, values),
+                    |result| {},
+                    status
+                )
+            }
+        }
+    };
+}
+
+impl<I> Common for TracedInstance<I>
+where
+    I: Common + FmiInstance<Status = Fmi2Status>,
+{
+    fn get_version(&self) -> &str {
+        Common::get_version(&self.inner)
+    }
+
+    fn get_types_platform(&self) -> &str {
+        self.inner.get_types_platform()
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2SetDebugLogging",
+            { "logging_on": logging_on, "categories": categories },
+            Common::set_debug_logging(&mut self.inner, logging_on, categories),
+            |result| {},
+            status
+        )
+    }
+
+    fn setup_experiment(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2SetupExperiment",
+            { "tolerance": tolerance, "start_time": start_time, "stop_time": stop_time },
+            self.inner.setup_experiment(tolerance, start_time, stop_time),
+            |result| {},
+            status
+        )
+    }
+
+    fn enter_initialization_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2EnterInitializationMode",
+            {},
+            Common::enter_initialization_mode(&mut self.inner),
+            |result| {},
+            status
+        )
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2ExitInitializationMode",
+            {},
+            Common::exit_initialization_mode(&mut self.inner),
+            |result| {},
+            status
+        )
+    }
+
+    fn terminate(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2Terminate",
+            {},
+            Common::terminate(&mut self.inner),
+            |result| {},
+            status
+        )
+    }
+
+    fn reset(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2Reset",
+            {},
+            Common::reset(&mut self.inner),
+            |result| {},
+            status
+        )
+    }
+
+    traced_getter_setter!(real, binding::fmi2Real, "Real");
+    traced_getter_setter!(integer, binding::fmi2Integer, "Integer");
+    traced_getter_setter!(boolean, binding::fmi2Boolean, "Boolean");
+
+    fn get_string(
+        &mut self,
+        vrs: &[binding::fmi2ValueReference],
+        values: &mut [CString],
+    ) -> Result<(), Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetString",
+            { "vrs": vrs },
+            self.inner.get_string(vrs, values),
+            |result| { "values": strings(values) },
+            value_status
+        )
+    }
+
+    fn set_string(
+        &mut self,
+        vrs: &[binding::fmi2ValueReference],
+        values: &[CString],
+    ) -> Result<(), Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2SetString",
+            { "vrs": vrs, "values": strings(values) },
+            self.inner.set_string(vrs, values),
+            |result| {},
+            value_status
+        )
+    }
+
+    fn get_directional_derivative(
+        &self,
+        unknown_vrs: &[binding::fmi2ValueReference],
+        known_vrs: &[binding::fmi2ValueReference],
+        dv_known_values: &[binding::fmi2Real],
+        dv_unknown_values: &mut [binding::fmi2Real],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.borrow_mut(),
+            "fmi2GetDirectionalDerivative",
+            {
+                "unknown_vrs": unknown_vrs,
+                "known_vrs": known_vrs,
+                "dv_known_values": dv_known_values
+            },
+            self.inner.get_directional_derivative(
+                unknown_vrs,
+                known_vrs,
+                dv_known_values,
+                dv_unknown_values
+            ),
+            |result| { "dv_unknown_values": dv_unknown_values },
+            status
+        )
+    }
+}
+
+impl<I> ModelExchange for TracedInstance<I>
+where
+    I: ModelExchange + FmiInstance<Status = Fmi2Status>,
+{
+    fn enter_event_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2EnterEventMode",
+            {},
+            ModelExchange::enter_event_mode(&mut self.inner),
+            |result| {},
+            status
+        )
+    }
+
+    fn new_discrete_states(&mut self, event_flags: &mut EventFlags) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2NewDiscreteStates",
+            {},
+            self.inner.new_discrete_states(event_flags),
+            |result| { "event_flags": event_flags_value(event_flags) },
+            status
+        )
+    }
+
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2EnterContinuousTimeMode",
+            {},
+            ModelExchange::enter_continuous_time_mode(&mut self.inner),
+            |result| {},
+            status
+        )
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2CompletedIntegratorStep",
+            { "no_set_fmu_state_prior": no_set_fmu_state_prior },
+            ModelExchange::completed_integrator_step(
+                &mut self.inner,
+                no_set_fmu_state_prior,
+                enter_event_mode,
+                terminate_simulation
+            ),
+            |result| {
+                "enter_event_mode": *enter_event_mode,
+                "terminate_simulation": *terminate_simulation
+            },
+            status
+        )
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2SetTime",
+            { "time": time },
+            ModelExchange::set_time(&mut self.inner, time),
+            |result| {},
+            status
+        )
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2SetContinuousStates",
+            { "states": states },
+            ModelExchange::set_continuous_states(&mut self.inner, states),
+            |result| {},
+            status
+        )
+    }
+
+    fn get_derivatives(&mut self, dx: &mut [f64]) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetDerivatives",
+            {},
+            self.inner.get_derivatives(dx),
+            |result| { "derivatives": dx },
+            status
+        )
+    }
+
+    fn get_event_indicators(&mut self, events: &mut [f64]) -> Result<bool, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetEventIndicators",
+            {},
+            ModelExchange::get_event_indicators(&mut self.inner, events),
+            |result| {
+                "event_indicators": events,
+                "computed": result.as_ref().ok()
+            },
+            value_status
+        )
+    }
+
+    fn get_continuous_states(&mut self, x: &mut [f64]) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetContinuousStates",
+            {},
+            ModelExchange::get_continuous_states(&mut self.inner, x),
+            |result| { "continuous_states": x },
+            status
+        )
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetNominalsOfContinuousStates",
+            {},
+            ModelExchange::get_nominals_of_continuous_states(&mut self.inner, nominals),
+            |result| { "nominals": nominals },
+            status
+        )
+    }
+}
+
+impl<I> CoSimulation for TracedInstance<I>
+where
+    I: CoSimulation + FmiInstance<Status = Fmi2Status>,
+{
+    fn do_step(
+        &self,
+        current_communication_point: f64,
+        communication_step_size: f64,
+        new_step: bool,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.borrow_mut(),
+            "fmi2DoStep",
+            {
+                "current_communication_point": current_communication_point,
+                "communication_step_size": communication_step_size,
+                "new_step": new_step
+            },
+            self.inner.do_step(
+                current_communication_point,
+                communication_step_size,
+                new_step
+            ),
+            |result| {},
+            status
+        )
+    }
+
+    fn cancel_step(&self) -> Result<Fmi2Res, Fmi2Error> {
+        traced_call!(
+            self.writer.borrow_mut(),
+            "fmi2CancelStep",
+            {},
+            self.inner.cancel_step(),
+            |result| {},
+            status
+        )
+    }
+
+    fn do_step_status(&mut self) -> Result<Fmi2Status, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetStatus",
+            { "kind": "fmi2DoStepStatus" },
+            self.inner.do_step_status(),
+            |result| {
+                "value": result.as_ref().ok().map(|step| status(&Fmi2Status(step.0).ok()))
+            },
+            value_status
+        )
+    }
+
+    fn pending_status(&mut self) -> Result<&str, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetStringStatus",
+            { "kind": "fmi2PendingStatus" },
+            self.inner.pending_status(),
+            |result| { "value": result.as_ref().ok() },
+            value_status
+        )
+    }
+
+    fn last_successful_time(&mut self) -> Result<f64, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetRealStatus",
+            { "kind": "fmi2LastSuccessfulTime" },
+            self.inner.last_successful_time(),
+            |result| { "value": result.as_ref().ok() },
+            value_status
+        )
+    }
+
+    fn terminated(&mut self) -> Result<bool, Fmi2Error> {
+        traced_call!(
+            self.writer.get_mut(),
+            "fmi2GetBooleanStatus",
+            { "kind": "fmi2Terminated" },
+            self.inner.terminated(),
+            |result| { "value": result.as_ref().ok() },
+            value_status
+        )
+    }
+}
+
+impl<I> FmiInstance for TracedInstance<I>
+where
+    I: Common + FmiInstance<Status = Fmi2Status>,
+{
+    type ModelDescription = I::ModelDescription;
+    type ValueRef = I::ValueRef;
+    type Status = Fmi2Status;
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn get_version(&self) -> &str {
+        Common::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        self.inner.interface_type()
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        Common::set_debug_logging(self, logging_on, categories)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        Common::setup_experiment(self, tolerance, start_time, stop_time)?;
+        Common::enter_initialization_mode(self)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        Common::exit_initialization_mode(self)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        Common::terminate(self)
+    }
+
+    fn reset(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        Common::reset(self)
+    }
+}
+
+impl<I> FmiModelExchange for TracedInstance<I>
+where
+    I: FmiInstance<Status = Fmi2Status> + ModelExchange,
+{
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::enter_continuous_time_mode(self)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::new_discrete_states(self, event_flags)
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::completed_integrator_step(
+            self,
+            no_set_fmu_state_prior,
+            enter_event_mode,
+            terminate_simulation,
+        )
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::set_time(self, time)
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::get_continuous_states(self, continuous_states)
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::set_continuous_states(self, states)
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::get_derivatives(self, derivatives)
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::get_nominals_of_continuous_states(self, nominals)
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi2Error> {
+        ModelExchange::get_event_indicators(self, event_indicators)
+    }
+}
+
+impl<I> FmiEventHandler for TracedInstance<I>
+where
+    I: FmiInstance<Status = Fmi2Status> + ModelExchange,
+{
+    fn enter_event_mode(&mut self) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi2Res, Fmi2Error> {
+        ModelExchange::new_discrete_states(self, event_flags)
+    }
+}
+
+/// Re-issue the recorded `calls` against a Model Exchange `instance`, and compare the results.
+///
+/// See [`crate::trace::compare_calls`] for the meaning of `tolerance`.
+pub fn replay_me<I>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+) -> Result<Vec<Mismatch>, Error>
+where
+    I: ModelExchange + FmiInstance<Status = Fmi2Status>,
+{
+    replay_with(instance, calls, tolerance, replay_model_exchange)
+}
+
+/// Re-issue the recorded `calls` against a Co-Simulation `instance`, and compare the results.
+///
+/// See [`crate::trace::compare_calls`] for the meaning of `tolerance`.
+pub fn replay_cs<I>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+) -> Result<Vec<Mismatch>, Error>
+where
+    I: CoSimulation + FmiInstance<Status = Fmi2Status>,
+{
+    replay_with(instance, calls, tolerance, replay_co_simulation)
+}
+
+/// Re-issues a call, returns `false` for functions of another interface
+type ReplayFn<I> = fn(&mut TracedInstance<I>, &TraceCall) -> Result<bool, Error>;
+
+fn replay_with<I>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+    replay_call: ReplayFn<I>,
+) -> Result<Vec<Mismatch>, Error> {
+    let mut traced = TracedInstance {
+        inner: instance,
+        writer: RefCell::new(TraceWriter::memory()),
+    };
+    let mut mismatches = Vec::new();
+    for call in calls {
+        if !replay_call(&mut traced, call)? {
+            return Err(Error::Trace(format!(
+                "call {}: `{}` can not be replayed on this interface",
+                call.seq, call.function
+            )));
+        }
+        let replayed = traced
+            .writer
+            .get_mut()
+            .take_calls()
+            .pop()
+            .expect("the replayed call is recorded");
+        mismatches.extend(compare_calls(call, &replayed, tolerance));
+    }
+    Ok(mismatches)
+}
+
+/// Buffer for the output `name` of a replayed call, sized like the recorded output, or like the
+/// value references if the recorded call failed.
+fn output_buffer<T: Clone + Default>(call: &TraceCall, name: &str, vrs: usize) -> Vec<T> {
+    let len = match output_len(call, name) {
+        0 if call.outputs.is_empty() => vrs,
+        len => len,
+    };
+    vec![T::default(); len]
+}
+
+fn replay_common<I>(instance: &mut TracedInstance<I>, call: &TraceCall) -> Result<bool, Error>
+where
+    I: Common + FmiInstance<Status = Fmi2Status>,
+{
+    macro_rules! replay_getter_setter {
+        ($($name:ident, $ty:ty, $fmi:literal;)*) => {
+            paste::paste! {
+                match call.function.as_str() {
+                    $(
+                        concat!("fmi2Get", $fmi) => {
+                            let vrs: Vec<binding::fmi2ValueReference> = arg(call, "vrs")?;
+                            let mut values = output_buffer::<$ty>(call, "values", vrs.len());
+                            let _ = instance.[<get_ $name>](&vrs, &mut values);
+                            return Ok(true);
+                        }
+                        concat!("fmi2Set", $fmi) => {
+                            let vrs: Vec<binding::fmi2ValueReference> = arg(call, "vrs")?;
+                            let values: Vec<$ty> = arg(call, "values")?;
+                            let _ = instance.[<set_ $name>](&vrs, &values);
+                            return Ok(true);
+                        }
+                    )*
+                    _ => {}
+                }
+            }
+        };
+    }
+
+    replay_getter_setter!(
+        real, binding::fmi2Real, "Real";
+        integer, binding::fmi2Integer, "Integer";
+        boolean, binding::fmi2Boolean, "Boolean";
+    );
+    match call.function.as_str() {
+        "fmi2GetString" => {
+            let vrs: Vec<binding::fmi2ValueReference> = arg(call, "vrs")?;
+            let mut values = output_buffer::<CString>(call, "values", vrs.len());
+            let _ = instance.get_string(&vrs, &mut values);
+        }
+        "fmi2SetString" => {
+            let vrs: Vec<binding::fmi2ValueReference> = arg(call, "vrs")?;
+            let values = arg::<Vec<String>>(call, "values")?
+                .into_iter()
+                .map(|s| CString::new(s).map_err(|e| Error::Trace(e.to_string())))
+                .collect::<Result<Vec<_>, _>>()?;
+            let _ = instance.set_string(&vrs, &values);
+        }
+        "fmi2SetDebugLogging" => {
+            let categories: Vec<String> = arg(call, "categories")?;
+            let categories: Vec<&str> = categories.iter().map(String::as_str).collect();
+            let _ = Common::set_debug_logging(instance, arg(call, "logging_on")?, &categories);
+        }
+        "fmi2SetupExperiment" => {
+            let _ = instance.setup_experiment(
+                arg(call, "tolerance")?,
+                arg(call, "start_time")?,
+                arg(call, "stop_time")?,
+            );
+        }
+        "fmi2EnterInitializationMode" => {
+            let _ = Common::enter_initialization_mode(instance);
+        }
+        "fmi2ExitInitializationMode" => {
+            let _ = Common::exit_initialization_mode(instance);
+        }
+        "fmi2Terminate" => {
+            let _ = Common::terminate(instance);
+        }
+        "fmi2Reset" => {
+            let _ = Common::reset(instance);
+        }
+        "fmi2GetDirectionalDerivative" => {
+            let unknown_vrs: Vec<binding::fmi2ValueReference> = arg(call, "unknown_vrs")?;
+            let known_vrs: Vec<binding::fmi2ValueReference> = arg(call, "known_vrs")?;
+            let dv_known_values: Vec<f64> = arg(call, "dv_known_values")?;
+            let mut dv_unknown_values =
+                output_buffer::<f64>(call, "dv_unknown_values", unknown_vrs.len());
+            let _ = instance.get_directional_derivative(
+                &unknown_vrs,
+                &known_vrs,
+                &dv_known_values,
+                &mut dv_unknown_values,
+            );
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn replay_model_exchange<I>(
+    instance: &mut TracedInstance<I>,
+    call: &TraceCall,
+) -> Result<bool, Error>
+where
+    I: ModelExchange + FmiInstance<Status = Fmi2Status>,
+{
+    if replay_common(instance, call)? {
+        return Ok(true);
+    }
+    match call.function.as_str() {
+        "fmi2EnterEventMode" => {
+            let _ = ModelExchange::enter_event_mode(instance);
+        }
+        "fmi2NewDiscreteStates" => {
+            let _ = instance.new_discrete_states(&mut EventFlags::default());
+        }
+        "fmi2EnterContinuousTimeMode" => {
+            let _ = ModelExchange::enter_continuous_time_mode(instance);
+        }
+        "fmi2CompletedIntegratorStep" => {
+            let _ = ModelExchange::completed_integrator_step(
+                instance,
+                arg(call, "no_set_fmu_state_prior")?,
+                &mut false,
+                &mut false,
+            );
+        }
+        "fmi2SetTime" => {
+            let _ = ModelExchange::set_time(instance, arg(call, "time")?);
+        }
+        "fmi2SetContinuousStates" => {
+            let states: Vec<f64> = arg(call, "states")?;
+            let _ = ModelExchange::set_continuous_states(instance, &states);
+        }
+        "fmi2GetDerivatives" => {
+            let mut values = output_buffer(call, "derivatives", 0);
+            let _ = instance.get_derivatives(&mut values);
+        }
+        "fmi2GetEventIndicators" => {
+            let mut values = output_buffer(call, "event_indicators", 0);
+            let _ = ModelExchange::get_event_indicators(instance, &mut values);
+        }
+        "fmi2GetContinuousStates" => {
+            let mut values = output_buffer(call, "continuous_states", 0);
+            let _ = ModelExchange::get_continuous_states(instance, &mut values);
+        }
+        "fmi2GetNominalsOfContinuousStates" => {
+            let mut values = output_buffer(call, "nominals", 0);
+            let _ = ModelExchange::get_nominals_of_continuous_states(instance, &mut values);
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn replay_co_simulation<I>(
+    instance: &mut TracedInstance<I>,
+    call: &TraceCall,
+) -> Result<bool, Error>
+where
+    I: CoSimulation + FmiInstance<Status = Fmi2Status>,
+{
+    if replay_common(instance, call)? {
+        return Ok(true);
+    }
+    match call.function.as_str() {
+        "fmi2DoStep" => {
+            let _ = instance.do_step(
+                arg(call, "current_communication_point")?,
+                arg(call, "communication_step_size")?,
+                arg(call, "new_step")?,
+            );
+        }
+        "fmi2CancelStep" => {
+            let _ = instance.cancel_step();
+        }
+        "fmi2GetStatus" => {
+            let _ = instance.do_step_status();
+        }
+        "fmi2GetStringStatus" => {
+            let _ = instance.pending_status();
+        }
+        "fmi2GetRealStatus" => {
+            let _ = instance.last_successful_time();
+        }
+        "fmi2GetBooleanStatus" => {
+            let _ = instance.terminated();
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
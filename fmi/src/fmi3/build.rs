@@ -0,0 +1,263 @@
+//! Building the shared library of source code FMUs.
+//!
+//! FMUs may ship their C sources in the `sources/` directory, described by
+//! `sources/buildDescription.xml`. [`build_shared_library`] compiles them with the C compiler of
+//! the host, located by the [`cc`] crate, and links the result into the `binaries/` directory of
+//! the extracted FMU, where it is found like a prebuilt binary. The usual `CC` and `CFLAGS`
+//! environment variables are respected.
+//!
+//! The library is written into the FMU directory even if it is managed elsewhere, as with
+//! [`crate::import::from_dir`] or the extraction cache, so that later imports of the directory
+//! find it.
+//!
+//! See <https://fmi-standard.org/docs/3.0.1/#fmu-sources>
+
+use std::path::{Path, PathBuf};
+
+use crate::{Error, fmi3::schema};
+
+/// The directory holding the sources within an FMU
+const SOURCES: &str = "sources";
+
+/// Standard filename of the build description within the sources
+const BUILD_DESCRIPTION: &str = "buildDescription.xml";
+
+/// Compile the sources of `model_identifier` in the FMU extracted to `fmu_dir` into a shared
+/// library for the host platform.
+///
+/// The build configuration for `model_identifier` and the host platform is taken from
+/// `sources/buildDescription.xml`. Each source file set is compiled with its preprocessor
+/// definitions and include directories, and the FMI 3.0 headers. The library is written to
+/// `binaries/<platform>/<model_identifier>`, replacing any library already there, and its path
+/// is returned.
+///
+/// The library is linked in a private directory and then renamed into place. Concurrent builds of
+/// the same directory therefore never load a partially written library, and a library that is
+/// already loaded keeps its contents.
+pub fn build_shared_library(fmu_dir: &Path, model_identifier: &str) -> Result<PathBuf, Error> {
+    use std::env::consts::{ARCH, DLL_SUFFIX, OS};
+
+    let platform = super::platform_folder(OS, ARCH)?;
+    let sources_dir = fmu_dir.join(SOURCES);
+    let build_description_path = sources_dir.join(BUILD_DESCRIPTION);
+    if !build_description_path.is_file() {
+        return Err(Error::ArchiveStructure(format!(
+            "{SOURCES}/{BUILD_DESCRIPTION} not found in {}",
+            fmu_dir.display()
+        )));
+    }
+    let build_description: schema::Fmi3BuildDescription =
+        fmi_schema::deserialize(&std::fs::read_to_string(build_description_path)?)?;
+
+    // A configuration for the host platform takes precedence over a platform independent one
+    let configuration = build_description
+        .build_configurations
+        .iter()
+        .filter(|configuration| configuration.model_identifier == model_identifier)
+        .filter(|configuration| {
+            configuration
+                .platform
+                .as_deref()
+                .is_none_or(|p| p == platform)
+        })
+        .max_by_key(|configuration| configuration.platform.is_some())
+        .ok_or_else(|| {
+            Error::Build(format!(
+                "no build configuration for {model_identifier} on {platform}"
+            ))
+        })?;
+
+    let work_dir = tempfile::Builder::new().prefix("fmi-rs-build").tempdir()?;
+    let include_dir = work_dir.path().join("include");
+    std::fs::create_dir(&include_dir)?;
+    for (name, contents) in fmi_sys::fmi3::HEADERS {
+        std::fs::write(include_dir.join(name), contents)?;
+    }
+
+    let mut objects = Vec::new();
+    let mut linker = None;
+    for (index, file_set) in configuration.source_file_sets.iter().enumerate() {
+        let build = compiler_for(file_set, &sources_dir, &include_dir, work_dir.path(), index)?;
+        log::debug!(
+            "Compiling {} source files of {model_identifier}",
+            file_set.source_files.len()
+        );
+        objects.extend(build.try_compile_intermediates().map_err(build_error)?);
+        // Link with the C++ compiler if any of the sources is C++
+        if linker.is_none() || is_cpp(file_set) {
+            linker = Some(build.try_get_compiler().map_err(build_error)?);
+        }
+    }
+    let linker = linker.ok_or_else(|| {
+        Error::Build(format!(
+            "no source files for {model_identifier} on {platform}"
+        ))
+    })?;
+
+    let binaries_dir = fmu_dir.join("binaries").join(platform);
+    std::fs::create_dir_all(&binaries_dir)?;
+    let lib_name = format!("{model_identifier}{DLL_SUFFIX}");
+    let lib_path = binaries_dir.join(&lib_name);
+    // On the same file system as the library, so that it can be renamed into place
+    let link_dir = tempfile::Builder::new()
+        .prefix(".fmi-rs-link")
+        .tempdir_in(&binaries_dir)?;
+    let link_path = link_dir.path().join(&lib_name);
+
+    let mut command = linker.to_command();
+    if linker.is_like_msvc() {
+        command
+            .args(&objects)
+            .arg("/LD")
+            .arg(format!("/Fe{}", link_path.display()));
+        command
+            .arg("/link")
+            .arg(format!("/LIBPATH:{}", sources_dir.display()));
+        for library in &configuration.libraries {
+            command.arg(format!("{}.lib", library.name));
+        }
+    } else {
+        command
+            .arg("-shared")
+            .arg("-o")
+            .arg(&link_path)
+            .args(&objects)
+            .arg(format!("-L{}", sources_dir.display()));
+        for library in &configuration.libraries {
+            command.arg(format!("-l{}", library.name));
+        }
+        if OS != "macos" {
+            command.arg("-lm");
+        }
+    }
+    log::debug!("Linking {lib_path:?}");
+    log::trace!("{command:?}");
+    let output = command.output()?;
+    if !output.status.success() {
+        return Err(Error::Build(format!(
+            "linking {model_identifier} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    std::fs::rename(&link_path, &lib_path)?;
+
+    Ok(lib_path)
+}
+
+/// Configure the compilation of one source file set into `work_dir`.
+fn compiler_for(
+    file_set: &schema::SourceFileSet,
+    sources_dir: &Path,
+    include_dir: &Path,
+    work_dir: &Path,
+    index: usize,
+) -> Result<cc::Build, Error> {
+    let out_dir = work_dir.join(format!("objects{index}"));
+    std::fs::create_dir(&out_dir)?;
+
+    let mut build = cc::Build::new();
+    build
+        .cargo_metadata(false)
+        .cargo_warnings(false)
+        .cargo_output(false)
+        .emit_rerun_if_env_changed(false)
+        .target(crate::built_info::TARGET)
+        .host(crate::built_info::HOST)
+        .opt_level(2)
+        .debug(false)
+        .pic(true)
+        .warnings(false)
+        .cpp(is_cpp(file_set))
+        .out_dir(out_dir)
+        .include(include_dir);
+    for directory in &file_set.include_directories {
+        build.include(sources_dir.join(&directory.name));
+    }
+    for definition in &file_set.preprocessor_definitions {
+        build.define(&definition.name, definition.value.as_deref());
+    }
+    for option in file_set
+        .compiler_options
+        .iter()
+        .flat_map(|options| options.split_whitespace())
+    {
+        // The options are meant for the compiler named in the file set, which may not be ours
+        build.flag_if_supported(option);
+    }
+    for source_file in &file_set.source_files {
+        build.file(sources_dir.join(&source_file.name));
+    }
+    Ok(build)
+}
+
+fn is_cpp(file_set: &schema::SourceFileSet) -> bool {
+    file_set
+        .language
+        .as_deref()
+        .is_some_and(|language| language.starts_with("C++"))
+}
+
+fn build_error(error: cc::Error) -> Error {
+    Error::Build(error.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BUILD_DESCRIPTION_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<fmiBuildDescription fmiVersion="3.0">
+    <BuildConfiguration modelIdentifier="Minimal">
+        <SourceFileSet language="C99">
+            <PreprocessorDefinition name="VERSION_STRING" value="&quot;3.0&quot;"/>
+            <IncludeDirectory name="include"/>
+            <SourceFile name="model.c"/>
+        </SourceFileSet>
+    </BuildConfiguration>
+</fmiBuildDescription>"#;
+
+    const MODEL_C: &str = r#"
+#include "fmi3Functions.h"
+#include "minimal.h"
+
+const char* fmi3GetVersion(void) {
+    return MINIMAL_VERSION;
+}
+"#;
+
+    #[test]
+    fn test_build_shared_library() {
+        let fmu_dir = tempfile::tempdir().unwrap();
+        let sources_dir = fmu_dir.path().join(SOURCES);
+        std::fs::create_dir_all(sources_dir.join("include")).unwrap();
+        std::fs::write(sources_dir.join(BUILD_DESCRIPTION), BUILD_DESCRIPTION_XML).unwrap();
+        std::fs::write(sources_dir.join("model.c"), MODEL_C).unwrap();
+        std::fs::write(
+            sources_dir.join("include/minimal.h"),
+            "#define MINIMAL_VERSION VERSION_STRING\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            build_shared_library(fmu_dir.path(), "Other"),
+            Err(Error::Build(_))
+        ));
+
+        let lib_path = build_shared_library(fmu_dir.path(), "Minimal").unwrap();
+        assert!(lib_path.starts_with(fmu_dir.path().join("binaries")));
+
+        let binding = unsafe { crate::fmi3::binding::Fmi3Binding::new(&lib_path) }.unwrap();
+        let version = unsafe { std::ffi::CStr::from_ptr(binding.fmi3GetVersion()) };
+        assert_eq!(version.to_str().unwrap(), "3.0");
+
+        // Rebuilding replaces the loaded library without touching it, and leaves nothing behind
+        assert_eq!(
+            build_shared_library(fmu_dir.path(), "Minimal").unwrap(),
+            lib_path
+        );
+        let version = unsafe { std::ffi::CStr::from_ptr(binding.fmi3GetVersion()) };
+        assert_eq!(version.to_str().unwrap(), "3.0");
+        let entries = std::fs::read_dir(lib_path.parent().unwrap()).unwrap();
+        assert_eq!(entries.count(), 1);
+    }
+}
@@ -1,11 +1,21 @@
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use fmi_schema::{MajorVersion, traits::FmiModelDescription};
-use tempfile::TempDir;
+use fmi_schema::{
+    MajorVersion,
+    traits::{FmiInterfaceType, FmiModelDescription},
+};
 
 use crate::{
     Error,
-    fmi3::{Fmi3Model, binding, instance, schema},
+    fmi3::{
+        Fmi3Model, TERMINALS_AND_ICONS, binding, instance, schema,
+        variable::{Fmi3Type, IndexedModelDescription, Variable},
+    },
+    import::{FmuDir, LibraryMode},
+    library::LibraryLease,
     traits::FmiImport,
 };
 
@@ -13,9 +23,97 @@ use crate::{
 #[derive(Debug)]
 pub struct Fmi3Import {
     /// Path to the unzipped FMU on disk
-    dir: tempfile::TempDir,
-    /// Parsed raw-schema model description
-    model_description: schema::Fmi3ModelDescription,
+    dir: FmuDir,
+    /// Parsed raw-schema model description with its variable index, shared with the instances
+    model_description: Arc<IndexedModelDescription>,
+    /// Parsed `terminalsAndIcons.xml`, if the FMU has one
+    terminals_and_icons: Option<schema::Fmi3TerminalsAndIcons>,
+    /// How the shared library is loaded for new instances
+    library_mode: LibraryMode,
+    /// Whether the shared library is built from the sources if there is no binary for the host
+    #[cfg(feature = "build")]
+    build_from_sources: bool,
+    /// Serializes builds of the shared library
+    #[cfg(feature = "build")]
+    build_lock: std::sync::Mutex<()>,
+}
+
+impl Fmi3Import {
+    /// Get a shared handle to the parsed raw-schema model description
+    pub(crate) fn shared_model_description(&self) -> Arc<IndexedModelDescription> {
+        self.model_description.clone()
+    }
+
+    /// Path to the shared library on disk, building it from the sources first if enabled.
+    fn library_path(&self, model_identifier: &str) -> Result<PathBuf, Error> {
+        let lib_path = self
+            .dir
+            .path()
+            .join(self.shared_lib_path(model_identifier)?);
+        #[cfg(feature = "build")]
+        if self.build_from_sources {
+            let _guard = self
+                .build_lock
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if !lib_path.is_file() {
+                log::info!("No binary for {model_identifier}, building it from the sources");
+                return super::build::build_shared_library(self.dir.path(), model_identifier);
+            }
+        }
+        Ok(lib_path)
+    }
+
+    /// Load the shared library of `interface` for a new instance, according to the library mode.
+    pub(crate) fn instance_binding(
+        &self,
+        interface: &impl FmiInterfaceType,
+    ) -> Result<(binding::Fmi3Binding, LibraryLease), Error> {
+        let model_identifier = interface.model_identifier();
+        let lib_path = self.library_path(model_identifier)?;
+        let lease = LibraryLease::acquire(
+            &lib_path,
+            model_identifier,
+            self.library_mode,
+            interface
+                .can_be_instantiated_only_once_per_process()
+                .unwrap_or(false),
+        )?;
+        log::trace!("Loading shared library {:?}", lease.path());
+        let binding = unsafe { binding::Fmi3Binding::new(lease.path()) }?;
+        Ok((binding, lease))
+    }
+
+    /// The terminals and graphical representation from `terminalsAndIcons/terminalsAndIcons.xml`,
+    /// if the FMU has one.
+    pub fn terminals_and_icons(&self) -> Option<&schema::Fmi3TerminalsAndIcons> {
+        self.terminals_and_icons.as_ref()
+    }
+
+    /// Resolve the variable `name` as a typed [`Variable`] handle.
+    ///
+    /// Fails when no such variable exists, or when it is not declared with the FMI type that
+    /// corresponds to `T`.
+    pub fn variable<T: Fmi3Type>(&self, name: &str) -> Result<Variable<T>, Error> {
+        Variable::from_variable(self.model_description.variable(name)?)
+    }
+
+    /// Whether the shared library is built from the sources of the FMU when it contains no binary
+    /// for the host platform.
+    #[cfg(feature = "build")]
+    pub fn build_from_sources(&self) -> bool {
+        self.build_from_sources
+    }
+
+    /// Build the shared library from the sources of the FMU when it contains no binary for the
+    /// host platform, see [`build_shared_library`](super::build::build_shared_library).
+    ///
+    /// The library is built into the directory of the FMU when it is first loaded, also if that
+    /// directory is managed elsewhere. Disabled by default.
+    #[cfg(feature = "build")]
+    pub fn set_build_from_sources(&mut self, build_from_sources: bool) {
+        self.build_from_sources = build_from_sources;
+    }
 }
 
 impl FmiImport for Fmi3Import {
@@ -25,17 +123,49 @@ impl FmiImport for Fmi3Import {
     type ValueRef = binding::fmi3ValueReference;
 
     /// Create a new FMI 3.0 import from a directory containing the unzipped FMU
-    fn new(dir: TempDir, schema_xml: &str) -> Result<Self, Error> {
+    ///
+    /// The optional `terminalsAndIcons.xml` is loaded as well. It is ignored with a warning if it
+    /// can not be read, and terminal members that do not match the model description are logged as
+    /// warnings.
+    fn new(dir: FmuDir, schema_xml: &str) -> Result<Self, Error> {
         let model_description = schema::Fmi3ModelDescription::deserialize(schema_xml)?;
+
+        let terminals_path = dir.path().join(TERMINALS_AND_ICONS);
+        let terminals_and_icons = if terminals_path.is_file() {
+            match read_terminals_and_icons(&terminals_path) {
+                Ok(terminals_and_icons) => {
+                    for finding in fmi_schema::validation::validate_terminals(
+                        &model_description,
+                        &terminals_and_icons,
+                    ) {
+                        log::warn!("{TERMINALS_AND_ICONS}: {finding}");
+                    }
+                    Some(terminals_and_icons)
+                }
+                Err(e) => {
+                    log::warn!("Ignoring {TERMINALS_AND_ICONS}: {e}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         Ok(Self {
             dir,
-            model_description,
+            model_description: Arc::new(IndexedModelDescription::new(model_description)),
+            terminals_and_icons,
+            library_mode: LibraryMode::default(),
+            #[cfg(feature = "build")]
+            build_from_sources: false,
+            #[cfg(feature = "build")]
+            build_lock: std::sync::Mutex::new(()),
         })
     }
 
     #[inline]
-    fn archive_path(&self) -> &std::path::Path {
-        self.dir.path()
+    fn dir(&self) -> &FmuDir {
+        &self.dir
     }
 
     /// Get the path to the shared library
@@ -55,14 +185,19 @@ impl FmiImport for Fmi3Import {
 
     /// Load the plugin shared library and return the raw bindings.
     fn binding(&self, model_identifier: &str) -> Result<Self::Binding, Error> {
-        let lib_path = self
-            .dir
-            .path()
-            .join(self.shared_lib_path(model_identifier)?);
+        let lib_path = self.library_path(model_identifier)?;
         log::debug!("Loading shared library {lib_path:?}");
         unsafe { binding::Fmi3Binding::new(lib_path).map_err(Error::from) }
     }
 
+    fn library_mode(&self) -> LibraryMode {
+        self.library_mode
+    }
+
+    fn set_library_mode(&mut self, mode: LibraryMode) {
+        self.library_mode = mode;
+    }
+
     /// Get a `String` representation of the resources path for this FMU
     ///
     /// As per the FMI3.0 standard, `resourcePath` is the absolute file path (with a trailing file separator) of the
@@ -134,3 +269,53 @@ impl Fmi3Model for Fmi3Import {
         instance::InstanceSE::new(self, instance_name, visible, logging_on)
     }
 }
+
+fn read_terminals_and_icons(path: &Path) -> Result<schema::Fmi3TerminalsAndIcons, Error> {
+    Ok(fmi_schema::deserialize(&std::fs::read_to_string(path)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminals_and_icons() {
+        let dir = tempfile::tempdir().unwrap();
+        let xml = r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="">
+    <ModelVariables>
+        <Float64 name="u" valueReference="1" causality="input" start="0"/>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#;
+        std::fs::write(dir.path().join("modelDescription.xml"), xml).unwrap();
+
+        let import: Fmi3Import = crate::import::from_dir(dir.path()).unwrap();
+        assert!(import.terminals_and_icons().is_none());
+        assert_eq!(import.layered_standards().unwrap(), vec![]);
+
+        std::fs::create_dir(dir.path().join("terminalsAndIcons")).unwrap();
+        std::fs::write(
+            dir.path().join(TERMINALS_AND_ICONS),
+            r#"<fmiTerminalsAndIcons fmiVersion="3.0">
+    <Terminals>
+        <Terminal name="bus" matchingRule="bus">
+            <TerminalMemberVariable variableName="u" memberName="u" variableKind="signal"/>
+        </Terminal>
+    </Terminals>
+</fmiTerminalsAndIcons>"#,
+        )
+        .unwrap();
+        let import: Fmi3Import = crate::import::from_dir(dir.path()).unwrap();
+        let bus = import
+            .terminals_and_icons()
+            .unwrap()
+            .terminal("bus")
+            .unwrap();
+        assert_eq!(bus.member_variables[0].variable_name, "u");
+
+        std::fs::write(dir.path().join(TERMINALS_AND_ICONS), "<Terminals/>").unwrap();
+        // A malformed file does not prevent the import
+        let import: Fmi3Import = crate::import::from_dir(dir.path()).unwrap();
+        assert!(import.terminals_and_icons().is_none());
+    }
+}
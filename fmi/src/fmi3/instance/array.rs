@@ -0,0 +1,316 @@
+//! Shape-aware access to array variables of FMI 3.0 instances.
+//!
+//! See <https://fmi-standard.org/docs/3.0.1/#arrays>
+
+use std::ffi::CString;
+
+use crate::{
+    Error,
+    fmi3::{
+        Fmi3Error, Fmi3Status, GetSet, binding, schema,
+        variable::{resolve_shape, type_name, variable_dimensions},
+    },
+    traits::FmiStatus,
+};
+
+use schema::VariableType;
+
+use super::{Instance, state::GETTABLE};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// The values of an array variable together with its shape.
+///
+/// Values are stored in row-major order, as serialized by the FMU. Scalar variables have an empty
+/// shape and a single value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array<T> {
+    shape: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T> Array<T> {
+    /// Create an array from its shape and its values in row-major order.
+    pub fn new(shape: Vec<usize>, values: Vec<T>) -> Result<Self, Error> {
+        let expected = shape.iter().product();
+        if values.len() != expected {
+            return Err(Error::BufferLength {
+                buffer: "values".to_owned(),
+                expected,
+                found: values.len(),
+            });
+        }
+        Ok(Self { shape, values })
+    }
+
+    /// The size of each dimension
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// The values in row-major order
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Consume the array, returning its values in row-major order
+    pub fn into_values(self) -> Vec<T> {
+        self.values
+    }
+
+    /// The total number of elements
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// View the array as an [`ndarray::ArrayViewD`].
+    #[cfg(feature = "ndarray")]
+    pub fn view(&self) -> ndarray::ArrayViewD<'_, T> {
+        ndarray::ArrayViewD::from_shape(ndarray::IxDyn(&self.shape), &self.values)
+            .expect("shape matches the number of values")
+    }
+
+    /// Convert the array into an [`ndarray::ArrayD`].
+    #[cfg(feature = "ndarray")]
+    pub fn into_ndarray(self) -> ndarray::ArrayD<T> {
+        ndarray::ArrayD::from_shape_vec(ndarray::IxDyn(&self.shape), self.values)
+            .expect("shape matches the number of values")
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T: Clone> From<ndarray::ArrayD<T>> for Array<T> {
+    fn from(array: ndarray::ArrayD<T>) -> Self {
+        Self {
+            shape: array.shape().to_vec(),
+            values: array.iter().cloned().collect(),
+        }
+    }
+}
+
+/// Element types of array variables.
+///
+/// This trait is sealed. Besides the numeric types and `bool`, it is implemented for `String`
+/// (`String` variables) and `Vec<u8>` (`Binary` variables).
+pub trait ArrayElement: Sized + private::Sealed {
+    /// The FMI type a variable must be declared with to be accessed as `Self`
+    const VARIABLE_TYPE: VariableType;
+
+    #[doc(hidden)]
+    fn get_values<Tag>(
+        instance: &mut Instance<Tag>,
+        vr: binding::fmi3ValueReference,
+        len: usize,
+    ) -> Result<Vec<Self>, Error>;
+
+    #[doc(hidden)]
+    fn set_values<Tag>(
+        instance: &mut Instance<Tag>,
+        vr: binding::fmi3ValueReference,
+        values: &[Self],
+    ) -> Result<(), Error>;
+}
+
+macro_rules! impl_array_element {
+    ($ty:ty, $variable_type:ident, $get:ident, $set:ident) => {
+        impl private::Sealed for $ty {}
+
+        impl ArrayElement for $ty {
+            const VARIABLE_TYPE: VariableType = VariableType::$variable_type;
+
+            fn get_values<Tag>(
+                instance: &mut Instance<Tag>,
+                vr: binding::fmi3ValueReference,
+                len: usize,
+            ) -> Result<Vec<Self>, Error> {
+                let mut values = vec![<$ty>::default(); len];
+                instance.$get(&[vr], &mut values)?;
+                Ok(values)
+            }
+
+            fn set_values<Tag>(
+                instance: &mut Instance<Tag>,
+                vr: binding::fmi3ValueReference,
+                values: &[Self],
+            ) -> Result<(), Error> {
+                instance.$set(&[vr], values)?;
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_array_element!(bool, FmiBoolean, get_boolean, set_boolean);
+impl_array_element!(f32, FmiFloat32, get_float32, set_float32);
+impl_array_element!(f64, FmiFloat64, get_float64, set_float64);
+impl_array_element!(i8, FmiInt8, get_int8, set_int8);
+impl_array_element!(i16, FmiInt16, get_int16, set_int16);
+impl_array_element!(i32, FmiInt32, get_int32, set_int32);
+impl_array_element!(i64, FmiInt64, get_int64, set_int64);
+impl_array_element!(u8, FmiUInt8, get_uint8, set_uint8);
+impl_array_element!(u16, FmiUInt16, get_uint16, set_uint16);
+impl_array_element!(u32, FmiUInt32, get_uint32, set_uint32);
+impl_array_element!(u64, FmiUInt64, get_uint64, set_uint64);
+
+impl private::Sealed for String {}
+
+impl ArrayElement for String {
+    const VARIABLE_TYPE: VariableType = VariableType::FmiString;
+
+    fn get_values<Tag>(
+        instance: &mut Instance<Tag>,
+        vr: binding::fmi3ValueReference,
+        len: usize,
+    ) -> Result<Vec<Self>, Error> {
+        let mut values = vec![CString::default(); len];
+        instance.get_string(&[vr], &mut values)?;
+        values
+            .into_iter()
+            .map(|value| value.into_string().map_err(|e| Error::from(e.utf8_error())))
+            .collect()
+    }
+
+    fn set_values<Tag>(
+        instance: &mut Instance<Tag>,
+        vr: binding::fmi3ValueReference,
+        values: &[Self],
+    ) -> Result<(), Error> {
+        let values = values
+            .iter()
+            .map(|value| CString::new(value.as_str()))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| Error::InvalidVariable {
+                name: format!("valueReference={vr}"),
+                reason: "String values must not contain nul bytes".to_owned(),
+            })?;
+        instance.set_string(&[vr], &values)?;
+        Ok(())
+    }
+}
+
+impl private::Sealed for Vec<u8> {}
+
+impl ArrayElement for Vec<u8> {
+    const VARIABLE_TYPE: VariableType = VariableType::FmiBinary;
+
+    fn get_values<Tag>(
+        instance: &mut Instance<Tag>,
+        vr: binding::fmi3ValueReference,
+        len: usize,
+    ) -> Result<Vec<Self>, Error> {
+        // The sizes are only known once the FMU returned its buffers, so the values are copied out
+        // of them directly rather than through `GetSet::get_binary`.
+        instance.require("fmi3GetBinary", GETTABLE)?;
+        let mut value_sizes = vec![0usize; len];
+        let mut value_ptrs: Vec<*const u8> = vec![std::ptr::null(); len];
+        let status = Fmi3Status::from(unsafe {
+            instance.binding.fmi3GetBinary(
+                instance.ptr,
+                &vr,
+                1,
+                value_sizes.as_mut_ptr(),
+                value_ptrs.as_mut_ptr(),
+                len,
+            )
+        });
+        instance.state_machine.update(status.ok(), None)?;
+
+        value_ptrs
+            .into_iter()
+            .zip(value_sizes)
+            .map(|(ptr, size)| {
+                if ptr.is_null() {
+                    log::error!("FMU returned null pointer for binary valueReference={vr}");
+                    Err(Error::from(Fmi3Error::Error))
+                } else {
+                    Ok(unsafe { std::slice::from_raw_parts(ptr, size) }.to_vec())
+                }
+            })
+            .collect()
+    }
+
+    fn set_values<Tag>(
+        instance: &mut Instance<Tag>,
+        vr: binding::fmi3ValueReference,
+        values: &[Self],
+    ) -> Result<(), Error> {
+        let values: Vec<&[u8]> = values.iter().map(Vec::as_slice).collect();
+        instance.set_binary(&[vr], &values)?;
+        Ok(())
+    }
+}
+
+impl<Tag> Instance<Tag> {
+    /// Get the size of each dimension of the variable `vr`, with [`schema::Dimension::Variable`]
+    /// dimensions resolved from the current values of the structural parameters.
+    ///
+    /// Scalar variables have an empty shape.
+    pub fn variable_shape(&mut self, vr: binding::fmi3ValueReference) -> Result<Vec<usize>, Error> {
+        let model_description = self.model_description.clone();
+        let variable = model_description.variable_by_vr(vr)?;
+        resolve_shape(self, variable_dimensions(variable))
+    }
+
+    /// Check that the variable `vr` is declared with the FMI type of `T`, and resolve its shape.
+    fn array_shape<T: ArrayElement>(
+        &mut self,
+        vr: binding::fmi3ValueReference,
+    ) -> Result<Vec<usize>, Error> {
+        let model_description = self.model_description.clone();
+        let variable = model_description.variable_by_vr(vr)?;
+        let abs = variable.as_abstract();
+        if abs.data_type() != T::VARIABLE_TYPE {
+            return Err(Error::VariableTypeMismatch {
+                name: abs.name().to_owned(),
+                declared: type_name(abs.data_type()).to_owned(),
+                requested: std::any::type_name::<T>().to_owned(),
+            });
+        }
+        resolve_shape(self, variable_dimensions(variable))
+    }
+
+    /// Get the values of the variable `vr` together with its shape.
+    ///
+    /// Works for all variable types except Clocks; `String` variables are read as [`String`] and
+    /// `Binary` variables as `Vec<u8>`.
+    pub fn get_array<T: ArrayElement>(
+        &mut self,
+        vr: binding::fmi3ValueReference,
+    ) -> Result<Array<T>, Error> {
+        let shape = self.array_shape::<T>(vr)?;
+        let values = T::get_values(self, vr, shape.iter().product())?;
+        Ok(Array { shape, values })
+    }
+
+    /// Set the values of the variable `vr`.
+    ///
+    /// The shape of `array` must match the current shape of the variable.
+    pub fn set_array<T: ArrayElement>(
+        &mut self,
+        vr: binding::fmi3ValueReference,
+        array: &Array<T>,
+    ) -> Result<(), Error> {
+        let shape = self.array_shape::<T>(vr)?;
+        if shape != array.shape {
+            return Err(Error::InvalidVariable {
+                name: self
+                    .model_description
+                    .variable_by_vr(vr)?
+                    .as_abstract()
+                    .name()
+                    .to_owned(),
+                reason: format!(
+                    "shape {:?} does not match the variable shape {shape:?}",
+                    array.shape
+                ),
+            });
+        }
+        T::set_values(self, vr, &array.values)
+    }
+}
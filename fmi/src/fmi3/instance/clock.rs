@@ -0,0 +1,322 @@
+//! Clock interval and shift access for FMI 3.0 instances.
+//!
+//! See <https://fmi-standard.org/docs/3.0.1/#clocks>
+
+use crate::{
+    Error,
+    fmi3::{Fmi3Error, Fmi3Res, Fmi3Status, binding, schema},
+    traits::FmiStatus,
+};
+
+use super::Instance;
+
+/// Qualifies the Clock interval returned by [`Instance::get_interval_decimal`] and
+/// [`Instance::get_interval_fraction`].
+///
+/// See <https://fmi-standard.org/docs/3.0.1/#fmi3IntervalQualifier>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntervalQualifier {
+    /// The interval is not yet known. Only returned for Clocks with `intervalVariability =
+    /// countdown`.
+    NotYetKnown,
+    /// The interval has not changed since the last call, the returned interval is not meaningful.
+    Unchanged,
+    /// The interval has changed since the last call and the returned interval is valid.
+    Changed,
+}
+
+impl TryFrom<binding::fmi3IntervalQualifier> for IntervalQualifier {
+    type Error = Fmi3Error;
+
+    fn try_from(qualifier: binding::fmi3IntervalQualifier) -> Result<Self, Self::Error> {
+        match qualifier {
+            binding::fmi3IntervalQualifier_fmi3IntervalNotYetKnown => Ok(Self::NotYetKnown),
+            binding::fmi3IntervalQualifier_fmi3IntervalUnchanged => Ok(Self::Unchanged),
+            binding::fmi3IntervalQualifier_fmi3IntervalChanged => Ok(Self::Changed),
+            _ => {
+                log::error!("FMU returned an invalid interval qualifier {qualifier}");
+                Err(Fmi3Error::Error)
+            }
+        }
+    }
+}
+
+impl<Tag> Instance<Tag> {
+    /// Look up the Clock variable with the value reference `vr` in the model description.
+    pub fn clock_variable(
+        &self,
+        vr: binding::fmi3ValueReference,
+    ) -> Result<&schema::FmiClock, Error> {
+        match self.model_description.variable_by_vr(vr) {
+            Ok(schema::Variable::Clock(clock)) => Ok(clock),
+            _ => Err(Error::UnknownVariable {
+                name: format!("Clock valueReference={vr}"),
+            }),
+        }
+    }
+
+    /// The `ClockType` referenced by the `declaredType` of a Clock variable, if any.
+    fn clock_type(&self, clock: &schema::FmiClock) -> Option<&schema::ClockType> {
+        let declared_type = clock.declared_type()?;
+        self.model_description
+            .type_definitions
+            .as_ref()?
+            .type_definitions
+            .iter()
+            .find_map(|t| match t {
+                schema::TypeDefinition::Clock(ct) if ct.name == declared_type => Some(ct),
+                _ => None,
+            })
+    }
+
+    /// The interval variability of the Clock `vr`, which tells periodic, aperiodic and triggered
+    /// Clocks apart. Attributes on the variable take precedence over its declared `ClockType`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#intervalVariability>
+    pub fn clock_interval_variability(
+        &self,
+        vr: binding::fmi3ValueReference,
+    ) -> Result<Option<schema::IntervalVariability>, Error> {
+        let clock = self.clock_variable(vr)?;
+        Ok(clock.interval_variability.or_else(|| {
+            self.clock_type(clock)
+                .and_then(|ct| ct.interval_variability)
+        }))
+    }
+
+    /// Check that each Clock in `vrs` exists and that its interval variability (when known)
+    /// allows the operation `op`.
+    fn check_clocks(
+        &self,
+        vrs: &[binding::fmi3ValueReference],
+        op: &str,
+        allowed: impl Fn(schema::IntervalVariability) -> bool,
+        needs_fraction: bool,
+    ) -> Result<(), Error> {
+        for vr in vrs {
+            let clock = self.clock_variable(*vr)?;
+            match self.clock_interval_variability(*vr)? {
+                Some(variability) if !allowed(variability) => {
+                    return Err(Error::InvalidVariable {
+                        name: clock.name.clone(),
+                        reason: format!(
+                            "{op} is not allowed for a Clock with intervalVariability={variability}"
+                        ),
+                    });
+                }
+                _ => {}
+            }
+            if needs_fraction {
+                let supports_fraction = clock
+                    .supports_fraction
+                    .or_else(|| self.clock_type(clock).and_then(|ct| ct.supports_fraction))
+                    .unwrap_or(false);
+                if !supports_fraction {
+                    return Err(Error::InvalidVariable {
+                        name: clock.name.clone(),
+                        reason: format!("{op} requires supportsFraction=true"),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the interval until the next tick of the time-based Clocks `vrs`, in seconds.
+    ///
+    /// Returns the qualifier of each interval.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetIntervalDecimal>
+    pub fn get_interval_decimal(
+        &mut self,
+        vrs: &[binding::fmi3ValueReference],
+        intervals: &mut [f64],
+    ) -> Result<Vec<IntervalQualifier>, Error> {
+        check_len("intervals", vrs.len(), intervals.len())?;
+        self.check_clocks(
+            vrs,
+            "fmi3GetIntervalDecimal",
+            |iv| !iv.is_triggered(),
+            false,
+        )?;
+
+        let mut qualifiers =
+            vec![binding::fmi3IntervalQualifier_fmi3IntervalNotYetKnown; vrs.len()];
+        Fmi3Status::from(unsafe {
+            self.binding.fmi3GetIntervalDecimal(
+                self.ptr,
+                vrs.as_ptr(),
+                vrs.len(),
+                intervals.as_mut_ptr(),
+                qualifiers.as_mut_ptr(),
+            )
+        })
+        .ok()?;
+
+        qualifiers
+            .into_iter()
+            .map(|q| IntervalQualifier::try_from(q).map_err(Error::from))
+            .collect()
+    }
+
+    /// Get the interval until the next tick of the time-based Clocks `vrs` as
+    /// `counters[i] / resolutions[i]` seconds.
+    ///
+    /// Returns the qualifier of each interval.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetIntervalFraction>
+    pub fn get_interval_fraction(
+        &mut self,
+        vrs: &[binding::fmi3ValueReference],
+        counters: &mut [u64],
+        resolutions: &mut [u64],
+    ) -> Result<Vec<IntervalQualifier>, Error> {
+        check_len("counters", vrs.len(), counters.len())?;
+        check_len("resolutions", vrs.len(), resolutions.len())?;
+        self.check_clocks(
+            vrs,
+            "fmi3GetIntervalFraction",
+            |iv| !iv.is_triggered(),
+            true,
+        )?;
+
+        let mut qualifiers =
+            vec![binding::fmi3IntervalQualifier_fmi3IntervalNotYetKnown; vrs.len()];
+        Fmi3Status::from(unsafe {
+            self.binding.fmi3GetIntervalFraction(
+                self.ptr,
+                vrs.as_ptr(),
+                vrs.len(),
+                counters.as_mut_ptr(),
+                resolutions.as_mut_ptr(),
+                qualifiers.as_mut_ptr(),
+            )
+        })
+        .ok()?;
+
+        qualifiers
+            .into_iter()
+            .map(|q| IntervalQualifier::try_from(q).map_err(Error::from))
+            .collect()
+    }
+
+    /// Get the shift of the first tick of the periodic Clocks `vrs`, in seconds.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetShiftDecimal>
+    pub fn get_shift_decimal(
+        &mut self,
+        vrs: &[binding::fmi3ValueReference],
+        shifts: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        check_len("shifts", vrs.len(), shifts.len())?;
+        self.check_clocks(vrs, "fmi3GetShiftDecimal", |iv| iv.is_periodic(), false)?;
+
+        Fmi3Status::from(unsafe {
+            self.binding
+                .fmi3GetShiftDecimal(self.ptr, vrs.as_ptr(), vrs.len(), shifts.as_mut_ptr())
+        })
+        .ok()
+        .map_err(Error::from)
+    }
+
+    /// Get the shift of the first tick of the periodic Clocks `vrs` as
+    /// `counters[i] / resolutions[i]` seconds.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetShiftFraction>
+    pub fn get_shift_fraction(
+        &mut self,
+        vrs: &[binding::fmi3ValueReference],
+        counters: &mut [u64],
+        resolutions: &mut [u64],
+    ) -> Result<Fmi3Res, Error> {
+        check_len("counters", vrs.len(), counters.len())?;
+        check_len("resolutions", vrs.len(), resolutions.len())?;
+        self.check_clocks(vrs, "fmi3GetShiftFraction", |iv| iv.is_periodic(), true)?;
+
+        Fmi3Status::from(unsafe {
+            self.binding.fmi3GetShiftFraction(
+                self.ptr,
+                vrs.as_ptr(),
+                vrs.len(),
+                counters.as_mut_ptr(),
+                resolutions.as_mut_ptr(),
+            )
+        })
+        .ok()
+        .map_err(Error::from)
+    }
+
+    /// Set the interval until the next tick of the input Clocks `vrs`, in seconds.
+    ///
+    /// Not allowed for Clocks with `intervalVariability` `constant` or `triggered`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3SetIntervalDecimal>
+    pub fn set_interval_decimal(
+        &mut self,
+        vrs: &[binding::fmi3ValueReference],
+        intervals: &[f64],
+    ) -> Result<Fmi3Res, Error> {
+        check_len("intervals", vrs.len(), intervals.len())?;
+        self.check_clocks(vrs, "fmi3SetIntervalDecimal", settable_interval, false)?;
+
+        Fmi3Status::from(unsafe {
+            self.binding.fmi3SetIntervalDecimal(
+                self.ptr,
+                vrs.as_ptr(),
+                vrs.len(),
+                intervals.as_ptr(),
+            )
+        })
+        .ok()
+        .map_err(Error::from)
+    }
+
+    /// Set the interval until the next tick of the input Clocks `vrs` as
+    /// `counters[i] / resolutions[i]` seconds.
+    ///
+    /// Not allowed for Clocks with `intervalVariability` `constant` or `triggered`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3SetIntervalFraction>
+    pub fn set_interval_fraction(
+        &mut self,
+        vrs: &[binding::fmi3ValueReference],
+        counters: &[u64],
+        resolutions: &[u64],
+    ) -> Result<Fmi3Res, Error> {
+        check_len("counters", vrs.len(), counters.len())?;
+        check_len("resolutions", vrs.len(), resolutions.len())?;
+        self.check_clocks(vrs, "fmi3SetIntervalFraction", settable_interval, true)?;
+
+        Fmi3Status::from(unsafe {
+            self.binding.fmi3SetIntervalFraction(
+                self.ptr,
+                vrs.as_ptr(),
+                vrs.len(),
+                counters.as_ptr(),
+                resolutions.as_ptr(),
+            )
+        })
+        .ok()
+        .map_err(Error::from)
+    }
+}
+
+/// Intervals can be set for all time-based Clocks, except those with a constant interval.
+fn settable_interval(variability: schema::IntervalVariability) -> bool {
+    !matches!(
+        variability,
+        schema::IntervalVariability::Constant | schema::IntervalVariability::Triggered
+    )
+}
+
+fn check_len(buffer: &str, expected: usize, found: usize) -> Result<(), Error> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(Error::BufferLength {
+            buffer: buffer.to_owned(),
+            expected,
+            found,
+        })
+    }
+}
@@ -1,14 +1,19 @@
-use std::ffi::CString;
+use std::{ffi::CString, sync::Arc};
 
 use crate::schema::traits::FmiInterfaceType;
 
 use crate::{
     Error, EventFlags,
-    fmi3::{CoSimulation, Common, Fmi3Error, Fmi3Res, Fmi3Status, binding, import, logger},
+    fmi3::{CoSimulation, Common, Fmi3Error, Fmi3Res, Fmi3Status, binding, import, logger, schema},
     traits::{FmiEventHandler, FmiImport, FmiStatus},
 };
 
-use super::{CS, Instance};
+use super::{
+    CS, Capabilities, Instance, IntermediateUpdate, IntermediateUpdateFn, SavedStates,
+    environment::{EnvironmentPtr, InstanceEnvironment},
+    intermediate_update::{IntermediateUpdateHandler, callback_intermediate_update},
+    state::{State, StateMachine},
+};
 
 impl Instance<CS> {
     /// Returns a new CoSimulation instance.
@@ -63,6 +68,64 @@ impl Instance<CS> {
         event_mode_used: bool,
         early_return_allowed: bool,
         required_intermediate_variables: &[binding::fmi3ValueReference],
+    ) -> Result<Self, Error> {
+        Self::instantiate(
+            import,
+            instance_name,
+            visible,
+            logging_on,
+            event_mode_used,
+            early_return_allowed,
+            required_intermediate_variables,
+            None,
+        )
+    }
+
+    /// Returns a new CoSimulation instance that calls `intermediate_update` from within
+    /// [`CoSimulation::do_step`] whenever the FMU enters Intermediate Update Mode.
+    ///
+    /// The closure receives an [`IntermediateUpdate`] handle, which only gives access to
+    /// `required_intermediate_variables` (getting all of them, setting only the inputs among them)
+    /// and can request an early return from the current step. See [`Self::new`] for the other
+    /// arguments.
+    ///
+    /// See: <https://fmi-standard.org/docs/3.0.1/#fmi3IntermediateUpdateCallback>
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_intermediate_update<F>(
+        import: &import::Fmi3Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        event_mode_used: bool,
+        early_return_allowed: bool,
+        required_intermediate_variables: &[binding::fmi3ValueReference],
+        intermediate_update: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(&mut IntermediateUpdate<'_>) + 'static,
+    {
+        Self::instantiate(
+            import,
+            instance_name,
+            visible,
+            logging_on,
+            event_mode_used,
+            early_return_allowed,
+            required_intermediate_variables,
+            Some(Box::new(intermediate_update)),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn instantiate(
+        import: &import::Fmi3Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        event_mode_used: bool,
+        early_return_allowed: bool,
+        required_intermediate_variables: &[binding::fmi3ValueReference],
+        intermediate_update: Option<IntermediateUpdateFn>,
     ) -> Result<Self, Error> {
         let model_description = import.model_description();
 
@@ -78,7 +141,8 @@ impl Instance<CS> {
             co_simulation.model_identifier()
         );
 
-        let binding = import.binding(&co_simulation.model_identifier())?;
+        let (binding, library) = import.instance_binding(co_simulation)?;
+        let binding = Arc::new(binding);
 
         let instance_name = CString::new(instance_name).expect("Invalid instance name");
         let instantiation_token = CString::new(model_description.instantiation_token.as_bytes())
@@ -89,6 +153,27 @@ impl Instance<CS> {
         // instanceEnvironment is a pointer that must be passed to fmi3IntermediateUpdateCallback,
         // fmi3ClockUpdateCallback, and fmi3LogMessageCallback to allow the simulation environment
         // an efficient way to identify the calling FMU.
+        let mut environment = InstanceEnvironment::new(&name);
+        let intermediate_update_callback: binding::fmi3IntermediateUpdateCallback =
+            intermediate_update.map(|callback| {
+                let inputs = model_description
+                    .model_variables
+                    .iter_abstract()
+                    .filter(|v| {
+                        v.causality() == schema::Causality::Input
+                            && required_intermediate_variables.contains(&v.value_reference())
+                    })
+                    .map(|v| v.value_reference())
+                    .collect();
+                environment.intermediate_update = Some(IntermediateUpdateHandler::new(
+                    binding.clone(),
+                    required_intermediate_variables.to_vec(),
+                    inputs,
+                    callback,
+                ));
+                callback_intermediate_update as _
+            });
+        let environment = EnvironmentPtr::new(environment);
 
         let instance = unsafe {
             binding.fmi3InstantiateCoSimulation(
@@ -101,21 +186,27 @@ impl Instance<CS> {
                 early_return_allowed as binding::fmi3Boolean,
                 required_intermediate_variables.as_ptr(),
                 required_intermediate_variables.len() as _,
-                std::ptr::null_mut() as binding::fmi3InstanceEnvironment,
+                environment.as_raw(),
                 Some(logger::callback_log),
-                // intermediateUpdate: fmi3IntermediateUpdateCallback,
-                None,
+                intermediate_update_callback,
             )
         };
 
         if instance.is_null() {
             return Err(Error::Instantiation);
         }
+        environment.get().instance.set(instance);
 
         Ok(Self {
             binding,
             ptr: instance,
             name,
+            model_description: import.shared_model_description(),
+            capabilities: Capabilities::new(co_simulation),
+            saved_states: SavedStates::new(),
+            state_machine: StateMachine::co_simulation(event_mode_used),
+            environment,
+            _library: library,
             _tag: std::marker::PhantomData,
         })
     }
@@ -123,7 +214,10 @@ impl Instance<CS> {
 
 impl CoSimulation for Instance<CS> {
     fn enter_step_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3EnterStepMode(self.ptr) }).ok()
+        self.require("fmi3EnterStepMode", &[State::EventMode])?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3EnterStepMode(self.ptr) });
+        self.state_machine
+            .update(status.ok(), Some(State::StepMode))
     }
 
     fn do_step(
@@ -136,7 +230,8 @@ impl CoSimulation for Instance<CS> {
         early_return: &mut bool,
         last_successful_time: &mut f64,
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3DoStep", &[State::StepMode])?;
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3DoStep(
                 self.ptr,
                 current_communication_point,
@@ -147,8 +242,8 @@ impl CoSimulation for Instance<CS> {
                 early_return,
                 last_successful_time,
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 }
 
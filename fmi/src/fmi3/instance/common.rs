@@ -1,11 +1,14 @@
 use std::mem::MaybeUninit;
 
 use crate::{
-    EventFlags,
+    Error, EventFlags,
     fmi3::{
         Fmi3Error, Fmi3Res, Fmi3Status, binding,
         import::Fmi3Import,
-        instance::Instance,
+        instance::{
+            Instance,
+            state::{ANY, GETTABLE, State},
+        },
         traits::{Common, GetSet},
     },
     traits::{FmiImport, FmiStatus},
@@ -18,7 +21,8 @@ macro_rules! impl_getter_setter {
             vrs: &[binding::fmi3ValueReference],
             values: &mut [$ty],
         ) -> Result<Fmi3Res, Fmi3Error> {
-            Fmi3Status::from(unsafe {
+            self.require(stringify!($fmi_get), GETTABLE)?;
+            let status = Fmi3Status::from(unsafe {
                 self.binding.$fmi_get(
                     self.ptr,
                     vrs.as_ptr(),
@@ -26,8 +30,8 @@ macro_rules! impl_getter_setter {
                     values.as_mut_ptr(),
                     values.len() as _,
                 )
-            })
-            .ok()
+            });
+            self.state_machine.update(status.ok(), None)
         }
 
         fn $set(
@@ -35,7 +39,8 @@ macro_rules! impl_getter_setter {
             vrs: &[binding::fmi3ValueReference],
             values: &[$ty],
         ) -> Result<Fmi3Res, Fmi3Error> {
-            Fmi3Status::from(unsafe {
+            self.require_settable(stringify!($fmi_set), vrs)?;
+            let status = Fmi3Status::from(unsafe {
                 self.binding.$fmi_set(
                     self.ptr,
                     vrs.as_ptr(),
@@ -43,8 +48,8 @@ macro_rules! impl_getter_setter {
                     values.as_ptr(),
                     values.len() as _,
                 )
-            })
-            .ok()
+            });
+            self.state_machine.update(status.ok(), None)
         }
     };
 }
@@ -86,6 +91,7 @@ impl<Tag> GetSet for Instance<Tag> {
         vrs: &[Self::ValueRef],
         values: &mut [std::ffi::CString],
     ) -> Result<(), Fmi3Error> {
+        self.require("fmi3GetString", GETTABLE)?;
         let n_values = values.len();
 
         const STACK_THRESHOLD: usize = 32;
@@ -95,7 +101,7 @@ impl<Tag> GetSet for Instance<Tag> {
             let mut stack_ptrs: [MaybeUninit<*const binding::fmi3Char>; STACK_THRESHOLD] =
                 unsafe { MaybeUninit::uninit().assume_init() };
 
-            Fmi3Status::from(unsafe {
+            let status = Fmi3Status::from(unsafe {
                 self.binding.fmi3GetString(
                     self.ptr,
                     vrs.as_ptr(),
@@ -103,8 +109,8 @@ impl<Tag> GetSet for Instance<Tag> {
                     stack_ptrs.as_mut_ptr() as *mut binding::fmi3String,
                     n_values,
                 )
-            })
-            .ok()?;
+            });
+            self.state_machine.update(status.ok(), None)?;
 
             // Copy the C strings into the output CString values
             for (i, value) in values.iter_mut().enumerate() {
@@ -120,7 +126,7 @@ impl<Tag> GetSet for Instance<Tag> {
             // Heap allocation for large arrays
             let mut value_ptrs: Vec<*const binding::fmi3Char> = vec![std::ptr::null(); n_values];
 
-            Fmi3Status::from(unsafe {
+            let status = Fmi3Status::from(unsafe {
                 self.binding.fmi3GetString(
                     self.ptr,
                     vrs.as_ptr(),
@@ -128,8 +134,8 @@ impl<Tag> GetSet for Instance<Tag> {
                     value_ptrs.as_mut_ptr(),
                     n_values,
                 )
-            })
-            .ok()?;
+            });
+            self.state_machine.update(status.ok(), None)?;
 
             // Copy the C strings into the output CString values
             for (value, ptr) in values.iter_mut().zip(value_ptrs.iter()) {
@@ -150,6 +156,7 @@ impl<Tag> GetSet for Instance<Tag> {
         vrs: &[Self::ValueRef],
         values: &[std::ffi::CString],
     ) -> Result<(), Fmi3Error> {
+        self.require_settable("fmi3SetString", vrs)?;
         let n_values = values.len();
 
         const STACK_THRESHOLD: usize = 32;
@@ -163,7 +170,7 @@ impl<Tag> GetSet for Instance<Tag> {
                 stack_ptrs[i] = MaybeUninit::new(value.as_ptr());
             }
 
-            Fmi3Status::from(unsafe {
+            let status = Fmi3Status::from(unsafe {
                 self.binding.fmi3SetString(
                     self.ptr,
                     vrs.as_ptr(),
@@ -171,14 +178,14 @@ impl<Tag> GetSet for Instance<Tag> {
                     stack_ptrs.as_mut_ptr() as *const binding::fmi3String,
                     n_values,
                 )
-            })
-            .ok()?;
+            });
+            self.state_machine.update(status.ok(), None)?;
         } else {
             // Heap allocation for large arrays
             let value_ptrs: Vec<*const binding::fmi3Char> =
                 values.iter().map(|value| value.as_ptr()).collect();
 
-            Fmi3Status::from(unsafe {
+            let status = Fmi3Status::from(unsafe {
                 self.binding.fmi3SetString(
                     self.ptr,
                     vrs.as_ptr(),
@@ -186,8 +193,8 @@ impl<Tag> GetSet for Instance<Tag> {
                     value_ptrs.as_ptr(),
                     n_values,
                 )
-            })
-            .ok()?;
+            });
+            self.state_machine.update(status.ok(), None)?;
         }
 
         Ok(())
@@ -198,6 +205,7 @@ impl<Tag> GetSet for Instance<Tag> {
         value_references: &[binding::fmi3ValueReference],
         value_buffers: &mut [&mut [u8]],
     ) -> Result<Vec<usize>, Fmi3Error> {
+        self.require("fmi3GetBinary", GETTABLE)?;
         let n_value_references = value_references.len();
         let n_values = value_buffers.len();
 
@@ -248,7 +256,7 @@ impl<Tag> GetSet for Instance<Tag> {
                 *ptr = MaybeUninit::new(std::ptr::null());
             }
 
-            Fmi3Status::from(unsafe {
+            let status = Fmi3Status::from(unsafe {
                 self.binding.fmi3GetBinary(
                     self.ptr,
                     value_references.as_ptr(),
@@ -257,8 +265,8 @@ impl<Tag> GetSet for Instance<Tag> {
                     stack_ptrs.as_mut_ptr() as *mut *const u8,
                     n_values,
                 )
-            })
-            .ok()?;
+            });
+            self.state_machine.update(status.ok(), None)?;
 
             // Convert stack pointers to slice for copy function
             let ptr_slice: Vec<*const u8> = (0..n_values)
@@ -270,7 +278,7 @@ impl<Tag> GetSet for Instance<Tag> {
             // Heap allocation for large arrays
             let mut value_ptrs: Vec<*const u8> = vec![std::ptr::null(); n_values];
 
-            Fmi3Status::from(unsafe {
+            let status = Fmi3Status::from(unsafe {
                 self.binding.fmi3GetBinary(
                     self.ptr,
                     value_references.as_ptr(),
@@ -279,8 +287,8 @@ impl<Tag> GetSet for Instance<Tag> {
                     value_ptrs.as_mut_ptr(),
                     n_values,
                 )
-            })
-            .ok()?;
+            });
+            self.state_machine.update(status.ok(), None)?;
 
             copy_binary_data(&value_ptrs, &value_sizes, value_buffers)?;
         }
@@ -293,13 +301,14 @@ impl<Tag> GetSet for Instance<Tag> {
         vrs: &[binding::fmi3ValueReference],
         values: &[&[u8]],
     ) -> Result<(), Fmi3Error> {
+        self.require_settable("fmi3SetBinary", vrs)?;
         let n_value_references = vrs.len();
         let n_values = values.len();
 
         let value_sizes: Vec<usize> = values.iter().map(|v| v.len()).collect();
         let value_ptrs: Vec<*const u8> = values.iter().map(|v| v.as_ptr()).collect();
 
-        Fmi3Status::from(unsafe {
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3SetBinary(
                 self.ptr,
                 vrs.as_ptr(),
@@ -308,8 +317,8 @@ impl<Tag> GetSet for Instance<Tag> {
                 value_ptrs.as_ptr() as *const binding::fmi3Binary,
                 n_values,
             )
-        })
-        .ok()?;
+        });
+        self.state_machine.update(status.ok(), None)?;
 
         Ok(())
     }
@@ -319,11 +328,15 @@ impl<Tag> GetSet for Instance<Tag> {
         vrs: &[Self::ValueRef],
         values: &mut [binding::fmi3Clock],
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require(
+            "fmi3GetClock",
+            &[State::EventMode, State::ClockActivationMode],
+        )?;
+        let status = Fmi3Status::from(unsafe {
             self.binding
                 .fmi3GetClock(self.ptr, vrs.as_ptr(), vrs.len() as _, values.as_mut_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_clock(
@@ -331,14 +344,26 @@ impl<Tag> GetSet for Instance<Tag> {
         vrs: &[Self::ValueRef],
         values: &[binding::fmi3Clock],
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3SetClock", &[State::EventMode])?;
+        self.require_settable("fmi3SetClock", vrs)?;
+        let status = Fmi3Status::from(unsafe {
             self.binding
                 .fmi3SetClock(self.ptr, vrs.as_ptr(), vrs.len() as _, values.as_ptr())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 }
 
+/// States in which partial derivatives can be computed
+const DERIVATIVES: &[State] = &[
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::StepMode,
+    State::ClockActivationMode,
+    State::Terminated,
+];
+
 impl<Tag> Common for Instance<Tag> {
     fn get_version(&self) -> &str {
         unsafe { std::ffi::CStr::from_ptr(self.binding.fmi3GetVersion()) }
@@ -351,6 +376,7 @@ impl<Tag> Common for Instance<Tag> {
         logging_on: bool,
         categories: &[&str],
     ) -> Result<Fmi3Res, Fmi3Error> {
+        self.require("fmi3SetDebugLogging", ANY)?;
         let cats_vec = categories
             .iter()
             .map(|cat| std::ffi::CString::new(cat.as_bytes()).expect("Error building CString"))
@@ -361,23 +387,40 @@ impl<Tag> Common for Instance<Tag> {
             .map(|cat| cat.as_c_str().as_ptr())
             .collect::<Vec<_>>();
 
-        Fmi3Status::from(unsafe {
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3SetDebugLogging(
                 self.ptr,
                 logging_on,
                 cats_vec_ptrs.len() as _,
                 cats_vec_ptrs.as_ptr() as *const binding::fmi3String,
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn enter_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3EnterConfigurationMode(self.ptr) }).ok()
+        self.require(
+            "fmi3EnterConfigurationMode",
+            &[
+                State::Instantiated,
+                State::EventMode,
+                State::StepMode,
+                State::ClockActivationMode,
+            ],
+        )?;
+        let next = self.state_machine.configuration_mode();
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3EnterConfigurationMode(self.ptr) });
+        self.state_machine.update(status.ok(), Some(next))
     }
 
     fn exit_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3ExitConfigurationMode(self.ptr) }).ok()
+        self.require(
+            "fmi3ExitConfigurationMode",
+            &[State::ConfigurationMode, State::ReconfigurationMode],
+        )?;
+        let next = self.state_machine.exit_configuration_mode();
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3ExitConfigurationMode(self.ptr) });
+        self.state_machine.update(status.ok(), Some(next))
     }
 
     fn enter_initialization_mode(
@@ -386,7 +429,8 @@ impl<Tag> Common for Instance<Tag> {
         start_time: f64,
         stop_time: Option<f64>,
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3EnterInitializationMode", &[State::Instantiated])?;
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3EnterInitializationMode(
                 self.ptr,
                 tolerance.is_some(),
@@ -395,38 +439,58 @@ impl<Tag> Common for Instance<Tag> {
                 stop_time.is_some(),
                 stop_time.unwrap_or_default(),
             )
-        })
-        .ok()
+        });
+        self.state_machine
+            .update(status.ok(), Some(State::InitializationMode))
     }
 
     fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3ExitInitializationMode(self.ptr) }).ok()
+        self.require("fmi3ExitInitializationMode", &[State::InitializationMode])?;
+        let next = self.state_machine.after_initialization();
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3ExitInitializationMode(self.ptr) });
+        self.state_machine.update(status.ok(), Some(next))
     }
 
     fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3EnterEventMode(self.ptr) }).ok()
+        self.require(
+            "fmi3EnterEventMode",
+            &[State::ContinuousTimeMode, State::StepMode],
+        )?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3EnterEventMode(self.ptr) });
+        self.state_machine
+            .update(status.ok(), Some(State::EventMode))
     }
 
     fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3Terminate(self.ptr) }).ok()
+        self.require(
+            "fmi3Terminate",
+            &[
+                State::EventMode,
+                State::ContinuousTimeMode,
+                State::StepMode,
+                State::ClockActivationMode,
+            ],
+        )?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3Terminate(self.ptr) });
+        self.state_machine
+            .update(status.ok(), Some(State::Terminated))
     }
 
     fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3Reset(self.ptr) }).ok()
-    }
-
-    #[cfg(false)]
-    fn get_fmu_state<T>(
-        &mut self,
-        state: Option<Fmu3State<'_, T>>,
-    ) -> Result<Fmu3State<'_, T>, Error> {
-        unsafe { self.binding.fmi3GetFMUState(self.instance, FMUState) }
+        self.require("fmi3Reset", ANY)?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3Reset(self.ptr) });
+        self.state_machine
+            .update(status.ok(), Some(State::Instantiated))
     }
 
     fn update_discrete_states(
         &mut self,
         event_flags: &mut EventFlags,
     ) -> Result<Fmi3Res, Fmi3Error> {
+        self.require(
+            "fmi3UpdateDiscreteStates",
+            &[State::EventMode, State::ClockActivationMode],
+        )?;
         let mut next_event_time_defined = event_flags.next_event_time.is_some();
         let mut next_event_time_value = event_flags.next_event_time.unwrap_or_default();
 
@@ -449,22 +513,23 @@ impl<Tag> Common for Instance<Tag> {
             None
         };
 
-        status.ok()
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_number_of_variable_dependencies(
         &mut self,
         vr: Self::ValueRef,
     ) -> Result<usize, Fmi3Error> {
+        self.require("fmi3GetNumberOfVariableDependencies", ANY)?;
         let mut n_dependencies: usize = 0;
-        Fmi3Status::from(unsafe {
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3GetNumberOfVariableDependencies(
                 self.ptr,
                 vr.into(),
                 &mut n_dependencies as *mut usize,
             )
-        })
-        .ok()?;
+        });
+        self.state_machine.update(status.ok(), None)?;
         Ok(n_dependencies)
     }
 
@@ -485,7 +550,7 @@ impl<Tag> Common for Instance<Tag> {
         let mut dependency_kinds =
             vec![MaybeUninit::<binding::fmi3DependencyKind>::uninit(); n_dependencies];
 
-        Fmi3Status::from(unsafe {
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3GetVariableDependencies(
                 self.ptr,
                 dependent.into(),
@@ -495,8 +560,8 @@ impl<Tag> Common for Instance<Tag> {
                 dependency_kinds.as_mut_ptr() as *mut binding::fmi3DependencyKind,
                 n_dependencies,
             )
-        })
-        .ok()?;
+        });
+        self.state_machine.update(status.ok(), None)?;
 
         // Convert MaybeUninit arrays to initialized values
         let element_indices_of_dependent: Vec<usize> = element_indices_of_dependent
@@ -534,4 +599,72 @@ impl<Tag> Common for Instance<Tag> {
 
         Ok(result)
     }
+
+    fn get_directional_derivative(
+        &mut self,
+        unknowns: &[Self::ValueRef],
+        knowns: &[Self::ValueRef],
+        seed: &[f64],
+        sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        if !self.capabilities.provides_directional_derivatives {
+            return Err(Error::UnsupportedCapability(
+                "providesDirectionalDerivatives".to_owned(),
+            ));
+        }
+        self.check_buffer_len("seed", knowns, seed.len())?;
+        self.check_buffer_len("sensitivity", unknowns, sensitivity.len())?;
+        self.require("fmi3GetDirectionalDerivative", DERIVATIVES)?;
+
+        let status = Fmi3Status::from(unsafe {
+            self.binding.fmi3GetDirectionalDerivative(
+                self.ptr,
+                unknowns.as_ptr(),
+                unknowns.len(),
+                knowns.as_ptr(),
+                knowns.len(),
+                seed.as_ptr(),
+                seed.len(),
+                sensitivity.as_mut_ptr(),
+                sensitivity.len(),
+            )
+        });
+        self.state_machine
+            .update(status.ok(), None)
+            .map_err(Error::from)
+    }
+
+    fn get_adjoint_derivative(
+        &mut self,
+        unknowns: &[Self::ValueRef],
+        knowns: &[Self::ValueRef],
+        seed: &[f64],
+        sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        if !self.capabilities.provides_adjoint_derivatives {
+            return Err(Error::UnsupportedCapability(
+                "providesAdjointDerivatives".to_owned(),
+            ));
+        }
+        self.check_buffer_len("seed", unknowns, seed.len())?;
+        self.check_buffer_len("sensitivity", knowns, sensitivity.len())?;
+        self.require("fmi3GetAdjointDerivative", DERIVATIVES)?;
+
+        let status = Fmi3Status::from(unsafe {
+            self.binding.fmi3GetAdjointDerivative(
+                self.ptr,
+                unknowns.as_ptr(),
+                unknowns.len(),
+                knowns.as_ptr(),
+                knowns.len(),
+                seed.as_ptr(),
+                seed.len(),
+                sensitivity.as_mut_ptr(),
+                sensitivity.len(),
+            )
+        });
+        self.state_machine
+            .update(status.ok(), None)
+            .map_err(Error::from)
+    }
 }
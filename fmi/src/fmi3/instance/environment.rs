@@ -0,0 +1,80 @@
+//! Per-instance state handed to the FMU as `fmi3InstanceEnvironment`.
+
+use std::{
+    cell::Cell,
+    ptr::NonNull,
+    sync::{Mutex, RwLock, atomic::AtomicBool},
+};
+
+use crate::fmi3::{LogSinkFn, binding};
+
+use super::{intermediate_update::IntermediateUpdateHandler, scheduled_execution::ClockUpdateFn};
+
+/// State shared between an [`super::Instance`] and the callbacks invoked by the FMU.
+///
+/// The FMU passes the `instanceEnvironment` pointer back to each callback, which is how a callback
+/// finds the instance that it was called for.
+pub(crate) struct InstanceEnvironment {
+    /// Instance name, reported with log messages
+    pub(crate) instance_name: String,
+    /// Raw instance pointer, set once instantiation returned.
+    pub(crate) instance: Cell<binding::fmi3Instance>,
+    /// Handler for `fmi3IntermediateUpdateCallback` (Co-Simulation only)
+    pub(crate) intermediate_update: Option<IntermediateUpdateHandler>,
+    /// Closure for `fmi3ClockUpdateCallback` (Scheduled Execution only)
+    pub(crate) clock_update: Option<Mutex<ClockUpdateFn>>,
+    /// Set by `fmi3ClockUpdateCallback`, cleared once the output Clocks have been queried
+    pub(crate) clock_updated: AtomicBool,
+    /// Log sink of the instance, `None` logs to the `log` facade
+    pub(crate) log_sink: RwLock<Option<LogSinkFn>>,
+}
+
+impl InstanceEnvironment {
+    pub(crate) fn new(instance_name: &str) -> Self {
+        Self {
+            instance_name: instance_name.to_owned(),
+            instance: Cell::new(std::ptr::null_mut()),
+            intermediate_update: None,
+            clock_update: None,
+            clock_updated: AtomicBool::new(false),
+            log_sink: RwLock::new(None),
+        }
+    }
+
+    /// Recover the environment from the pointer passed to a callback.
+    ///
+    /// # Safety
+    /// `ptr` must be null or come from [`EnvironmentPtr::as_raw`] of a live [`EnvironmentPtr`].
+    pub(crate) unsafe fn from_raw<'a>(
+        ptr: binding::fmi3InstanceEnvironment,
+    ) -> Option<&'a InstanceEnvironment> {
+        unsafe { (ptr as *const InstanceEnvironment).as_ref() }
+    }
+}
+
+/// Owning pointer to a heap allocated [`InstanceEnvironment`].
+///
+/// A raw pointer is used rather than a `Box` since the FMU holds on to an alias of the allocation
+/// for the lifetime of the instance. The environment is only ever accessed through shared
+/// references.
+pub(crate) struct EnvironmentPtr(NonNull<InstanceEnvironment>);
+
+impl EnvironmentPtr {
+    pub(crate) fn new(environment: InstanceEnvironment) -> Self {
+        Self(NonNull::from(Box::leak(Box::new(environment))))
+    }
+
+    pub(crate) fn get(&self) -> &InstanceEnvironment {
+        unsafe { self.0.as_ref() }
+    }
+
+    pub(crate) fn as_raw(&self) -> binding::fmi3InstanceEnvironment {
+        self.0.as_ptr() as binding::fmi3InstanceEnvironment
+    }
+}
+
+impl Drop for EnvironmentPtr {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.0.as_ptr()) });
+    }
+}
@@ -0,0 +1,267 @@
+//! Intermediate Update Mode for FMI 3.0 Co-Simulation instances.
+//!
+//! See <https://fmi-standard.org/docs/3.0.1/#IntermediateUpdateMode>
+
+use std::{cell::RefCell, sync::Arc};
+
+use crate::{
+    Error,
+    fmi3::{Fmi3Res, Fmi3Status, binding},
+    traits::FmiStatus,
+};
+
+use super::environment::InstanceEnvironment;
+
+/// Closure invoked by the FMU through `fmi3IntermediateUpdateCallback`.
+pub type IntermediateUpdateFn = Box<dyn FnMut(&mut IntermediateUpdate<'_>)>;
+
+/// State needed to service `fmi3IntermediateUpdateCallback` for one instance.
+pub(crate) struct IntermediateUpdateHandler {
+    binding: Arc<binding::Fmi3Binding>,
+    /// Value references of `requiredIntermediateVariables`
+    required: Vec<binding::fmi3ValueReference>,
+    /// The subset of `required` with `causality = input`
+    inputs: Vec<binding::fmi3ValueReference>,
+    callback: RefCell<IntermediateUpdateFn>,
+}
+
+impl IntermediateUpdateHandler {
+    pub(crate) fn new(
+        binding: Arc<binding::Fmi3Binding>,
+        required: Vec<binding::fmi3ValueReference>,
+        inputs: Vec<binding::fmi3ValueReference>,
+        callback: IntermediateUpdateFn,
+    ) -> Self {
+        Self {
+            binding,
+            required,
+            inputs,
+            callback: RefCell::new(callback),
+        }
+    }
+}
+
+/// Restricted access to a Co-Simulation instance in Intermediate Update Mode.
+///
+/// Passed to the closure given to [`super::InstanceCS::new_with_intermediate_update`]. Only the
+/// `requiredIntermediateVariables` declared at instantiation can be read, and only the inputs
+/// among them can be set.
+pub struct IntermediateUpdate<'a> {
+    binding: &'a binding::Fmi3Binding,
+    instance: binding::fmi3Instance,
+    required: &'a [binding::fmi3ValueReference],
+    inputs: &'a [binding::fmi3ValueReference],
+    intermediate_update_time: f64,
+    intermediate_variable_set_requested: bool,
+    intermediate_variable_get_allowed: bool,
+    intermediate_step_finished: bool,
+    can_return_early: bool,
+    early_return_time: Option<f64>,
+}
+
+macro_rules! impl_intermediate_getter_setter {
+    ($ty:ty, $get:ident, $set:ident, $fmi_get:ident, $fmi_set:ident) => {
+        #[doc = concat!("Get values of required intermediate variables, see [`crate::fmi3::GetSet::", stringify!($get), "`].")]
+        pub fn $get(
+            &mut self,
+            vrs: &[binding::fmi3ValueReference],
+            values: &mut [$ty],
+        ) -> Result<Fmi3Res, Error> {
+            self.check_get(vrs)?;
+            Fmi3Status::from(unsafe {
+                self.binding.$fmi_get(
+                    self.instance,
+                    vrs.as_ptr(),
+                    vrs.len() as _,
+                    values.as_mut_ptr(),
+                    values.len() as _,
+                )
+            })
+            .ok()
+            .map_err(Error::from)
+        }
+
+        #[doc = concat!("Set values of required intermediate inputs, see [`crate::fmi3::GetSet::", stringify!($set), "`].")]
+        pub fn $set(
+            &mut self,
+            vrs: &[binding::fmi3ValueReference],
+            values: &[$ty],
+        ) -> Result<Fmi3Res, Error> {
+            self.check_set(vrs)?;
+            Fmi3Status::from(unsafe {
+                self.binding.$fmi_set(
+                    self.instance,
+                    vrs.as_ptr(),
+                    vrs.len() as _,
+                    values.as_ptr(),
+                    values.len() as _,
+                )
+            })
+            .ok()
+            .map_err(Error::from)
+        }
+    };
+}
+
+impl IntermediateUpdate<'_> {
+    /// Internal time of the FMU at which the callback was called.
+    pub fn intermediate_update_time(&self) -> f64 {
+        self.intermediate_update_time
+    }
+
+    /// The FMU requests new values for the intermediate inputs.
+    pub fn intermediate_variable_set_requested(&self) -> bool {
+        self.intermediate_variable_set_requested
+    }
+
+    /// The intermediate outputs can be retrieved.
+    pub fn intermediate_variable_get_allowed(&self) -> bool {
+        self.intermediate_variable_get_allowed
+    }
+
+    /// The FMU has completed an internal step up to [`Self::intermediate_update_time`].
+    pub fn intermediate_step_finished(&self) -> bool {
+        self.intermediate_step_finished
+    }
+
+    /// The FMU is able to return early from `fmi3DoStep` at this point.
+    pub fn can_return_early(&self) -> bool {
+        self.can_return_early
+    }
+
+    /// Request the FMU to return early from `fmi3DoStep` at `early_return_time`.
+    ///
+    /// Fails when [`Self::can_return_early`] is `false`.
+    pub fn request_early_return(&mut self, early_return_time: f64) -> Result<(), Error> {
+        if !self.can_return_early {
+            return Err(Error::UnsupportedCapability(
+                "canReturnEarly in this intermediate update".to_string(),
+            ));
+        }
+        self.early_return_time = Some(early_return_time);
+        Ok(())
+    }
+
+    fn check_get(&self, vrs: &[binding::fmi3ValueReference]) -> Result<(), Error> {
+        check_vrs(
+            vrs,
+            self.intermediate_variable_get_allowed,
+            self.required,
+            "intermediate variables can not be retrieved in this intermediate update",
+            "not in requiredIntermediateVariables",
+        )
+    }
+
+    fn check_set(&self, vrs: &[binding::fmi3ValueReference]) -> Result<(), Error> {
+        check_vrs(
+            vrs,
+            self.intermediate_variable_set_requested,
+            self.inputs,
+            "intermediate inputs were not requested in this intermediate update",
+            "not an input in requiredIntermediateVariables",
+        )
+    }
+
+    impl_intermediate_getter_setter!(
+        bool,
+        get_boolean,
+        set_boolean,
+        fmi3GetBoolean,
+        fmi3SetBoolean
+    );
+    impl_intermediate_getter_setter!(
+        f32,
+        get_float32,
+        set_float32,
+        fmi3GetFloat32,
+        fmi3SetFloat32
+    );
+    impl_intermediate_getter_setter!(
+        f64,
+        get_float64,
+        set_float64,
+        fmi3GetFloat64,
+        fmi3SetFloat64
+    );
+    impl_intermediate_getter_setter!(i8, get_int8, set_int8, fmi3GetInt8, fmi3SetInt8);
+    impl_intermediate_getter_setter!(i16, get_int16, set_int16, fmi3GetInt16, fmi3SetInt16);
+    impl_intermediate_getter_setter!(i32, get_int32, set_int32, fmi3GetInt32, fmi3SetInt32);
+    impl_intermediate_getter_setter!(i64, get_int64, set_int64, fmi3GetInt64, fmi3SetInt64);
+    impl_intermediate_getter_setter!(u8, get_uint8, set_uint8, fmi3GetUInt8, fmi3SetUInt8);
+    impl_intermediate_getter_setter!(u16, get_uint16, set_uint16, fmi3GetUInt16, fmi3SetUInt16);
+    impl_intermediate_getter_setter!(u32, get_uint32, set_uint32, fmi3GetUInt32, fmi3SetUInt32);
+    impl_intermediate_getter_setter!(u64, get_uint64, set_uint64, fmi3GetUInt64, fmi3SetUInt64);
+}
+
+fn check_vrs(
+    vrs: &[binding::fmi3ValueReference],
+    allowed: bool,
+    permitted: &[binding::fmi3ValueReference],
+    not_allowed_reason: &str,
+    not_permitted_reason: &str,
+) -> Result<(), Error> {
+    for vr in vrs {
+        let reason = if !allowed {
+            not_allowed_reason
+        } else if !permitted.contains(vr) {
+            not_permitted_reason
+        } else {
+            continue;
+        };
+        return Err(Error::InvalidVariable {
+            name: format!("valueReference={vr}"),
+            reason: reason.to_owned(),
+        });
+    }
+    Ok(())
+}
+
+/// Callback function for `fmi3IntermediateUpdateCallback`
+#[allow(clippy::too_many_arguments)]
+pub(crate) unsafe extern "C" fn callback_intermediate_update(
+    instance_environment: binding::fmi3InstanceEnvironment,
+    intermediate_update_time: binding::fmi3Float64,
+    intermediate_variable_set_requested: binding::fmi3Boolean,
+    intermediate_variable_get_allowed: binding::fmi3Boolean,
+    intermediate_step_finished: binding::fmi3Boolean,
+    can_return_early: binding::fmi3Boolean,
+    early_return_requested: *mut binding::fmi3Boolean,
+    early_return_time: *mut binding::fmi3Float64,
+) {
+    let Some(environment) = (unsafe { InstanceEnvironment::from_raw(instance_environment) }) else {
+        return;
+    };
+    let Some(handler) = environment.intermediate_update.as_ref() else {
+        return;
+    };
+    let Ok(mut callback) = handler.callback.try_borrow_mut() else {
+        log::error!("Re-entrant call of fmi3IntermediateUpdateCallback ignored");
+        return;
+    };
+
+    let mut update = IntermediateUpdate {
+        binding: &handler.binding,
+        instance: environment.instance.get(),
+        required: &handler.required,
+        inputs: &handler.inputs,
+        intermediate_update_time,
+        intermediate_variable_set_requested,
+        intermediate_variable_get_allowed,
+        intermediate_step_finished,
+        can_return_early,
+        early_return_time: None,
+    };
+
+    // Unwinding into the FMU is undefined behavior
+    if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| callback(&mut update))).is_err() {
+        log::error!("Intermediate update closure panicked at t={intermediate_update_time}");
+    }
+
+    if !early_return_requested.is_null() {
+        unsafe { *early_return_requested = update.early_return_time.is_some() };
+    }
+    match update.early_return_time {
+        Some(time) if !early_return_time.is_null() => unsafe { *early_return_time = time },
+        _ => {}
+    }
+}
@@ -1,20 +1,33 @@
 //! FMI 3.0 instance interface
 
 use crate::{
-    CS, InterfaceType, ME, SE,
-    fmi3::{
-        Fmi3Error, Fmi3Res,
-        traits::{Common, GetSet},
-    },
-    traits::{FmiImport, FmiInstance, InstanceTag},
+    CS, Error, InterfaceType, ME, SE,
+    fmi3::{Fmi3Error, Fmi3Res, LogMessage, traits::Common},
+    library::LibraryLease,
+    traits::{FmiImport, FmiInstance, FmiStatus, InstanceTag},
 };
 
-use super::{Fmi3Status, binding, import::Fmi3Import, schema};
+use super::{Fmi3Status, binding, import::Fmi3Import, schema, variable::IndexedModelDescription};
+use crate::schema::traits::FmiInterfaceType;
 
+mod array;
+mod clock;
 mod co_simulation;
 mod common;
+pub(crate) mod environment;
+mod intermediate_update;
 mod model_exchange;
 mod scheduled_execution;
+mod state;
+
+pub use array::{Array, ArrayElement};
+pub use clock::IntervalQualifier;
+pub use intermediate_update::{IntermediateUpdate, IntermediateUpdateFn};
+pub use scheduled_execution::{ClockUpdateFn, PreemptionLock};
+pub use state::State;
+
+use environment::EnvironmentPtr;
+use state::{ANY, StateMachine};
 
 pub type InstanceME = Instance<ME>;
 pub type InstanceCS = Instance<CS>;
@@ -22,61 +35,130 @@ pub type InstanceSE = Instance<SE>;
 
 /// An imported FMI 3.0 instance
 pub struct Instance<Tag> {
-    /// Raw FMI 3.0 bindings
-    binding: binding::Fmi3Binding,
+    /// Raw FMI 3.0 bindings, shared with callbacks that need to call back into the FMU
+    binding: std::sync::Arc<binding::Fmi3Binding>,
     /// Pointer to the raw FMI 3.0 instance
     ptr: binding::fmi3Instance,
     /// Instance name
     name: String,
+    /// Model description of the import this instance was created from
+    model_description: std::sync::Arc<IndexedModelDescription>,
+    /// Capability flags of the interface type this instance was created for
+    capabilities: Capabilities,
+    /// FMU states allocated by this instance
+    saved_states: SavedStates,
+    /// Tracked state of the FMI state machine
+    state_machine: StateMachine,
+    /// State passed to the FMU as `instanceEnvironment`, freed after the instance
+    environment: EnvironmentPtr,
+    /// Shared library the instance is loaded from, released after the bindings are dropped
+    _library: LibraryLease,
     _tag: std::marker::PhantomData<Tag>,
 }
 
 impl<Tag> Drop for Instance<Tag> {
     fn drop(&mut self) {
         unsafe {
+            for state in self.saved_states.states.iter_mut().filter(|s| !s.is_null()) {
+                log::trace!("Freeing state {:?}", state);
+                self.binding.fmi3FreeFMUState(self.ptr, state);
+            }
             log::trace!("Freeing instance {:?}", self.ptr);
             self.binding.fmi3FreeInstance(self.ptr);
         }
     }
 }
 
+impl<Tag> Instance<Tag> {
+    /// Route the log messages of this instance to `sink` instead of the `log` facade.
+    ///
+    /// The sink may be called from any thread the FMU logs from.
+    pub fn set_log_sink<F>(&mut self, sink: F)
+    where
+        F: Fn(&LogMessage<'_>) + Send + Sync + 'static,
+    {
+        *self
+            .environment
+            .get()
+            .log_sink
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(Box::new(sink));
+    }
+
+    /// Route the log messages of this instance back to the `log` facade.
+    pub fn reset_log_sink(&mut self) {
+        *self
+            .environment
+            .get()
+            .log_sink
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = None;
+    }
+}
+
+/// Capability flags copied from the interface type (`ModelExchange`, `CoSimulation` or
+/// `ScheduledExecution`) element of the model description at instantiation.
+#[derive(Debug, Default, Clone, Copy)]
+struct Capabilities {
+    can_get_and_set_fmu_state: bool,
+    can_serialize_fmu_state: bool,
+    provides_directional_derivatives: bool,
+    provides_adjoint_derivatives: bool,
+}
+
+impl Capabilities {
+    fn new(interface: &impl FmiInterfaceType) -> Self {
+        Self {
+            can_get_and_set_fmu_state: interface.can_get_and_set_fmu_state().unwrap_or(false),
+            can_serialize_fmu_state: interface.can_serialize_fmu_state().unwrap_or(false),
+            provides_directional_derivatives: interface
+                .provides_directional_derivatives()
+                .unwrap_or(false),
+            provides_adjoint_derivatives: interface.provides_adjoint_derivatives().unwrap_or(false),
+        }
+    }
+}
+
 impl<Tag> Instance<Tag>
 where
     Self: Common,
 {
     /// Get the sum of the product of the dimensions of the variables with the given value references.
     ///
+    /// [`schema::Dimension::Variable`] dimensions are resolved from the current values of the
+    /// structural parameters.
+    ///
     /// # Arguments
-    /// * `model_description` - The model description to look up variable information
     /// * `var_refs` - Value references of the variables to get dimensions for
-    pub fn get_variable_dimensions(
-        &mut self,
-        model_description: &schema::Fmi3ModelDescription,
-        var_refs: &[u32],
-    ) -> usize {
-        let var_dims = var_refs.iter().map(|vr| {
-            model_description
-                .model_variables
-                .iter_floating()
-                .find(|fv| fv.value_reference() == *vr)
-                .map(|fv| fv.dimensions().iter())
-                .expect("Variable not found")
-        });
+    pub fn get_variable_dimensions(&mut self, var_refs: &[u32]) -> Result<usize, Error> {
+        var_refs
+            .iter()
+            .map(|vr| Ok(self.variable_shape(*vr)?.iter().product::<usize>()))
+            .sum()
+    }
+}
 
-        var_dims
-            .map(|dims| {
-                dims.map(|dim| match dim {
-                    schema::Dimension::Fixed(start) => *start as usize,
-                    schema::Dimension::Variable(vr) => {
-                        let mut dim_val = [0];
-                        self.get_uint64(&[(*vr).into()], &mut dim_val)
-                            .expect("Error getting dimension");
-                        dim_val[0] as usize
-                    }
-                })
-                .product::<usize>()
+impl<Tag> Instance<Tag> {
+    /// Check that a buffer has one entry for each scalar element of the variables `vrs`.
+    fn check_buffer_len(
+        &mut self,
+        buffer: &str,
+        vrs: &[binding::fmi3ValueReference],
+        len: usize,
+    ) -> Result<(), Error> {
+        let expected = vrs
+            .iter()
+            .map(|vr| Ok(self.variable_shape(*vr)?.iter().product::<usize>()))
+            .sum::<Result<usize, Error>>()?;
+        if len == expected {
+            Ok(())
+        } else {
+            Err(Error::BufferLength {
+                buffer: buffer.to_owned(),
+                expected,
+                found: len,
             })
-            .sum()
+        }
     }
 }
 
@@ -127,19 +209,266 @@ impl<Tag: InstanceTag> FmiInstance for Instance<Tag> {
     }
 }
 
+/// Handle to an FMU state retrieved with [`Instance::get_fmu_state`] or
+/// [`Instance::deserialize_fmu_state`].
+///
+/// The underlying `fmi3FMUState` is owned by the instance that created it, and is freed either by
+/// [`Instance::free_fmu_state`] or when the instance is dropped. A handle is only valid for the
+/// instance it was created from.
+///
+/// The handle does not borrow its instance. A handle returned from `get_fmu_state(&mut self)`
+/// with the lifetime of the instance would keep it mutably borrowed, so the instance could not be
+/// stepped or restored while the handle exists. Instead, the handle only holds an index and the id
+/// of its instance, which is never reused within the process. Using a handle on any other
+/// instance, including after its own instance was dropped, fails with an error and never reaches
+/// the FMU.
+///
+/// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetFMUState>
+#[derive(Debug)]
 pub struct Fmu3State<Tag> {
-    instance: Instance<Tag>,
-    /// Pointer to the raw FMI 3.0 state
-    state: binding::fmi3FMUState,
+    /// Index into the saved states of the owning instance
+    index: usize,
+    /// Id of the owning instance, see [`SavedStates::owner`]
+    owner: u64,
+    _tag: std::marker::PhantomData<Tag>,
 }
 
-impl<Tag> Drop for Fmu3State<Tag> {
-    fn drop(&mut self) {
-        unsafe {
-            log::trace!("Freeing state {:?}", self.state);
-            self.instance
-                .binding
-                .fmi3FreeFMUState(self.instance.ptr, &mut self.state);
+/// The FMU states allocated by an instance, addressed by the index in their [`Fmu3State`] handle.
+struct SavedStates {
+    /// Id of the owning instance, unique within the process. Unlike the `fmi3Instance` pointer, it
+    /// is never reused by a later instance.
+    owner: u64,
+    /// Allocated FMU states, freed slots are null and reused by the next state
+    states: Vec<binding::fmi3FMUState>,
+}
+
+impl SavedStates {
+    fn new() -> Self {
+        static NEXT_OWNER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+        Self {
+            owner: NEXT_OWNER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            states: Vec::new(),
+        }
+    }
+
+    /// Store a newly allocated state and return its index.
+    fn insert(&mut self, state: binding::fmi3FMUState) -> usize {
+        match self.states.iter().position(|s| s.is_null()) {
+            Some(index) => {
+                self.states[index] = state;
+                index
+            }
+            None => {
+                self.states.push(state);
+                self.states.len() - 1
+            }
+        }
+    }
+
+    /// The state of a handle, if it belongs to these states and has not been freed.
+    fn get<Tag>(&self, handle: &Fmu3State<Tag>) -> Option<binding::fmi3FMUState> {
+        if handle.owner != self.owner {
+            return None;
+        }
+        self.states
+            .get(handle.index)
+            .copied()
+            .filter(|s| !s.is_null())
+    }
+}
+
+impl<Tag> Instance<Tag> {
+    fn check_get_and_set_fmu_state(&self) -> Result<(), Error> {
+        if self.capabilities.can_get_and_set_fmu_state {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedCapability(
+                "canGetAndSetFMUState".to_owned(),
+            ))
+        }
+    }
+
+    fn check_serialize_fmu_state(&self) -> Result<(), Error> {
+        if self.capabilities.can_serialize_fmu_state {
+            Ok(())
+        } else {
+            Err(Error::UnsupportedCapability(
+                "canSerializeFMUState".to_owned(),
+            ))
+        }
+    }
+
+    /// Look up the raw state for a handle, checking that it belongs to this instance.
+    fn saved_state(&self, state: &Fmu3State<Tag>) -> Result<binding::fmi3FMUState, Error> {
+        if state.owner != self.saved_states.owner {
+            log::error!("FMU state does not belong to instance '{}'", self.name);
+            return Err(Fmi3Error::Error.into());
         }
+        self.saved_states.get(state).ok_or_else(|| {
+            log::error!("FMU state has already been freed");
+            Fmi3Error::Error.into()
+        })
+    }
+
+    fn push_state(&mut self, state: binding::fmi3FMUState) -> Result<Fmu3State<Tag>, Error> {
+        if state.is_null() {
+            log::error!("FMU returned a null state");
+            return Err(Fmi3Error::Fatal.into());
+        }
+        let index = self.saved_states.insert(state);
+        self.state_machine.save(index);
+        Ok(Fmu3State {
+            index,
+            owner: self.saved_states.owner,
+            _tag: std::marker::PhantomData,
+        })
+    }
+
+    /// Make a copy of the internal FMU state and return a handle to it.
+    ///
+    /// Requires the capability flag `canGetAndSetFMUState = true`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetFMUState>
+    pub fn get_fmu_state(&mut self) -> Result<Fmu3State<Tag>, Error> {
+        self.check_get_and_set_fmu_state()?;
+        self.require("fmi3GetFMUState", ANY)?;
+        let mut state: binding::fmi3FMUState = std::ptr::null_mut();
+        let status =
+            Fmi3Status::from(unsafe { self.binding.fmi3GetFMUState(self.ptr, &mut state) });
+        self.state_machine.update(status.ok(), None)?;
+        self.push_state(state)
+    }
+
+    /// Overwrite a previously retrieved FMU state with the current internal FMU state.
+    ///
+    /// Requires the capability flag `canGetAndSetFMUState = true`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetFMUState>
+    pub fn update_fmu_state(&mut self, state: &Fmu3State<Tag>) -> Result<Fmi3Res, Error> {
+        self.check_get_and_set_fmu_state()?;
+        self.require("fmi3GetFMUState", ANY)?;
+        let mut raw = self.saved_state(state)?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3GetFMUState(self.ptr, &mut raw) });
+        let res = self.state_machine.update(status.ok(), None)?;
+        self.saved_states.states[state.index] = raw;
+        self.state_machine.save(state.index);
+        Ok(res)
+    }
+
+    /// Copy the content of a previously retrieved FMU state back into the FMU.
+    ///
+    /// Requires the capability flag `canGetAndSetFMUState = true`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3SetFMUState>
+    pub fn set_fmu_state(&mut self, state: &Fmu3State<Tag>) -> Result<Fmi3Res, Error> {
+        self.check_get_and_set_fmu_state()?;
+        self.require("fmi3SetFMUState", ANY)?;
+        let raw = self.saved_state(state)?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3SetFMUState(self.ptr, raw) });
+        let saved = self.state_machine.saved(state.index);
+        self.state_machine
+            .update(status.ok(), Some(saved))
+            .map_err(Error::from)
+    }
+
+    /// Free the memory allocated for a previously retrieved FMU state.
+    ///
+    /// States that are not freed explicitly are freed when the instance is dropped.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3FreeFMUState>
+    pub fn free_fmu_state(&mut self, state: Fmu3State<Tag>) -> Result<Fmi3Res, Error> {
+        self.require("fmi3FreeFMUState", ANY)?;
+        let mut raw = self.saved_state(&state)?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3FreeFMUState(self.ptr, &mut raw) });
+        let res = self.state_machine.update(status.ok(), None)?;
+        self.saved_states.states[state.index] = std::ptr::null_mut();
+        Ok(res)
+    }
+
+    /// Serialize a previously retrieved FMU state into a byte vector.
+    ///
+    /// Requires the capability flag `canSerializeFMUState = true`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3SerializeFMUState>
+    pub fn serialize_fmu_state(&mut self, state: &Fmu3State<Tag>) -> Result<Vec<u8>, Error> {
+        self.check_serialize_fmu_state()?;
+        self.require("fmi3SerializeFMUState", ANY)?;
+        let raw = self.saved_state(state)?;
+
+        let mut size = 0;
+        let status = Fmi3Status::from(unsafe {
+            self.binding
+                .fmi3SerializedFMUStateSize(self.ptr, raw, &mut size)
+        });
+        self.state_machine.update(status.ok(), None)?;
+
+        let mut buffer: Vec<u8> = vec![0; size];
+        let status = Fmi3Status::from(unsafe {
+            self.binding
+                .fmi3SerializeFMUState(self.ptr, raw, buffer.as_mut_ptr(), size)
+        });
+        self.state_machine.update(status.ok(), None)?;
+
+        Ok(buffer)
+    }
+
+    /// Deserialize a byte vector previously returned by [`Instance::serialize_fmu_state`] into a
+    /// new FMU state. The state is not applied, use [`Instance::set_fmu_state`] for that.
+    ///
+    /// The [`State`] the FMU state was retrieved in is not serialized. Applying the deserialized FMU
+    /// state enters the state the instance was in when it was deserialized.
+    ///
+    /// Requires the capability flag `canSerializeFMUState = true`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3DeserializeFMUState>
+    pub fn deserialize_fmu_state(&mut self, buffer: &[u8]) -> Result<Fmu3State<Tag>, Error> {
+        self.check_serialize_fmu_state()?;
+        self.require("fmi3DeserializeFMUState", ANY)?;
+        let mut state: binding::fmi3FMUState = std::ptr::null_mut();
+        let status = Fmi3Status::from(unsafe {
+            self.binding.fmi3DeserializeFMUState(
+                self.ptr,
+                buffer.as_ptr(),
+                buffer.len(),
+                &mut state,
+            )
+        });
+        self.state_machine.update(status.ok(), None)?;
+        self.push_state(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(states: &SavedStates, index: usize) -> Fmu3State<CS> {
+        Fmu3State {
+            index,
+            owner: states.owner,
+            _tag: std::marker::PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_saved_states() {
+        // Only compared, never dereferenced
+        let (a, b, c) = (8 as binding::fmi3FMUState, 16 as _, 24 as _);
+
+        let mut states = SavedStates::new();
+        assert_eq!(states.insert(a), 0);
+        assert_eq!(states.insert(b), 1);
+        assert_eq!(states.get(&handle(&states, 1)), Some(b));
+
+        // Freed slots are reused, so getting and freeing states in a loop doesn't grow the states
+        states.states[0] = std::ptr::null_mut();
+        assert_eq!(states.get(&handle(&states, 0)), None);
+        assert_eq!(states.insert(c), 0);
+        assert_eq!(states.states.len(), 2);
+
+        // Handles of another instance are rejected even if their index is valid
+        let other = SavedStates::new();
+        assert_ne!(other.owner, states.owner);
+        assert_eq!(states.get(&handle(&other, 0)), None);
     }
 }
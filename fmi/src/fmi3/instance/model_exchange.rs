@@ -1,4 +1,4 @@
-use std::ffi::CString;
+use std::{ffi::CString, sync::Arc};
 
 use crate::schema::traits::FmiInterfaceType;
 
@@ -8,7 +8,11 @@ use crate::{
     traits::{FmiEventHandler, FmiImport, FmiModelExchange, FmiStatus},
 };
 
-use super::{Instance, ME};
+use super::{
+    Capabilities, Instance, ME, SavedStates,
+    environment::{EnvironmentPtr, InstanceEnvironment},
+    state::{ANY, CONTINUOUS_GETTABLE, State, StateMachine},
+};
 
 impl Instance<ME> {
     pub fn new(
@@ -31,7 +35,7 @@ impl Instance<ME> {
             model_exchange.model_identifier()
         );
 
-        let binding = import.binding(&model_exchange.model_identifier())?;
+        let (binding, library) = import.instance_binding(model_exchange)?;
 
         let instance_name = CString::new(instance_name).expect("Invalid instance name");
         let instantiation_token = CString::new(schema.instantiation_token.as_bytes())
@@ -39,6 +43,8 @@ impl Instance<ME> {
         let resource_path =
             CString::new(import.canonical_resource_path_string()).expect("Invalid resource path");
 
+        let environment = EnvironmentPtr::new(InstanceEnvironment::new(&name));
+
         let instance = unsafe {
             binding.fmi3InstantiateModelExchange(
                 instance_name.as_ptr() as binding::fmi3String,
@@ -46,7 +52,7 @@ impl Instance<ME> {
                 resource_path.as_ptr() as binding::fmi3String,
                 visible,
                 logging_on,
-                std::ptr::null_mut() as binding::fmi3InstanceEnvironment,
+                environment.as_raw(),
                 Some(logger::callback_log),
             )
         };
@@ -54,11 +60,18 @@ impl Instance<ME> {
         if instance.is_null() {
             return Err(Error::Instantiation);
         }
+        environment.get().instance.set(instance);
 
         Ok(Self {
-            binding,
+            binding: Arc::new(binding),
             ptr: instance,
             name,
+            model_description: import.shared_model_description(),
+            capabilities: Capabilities::new(model_exchange),
+            saved_states: SavedStates::new(),
+            state_machine: StateMachine::model_exchange(),
+            environment,
+            _library: library,
             _tag: std::marker::PhantomData,
         })
     }
@@ -68,7 +81,11 @@ impl ModelExchange for Instance<ME> {
     /// This function must be called to change from Event Mode into Continuous-Time Mode in Model
     /// Exchange.
     fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3EnterContinuousTimeMode(self.ptr) }).ok()
+        self.require("fmi3EnterContinuousTimeMode", &[State::EventMode])?;
+        let status =
+            Fmi3Status::from(unsafe { self.binding.fmi3EnterContinuousTimeMode(self.ptr) });
+        self.state_machine
+            .update(status.ok(), Some(State::ContinuousTimeMode))
     }
 
     fn completed_integrator_step(
@@ -77,72 +94,80 @@ impl ModelExchange for Instance<ME> {
         enter_event_mode: &mut bool,
         terminate_simulation: &mut bool,
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3CompletedIntegratorStep", &[State::ContinuousTimeMode])?;
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3CompletedIntegratorStep(
                 self.ptr,
                 no_set_fmu_state_prior as _,
                 enter_event_mode as *mut _,
                 terminate_simulation as *mut _,
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe { self.binding.fmi3SetTime(self.ptr, time) }).ok()
+        self.require("fmi3SetTime", &[State::ContinuousTimeMode])?;
+        let status = Fmi3Status::from(unsafe { self.binding.fmi3SetTime(self.ptr, time) });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_continuous_states(
         &mut self,
         continuous_states: &mut [f64],
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3GetContinuousStates", CONTINUOUS_GETTABLE)?;
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3GetContinuousStates(
                 self.ptr,
                 continuous_states.as_mut_ptr(),
                 continuous_states.len(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3SetContinuousStates", &[State::ContinuousTimeMode])?;
+        let status = Fmi3Status::from(unsafe {
             self.binding
                 .fmi3SetContinuousStates(self.ptr, states.as_ptr(), states.len())
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_continuous_state_derivatives(
         &mut self,
         derivatives: &mut [f64],
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3GetContinuousStateDerivatives", CONTINUOUS_GETTABLE)?;
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3GetContinuousStateDerivatives(
                 self.ptr,
                 derivatives.as_mut_ptr(),
                 derivatives.len(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_nominals_of_continuous_states(
         &mut self,
         nominals: &mut [f64],
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3GetNominalsOfContinuousStates", CONTINUOUS_GETTABLE)?;
+        let status = Fmi3Status::from(unsafe {
             self.binding.fmi3GetNominalsOfContinuousStates(
                 self.ptr,
                 nominals.as_mut_ptr(),
                 nominals.len(),
             )
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 
     fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        self.require("fmi3GetEventIndicators", CONTINUOUS_GETTABLE)?;
         let status = unsafe {
             self.binding.fmi3GetEventIndicators(
                 self.ptr,
@@ -152,7 +177,10 @@ impl ModelExchange for Instance<ME> {
         };
 
         // Convert status and handle Discard case
-        match Fmi3Status::from(status).ok() {
+        match self
+            .state_machine
+            .update(Fmi3Status::from(status).ok(), None)
+        {
             Ok(_) => Ok(true), // Successfully computed indicators
             Err(Fmi3Error::Discard) => {
                 // FMU couldn't compute indicators due to numerical issues
@@ -164,22 +192,24 @@ impl ModelExchange for Instance<ME> {
     }
 
     fn get_number_of_event_indicators(&mut self) -> Result<usize, Fmi3Error> {
+        self.require("fmi3GetNumberOfEventIndicators", ANY)?;
         let mut number_of_event_indicators = 0usize;
-        Fmi3Status::from(unsafe {
+        let status = Fmi3Status::from(unsafe {
             self.binding
                 .fmi3GetNumberOfEventIndicators(self.ptr, &mut number_of_event_indicators)
-        })
-        .ok()?;
+        });
+        self.state_machine.update(status.ok(), None)?;
         Ok(number_of_event_indicators)
     }
 
     fn get_number_of_continuous_states(&mut self) -> Result<usize, Fmi3Error> {
+        self.require("fmi3GetNumberOfContinuousStates", ANY)?;
         let mut number_of_continuous_states = 0usize;
-        Fmi3Status::from(unsafe {
+        let status = Fmi3Status::from(unsafe {
             self.binding
                 .fmi3GetNumberOfContinuousStates(self.ptr, &mut number_of_continuous_states)
-        })
-        .ok()?;
+        });
+        self.state_machine.update(status.ok(), None)?;
         Ok(number_of_continuous_states)
     }
 }
@@ -1,31 +1,166 @@
-use std::ffi::CString;
+use std::{
+    ffi::CString,
+    panic::AssertUnwindSafe,
+    sync::{Arc, Condvar, Mutex, PoisonError, atomic::Ordering},
+};
 
 use crate::schema::traits::FmiInterfaceType;
 
 use crate::{
     Error,
-    fmi3::{Fmi3Error, Fmi3Res, Fmi3Status, ScheduledExecution, binding, import, logger},
+    fmi3::{
+        Fmi3Error, Fmi3Res, Fmi3Status, GetSet, ScheduledExecution, binding, import, logger, schema,
+    },
     traits::{FmiImport, FmiStatus},
 };
 
-use super::{Instance, SE};
+use super::{
+    Capabilities, Instance, SE, SavedStates,
+    environment::{EnvironmentPtr, InstanceEnvironment},
+    state::{State, StateMachine},
+};
+
+/// Closure invoked by the FMU through `fmi3ClockUpdateCallback`.
+///
+/// The FMU may call it from any thread that executes a model partition, hence the `Send` bound.
+pub type ClockUpdateFn = Box<dyn FnMut() + Send>;
 
-unsafe extern "C" fn clock_update(_instance_environment: binding::fmi3InstanceEnvironment) {
-    todo!();
+/// Callback function for `fmi3ClockUpdateCallback`
+///
+/// Records that output Clocks may have ticked (or countdown Clock intervals changed), then runs
+/// the closure of the instance, if any.
+unsafe extern "C" fn callback_clock_update(instance_environment: binding::fmi3InstanceEnvironment) {
+    let Some(environment) = (unsafe { InstanceEnvironment::from_raw(instance_environment) }) else {
+        return;
+    };
+    environment.clock_updated.store(true, Ordering::Release);
+
+    if let Some(clock_update) = &environment.clock_update {
+        let mut clock_update = clock_update.lock().unwrap_or_else(PoisonError::into_inner);
+        // Unwinding into the FMU is undefined behavior
+        if std::panic::catch_unwind(AssertUnwindSafe(|| (*clock_update)())).is_err() {
+            log::error!("Clock update closure panicked");
+        }
+    }
 }
-unsafe extern "C" fn lock_preemption() {
-    todo!();
+
+/// `fmi3LockPreemptionCallback` and `fmi3UnlockPreemptionCallback` passed to the FMU.
+///
+/// These callbacks take no `instanceEnvironment`, so they can only act on process-wide state. The
+/// [`Default`] is a mutex shared by all instances in the process. A scheduler that handles
+/// preemption itself, e.g. by raising the priority of the running task, passes its own callbacks.
+#[derive(Clone, Copy, Debug)]
+pub struct PreemptionLock {
+    /// Called by the FMU before entering a critical section
+    pub lock: unsafe extern "C" fn(),
+    /// Called by the FMU after leaving a critical section
+    pub unlock: unsafe extern "C" fn(),
 }
-unsafe extern "C" fn unlock_preemption() {
-    todo!();
+
+impl Default for PreemptionLock {
+    fn default() -> Self {
+        Self {
+            lock: callback_lock_preemption,
+            unlock: callback_unlock_preemption,
+        }
+    }
+}
+
+/// Process-wide mutex of the default [`PreemptionLock`].
+///
+/// Lock and unlock are separate calls, so a `Mutex` guard can not be held in between.
+struct PreemptionMutex {
+    locked: Mutex<bool>,
+    unlocked: Condvar,
+}
+
+static PREEMPTION_LOCK: PreemptionMutex = PreemptionMutex {
+    locked: Mutex::new(false),
+    unlocked: Condvar::new(),
+};
+
+/// Callback function for `fmi3LockPreemptionCallback`
+unsafe extern "C" fn callback_lock_preemption() {
+    let mut locked = PREEMPTION_LOCK
+        .locked
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    while *locked {
+        locked = PREEMPTION_LOCK
+            .unlocked
+            .wait(locked)
+            .unwrap_or_else(PoisonError::into_inner);
+    }
+    *locked = true;
+}
+
+/// Callback function for `fmi3UnlockPreemptionCallback`
+unsafe extern "C" fn callback_unlock_preemption() {
+    *PREEMPTION_LOCK
+        .locked
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = false;
+    PREEMPTION_LOCK.unlocked.notify_one();
 }
 
 impl Instance<SE> {
+    /// Returns a new ScheduledExecution instance.
+    ///
+    /// Calls to `fmi3ClockUpdateCallback` are recorded, see [`Self::output_clock_ticks`].
+    /// Preemption is handled with the default [`PreemptionLock`].
+    ///
+    /// See: <https://fmi-standard.org/docs/3.0.1/#fmi3InstantiateScheduledExecution>
     pub fn new(
         import: &import::Fmi3Import,
         instance_name: &str,
         visible: bool,
         logging_on: bool,
+    ) -> Result<Self, Error> {
+        Self::new_with_preemption_lock(
+            import,
+            instance_name,
+            visible,
+            logging_on,
+            None,
+            PreemptionLock::default(),
+        )
+    }
+
+    /// Returns a new ScheduledExecution instance that additionally calls `clock_update` whenever
+    /// the FMU signals `fmi3ClockUpdateCallback`, e.g. to wake up a scheduler thread.
+    ///
+    /// See [`Self::new`] for the other arguments.
+    pub fn new_with_clock_update<F>(
+        import: &import::Fmi3Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        clock_update: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut() + Send + 'static,
+    {
+        Self::new_with_preemption_lock(
+            import,
+            instance_name,
+            visible,
+            logging_on,
+            Some(Box::new(clock_update)),
+            PreemptionLock::default(),
+        )
+    }
+
+    /// Returns a new ScheduledExecution instance with an optional `clock_update` closure and the
+    /// preemption callbacks of `preemption_lock`.
+    ///
+    /// See [`Self::new`] for the other arguments.
+    pub fn new_with_preemption_lock(
+        import: &import::Fmi3Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        clock_update: Option<ClockUpdateFn>,
+        preemption_lock: PreemptionLock,
     ) -> Result<Self, Error> {
         let schema = import.model_description();
 
@@ -37,11 +172,11 @@ impl Instance<SE> {
             .ok_or(Error::UnsupportedFmuType("ScheduledExecution".to_owned()))?;
 
         log::debug!(
-            "Instantiating ME: {} '{name}'",
+            "Instantiating SE: {} '{name}'",
             scheduled_execution.model_identifier()
         );
 
-        let binding = import.binding(&scheduled_execution.model_identifier())?;
+        let (binding, library) = import.instance_binding(scheduled_execution)?;
 
         let instance_name = CString::new(instance_name).expect("Invalid instance name");
         let instantiation_token = CString::new(schema.instantiation_token.as_bytes())
@@ -49,6 +184,10 @@ impl Instance<SE> {
         let resource_path =
             CString::new(import.canonical_resource_path_string()).expect("Invalid resource path");
 
+        let mut environment = InstanceEnvironment::new(&name);
+        environment.clock_update = clock_update.map(Mutex::new);
+        let environment = EnvironmentPtr::new(environment);
+
         let instance = unsafe {
             binding.fmi3InstantiateScheduledExecution(
                 instance_name.as_ptr(),
@@ -56,37 +195,83 @@ impl Instance<SE> {
                 resource_path.as_ptr() as binding::fmi3String,
                 visible,
                 logging_on,
-                std::ptr::null_mut() as binding::fmi3InstanceEnvironment,
+                environment.as_raw(),
                 Some(logger::callback_log),
-                Some(clock_update),
-                Some(lock_preemption),
-                Some(unlock_preemption),
+                Some(callback_clock_update),
+                Some(preemption_lock.lock),
+                Some(preemption_lock.unlock),
             )
         };
 
         if instance.is_null() {
             return Err(Error::Instantiation);
         }
+        environment.get().instance.set(instance);
 
         Ok(Self {
-            binding,
+            binding: Arc::new(binding),
             ptr: instance,
             name,
+            model_description: import.shared_model_description(),
+            capabilities: Capabilities::new(scheduled_execution),
+            saved_states: SavedStates::new(),
+            state_machine: StateMachine::scheduled_execution(),
+            environment,
+            _library: library,
             _tag: std::marker::PhantomData,
         })
     }
 }
 
+impl Instance<SE> {
+    /// Returns the value references of the output Clocks that ticked since the last call.
+    ///
+    /// Call this after each [`ScheduledExecution::activate_model_partition`]. The output Clocks
+    /// are only queried with `fmi3GetClock` if the FMU called `fmi3ClockUpdateCallback` in
+    /// between, which also resets the ticks in the FMU.
+    pub fn output_clock_ticks(&mut self) -> Result<Vec<binding::fmi3ValueReference>, Error> {
+        if !self
+            .environment
+            .get()
+            .clock_updated
+            .swap(false, Ordering::AcqRel)
+        {
+            return Ok(Vec::new());
+        }
+
+        let output_clocks: Vec<_> = self
+            .model_description
+            .model_variables
+            .clock()
+            .into_iter()
+            .filter(|clock| clock.causality == Some(schema::Causality::Output))
+            .map(|clock| clock.value_reference)
+            .collect();
+        if output_clocks.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut ticks = vec![false; output_clocks.len()];
+        self.get_clock(&output_clocks, &mut ticks)?;
+        Ok(output_clocks
+            .into_iter()
+            .zip(ticks)
+            .filter_map(|(vr, ticked)| ticked.then_some(vr))
+            .collect())
+    }
+}
+
 impl ScheduledExecution for Instance<SE> {
     fn activate_model_partition(
         &mut self,
         clock_reference: Self::ValueRef,
         activation_time: f64,
     ) -> Result<Fmi3Res, Fmi3Error> {
-        Fmi3Status::from(unsafe {
+        self.require("fmi3ActivateModelPartition", &[State::ClockActivationMode])?;
+        let status = Fmi3Status::from(unsafe {
             self.binding
                 .fmi3ActivateModelPartition(self.ptr, clock_reference, activation_time)
-        })
-        .ok()
+        });
+        self.state_machine.update(status.ok(), None)
     }
 }
@@ -0,0 +1,471 @@
+//! Tracking of the FMI 3.0 state machine, and the checked mode of instances.
+//!
+//! Every instance tracks its state from the calls made through it. In checked mode (see
+//! [`Instance::set_checked`]), calls that the state machine does not allow in the current state
+//! are rejected with [`Fmi3Error::IllegalCall`] instead of being passed on to the FMU. Setting a
+//! variable is additionally checked against its causality, variability and initial attribute.
+//!
+//! The Clock interval and FMU state functions are not checked, but restoring an FMU state also
+//! restores the state it was retrieved in.
+//!
+//! See <https://fmi-standard.org/docs/3.0.1/#state-machine-model-exchange>,
+//! <https://fmi-standard.org/docs/3.0.1/#state-machine-co-simulation> and
+//! <https://fmi-standard.org/docs/3.0.1/#state-machine-scheduled-execution>
+
+use std::fmt::Display;
+
+use crate::fmi3::{
+    Fmi3Error, binding,
+    schema::{self, Causality, Initial, InitializableVariableTrait, Variability},
+};
+
+use super::Instance;
+
+/// A state of the FMI 3.0 state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    /// After instantiation, after [`Common::reset`](crate::fmi3::Common::reset), or after leaving
+    /// Configuration Mode
+    Instantiated,
+    ConfigurationMode,
+    InitializationMode,
+    EventMode,
+    /// Model Exchange only
+    ContinuousTimeMode,
+    /// Co-Simulation only
+    StepMode,
+    /// Scheduled Execution only
+    ClockActivationMode,
+    /// Configuration Mode entered after initialization
+    ReconfigurationMode,
+    Terminated,
+    /// A call returned [`Fmi3Error::Error`]. Only getters and
+    /// [`Common::reset`](crate::fmi3::Common::reset) are allowed.
+    Error,
+    /// A call returned [`Fmi3Error::Fatal`]. The instance can only be dropped.
+    Fatal,
+}
+
+impl Display for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            State::Instantiated => "Instantiated",
+            State::ConfigurationMode => "Configuration Mode",
+            State::InitializationMode => "Initialization Mode",
+            State::EventMode => "Event Mode",
+            State::ContinuousTimeMode => "Continuous-Time Mode",
+            State::StepMode => "Step Mode",
+            State::ClockActivationMode => "Clock Activation Mode",
+            State::ReconfigurationMode => "Reconfiguration Mode",
+            State::Terminated => "Terminated",
+            State::Error => "Error",
+            State::Fatal => "Fatal",
+        })
+    }
+}
+
+/// All states that allow calls
+pub(super) const ANY: &[State] = &[
+    State::Instantiated,
+    State::ConfigurationMode,
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::StepMode,
+    State::ClockActivationMode,
+    State::ReconfigurationMode,
+    State::Terminated,
+    State::Error,
+];
+
+/// States in which variables can be read
+pub(super) const GETTABLE: &[State] = &[
+    State::ConfigurationMode,
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::StepMode,
+    State::ClockActivationMode,
+    State::ReconfigurationMode,
+    State::Terminated,
+    State::Error,
+];
+
+/// States in which the continuous states, their derivatives and the event indicators can be read
+pub(super) const CONTINUOUS_GETTABLE: &[State] = &[
+    State::InitializationMode,
+    State::EventMode,
+    State::ContinuousTimeMode,
+    State::Terminated,
+    State::Error,
+];
+
+/// The state machine of an instance.
+#[derive(Debug)]
+pub(super) struct StateMachine {
+    state: State,
+    /// Whether calls that are not allowed in `state` are rejected
+    checked: bool,
+    /// Whether the importer uses Event Mode, always for Model Exchange
+    event_mode_used: bool,
+    /// State entered by `fmi3ExitInitializationMode`
+    after_initialization: State,
+    /// State left for Reconfiguration Mode, and entered again by `fmi3ExitConfigurationMode`
+    before_reconfiguration: State,
+    /// State each FMU state was retrieved in, indexed like the saved states of the instance
+    saved: Vec<State>,
+}
+
+impl StateMachine {
+    fn new(event_mode_used: bool, after_initialization: State) -> Self {
+        Self {
+            state: State::Instantiated,
+            checked: false,
+            event_mode_used,
+            after_initialization,
+            before_reconfiguration: State::Instantiated,
+            saved: Vec::new(),
+        }
+    }
+
+    pub(super) fn model_exchange() -> Self {
+        Self::new(true, State::EventMode)
+    }
+
+    pub(super) fn co_simulation(event_mode_used: bool) -> Self {
+        let after_initialization = if event_mode_used {
+            State::EventMode
+        } else {
+            State::StepMode
+        };
+        Self::new(event_mode_used, after_initialization)
+    }
+
+    pub(super) fn scheduled_execution() -> Self {
+        Self::new(false, State::ClockActivationMode)
+    }
+
+    /// Track the result of a call that reached the FMU.
+    ///
+    /// A successful call enters `next`, if any. `fmi3Error` and `fmi3Fatal` enter the
+    /// [`State::Error`] and [`State::Fatal`] states.
+    pub(super) fn update<T>(
+        &mut self,
+        result: Result<T, Fmi3Error>,
+        next: Option<State>,
+    ) -> Result<T, Fmi3Error> {
+        match (&result, next) {
+            (Ok(_), Some(next)) => {
+                if next == State::ReconfigurationMode {
+                    self.before_reconfiguration = self.state;
+                }
+                self.state = next;
+            }
+            (Err(Fmi3Error::Error), _) => self.state = State::Error,
+            (Err(Fmi3Error::Fatal), _) => self.state = State::Fatal,
+            _ => {}
+        }
+        result
+    }
+
+    /// The state entered by `fmi3EnterConfigurationMode`
+    pub(super) fn configuration_mode(&self) -> State {
+        if self.state == State::Instantiated {
+            State::ConfigurationMode
+        } else {
+            State::ReconfigurationMode
+        }
+    }
+
+    /// The state entered by `fmi3ExitConfigurationMode`
+    pub(super) fn exit_configuration_mode(&self) -> State {
+        if self.state == State::ReconfigurationMode {
+            self.before_reconfiguration
+        } else {
+            State::Instantiated
+        }
+    }
+
+    /// The state entered by `fmi3ExitInitializationMode`
+    pub(super) fn after_initialization(&self) -> State {
+        self.after_initialization
+    }
+
+    /// Remember the current state for the FMU state at `index`, which is newly retrieved, reuses
+    /// the slot of a freed FMU state, or is updated.
+    pub(super) fn save(&mut self, index: usize) {
+        if index == self.saved.len() {
+            self.saved.push(self.state);
+        } else {
+            self.saved[index] = self.state;
+        }
+    }
+
+    /// The state an FMU state was retrieved in
+    pub(super) fn saved(&self, index: usize) -> State {
+        self.saved[index]
+    }
+}
+
+impl<Tag> Instance<Tag> {
+    /// The current state of the instance in the FMI 3.0 state machine.
+    pub fn state(&self) -> State {
+        self.state_machine.state
+    }
+
+    /// Enable or disable checked mode.
+    ///
+    /// In checked mode, calls that are not allowed in the current [`State`], and setting variables
+    /// that can not be set in it, fail with [`Fmi3Error::IllegalCall`] without reaching the FMU.
+    /// The state is tracked in either mode, so checking can be enabled at any time.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.state_machine.checked = checked;
+    }
+
+    /// Whether the instance is in checked mode, see [`Self::set_checked`].
+    pub fn is_checked(&self) -> bool {
+        self.state_machine.checked
+    }
+
+    /// In checked mode, check that `function` may be called in the current state.
+    pub(super) fn require(&self, function: &str, allowed: &[State]) -> Result<(), Fmi3Error> {
+        let state = self.state_machine.state;
+        if !self.state_machine.checked || allowed.contains(&state) {
+            Ok(())
+        } else {
+            Err(Fmi3Error::IllegalCall(format!(
+                "`{function}` is not allowed in {state} (instance '{}')",
+                self.name
+            )))
+        }
+    }
+
+    /// In checked mode, check that `function` may set the variables `vrs` in the current state.
+    pub(super) fn require_settable(
+        &self,
+        function: &str,
+        vrs: &[binding::fmi3ValueReference],
+    ) -> Result<(), Fmi3Error> {
+        if !self.state_machine.checked {
+            return Ok(());
+        }
+        let state = self.state_machine.state;
+        for vr in vrs {
+            let Ok(variable) = self.model_description.variable_by_vr(*vr) else {
+                return Err(Fmi3Error::IllegalCall(format!(
+                    "`{function}` of unknown valueReference {vr} (instance '{}')",
+                    self.name
+                )));
+            };
+            let abs = variable.as_abstract();
+            let initial = variable_initial(variable, abs.causality());
+            if !settable(
+                state,
+                abs.causality(),
+                abs.variability(),
+                initial,
+                self.state_machine.event_mode_used,
+            ) {
+                return Err(Fmi3Error::IllegalCall(format!(
+                    "`{function}` of variable `{}` (causality {}, variability {}) is not allowed \
+                     in {state} (instance '{}')",
+                    abs.name(),
+                    abs.causality(),
+                    abs.variability(),
+                    self.name
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `initial` attribute of `variable`, or its default for `causality`.
+fn variable_initial(variable: &schema::Variable, causality: Causality) -> Option<Initial> {
+    let initial = match variable {
+        schema::Variable::Int8(v) => v.initial(),
+        schema::Variable::UInt8(v) => v.initial(),
+        schema::Variable::Int16(v) => v.initial(),
+        schema::Variable::UInt16(v) => v.initial(),
+        schema::Variable::Int32(v) => v.initial(),
+        schema::Variable::UInt32(v) => v.initial(),
+        schema::Variable::Int64(v) => v.initial(),
+        schema::Variable::UInt64(v) => v.initial(),
+        schema::Variable::Float32(v) => v.initial(),
+        schema::Variable::Float64(v) => v.initial(),
+        schema::Variable::Boolean(v) => v.initial(),
+        schema::Variable::String(v) => v.initial(),
+        schema::Variable::Binary(v) => v.initial(),
+        schema::Variable::Enumeration(v) => v.initial(),
+        schema::Variable::Clock(_) => None,
+    };
+    initial.or(match causality {
+        Causality::Parameter | Causality::StructuralParameter => Some(Initial::Exact),
+        Causality::CalculatedParameter
+        | Causality::Output
+        | Causality::Local
+        | Causality::Dependent => Some(Initial::Calculated),
+        Causality::Input | Causality::Independent => None,
+    })
+}
+
+/// Whether a variable can be set in `state`.
+///
+/// See <https://fmi-standard.org/docs/3.0.1/#table-of-function-calls>
+fn settable(
+    state: State,
+    causality: Causality,
+    variability: Variability,
+    initial: Option<Initial>,
+    event_mode_used: bool,
+) -> bool {
+    if variability == Variability::Constant || causality == Causality::Independent {
+        return false;
+    }
+    let input = causality == Causality::Input;
+    let tunable_parameter =
+        causality == Causality::Parameter && variability == Variability::Tunable;
+    match state {
+        State::Instantiated => input || matches!(initial, Some(Initial::Exact | Initial::Approx)),
+        State::InitializationMode => input || initial == Some(Initial::Exact),
+        State::ConfigurationMode => causality == Causality::StructuralParameter,
+        State::ReconfigurationMode => {
+            causality == Causality::StructuralParameter && variability == Variability::Tunable
+        }
+        State::EventMode | State::ClockActivationMode => input || tunable_parameter,
+        State::ContinuousTimeMode => input && variability == Variability::Continuous,
+        State::StepMode => input || (tunable_parameter && !event_mode_used),
+        State::Terminated | State::Error | State::Fatal => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transitions() {
+        let mut machine = StateMachine::co_simulation(false);
+        assert_eq!(machine.state, State::Instantiated);
+        machine
+            .update(Ok(()), Some(machine.configuration_mode()))
+            .unwrap();
+        assert_eq!(machine.state, State::ConfigurationMode);
+        machine
+            .update(Ok(()), Some(machine.exit_configuration_mode()))
+            .unwrap();
+        assert_eq!(machine.state, State::Instantiated);
+
+        machine
+            .update(Ok(()), Some(State::InitializationMode))
+            .unwrap();
+        machine
+            .update(Ok(()), Some(machine.after_initialization()))
+            .unwrap();
+        assert_eq!(machine.state, State::StepMode);
+
+        // Reconfiguration returns to the state it was entered from
+        machine
+            .update(Ok(()), Some(machine.configuration_mode()))
+            .unwrap();
+        assert_eq!(machine.state, State::ReconfigurationMode);
+        machine
+            .update(Ok(()), Some(machine.exit_configuration_mode()))
+            .unwrap();
+        assert_eq!(machine.state, State::StepMode);
+
+        // Discard keeps the state, errors don't enter the next state
+        machine
+            .update::<()>(Err(Fmi3Error::Discard), Some(State::Terminated))
+            .unwrap_err();
+        assert_eq!(machine.state, State::StepMode);
+        machine
+            .update::<()>(Err(Fmi3Error::Error), Some(State::Terminated))
+            .unwrap_err();
+        assert_eq!(machine.state, State::Error);
+    }
+
+    #[test]
+    fn test_settable() {
+        use Causality::*;
+        use Variability::*;
+
+        // Inputs can be set in all modes except Terminated
+        assert!(settable(State::Instantiated, Input, Continuous, None, true));
+        assert!(settable(State::EventMode, Input, Discrete, None, true));
+        assert!(settable(
+            State::ContinuousTimeMode,
+            Input,
+            Continuous,
+            None,
+            true
+        ));
+        assert!(!settable(
+            State::ContinuousTimeMode,
+            Input,
+            Discrete,
+            None,
+            true
+        ));
+        assert!(!settable(State::Terminated, Input, Continuous, None, true));
+
+        // Outputs can't be set, parameters only before initialization or if tunable
+        let calculated = Some(Initial::Calculated);
+        assert!(!settable(
+            State::Instantiated,
+            Output,
+            Continuous,
+            calculated,
+            true
+        ));
+        assert!(!settable(
+            State::StepMode,
+            Output,
+            Continuous,
+            calculated,
+            false
+        ));
+        let exact = Some(Initial::Exact);
+        assert!(settable(
+            State::InitializationMode,
+            Parameter,
+            Fixed,
+            exact,
+            true
+        ));
+        assert!(!settable(State::EventMode, Parameter, Fixed, exact, true));
+        assert!(settable(State::EventMode, Parameter, Tunable, exact, true));
+        assert!(settable(State::StepMode, Parameter, Tunable, exact, false));
+        assert!(!settable(State::StepMode, Parameter, Tunable, exact, true));
+
+        // Structural parameters in Configuration Mode, constants never
+        assert!(settable(
+            State::ConfigurationMode,
+            StructuralParameter,
+            Fixed,
+            exact,
+            true
+        ));
+        assert!(!settable(
+            State::ReconfigurationMode,
+            StructuralParameter,
+            Fixed,
+            exact,
+            true
+        ));
+        assert!(!settable(
+            State::Instantiated,
+            Parameter,
+            Constant,
+            exact,
+            true
+        ));
+        assert!(!settable(
+            State::Instantiated,
+            Independent,
+            Continuous,
+            None,
+            true
+        ));
+    }
+}
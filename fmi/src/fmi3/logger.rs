@@ -1,8 +1,23 @@
-use super::{Fmi3Status, binding};
+use super::{Fmi3Status, binding, instance::environment::InstanceEnvironment};
+
+/// A message logged by an FMU instance through `fmi3LogMessageCallback`.
+#[derive(Debug)]
+pub struct LogMessage<'a> {
+    /// Name of the instance that logged the message
+    pub instance_name: &'a str,
+    pub status: Fmi3Status,
+    pub category: &'a str,
+    pub message: &'a str,
+}
+
+/// Receives the [`LogMessage`]s of one instance, see [`super::instance::Instance::set_log_sink`].
+pub type LogSinkFn = Box<dyn Fn(&LogMessage<'_>) + Send + Sync>;
 
 /// Callback function for logging
+///
+/// Messages go to the log sink of the instance if it has one, otherwise to the `log` facade.
 pub(crate) unsafe extern "C" fn callback_log(
-    _instance_environment: binding::fmi3InstanceEnvironment,
+    instance_environment: binding::fmi3InstanceEnvironment,
     status: binding::fmi3Status,
     category: binding::fmi3String,
     message: binding::fmi3String,
@@ -15,6 +30,33 @@ pub(crate) unsafe extern "C" fn callback_log(
         .to_str()
         .unwrap_or("INVALID");
 
+    if let Some(environment) = unsafe { InstanceEnvironment::from_raw(instance_environment) } {
+        let log_sink = environment
+            .log_sink
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(log_sink) = log_sink.as_ref() {
+            let log_message = LogMessage {
+                instance_name: &environment.instance_name,
+                status,
+                category,
+                message,
+            };
+            // Unwinding into the FMU is undefined behavior
+            if std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| log_sink(&log_message)))
+                .is_err()
+            {
+                log::error!("Log sink of {} panicked", environment.instance_name);
+            }
+            return;
+        }
+    }
+
+    log_to_facade(&status, category, message);
+}
+
+/// Forward a message logged by an FMU to the `log` facade.
+pub fn log_to_facade(status: &Fmi3Status, category: &str, message: &str) {
     let level = match status.0 {
         binding::fmi3Status_fmi3OK => log::Level::Info,
         binding::fmi3Status_fmi3Warning => log::Level::Warn,
@@ -0,0 +1,417 @@
+//! Co-Simulation on top of a Model Exchange instance.
+
+use std::ffi::CString;
+
+use crate::{
+    Error, EventFlags, InterfaceType,
+    fmi3::{
+        CoSimulation, Common, Fmi3Error, Fmi3Res, Fmi3Status, GetSet, ModelExchange,
+        VariableDependency, binding,
+    },
+    traits::{FmiEventHandler, FmiInstance},
+};
+
+/// Tolerance for reaching the end of a communication step
+const TIME_EPSILON: f64 = 1e-12;
+
+/// A Co-Simulation instance that integrates a Model Exchange instance with fixed-step forward
+/// Euler.
+///
+/// State events and step events are detected at the end of the internal step they occur in, time
+/// events end the internal step early. Without Event Mode, events are handled internally. With
+/// Event Mode, [`CoSimulation::do_step`] returns at the event with `event_handling_needed` set,
+/// and the importer handles it.
+#[derive(Debug)]
+pub struct MockCoSimulation<I> {
+    inner: I,
+    step_size: f64,
+    event_mode_used: bool,
+    time: f64,
+    states: Vec<f64>,
+    derivatives: Vec<f64>,
+    /// Event indicators at the end of the previous internal step
+    indicators: Vec<f64>,
+    next_event_time: Option<f64>,
+}
+
+macro_rules! forward_getter_setter {
+    ($name:ident, $ty:ty) => {
+        paste::paste! {
+            fn [<get_ $name>](
+                &mut self,
+                vrs: &[Self::ValueRef],
+                values: &mut [$ty],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                self.inner.[<get_ $name>](vrs, values)
+            }
+
+            fn [<set_ $name>](
+                &mut self,
+                vrs: &[Self::ValueRef],
+                values: &[$ty],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                self.inner.[<set_ $name>](vrs, values)
+            }
+        }
+    };
+}
+
+impl<I: ModelExchange> MockCoSimulation<I> {
+    /// Wrap the Model Exchange instance `inner`, integrating it with internal steps of at most
+    /// `step_size`, which must be positive and finite.
+    pub fn new(inner: I, step_size: f64, event_mode_used: bool) -> Result<Self, Error> {
+        Ok(Self {
+            inner,
+            step_size: super::check_step_size(step_size)?,
+            event_mode_used,
+            time: 0.0,
+            states: Vec::new(),
+            derivatives: Vec::new(),
+            indicators: Vec::new(),
+            next_event_time: None,
+        })
+    }
+
+    /// The wrapped Model Exchange instance.
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// The wrapped Model Exchange instance.
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Return the wrapped Model Exchange instance.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Enter Continuous-Time Mode, and size the buffers for the integration.
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        let res = self.inner.enter_continuous_time_mode()?;
+        self.states = vec![0.0; self.inner.get_number_of_continuous_states()?];
+        self.derivatives = vec![0.0; self.states.len()];
+        self.indicators = vec![0.0; self.inner.get_number_of_event_indicators()?];
+        if !self.inner.get_event_indicators(&mut self.indicators)? {
+            return Err(Fmi3Error::Discard);
+        }
+        Ok(res)
+    }
+
+    /// Handle an event in Event Mode, and return whether the FMU requested to terminate.
+    fn handle_event(&mut self) -> Result<bool, Fmi3Error> {
+        self.inner.enter_event_mode()?;
+        let mut event_flags = EventFlags::default();
+        loop {
+            self.inner.update_discrete_states(&mut event_flags)?;
+            if event_flags.terminate_simulation {
+                return Ok(true);
+            }
+            if !event_flags.discrete_states_need_update {
+                break;
+            }
+        }
+        self.next_event_time = event_flags.next_event_time;
+        self.enter_continuous_time_mode()?;
+        Ok(false)
+    }
+
+    /// Integrate one internal step, and return whether an event occurred and whether the FMU
+    /// requested to terminate.
+    fn step(&mut self, end_time: f64) -> Result<(bool, bool), Fmi3Error> {
+        let mut step_end = (self.time + self.step_size).min(end_time);
+        let time_event = match self.next_event_time {
+            Some(event_time) if event_time <= step_end => {
+                step_end = event_time;
+                true
+            }
+            _ => false,
+        };
+
+        self.inner.get_continuous_states(&mut self.states)?;
+        self.inner
+            .get_continuous_state_derivatives(&mut self.derivatives)?;
+        let dt = step_end - self.time;
+        for (state, derivative) in self.states.iter_mut().zip(&self.derivatives) {
+            *state += dt * derivative;
+        }
+        self.time = step_end;
+        self.inner.set_time(self.time)?;
+        self.inner.set_continuous_states(&self.states)?;
+
+        let (mut step_event, mut terminate) = (false, false);
+        self.inner
+            .completed_integrator_step(true, &mut step_event, &mut terminate)?;
+
+        let mut indicators = vec![0.0; self.indicators.len()];
+        if !self.inner.get_event_indicators(&mut indicators)? {
+            return Err(Fmi3Error::Discard);
+        }
+        let state_event = self
+            .indicators
+            .iter()
+            .zip(&indicators)
+            .any(|(&before, &after)| (before > 0.0) != (after > 0.0));
+        self.indicators = indicators;
+
+        Ok((time_event || step_event || state_event, terminate))
+    }
+}
+
+impl<I: ModelExchange> GetSet for MockCoSimulation<I> {
+    type ValueRef = I::ValueRef;
+
+    forward_getter_setter!(boolean, bool);
+    forward_getter_setter!(float32, f32);
+    forward_getter_setter!(float64, f64);
+    forward_getter_setter!(int8, i8);
+    forward_getter_setter!(int16, i16);
+    forward_getter_setter!(int32, i32);
+    forward_getter_setter!(int64, i64);
+    forward_getter_setter!(uint8, u8);
+    forward_getter_setter!(uint16, u16);
+    forward_getter_setter!(uint32, u32);
+    forward_getter_setter!(uint64, u64);
+
+    fn get_string(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [CString],
+    ) -> Result<(), Fmi3Error> {
+        self.inner.get_string(vrs, values)
+    }
+
+    fn set_string(&mut self, vrs: &[Self::ValueRef], values: &[CString]) -> Result<(), Fmi3Error> {
+        self.inner.set_string(vrs, values)
+    }
+
+    fn get_binary(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [&mut [u8]],
+    ) -> Result<Vec<usize>, Fmi3Error> {
+        self.inner.get_binary(vrs, values)
+    }
+
+    fn set_binary(&mut self, vrs: &[Self::ValueRef], values: &[&[u8]]) -> Result<(), Fmi3Error> {
+        self.inner.set_binary(vrs, values)
+    }
+
+    fn get_clock(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.get_clock(vrs, values)
+    }
+
+    fn set_clock(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &[binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.set_clock(vrs, values)
+    }
+}
+
+impl<I: ModelExchange> Common for MockCoSimulation<I> {
+    fn get_version(&self) -> &str {
+        self.inner.get_version()
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.set_debug_logging(logging_on, categories)
+    }
+
+    fn enter_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.enter_configuration_mode()
+    }
+
+    fn exit_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.exit_configuration_mode()
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.time = start_time;
+        self.inner
+            .enter_initialization_mode(tolerance, start_time, stop_time)
+    }
+
+    /// Enter Event Mode if Event Mode is used, otherwise handle the initial event and enter Step
+    /// Mode.
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        // A Model Exchange instance enters Event Mode
+        let res = self.inner.exit_initialization_mode()?;
+        if !self.event_mode_used {
+            let mut event_flags = EventFlags::default();
+            loop {
+                self.inner.update_discrete_states(&mut event_flags)?;
+                if event_flags.terminate_simulation || !event_flags.discrete_states_need_update {
+                    break;
+                }
+            }
+            self.next_event_time = event_flags.next_event_time;
+            self.enter_continuous_time_mode()?;
+        }
+        Ok(res)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.enter_event_mode()
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.inner.terminate()
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.time = 0.0;
+        self.next_event_time = None;
+        self.inner.reset()
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        let res = self.inner.update_discrete_states(event_flags)?;
+        self.next_event_time = event_flags.next_event_time;
+        Ok(res)
+    }
+
+    fn get_number_of_variable_dependencies(
+        &mut self,
+        vr: Self::ValueRef,
+    ) -> Result<usize, Fmi3Error> {
+        self.inner.get_number_of_variable_dependencies(vr)
+    }
+
+    fn get_variable_dependencies(
+        &mut self,
+        dependent: Self::ValueRef,
+    ) -> Result<Vec<VariableDependency<Self::ValueRef>>, Fmi3Error> {
+        self.inner.get_variable_dependencies(dependent)
+    }
+}
+
+impl<I: ModelExchange> CoSimulation for MockCoSimulation<I> {
+    fn enter_step_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.enter_continuous_time_mode()
+    }
+
+    fn do_step(
+        &mut self,
+        current_communication_point: f64,
+        communication_step_size: f64,
+        _no_set_fmu_state_prior_to_current_point: bool,
+        event_handling_needed: &mut bool,
+        terminate_simulation: &mut bool,
+        early_return: &mut bool,
+        last_successful_time: &mut f64,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        let end_time = current_communication_point + communication_step_size;
+        *event_handling_needed = false;
+        *terminate_simulation = false;
+        *early_return = false;
+
+        while self.time < end_time - TIME_EPSILON {
+            let (event, terminate) = self.step(end_time)?;
+            if terminate {
+                *terminate_simulation = true;
+                break;
+            }
+            if event {
+                if self.event_mode_used {
+                    *event_handling_needed = true;
+                    break;
+                }
+                if self.handle_event()? {
+                    *terminate_simulation = true;
+                    break;
+                }
+            }
+        }
+
+        *early_return = self.time < end_time - TIME_EPSILON;
+        *last_successful_time = self.time;
+        Ok(Fmi3Res::OK)
+    }
+}
+
+impl<I> FmiInstance for MockCoSimulation<I>
+where
+    I: ModelExchange + FmiInstance<Status = Fmi3Status>,
+{
+    type ModelDescription = I::ModelDescription;
+    type ValueRef = <I as FmiInstance>::ValueRef;
+    type Status = Fmi3Status;
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn get_version(&self) -> &str {
+        Common::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        InterfaceType::CoSimulation
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::set_debug_logging(self, logging_on, categories)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_initialization_mode(self, tolerance, start_time, stop_time)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::exit_initialization_mode(self)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::terminate(self)
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::reset(self)
+    }
+}
+
+impl<I> FmiEventHandler for MockCoSimulation<I>
+where
+    I: ModelExchange + FmiInstance<Status = Fmi3Status>,
+{
+    #[inline]
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    #[inline]
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+}
@@ -0,0 +1,157 @@
+//! In-process FMUs for testing, without any shared library involved.
+//!
+//! A [`MockImport`] implements [`Fmi3Model`] on top of a factory of Model Exchange instances that
+//! are implemented in Rust. Co-Simulation instances wrap a Model Exchange instance in a
+//! [`MockCoSimulation`], which integrates it with fixed-step forward Euler. Scheduled Execution is
+//! not supported.
+//!
+//! The Model Exchange instances can be defined with closures in an [`OdeModel`], or come from a
+//! model type of the `fmi-export` crate (see `ModelInstance::mock_import` there).
+//!
+//! ```rust
+//! use fmi::fmi3::{CoSimulation, Common, Fmi3Model, GetSet, mock::OdeModel};
+//!
+//! // der(x) = -k * x
+//! let model = OdeModel::new("Dahlquist")
+//!     .state("x", 1.0)
+//!     .parameter("k", 1.0)
+//!     .derivatives(|_time, values, derivatives| derivatives[0] = -values[3] * values[1]);
+//! let x = model.value_reference("x").unwrap();
+//!
+//! let import = model.import().with_step_size(1e-3).unwrap();
+//! let mut inst = import
+//!     .instantiate_cs("inst1", false, false, false, false, &[])
+//!     .unwrap();
+//! inst.enter_initialization_mode(None, 0.0, None).unwrap();
+//! inst.exit_initialization_mode().unwrap();
+//! let (mut event, mut terminate, mut early_return, mut time) = (false, false, false, 0.0);
+//! inst.do_step(0.0, 1.0, true, &mut event, &mut terminate, &mut early_return, &mut time)
+//!     .unwrap();
+//!
+//! let mut values = [0.0];
+//! inst.get_float64(&[x], &mut values).unwrap();
+//! assert!((values[0] - (-1.0f64).exp()).abs() < 1e-3);
+//! ```
+
+use crate::{Error, fmi3::binding};
+
+use super::{Fmi3Model, ModelExchange, Unsupported, schema};
+
+mod co_simulation;
+mod ode;
+
+pub use co_simulation::MockCoSimulation;
+pub use ode::{OdeInstance, OdeModel};
+
+/// Internal step size of Co-Simulation instances, if the model description has no default
+const DEFAULT_STEP_SIZE: f64 = 1e-3;
+
+/// Creates a Model Exchange instance from the instance name and the `logging_on` flag.
+type Factory<I> = Box<dyn Fn(&str, bool) -> Result<I, Error> + Send + Sync>;
+
+/// An FMU whose instances are created in-process, see the [module documentation](self).
+pub struct MockImport<I> {
+    model_description: schema::Fmi3ModelDescription,
+    factory: Factory<I>,
+    /// Internal step size of Co-Simulation instances
+    step_size: f64,
+}
+
+impl<I> std::fmt::Debug for MockImport<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockImport")
+            .field("model_name", &self.model_description.model_name)
+            .field("step_size", &self.step_size)
+            .finish()
+    }
+}
+
+impl<I: ModelExchange> MockImport<I> {
+    /// Create an import described by `model_description`, whose instances are created by
+    /// `factory` from the instance name and the `logging_on` flag.
+    ///
+    /// The interfaces that can be instantiated are the ones present in `model_description`. Fails
+    /// if the step size of its default experiment is not positive and finite.
+    pub fn new<F>(
+        model_description: schema::Fmi3ModelDescription,
+        factory: F,
+    ) -> Result<Self, Error>
+    where
+        F: Fn(&str, bool) -> Result<I, Error> + Send + Sync + 'static,
+    {
+        let step_size = match model_description
+            .default_experiment
+            .as_ref()
+            .and_then(|experiment| experiment.step_size)
+        {
+            Some(step_size) => check_step_size(step_size)?,
+            None => DEFAULT_STEP_SIZE,
+        };
+        Ok(Self {
+            model_description,
+            factory: Box::new(factory),
+            step_size,
+        })
+    }
+
+    /// Set the internal step size of Co-Simulation instances.
+    ///
+    /// Defaults to the step size of the default experiment, or 1e-3. Fails if `step_size` is not
+    /// positive and finite.
+    pub fn with_step_size(mut self, step_size: f64) -> Result<Self, Error> {
+        self.step_size = check_step_size(step_size)?;
+        Ok(self)
+    }
+
+    /// The model description of the FMU.
+    pub fn model_description(&self) -> &schema::Fmi3ModelDescription {
+        &self.model_description
+    }
+}
+
+impl<I: ModelExchange> Fmi3Model for MockImport<I> {
+    type InstanceME = I;
+    type InstanceCS = MockCoSimulation<I>;
+    type InstanceSE = Unsupported;
+
+    fn instantiate_me(
+        &self,
+        instance_name: &str,
+        _visible: bool,
+        logging_on: bool,
+    ) -> Result<Self::InstanceME, Error> {
+        if self.model_description.model_exchange.is_none() {
+            return Err(Error::UnsupportedFmuType("ModelExchange".to_owned()));
+        }
+        (self.factory)(instance_name, logging_on)
+    }
+
+    /// Create a new instance of the FMU for Co-Simulation
+    ///
+    /// With `event_mode_used`, the instance returns from [`CoSimulation::do_step`] at every event
+    /// as if early return was allowed. Otherwise events are handled internally.
+    fn instantiate_cs(
+        &self,
+        instance_name: &str,
+        _visible: bool,
+        logging_on: bool,
+        event_mode_used: bool,
+        _early_return_allowed: bool,
+        _required_intermediate_variables: &[binding::fmi3ValueReference],
+    ) -> Result<Self::InstanceCS, Error> {
+        if self.model_description.co_simulation.is_none() {
+            return Err(Error::UnsupportedFmuType("CoSimulation".to_owned()));
+        }
+        let inner = (self.factory)(instance_name, logging_on)?;
+        MockCoSimulation::new(inner, self.step_size, event_mode_used)
+    }
+}
+
+/// `step_size` if the integration advances with it, that is if it is positive and finite.
+fn check_step_size(step_size: f64) -> Result<f64, Error> {
+    if step_size > 0.0 && step_size.is_finite() {
+        Ok(step_size)
+    } else {
+        Err(Error::InvalidStepSize(step_size))
+    }
+}
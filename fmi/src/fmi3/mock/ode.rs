@@ -0,0 +1,764 @@
+//! Model Exchange instances of a system of ODEs defined with closures.
+
+use std::{ffi::CString, sync::Arc};
+
+use crate::{
+    EventFlags, InterfaceType,
+    fmi3::{
+        Common, Fmi3Error, Fmi3Res, Fmi3Status, GetSet, ModelExchange, VariableDependency, binding,
+        schema,
+    },
+    traits::{FmiEventHandler, FmiInstance, FmiModelExchange},
+};
+
+use super::MockImport;
+
+type DerivativesFn = dyn Fn(f64, &[f64], &mut [f64]) + Send + Sync;
+type EquationsFn = dyn Fn(f64, &mut [f64]) + Send + Sync;
+type EventIndicatorsFn = dyn Fn(f64, &[f64], &mut [f64]) + Send + Sync;
+type EventUpdateFn = dyn Fn(f64, &mut [f64], &mut EventFlags) + Send + Sync;
+
+#[derive(Debug, Clone)]
+struct OdeVariable {
+    name: String,
+    causality: schema::Causality,
+    variability: schema::Variability,
+    start: Option<f64>,
+    /// Value reference of the state, for derivatives
+    derivative: Option<u32>,
+}
+
+/// A system of ODEs defined with closures, for testing importers without a compiled FMU.
+///
+/// All variables are scalar `Float64` variables. Their value references are assigned in the
+/// order of declaration, starting with the independent variable `time` at 0. The closures receive
+/// the current time and the values of all variables, indexed by value reference:
+///
+/// * [`Self::equations`] computes the calculated variables, such as outputs, in place. It is
+///   evaluated before any variable is read.
+/// * [`Self::derivatives`] computes the derivatives of the continuous states, in the order the
+///   states were declared.
+/// * [`Self::event_indicators`] computes the event indicators, whose sign changes are state
+///   events, in the order they were declared.
+/// * [`Self::event_update`] updates the variables in Event Mode, and sets the [`EventFlags`].
+///
+/// See the [module documentation](super) for an example.
+#[derive(Clone)]
+pub struct OdeModel {
+    name: String,
+    variables: Vec<OdeVariable>,
+    /// Value references of the continuous states and of their derivatives
+    states: Vec<(u32, u32)>,
+    /// Value references of the event indicators
+    event_indicator_vrs: Vec<u32>,
+    derivatives: Option<Arc<DerivativesFn>>,
+    equations: Option<Arc<EquationsFn>>,
+    event_indicators: Option<Arc<EventIndicatorsFn>>,
+    event_update: Option<Arc<EventUpdateFn>>,
+}
+
+impl std::fmt::Debug for OdeModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OdeModel")
+            .field("name", &self.name)
+            .field("variables", &self.variables)
+            .finish()
+    }
+}
+
+impl OdeModel {
+    /// Create a model without states, with only the independent variable `time`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variables: vec![OdeVariable {
+                name: "time".to_owned(),
+                causality: schema::Causality::Independent,
+                variability: schema::Variability::Continuous,
+                start: None,
+                derivative: None,
+            }],
+            states: Vec::new(),
+            event_indicator_vrs: Vec::new(),
+            derivatives: None,
+            equations: None,
+            event_indicators: None,
+            event_update: None,
+        }
+    }
+
+    fn push(
+        &mut self,
+        name: impl Into<String>,
+        causality: schema::Causality,
+        variability: schema::Variability,
+        start: Option<f64>,
+    ) -> u32 {
+        self.variables.push(OdeVariable {
+            name: name.into(),
+            causality,
+            variability,
+            start,
+            derivative: None,
+        });
+        (self.variables.len() - 1) as u32
+    }
+
+    /// Add a continuous state with causality output, followed by its derivative `der(<name>)`.
+    pub fn state(mut self, name: &str, start: f64) -> Self {
+        let state = self.push(
+            name,
+            schema::Causality::Output,
+            schema::Variability::Continuous,
+            Some(start),
+        );
+        let derivative = self.push(
+            format!("der({name})"),
+            schema::Causality::Local,
+            schema::Variability::Continuous,
+            None,
+        );
+        self.variables[derivative as usize].derivative = Some(state);
+        self.states.push((state, derivative));
+        self
+    }
+
+    /// Add a fixed parameter.
+    pub fn parameter(mut self, name: &str, start: f64) -> Self {
+        self.push(
+            name,
+            schema::Causality::Parameter,
+            schema::Variability::Fixed,
+            Some(start),
+        );
+        self
+    }
+
+    /// Add a continuous input.
+    pub fn input(mut self, name: &str, start: f64) -> Self {
+        self.push(
+            name,
+            schema::Causality::Input,
+            schema::Variability::Continuous,
+            Some(start),
+        );
+        self
+    }
+
+    /// Add a continuous output, computed by [`Self::equations`].
+    pub fn output(mut self, name: &str) -> Self {
+        self.push(
+            name,
+            schema::Causality::Output,
+            schema::Variability::Continuous,
+            None,
+        );
+        self
+    }
+
+    /// Set the function computing the derivatives of the continuous states.
+    pub fn derivatives<F>(mut self, derivatives: F) -> Self
+    where
+        F: Fn(f64, &[f64], &mut [f64]) + Send + Sync + 'static,
+    {
+        self.derivatives = Some(Arc::new(derivatives));
+        self
+    }
+
+    /// Set the function computing the calculated variables in place.
+    pub fn equations<F>(mut self, equations: F) -> Self
+    where
+        F: Fn(f64, &mut [f64]) + Send + Sync + 'static,
+    {
+        self.equations = Some(Arc::new(equations));
+        self
+    }
+
+    /// Add `number` event indicators, and set the function computing them.
+    ///
+    /// The event indicators are local variables named `indicator<i>`, starting at 1.
+    pub fn event_indicators<F>(mut self, number: usize, event_indicators: F) -> Self
+    where
+        F: Fn(f64, &[f64], &mut [f64]) + Send + Sync + 'static,
+    {
+        for i in 1..=number {
+            let vr = self.push(
+                format!("indicator{i}"),
+                schema::Causality::Local,
+                schema::Variability::Continuous,
+                None,
+            );
+            self.event_indicator_vrs.push(vr);
+        }
+        self.event_indicators = Some(Arc::new(event_indicators));
+        self
+    }
+
+    /// Set the function updating the variables in Event Mode.
+    ///
+    /// The [`EventFlags`] are reset before each call.
+    pub fn event_update<F>(mut self, event_update: F) -> Self
+    where
+        F: Fn(f64, &mut [f64], &mut EventFlags) + Send + Sync + 'static,
+    {
+        self.event_update = Some(Arc::new(event_update));
+        self
+    }
+
+    /// The value reference of the variable `name`.
+    pub fn value_reference(&self, name: &str) -> Option<binding::fmi3ValueReference> {
+        self.variables
+            .iter()
+            .position(|variable| variable.name == name)
+            .map(|vr| vr as binding::fmi3ValueReference)
+    }
+
+    /// A model description of the model, supporting Model Exchange and Co-Simulation.
+    pub fn model_description(&self) -> schema::Fmi3ModelDescription {
+        let variables = self
+            .variables
+            .iter()
+            .enumerate()
+            .map(|(vr, variable)| {
+                let initial = match (variable.causality, variable.start) {
+                    (schema::Causality::Independent, _) => None,
+                    (_, Some(_)) => Some(schema::Initial::Exact),
+                    (_, None) => Some(schema::Initial::Calculated),
+                };
+                let mut float64 = schema::FmiFloat64::new(
+                    variable.name.clone(),
+                    vr as u32,
+                    None,
+                    variable.causality,
+                    variable.variability,
+                    variable.start.map(|start| vec![start]),
+                    initial,
+                );
+                float64.derivative = variable.derivative;
+                schema::Variable::Float64(float64)
+            })
+            .collect();
+
+        let unknown = |vr: usize| schema::Fmi3Unknown {
+            value_reference: vr as u32,
+            ..Default::default()
+        };
+        let outputs = self
+            .variables
+            .iter()
+            .enumerate()
+            .filter(|(_, variable)| variable.causality == schema::Causality::Output)
+            .map(|(vr, _)| schema::VariableDependency::Output(unknown(vr)));
+        let derivatives = self.states.iter().map(|&(_, derivative)| {
+            schema::VariableDependency::ContinuousStateDerivative(unknown(derivative as usize))
+        });
+        let event_indicators = self
+            .event_indicator_vrs
+            .iter()
+            .map(|&vr| schema::VariableDependency::EventIndicator(unknown(vr as usize)));
+
+        schema::Fmi3ModelDescription {
+            fmi_version: "3.0".to_owned(),
+            model_name: self.name.clone(),
+            instantiation_token: format!("{{mock-{}}}", self.name),
+            generation_tool: Some("rust-fmi mock".to_owned()),
+            model_exchange: Some(schema::Fmi3ModelExchange {
+                model_identifier: self.name.clone(),
+                ..Default::default()
+            }),
+            co_simulation: Some(schema::Fmi3CoSimulation {
+                model_identifier: self.name.clone(),
+                ..Default::default()
+            }),
+            model_variables: schema::ModelVariables { variables },
+            model_structure: schema::ModelStructure {
+                unknowns: outputs.chain(derivatives).chain(event_indicators).collect(),
+            },
+            ..Default::default()
+        }
+    }
+
+    /// An import creating instances of this model.
+    pub fn import(self) -> MockImport<OdeInstance> {
+        let model_description = self.model_description();
+        let model = Arc::new(self);
+        // The model description has no default experiment whose step size could be invalid
+        MockImport::new(model_description, move |name, _logging_on| {
+            Ok(OdeInstance::new(model.clone(), name))
+        })
+        .expect("the default step size is valid")
+    }
+
+    fn start_values(&self) -> Vec<f64> {
+        self.variables
+            .iter()
+            .map(|variable| variable.start.unwrap_or_default())
+            .collect()
+    }
+}
+
+/// A Model Exchange instance of an [`OdeModel`].
+///
+/// The instance does not check the FMI state machine.
+#[derive(Debug)]
+pub struct OdeInstance {
+    model: Arc<OdeModel>,
+    name: String,
+    /// Values of all variables, indexed by value reference
+    values: Vec<f64>,
+    /// Whether the equations, derivatives and event indicators need to be evaluated
+    dirty: bool,
+}
+
+macro_rules! unsupported_getter_setter {
+    ($name:ident, $ty:ty) => {
+        paste::paste! {
+            fn [<get_ $name>](
+                &mut self,
+                _vrs: &[Self::ValueRef],
+                _values: &mut [$ty],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                Err(Fmi3Error::Error)
+            }
+
+            fn [<set_ $name>](
+                &mut self,
+                _vrs: &[Self::ValueRef],
+                _values: &[$ty],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                Err(Fmi3Error::Error)
+            }
+        }
+    };
+}
+
+impl OdeInstance {
+    fn new(model: Arc<OdeModel>, name: &str) -> Self {
+        Self {
+            values: model.start_values(),
+            model,
+            name: name.to_owned(),
+            dirty: true,
+        }
+    }
+
+    /// Evaluate the equations, derivatives and event indicators, if any variable changed.
+    fn evaluate(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        let time = self.values[0];
+        if let Some(equations) = &self.model.equations {
+            equations(time, &mut self.values);
+        }
+        if let Some(derivatives) = &self.model.derivatives {
+            let mut dx = vec![0.0; self.model.states.len()];
+            derivatives(time, &self.values, &mut dx);
+            for (&(_, derivative), value) in self.model.states.iter().zip(dx) {
+                self.values[derivative as usize] = value;
+            }
+        }
+        if let Some(event_indicators) = &self.model.event_indicators {
+            let mut indicators = vec![0.0; self.model.event_indicator_vrs.len()];
+            event_indicators(time, &self.values, &mut indicators);
+            for (&vr, value) in self.model.event_indicator_vrs.iter().zip(indicators) {
+                self.values[vr as usize] = value;
+            }
+        }
+        self.dirty = false;
+    }
+
+    fn index(&self, vr: binding::fmi3ValueReference) -> Result<usize, Fmi3Error> {
+        let index = vr as usize;
+        if index < self.values.len() {
+            Ok(index)
+        } else {
+            Err(Fmi3Error::Error)
+        }
+    }
+
+    fn check_states(&self, len: usize) -> Result<(), Fmi3Error> {
+        if len == self.model.states.len() {
+            Ok(())
+        } else {
+            Err(Fmi3Error::Error)
+        }
+    }
+}
+
+impl GetSet for OdeInstance {
+    type ValueRef = binding::fmi3ValueReference;
+
+    unsupported_getter_setter!(boolean, bool);
+    unsupported_getter_setter!(float32, f32);
+    unsupported_getter_setter!(int8, i8);
+    unsupported_getter_setter!(int16, i16);
+    unsupported_getter_setter!(int32, i32);
+    unsupported_getter_setter!(int64, i64);
+    unsupported_getter_setter!(uint8, u8);
+    unsupported_getter_setter!(uint16, u16);
+    unsupported_getter_setter!(uint32, u32);
+    unsupported_getter_setter!(uint64, u64);
+
+    fn get_float64(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        if vrs.len() != values.len() {
+            return Err(Fmi3Error::Error);
+        }
+        self.evaluate();
+        for (&vr, value) in vrs.iter().zip(values.iter_mut()) {
+            *value = self.values[self.index(vr)?];
+        }
+        Ok(Fmi3Res::OK)
+    }
+
+    fn set_float64(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &[f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        if vrs.len() != values.len() || vrs.contains(&0) {
+            return Err(Fmi3Error::Error);
+        }
+        for (&vr, &value) in vrs.iter().zip(values) {
+            let index = self.index(vr)?;
+            self.values[index] = value;
+        }
+        self.dirty = true;
+        Ok(Fmi3Res::OK)
+    }
+
+    fn get_string(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &mut [CString],
+    ) -> Result<(), Fmi3Error> {
+        Err(Fmi3Error::Error)
+    }
+
+    fn set_string(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &[CString],
+    ) -> Result<(), Fmi3Error> {
+        Err(Fmi3Error::Error)
+    }
+
+    fn get_binary(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &mut [&mut [u8]],
+    ) -> Result<Vec<usize>, Fmi3Error> {
+        Err(Fmi3Error::Error)
+    }
+
+    fn set_binary(&mut self, _vrs: &[Self::ValueRef], _values: &[&[u8]]) -> Result<(), Fmi3Error> {
+        Err(Fmi3Error::Error)
+    }
+
+    fn get_clock(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &mut [binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Err(Fmi3Error::Error)
+    }
+
+    fn set_clock(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &[binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Err(Fmi3Error::Error)
+    }
+}
+
+impl Common for OdeInstance {
+    fn get_version(&self) -> &str {
+        "3.0"
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        _logging_on: bool,
+        _categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn enter_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn exit_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        _tolerance: Option<f64>,
+        start_time: f64,
+        _stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.values[0] = start_time;
+        self.dirty = true;
+        Ok(Fmi3Res::OK)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.values = self.model.start_values();
+        self.dirty = true;
+        Ok(Fmi3Res::OK)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        event_flags.reset();
+        if let Some(event_update) = self.model.event_update.clone() {
+            self.evaluate();
+            event_update(self.values[0], &mut self.values, event_flags);
+            self.dirty = true;
+        }
+        Ok(Fmi3Res::OK)
+    }
+
+    fn get_number_of_variable_dependencies(
+        &mut self,
+        _vr: Self::ValueRef,
+    ) -> Result<usize, Fmi3Error> {
+        Ok(0)
+    }
+
+    fn get_variable_dependencies(
+        &mut self,
+        _dependent: Self::ValueRef,
+    ) -> Result<Vec<VariableDependency<Self::ValueRef>>, Fmi3Error> {
+        Ok(Vec::new())
+    }
+}
+
+impl ModelExchange for OdeInstance {
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Ok(Fmi3Res::OK)
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        _no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        *enter_event_mode = false;
+        *terminate_simulation = false;
+        Ok(Fmi3Res::OK)
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
+        self.values[0] = time;
+        self.dirty = true;
+        Ok(Fmi3Res::OK)
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
+        self.check_states(states.len())?;
+        for (&(state, _), &value) in self.model.states.iter().zip(states) {
+            self.values[state as usize] = value;
+        }
+        self.dirty = true;
+        Ok(Fmi3Res::OK)
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.check_states(continuous_states.len())?;
+        for (&(state, _), value) in self.model.states.iter().zip(continuous_states) {
+            *value = self.values[state as usize];
+        }
+        Ok(Fmi3Res::OK)
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.check_states(derivatives.len())?;
+        self.evaluate();
+        for (&(_, derivative), value) in self.model.states.iter().zip(derivatives) {
+            *value = self.values[derivative as usize];
+        }
+        Ok(Fmi3Res::OK)
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.check_states(nominals.len())?;
+        nominals.fill(1.0);
+        Ok(Fmi3Res::OK)
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        if event_indicators.len() != self.model.event_indicator_vrs.len() {
+            return Err(Fmi3Error::Error);
+        }
+        self.evaluate();
+        for (&vr, value) in self.model.event_indicator_vrs.iter().zip(event_indicators) {
+            *value = self.values[vr as usize];
+        }
+        Ok(true)
+    }
+
+    fn get_number_of_event_indicators(&mut self) -> Result<usize, Fmi3Error> {
+        Ok(self.model.event_indicator_vrs.len())
+    }
+
+    fn get_number_of_continuous_states(&mut self) -> Result<usize, Fmi3Error> {
+        Ok(self.model.states.len())
+    }
+}
+
+impl FmiInstance for OdeInstance {
+    type ModelDescription = schema::Fmi3ModelDescription;
+    type ValueRef = binding::fmi3ValueReference;
+    type Status = Fmi3Status;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_version(&self) -> &str {
+        Common::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        InterfaceType::ModelExchange
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::set_debug_logging(self, logging_on, categories)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_initialization_mode(self, tolerance, start_time, stop_time)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::exit_initialization_mode(self)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::terminate(self)
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::reset(self)
+    }
+}
+
+impl FmiModelExchange for OdeInstance {
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::enter_continuous_time_mode(self)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::completed_integrator_step(
+            self,
+            no_set_fmu_state_prior,
+            enter_event_mode,
+            terminate_simulation,
+        )
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::set_time(self, time)
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_continuous_states(self, continuous_states)
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::set_continuous_states(self, states)
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_continuous_state_derivatives(self, derivatives)
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_nominals_of_continuous_states(self, nominals)
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        ModelExchange::get_event_indicators(self, event_indicators)
+    }
+}
+
+impl FmiEventHandler for OdeInstance {
+    #[inline]
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    #[inline]
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+}
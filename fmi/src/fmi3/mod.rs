@@ -1,11 +1,20 @@
 //! FMI 3.0 API
 
+#[cfg(feature = "build")]
+pub mod build;
 pub mod import;
 pub mod instance;
 pub(crate) mod logger;
-#[cfg(false)]
+#[cfg(feature = "mock")]
+pub mod mock;
+#[cfg(feature = "model")]
 pub mod model;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "trace")]
+pub mod trace;
 mod traits;
+pub mod variable;
 use std::fmt::Display;
 
 // Re-export
@@ -13,8 +22,10 @@ pub use crate::schema::fmi3 as schema;
 #[doc = "Autogenerated bindings for the FMI 3.0 API"]
 pub use fmi_sys::fmi3 as binding;
 
+pub use logger::{LogMessage, LogSinkFn, log_to_facade};
 pub use traits::{
-    CoSimulation, Common, Fmi3Model, GetSet, ModelExchange, ScheduledExecution, VariableDependency,
+    CoSimulation, Common, Fmi3Model, GetSet, ModelExchange, ScheduledExecution, Unsupported,
+    VariableDependency,
 };
 
 use crate::{Error, traits::FmiStatus};
@@ -57,6 +68,11 @@ pub enum Fmi3Error {
     Error,
     #[error("Fatal")]
     Fatal,
+    /// The call was rejected by an instance in checked mode without reaching the FMU, because the
+    /// FMI state machine does not allow it in the current state. See
+    /// [`instance::Instance::set_checked`].
+    #[error("Illegal call: {0}")]
+    IllegalCall(String),
 }
 
 #[derive(Debug)]
@@ -116,7 +132,7 @@ impl From<Fmi3Error> for Fmi3Status {
     fn from(err: Fmi3Error) -> Self {
         match err {
             Fmi3Error::Discard => Self(binding::fmi3Status_fmi3Discard),
-            Fmi3Error::Error => Self(binding::fmi3Status_fmi3Error),
+            Fmi3Error::Error | Fmi3Error::IllegalCall(_) => Self(binding::fmi3Status_fmi3Error),
             Fmi3Error::Fatal => Self(binding::fmi3Status_fmi3Fatal),
         }
     }
@@ -135,6 +151,9 @@ impl From<Fmi3Status> for Result<Fmi3Res, Fmi3Error> {
     }
 }
 
+/// Path of the optional terminals and icons description within an FMU
+pub const TERMINALS_AND_ICONS: &str = "terminalsAndIcons/terminalsAndIcons.xml";
+
 /// Get the platform folder name within an FMU for the given OS and architecture.
 ///
 /// See <https://fmi-standard.org/docs/3.0.1/#platform-tupe-definition>
@@ -1,18 +1,47 @@
-use std::str::FromStr;
-
-use itertools::Itertools;
-use slotmap::{SecondaryMap, SlotMap, new_key_type};
+//! A resolved, cross-linked view of an FMI 3.0 model description.
+//!
+//! The [`schema::Fmi3ModelDescription`] refers to units and types by name, and to variables by
+//! value reference. [`ModelDescription`] resolves these references once, into keys of the
+//! [`SlotMap`]s holding the units, type definitions and variables. A broken reference is reported
+//! as a [`ModelError`].
+//!
+//! ```rust
+//! use fmi::fmi3::{model::ModelDescription, schema::Fmi3ModelDescription};
+//!
+//! let xml = r#"<fmiModelDescription fmiVersion="3.0" modelName="Ball" instantiationToken="">
+//!     <ModelVariables>
+//!         <Float64 name="h" valueReference="1" causality="output" initial="exact" start="1"/>
+//!         <Float64 name="der(h)" valueReference="2" derivative="1"/>
+//!     </ModelVariables>
+//!     <ModelStructure>
+//!         <Output valueReference="1"/>
+//!         <ContinuousStateDerivative valueReference="2"/>
+//!     </ModelStructure>
+//! </fmiModelDescription>"#;
+//! let md: Fmi3ModelDescription = fmi::schema::deserialize(xml).unwrap();
+//! let model = ModelDescription::try_from(md).unwrap();
+//!
+//! let h = model.key_by_name("h").unwrap();
+//! let der_h = model.key_by_value_reference(2).unwrap();
+//! assert_eq!(model.model_variables[h].derivative, Some(der_h));
+//! assert_eq!(model.states().collect::<Vec<_>>(), vec![(h, der_h)]);
+//! ```
+
+use std::{collections::HashMap, str::FromStr};
+
+use slotmap::{SlotMap, new_key_type};
 use thiserror::Error;
 
-use super::{
-    DateTime,
-    schema::{self, AbstractVariableTrait, Category, Fmi3Unit, TypedArrayableariableTrait},
-};
+use super::schema::{self, Category};
+use crate::schema::{date_time::DateTime, traits::FmiVariable, units::Unit};
 
 #[derive(Debug, Error)]
 pub enum ModelError {
     #[error("Error in model description: {0}")]
     ReferenceError(String),
+
+    #[error("Invalid generationDateAndTime '{0}': {1}")]
+    DateTime(String, String),
 }
 
 new_key_type! { pub struct TypeKey; }
@@ -20,33 +49,72 @@ new_key_type! { pub struct UnitKey; }
 new_key_type! { pub struct VariableKey; }
 new_key_type! { pub struct LogCategoryKey; }
 
+/// A type definition with its unit resolved
 #[derive(Debug)]
-struct UnitDefinition<'a> {
-    // TODO
-    unit: Fmi3Unit<'a>,
+pub struct TypeDefinition {
+    pub definition: schema::TypeDefinition,
+    /// The unit of a Float32 or Float64 type
+    pub unit: Option<UnitKey>,
+}
+
+/// An alternative name of a variable
+#[derive(Debug, PartialEq)]
+pub struct Alias {
+    pub name: String,
+    pub description: Option<String>,
+    /// The display unit of an alias of a Float32 or Float64 variable
+    pub display_unit: Option<String>,
+}
+
+/// The size of one dimension of an array variable
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Fixed(u64),
+    /// The size is the value of this structural parameter or constant
+    Variable(VariableKey),
 }
 
+/// A variable with its references to types, units and other variables resolved
 #[derive(Debug)]
-pub enum TypeDefinition {
-    Float32(schema::Float32Type),
-    Float64 {
-        r#type: schema::Float64Type,
-        unit: Option<UnitKey>,
-    },
+pub struct ModelVariable {
+    pub variable: schema::Variable,
+    pub declared_type: Option<TypeKey>,
+    /// The unit of a Float32 or Float64 variable, declared on the variable or its declared type
+    pub unit: Option<UnitKey>,
+    /// The continuous state this variable is the derivative of, from its `derivative` attribute
+    pub state: Option<VariableKey>,
+    /// The derivative of this continuous state, the variable whose `state` this is
+    pub derivative: Option<VariableKey>,
+    /// The variable holding the value of this variable at the previous clock tick
+    pub previous: Option<VariableKey>,
+    /// The clocks the variable belongs to
+    pub clocks: Vec<VariableKey>,
+    pub dimensions: Vec<Dimension>,
+    pub aliases: Vec<Alias>,
 }
 
+/// A dependency of an unknown on a known variable
 #[derive(Debug, PartialEq)]
-pub enum ModelVariable {
-    Float32 {
-        var: schema::FmiFloat32,
-        declared_type: Option<TypeKey>,
-        derivative: Option<VariableKey>,
-    },
-    Float64 {
-        var: schema::FmiFloat64,
-        declared_type: Option<TypeKey>,
-        derivative: Option<VariableKey>,
-    },
+pub struct Dependency {
+    pub variable: VariableKey,
+    pub kind: Option<schema::DependenciesKind>,
+}
+
+/// An unknown of the model structure
+#[derive(Debug, PartialEq)]
+pub struct Unknown {
+    pub variable: VariableKey,
+    /// `None` if the unknown depends on all knowns
+    pub dependencies: Option<Vec<Dependency>>,
+}
+
+#[derive(Debug, Default)]
+pub struct ModelStructure {
+    pub outputs: Vec<Unknown>,
+    pub continuous_state_derivatives: Vec<Unknown>,
+    pub clocked_states: Vec<Unknown>,
+    pub initial_unknowns: Vec<Unknown>,
+    pub event_indicators: Vec<Unknown>,
 }
 
 #[derive(Debug)]
@@ -63,294 +131,633 @@ pub struct ModelDescription {
     pub instantiation_token: String,
     /// Optional string with a brief description of the model.
     pub description: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub copyright: Option<String>,
+    pub license: Option<String>,
+    pub generation_tool: Option<String>,
     /// Optional date and time when the XML file was generated.
-    pub generation_date_and_time: Option<super::DateTime>,
+    pub generation_date_and_time: Option<DateTime>,
+    pub variable_naming_convention: Option<String>,
+    pub model_exchange: Option<schema::Fmi3ModelExchange>,
+    pub co_simulation: Option<schema::Fmi3CoSimulation>,
+    pub scheduled_execution: Option<schema::Fmi3ScheduledExecution>,
+    pub default_experiment: Option<schema::DefaultExperiment>,
     /// A list of log categories that can be set to define the log information that is supported
     /// from the FMU.
     pub log_categories: SlotMap<LogCategoryKey, Category>,
     /// A global list of unit and display unit definitions
-    unit_definitions: SlotMap<UnitKey, UnitDefinition>,
+    pub units: SlotMap<UnitKey, Unit>,
     /// A global list of type definitions that are utilized in `ModelVariables`
-    type_definitions: SlotMap<TypeKey, TypeDefinition>,
+    pub type_definitions: SlotMap<TypeKey, TypeDefinition>,
+    /// The variables, in the order of the model description
     pub model_variables: SlotMap<VariableKey, ModelVariable>,
-
     pub model_structure: ModelStructure,
-}
 
-#[derive(Debug)]
-pub struct ModelStructure {
-    pub outputs: Vec<VariableKey>,
-    pub continuous_state_derivatives: Vec<VariableKey>,
-    pub derivatives: Vec<VariableKey>,
-    pub initial_unknowns: Vec<VariableKey>,
+    /// The variables by their names and the names of their aliases
+    by_name: HashMap<String, VariableKey>,
+    by_value_reference: HashMap<u32, VariableKey>,
 }
 
-fn find_unit_definition_by_name<'a>(
-    mut unit_definitions: impl Iterator<Item = (UnitKey, &'a UnitDefinition)>,
-    name: &str,
-) -> Option<UnitKey> {
-    unit_definitions.find_map(|(key, unit)| {
-        if unit.unit.name == name {
-            Some(key)
-        } else {
-            None
-        }
-    })
-}
+impl ModelDescription {
+    /// The key of the variable named `name`, or having an alias named `name`
+    pub fn key_by_name(&self, name: &str) -> Option<VariableKey> {
+        self.by_name.get(name).copied()
+    }
 
-fn find_type_definition_by_name<'a>(
-    mut type_definitions: impl Iterator<Item = (TypeKey, &'a TypeDefinition)>,
-    name: &str,
-) -> Option<TypeKey> {
-    type_definitions.find_map(|(key, ty)| match ty {
-        TypeDefinition::Float32(inner_ty) if inner_ty.base.name == name => Some(key),
-        TypeDefinition::Float64 {
-            r#type: inner_ty, ..
-        } if inner_ty.base.name == name => Some(key),
-        _ => None,
-    })
-}
+    /// The key of the variable with the value reference `vr`
+    pub fn key_by_value_reference(&self, vr: u32) -> Option<VariableKey> {
+        self.by_value_reference.get(&vr).copied()
+    }
 
-fn find_variable_by_name<'a>(
-    mut model_variables: impl Iterator<Item = (VariableKey, &'a ModelVariable)>,
-    name: &str,
-) -> Option<VariableKey> {
-    model_variables.find_map(|(key, var)| match var {
-        ModelVariable::Float32 { var, .. } if var.name() == name => Some(key),
-        ModelVariable::Float64 { var, .. } if var.name() == name => Some(key),
-        _ => None,
-    })
-}
+    /// The variable named `name`, or having an alias named `name`
+    pub fn variable_by_name(&self, name: &str) -> Option<&ModelVariable> {
+        self.key_by_name(name).map(|key| &self.model_variables[key])
+    }
 
-fn find_variable_by_vr<'a>(
-    mut model_variables: impl Iterator<Item = (VariableKey, &'a ModelVariable)>,
-    vr: u32,
-) -> Option<VariableKey> {
-    model_variables.find_map(|(key, var)| match var {
-        ModelVariable::Float32 { var, .. } if var.value_reference() == vr => Some(key),
-        ModelVariable::Float64 { var, .. } if var.value_reference() == vr => Some(key),
-        _ => None,
-    })
-}
+    /// The variable with the value reference `vr`
+    pub fn variable_by_value_reference(&self, vr: u32) -> Option<&ModelVariable> {
+        self.key_by_value_reference(vr)
+            .map(|key| &self.model_variables[key])
+    }
 
-fn build_unit_definitions<'a>(
-    unit_definitions: schema::UnitDefinitions,
-) -> Result<SlotMap<UnitKey, UnitDefinition>, ModelError> {
-    let mut map = SlotMap::with_key();
-    for unit in unit_definitions.units {
-        map.insert(UnitDefinition { unit });
+    /// The continuous states and their derivatives, in the order of the continuous state
+    /// derivatives of the model structure
+    pub fn states(&self) -> impl Iterator<Item = (VariableKey, VariableKey)> + '_ {
+        self.model_structure
+            .continuous_state_derivatives
+            .iter()
+            .filter_map(|unknown| {
+                let state = self.model_variables[unknown.variable].state?;
+                Some((state, unknown.variable))
+            })
     }
-    Ok(map)
+}
 
-    // Ok(md.unit_definitions.as_ref().map_or_else( || SlotMap::default(), |defs| { },))
+fn reference_error(message: String) -> ModelError {
+    ModelError::ReferenceError(message)
 }
 
-fn build_type_definitions<'a>(
-    type_definitions: schema::TypeDefinitions,
-    unit_definitions: &SlotMap<UnitKey, UnitDefinition>,
-) -> Result<SlotMap<TypeKey, TypeDefinition>, ModelError> {
-    let mut map = SlotMap::with_key();
-    for ty in type_definitions.float32_type {
-        map.insert(TypeDefinition::Float32(ty));
-    }
-    for ty in type_definitions.float64_type {
-        let unit = match &ty.base_attr.unit {
-            Some(unit_name) => Some(
-                find_unit_definition_by_name(unit_definitions.iter(), unit_name).ok_or(
-                    ModelError::ReferenceError(format!(
-                        "Unit '{}' not found in TypeDefinition '{}'",
-                        unit_name, ty.base.name,
-                    )),
-                )?,
-            ),
-            None => None,
-        };
+/// The elements of a [`SlotMap`], and their keys by name
+type Named<K, V> = (SlotMap<K, V>, HashMap<String, K>);
 
-        map.insert(TypeDefinition::Float64 { r#type: ty, unit });
+fn build_units(unit_definitions: Option<schema::UnitDefinitions>) -> Named<UnitKey, Unit> {
+    let mut units = SlotMap::with_key();
+    let mut by_name = HashMap::new();
+    for unit in unit_definitions.iter().flat_map(|defs| defs.units.iter()) {
+        let unit = Unit::from(unit);
+        let name = unit.name.clone();
+        by_name.insert(name, units.insert(unit));
     }
-    Ok(map)
+    (units, by_name)
 }
 
-fn build_model_variables<'a>(
-    model_variables: schema::ModelVariables,
-    type_definitions: &SlotMap<TypeKey, TypeDefinition>,
-) -> Result<SlotMap<VariableKey, ModelVariable>, ModelError> {
-    let mut ret_map = SlotMap::with_capacity_and_key(model_variables.len());
-
-    // Helper function to dereference a declared type
-    let deref_declared_type =
-        |declared_type: &str, var_name: &str| -> Result<TypeKey, ModelError> {
-            find_type_definition_by_name(type_definitions.iter(), declared_type).ok_or(
-                ModelError::ReferenceError(format!(
-                    "TypeDefinition '{}' not found in ScalarVariable '{}'",
-                    declared_type, var_name
-                )),
-            )
+fn build_type_definitions(
+    type_definitions: Option<schema::TypeDefinitions>,
+    units: &HashMap<String, UnitKey>,
+) -> Result<Named<TypeKey, TypeDefinition>, ModelError> {
+    let mut map = SlotMap::with_key();
+    let mut by_name = HashMap::new();
+    for definition in type_definitions
+        .into_iter()
+        .flat_map(|defs| defs.type_definitions)
+    {
+        let unit_name = match &definition {
+            schema::TypeDefinition::Float32(ty) => ty.unit.as_deref(),
+            schema::TypeDefinition::Float64(ty) => ty.unit.as_deref(),
+            _ => None,
         };
+        let unit = unit_name
+            .map(|unit| {
+                units.get(unit).copied().ok_or_else(|| {
+                    reference_error(format!(
+                        "Unit '{unit}' not found in TypeDefinition '{}'",
+                        definition.name()
+                    ))
+                })
+            })
+            .transpose()?;
+        let name = definition.name().to_owned();
+        by_name.insert(name, map.insert(TypeDefinition { definition, unit }));
+    }
+    Ok((map, by_name))
+}
 
-    // Helper function to find a declared variable
-    let variable_by_vr = |model_variables: &SlotMap<VariableKey, _>,
-                          variable_vr: u32,
-                          var_name: &str|
-     -> Result<VariableKey, ModelError> {
-        find_variable_by_vr(model_variables.iter(), variable_vr).ok_or(ModelError::ReferenceError(
-            format!(
-                "Variable '{}' not found in ScalarVariable '{}'",
-                variable_vr, var_name
-            ),
-        ))
+/// The aliases of `variable`
+fn aliases(variable: &schema::Variable) -> Vec<Alias> {
+    let float_aliases = |aliases: &[schema::FloatVariableAlias]| {
+        aliases
+            .iter()
+            .map(|alias| Alias {
+                name: alias.name.clone(),
+                description: alias.description.clone(),
+                display_unit: alias.display_unit.clone(),
+            })
+            .collect()
     };
+    let aliases: &[schema::VariableAlias] = match variable {
+        schema::Variable::Float32(var) => return float_aliases(&var.aliases),
+        schema::Variable::Float64(var) => return float_aliases(&var.aliases),
+        schema::Variable::Int8(var) => &var.aliases,
+        schema::Variable::UInt8(var) => &var.aliases,
+        schema::Variable::Int16(var) => &var.aliases,
+        schema::Variable::UInt16(var) => &var.aliases,
+        schema::Variable::Int32(var) => &var.aliases,
+        schema::Variable::UInt32(var) => &var.aliases,
+        schema::Variable::Int64(var) => &var.aliases,
+        schema::Variable::UInt64(var) => &var.aliases,
+        schema::Variable::Boolean(var) => &var.aliases,
+        schema::Variable::String(var) => &var.aliases,
+        schema::Variable::Binary(var) => &var.aliases,
+        schema::Variable::Enumeration(var) => &var.aliases,
+        schema::Variable::Clock(var) => &var.aliases,
+    };
+    aliases
+        .iter()
+        .map(|alias| Alias {
+            name: alias.name.clone(),
+            description: alias.description.clone(),
+            display_unit: None,
+        })
+        .collect()
+}
 
-    for var in model_variables
-        .float32
-        .into_iter()
-        .sorted_by_key(|var| var.derivative())
-    {
-        let declared_type = if let Some(type_name) = var.declared_type() {
-            Some(deref_declared_type(type_name, var.name())?)
-        } else {
-            None
-        };
+/// The variables and their keys by name and value reference
+struct Variables {
+    map: SlotMap<VariableKey, ModelVariable>,
+    by_name: HashMap<String, VariableKey>,
+    by_value_reference: HashMap<u32, VariableKey>,
+}
 
-        let derivative = if let Some(derivative) = var.derivative() {
-            Some(variable_by_vr(&ret_map, derivative, var.name())?)
-        } else {
-            None
+/// Insert the variables with their names and value references, and resolve the references to
+/// other variables in a second pass
+fn build_model_variables(
+    model_variables: schema::ModelVariables,
+    type_definitions: &HashMap<String, TypeKey>,
+    type_units: &SlotMap<TypeKey, TypeDefinition>,
+    units: &HashMap<String, UnitKey>,
+) -> Result<Variables, ModelError> {
+    let mut map = SlotMap::with_capacity_and_key(model_variables.variables.len());
+    let mut by_name = HashMap::new();
+    let mut by_value_reference = HashMap::new();
+
+    for variable in model_variables.variables {
+        let name = variable.name().to_owned();
+        let declared_type = variable
+            .declared_type()
+            .map(|declared_type| {
+                type_definitions.get(declared_type).copied().ok_or_else(|| {
+                    reference_error(format!(
+                        "TypeDefinition '{declared_type}' not found in Variable '{name}'"
+                    ))
+                })
+            })
+            .transpose()?;
+        let unit = match variable.unit() {
+            Some(unit) => Some(units.get(unit).copied().ok_or_else(|| {
+                reference_error(format!("Unit '{unit}' not found in Variable '{name}'"))
+            })?),
+            None => declared_type.and_then(|ty| type_units[ty].unit),
         };
+        let aliases = aliases(&variable);
+        let value_reference = variable.value_reference();
 
-        ret_map.insert(ModelVariable::Float32 {
-            var,
+        let key = map.insert(ModelVariable {
+            variable,
             declared_type,
-            derivative,
+            unit,
+            state: None,
+            derivative: None,
+            previous: None,
+            clocks: Vec::new(),
+            dimensions: Vec::new(),
+            aliases,
         });
-    }
 
-    for var in model_variables
-        .float64
-        .into_iter()
-        .sorted_by_key(|var| var.derivative())
-    {
-        let x = var
-            .declared_type()
-            .map(|x| deref_declared_type(x, var.name()));
+        if let Some(other) = by_value_reference.insert(value_reference, key) {
+            let other: &ModelVariable = &map[other];
+            return Err(reference_error(format!(
+                "Value reference {value_reference} of Variable '{name}' is also used by Variable \
+                 '{}'",
+                other.variable.name()
+            )));
+        }
+        for name in std::iter::once(name).chain(map[key].aliases.iter().map(|a| a.name.clone())) {
+            if by_name.insert(name.clone(), key).is_some() {
+                return Err(reference_error(format!(
+                    "Variable name '{name}' is not unique"
+                )));
+            }
+        }
+    }
 
-        let declared_type = if let Some(type_name) = var.declared_type() {
-            Some(deref_declared_type(type_name, var.name())?)
-        } else {
-            None
+    let keys = map.keys().collect::<Vec<_>>();
+    for key in keys {
+        let variable = &map[key].variable;
+        let name = variable.name();
+        let lookup = |vr: u32, attribute: &str| {
+            by_value_reference.get(&vr).copied().ok_or_else(|| {
+                reference_error(format!(
+                    "Variable with valueReference {vr} not found in {attribute} of Variable \
+                     '{name}'"
+                ))
+            })
         };
 
-        let derivative = if let Some(derivative) = var.derivative() {
-            Some(variable_by_vr(&ret_map, derivative, var.name())?)
-        } else {
-            None
-        };
+        let state = match variable {
+            schema::Variable::Float32(var) => var.derivative(),
+            schema::Variable::Float64(var) => var.derivative(),
+            _ => None,
+        }
+        .map(|vr| lookup(vr, "derivative"))
+        .transpose()?;
+        match state.map(|state| &map[state].variable) {
+            Some(schema::Variable::Float32(_) | schema::Variable::Float64(_)) | None => {}
+            Some(other) => {
+                return Err(reference_error(format!(
+                    "The derivative of Variable '{name}' refers to the non-float Variable '{}'",
+                    other.name()
+                )));
+            }
+        }
 
-        ret_map.insert(ModelVariable::Float64 {
-            var,
-            declared_type,
-            derivative,
-        });
+        let arrayable = variable.as_arrayable();
+        let previous = arrayable
+            .and_then(|var| var.previous())
+            .map(|vr| lookup(vr, "previous"))
+            .transpose()?;
+        let dimensions = arrayable
+            .map(|var| var.dimensions())
+            .unwrap_or_default()
+            .iter()
+            .map(|dimension| match dimension {
+                schema::Dimension::Fixed(size) => Ok(Dimension::Fixed(*size)),
+                schema::Dimension::Variable(vr) => {
+                    lookup(*vr, "Dimension").map(Dimension::Variable)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let clocks = variable
+            .as_abstract()
+            .clocks()
+            .unwrap_or_default()
+            .iter()
+            .map(|&vr| {
+                let clock = lookup(vr, "clocks")?;
+                match map[clock].variable {
+                    schema::Variable::Clock(_) => Ok(clock),
+                    _ => Err(reference_error(format!(
+                        "The clocks of Variable '{name}' refer to the non-clock Variable '{}'",
+                        map[clock].variable.name()
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let variable = &mut map[key];
+        variable.state = state;
+        variable.previous = previous;
+        variable.dimensions = dimensions;
+        variable.clocks = clocks;
+        if let Some(state) = state {
+            map[state].derivative = Some(key);
+        }
     }
 
-    Ok(ret_map)
+    Ok(Variables {
+        map,
+        by_name,
+        by_value_reference,
+    })
 }
 
 fn build_model_structure(
     model_structure: schema::ModelStructure,
-    model_variables: &SlotMap<VariableKey, ModelVariable>,
+    by_value_reference: &HashMap<u32, VariableKey>,
 ) -> Result<ModelStructure, ModelError> {
-    let mut outputs = Vec::new();
-    let mut continuous_state_derivatives = Vec::new();
-    let mut derivatives = Vec::new();
-    let mut initial_unknowns = Vec::new();
-
-    for var in model_structure.continuous_state_derivative {
-        continuous_state_derivatives.push(
-            find_variable_by_vr(model_variables.iter(), var.value_reference).ok_or(
-                ModelError::ReferenceError(format!(
-                    "Variable '{}' not found in ModelStructure",
-                    var.value_reference
-                )),
-            )?,
-        );
-    }
-
-    // for var in &md.model_structure.output {
-    // outputs.push(find_variable_by_name(model_variables.iter(), var).ok_or(
-    // ModelError::ReferenceError(format!(
-    // "Variable '{}' not found in ModelStructure",
-    // var
-    // )),
-    // )?);
-    // }
-    //
-    // for var in &md.model_structure.derivative {
-    // derivatives.push(find_variable_by_name(model_variables.iter(), var).ok_or(
-    // ModelError::ReferenceError(format!(
-    // "Variable '{}' not found in ModelStructure",
-    // var
-    // )),
-    // )?);
-    // }
-    //
-    // for var in &md.model_structure.initial_unknown {
-    // initial_unknowns.push(find_variable_by_name(model_variables.iter(), var).ok_or(
-    // ModelError::ReferenceError(format!(
-    // "Variable '{}' not found in ModelStructure",
-    // var
-    // )),
-    // )?);
-    // }
-
-    Ok(ModelStructure {
-        outputs,
-        continuous_state_derivatives,
-        derivatives,
-        initial_unknowns,
-    })
-}
-
-impl TryFrom<schema::FmiModelDescription> for ModelDescription {
-    type Error = ModelError;
+    let lookup = |vr: u32, element: &str| {
+        by_value_reference.get(&vr).copied().ok_or_else(|| {
+            reference_error(format!(
+                "Variable with valueReference {vr} not found in ModelStructure/{element}"
+            ))
+        })
+    };
 
-    fn try_from(md: schema::FmiModelDescription) -> Result<Self, ModelError> {
-        let generation_date_and_time = if let Some(s) = md.generation_date_and_time {
-            Some(DateTime::from_str(&s).expect("todo"))
-        } else {
-            None
+    let mut structure = ModelStructure::default();
+    for dependency in model_structure.unknowns {
+        let (element, unknowns, unknown) = match dependency {
+            schema::VariableDependency::Output(unknown) => {
+                ("Output", &mut structure.outputs, unknown)
+            }
+            schema::VariableDependency::ContinuousStateDerivative(unknown) => (
+                "ContinuousStateDerivative",
+                &mut structure.continuous_state_derivatives,
+                unknown,
+            ),
+            schema::VariableDependency::ClockedState(unknown) => {
+                ("ClockedState", &mut structure.clocked_states, unknown)
+            }
+            schema::VariableDependency::InitialUnknown(unknown) => {
+                ("InitialUnknown", &mut structure.initial_unknowns, unknown)
+            }
+            schema::VariableDependency::EventIndicator(unknown) => {
+                ("EventIndicator", &mut structure.event_indicators, unknown)
+            }
         };
 
-        let log_categories = md.log_categories.map_or(Ok(Default::default()), |cats| {
-            let mut map = SlotMap::with_key();
-            for c in cats.categories {
-                map.insert(c);
-            }
-            Ok(map)
-        })?;
+        let variable = lookup(unknown.value_reference, element)?;
+        let dependencies = unknown
+            .dependencies
+            .map(|dependencies| {
+                let kinds = unknown.dependencies_kind.map(|kinds| kinds.0);
+                if kinds
+                    .as_ref()
+                    .is_some_and(|kinds| kinds.len() != dependencies.0.len())
+                {
+                    return Err(reference_error(format!(
+                        "The dependencies and dependenciesKind of ModelStructure/{element} with \
+                         valueReference {} differ in length",
+                        unknown.value_reference
+                    )));
+                }
+                let mut kinds = kinds.into_iter().flatten();
+                dependencies
+                    .0
+                    .into_iter()
+                    .map(|vr| {
+                        Ok(Dependency {
+                            variable: lookup(vr, element)?,
+                            kind: kinds.next(),
+                        })
+                    })
+                    .collect()
+            })
+            .transpose()?;
+        unknowns.push(Unknown {
+            variable,
+            dependencies,
+        });
+    }
+    Ok(structure)
+}
 
-        let unit_definitions = md
-            .unit_definitions
-            .map_or(Ok(Default::default()), |defs| build_unit_definitions(defs))?;
+impl TryFrom<schema::Fmi3ModelDescription> for ModelDescription {
+    type Error = ModelError;
 
-        let type_definitions = md.type_definitions.map_or(Ok(Default::default()), |defs| {
-            build_type_definitions(defs, &unit_definitions)
-        })?;
+    fn try_from(md: schema::Fmi3ModelDescription) -> Result<Self, ModelError> {
+        let generation_date_and_time = md
+            .generation_date_and_time
+            .map(|s| DateTime::from_str(&s).map_err(|e| ModelError::DateTime(s, e.to_string())))
+            .transpose()?;
+
+        let mut log_categories = SlotMap::with_key();
+        for category in md
+            .log_categories
+            .into_iter()
+            .flat_map(|cats| cats.categories)
+        {
+            log_categories.insert(category);
+        }
 
-        let model_variables = build_model_variables(md.model_variables, &type_definitions)?;
-        let model_structure = build_model_structure(md.model_structure, &model_variables)?;
+        let (units, units_by_name) = build_units(md.unit_definitions);
+        let (type_definitions, types_by_name) =
+            build_type_definitions(md.type_definitions, &units_by_name)?;
+        let variables = build_model_variables(
+            md.model_variables,
+            &types_by_name,
+            &type_definitions,
+            &units_by_name,
+        )?;
+        let model_structure =
+            build_model_structure(md.model_structure, &variables.by_value_reference)?;
 
         Ok(Self {
             fmi_version: md.fmi_version,
             model_name: md.model_name,
             instantiation_token: md.instantiation_token,
             description: md.description,
+            author: md.author,
+            version: md.version,
+            copyright: md.copyright,
+            license: md.license,
+            generation_tool: md.generation_tool,
             generation_date_and_time,
+            variable_naming_convention: md.variable_naming_convention,
+            model_exchange: md.model_exchange,
+            co_simulation: md.co_simulation,
+            scheduled_execution: md.scheduled_execution,
+            default_experiment: md.default_experiment,
             log_categories,
-            unit_definitions,
+            units,
             type_definitions,
-            model_variables,
+            model_variables: variables.map,
             model_structure,
+            by_name: variables.by_name,
+            by_value_reference: variables.by_value_reference,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(xml: &str) -> Result<ModelDescription, ModelError> {
+        let md: schema::Fmi3ModelDescription = crate::schema::deserialize(xml).unwrap();
+        ModelDescription::try_from(md)
+    }
+
+    #[test]
+    fn test_model() {
+        let model = model(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="{}"
+    generationDateAndTime="2024-01-01T12:00:00Z">
+    <UnitDefinitions>
+        <Unit name="m"/>
+    </UnitDefinitions>
+    <TypeDefinitions>
+        <Float64Type name="Position" unit="m"/>
+    </TypeDefinitions>
+    <LogCategories>
+        <Category name="logEvents"/>
+    </LogCategories>
+    <ModelVariables>
+        <Float64 name="x" valueReference="1" causality="output" declaredType="Position" initial="exact" start="0">
+            <Alias name="position" displayUnit="m"/>
+        </Float64>
+        <Float64 name="der(x)" valueReference="2" derivative="1"/>
+        <UInt64 name="n" valueReference="3" causality="structuralParameter" variability="fixed" start="2"/>
+        <Float64 name="y" valueReference="4" causality="output" start="0 0">
+            <Dimension valueReference="3"/>
+        </Float64>
+        <Clock name="tick" valueReference="5" causality="input" intervalVariability="triggered"/>
+        <Int32 name="count" valueReference="6" causality="output" clocks="5" previous="7"/>
+        <Int32 name="pre(count)" valueReference="7"/>
+    </ModelVariables>
+    <ModelStructure>
+        <Output valueReference="1"/>
+        <Output valueReference="4" dependencies="1 3" dependenciesKind="constant dependent"/>
+        <Output valueReference="6" dependencies=""/>
+        <ContinuousStateDerivative valueReference="2"/>
+        <ClockedState valueReference="6"/>
+    </ModelStructure>
+</fmiModelDescription>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            model.generation_date_and_time.as_ref().unwrap().to_string(),
+            "2024-01-01T12:00:00+00:00"
+        );
+        assert_eq!(model.log_categories.len(), 1);
+
+        let x = model.key_by_name("x").unwrap();
+        let der_x = model.key_by_name("der(x)").unwrap();
+        let n = model.key_by_value_reference(3).unwrap();
+        let y = model.key_by_name("y").unwrap();
+        let tick = model.key_by_name("tick").unwrap();
+        let count = model.key_by_name("count").unwrap();
+        let pre_count = model.key_by_name("pre(count)").unwrap();
+
+        // Declared type and unit
+        let var = &model.model_variables[x];
+        let position = var.declared_type.unwrap();
+        assert_eq!(
+            model.type_definitions[position].definition.name(),
+            "Position"
+        );
+        assert_eq!(model.units[var.unit.unwrap()].name, "m");
+        assert_eq!(model.type_definitions[position].unit, var.unit);
+
+        // Aliases are found by name
+        assert_eq!(model.key_by_name("position"), Some(x));
+        assert_eq!(
+            var.aliases,
+            vec![Alias {
+                name: "position".to_owned(),
+                description: None,
+                display_unit: Some("m".to_owned()),
+            }]
+        );
+
+        // State and derivative link both ways
+        assert_eq!(model.model_variables[der_x].state, Some(x));
+        assert_eq!(var.derivative, Some(der_x));
+        assert_eq!(model.states().collect::<Vec<_>>(), vec![(x, der_x)]);
+
+        assert_eq!(
+            model.model_variables[y].dimensions,
+            vec![Dimension::Variable(n)]
+        );
+        assert_eq!(model.model_variables[count].clocks, vec![tick]);
+        assert_eq!(model.model_variables[count].previous, Some(pre_count));
+
+        let structure = &model.model_structure;
+        assert_eq!(
+            structure.outputs,
+            vec![
+                Unknown {
+                    variable: x,
+                    dependencies: None,
+                },
+                Unknown {
+                    variable: y,
+                    dependencies: Some(vec![
+                        Dependency {
+                            variable: x,
+                            kind: Some(schema::DependenciesKind::Constant),
+                        },
+                        Dependency {
+                            variable: n,
+                            kind: Some(schema::DependenciesKind::Dependent),
+                        },
+                    ]),
+                },
+                Unknown {
+                    variable: count,
+                    dependencies: Some(vec![]),
+                },
+            ]
+        );
+        assert_eq!(structure.clocked_states[0].variable, count);
+        assert!(structure.initial_unknowns.is_empty());
+    }
+
+    #[test]
+    fn test_broken_references() {
+        let wrap = |variables: &str, structure: &str| {
+            format!(
+                r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken="{{}}">
+    <ModelVariables>{variables}</ModelVariables>
+    <ModelStructure>{structure}</ModelStructure>
+</fmiModelDescription>"#
+            )
+        };
+        let error = |variables: &str, structure: &str| {
+            model(&wrap(variables, structure)).unwrap_err().to_string()
+        };
+
+        assert_eq!(
+            error(r#"<Float64 name="x" valueReference="1" unit="m"/>"#, ""),
+            "Error in model description: Unit 'm' not found in Variable 'x'"
+        );
+        assert_eq!(
+            error(
+                r#"<Float64 name="x" valueReference="1" declaredType="T"/>"#,
+                ""
+            ),
+            "Error in model description: TypeDefinition 'T' not found in Variable 'x'"
+        );
+        assert_eq!(
+            error(
+                r#"<Float64 name="der(x)" valueReference="1" derivative="2"/>"#,
+                ""
+            ),
+            "Error in model description: Variable with valueReference 2 not found in derivative \
+             of Variable 'der(x)'"
+        );
+        assert_eq!(
+            error(
+                r#"<Float64 name="x" valueReference="1"/><Int32 name="y" valueReference="1"/>"#,
+                ""
+            ),
+            "Error in model description: Value reference 1 of Variable 'y' is also used by \
+             Variable 'x'"
+        );
+        assert_eq!(
+            error(
+                r#"<Int32 name="x" valueReference="1" clocks="2"/><Int32 name="y" valueReference="2"/>"#,
+                ""
+            ),
+            "Error in model description: The clocks of Variable 'x' refer to the non-clock \
+             Variable 'y'"
+        );
+        assert_eq!(
+            error(
+                r#"<Float64 name="x" valueReference="1"/>"#,
+                r#"<Output valueReference="1" dependencies="3"/>"#
+            ),
+            "Error in model description: Variable with valueReference 3 not found in \
+             ModelStructure/Output"
+        );
+        assert_eq!(
+            error(
+                r#"<Float64 name="x" valueReference="1"/>"#,
+                r#"<Output valueReference="1" dependencies="1" dependenciesKind="fixed fixed"/>"#
+            ),
+            "Error in model description: The dependencies and dependenciesKind of \
+             ModelStructure/Output with valueReference 1 differ in length"
+        );
+
+        let md: schema::Fmi3ModelDescription = crate::schema::deserialize(
+            r#"<fmiModelDescription fmiVersion="3.0" modelName="Model" instantiationToken=""
+                generationDateAndTime="yesterday"><ModelVariables/><ModelStructure/></fmiModelDescription>"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            ModelDescription::try_from(md),
+            Err(ModelError::DateTime(s, _)) if s == "yesterday"
+        ));
+    }
+}
@@ -0,0 +1,1122 @@
+//! Out-of-process FMI 3.0 instances.
+//!
+//! A [`RemoteInstance`] runs the FMU in a separate `fmi-worker` process and forwards every call to
+//! it over the stdin/stdout pipes of the worker. A crash, `abort()` or hang of the FMU then only
+//! takes down the worker: the parent sees it as an [`Error::RemoteWorker`] or
+//! [`Error::RemoteTimeout`].
+//!
+//! `RemoteInstance` implements the same traits as [`Instance`](super::instance::Instance), so
+//! code written against [`Common`], [`ModelExchange`], [`CoSimulation`] or the generic
+//! [`FmiInstance`] traits works with either backend. Since the trait methods can only report an
+//! [`Fmi3Error`], they report a failed worker as [`Fmi3Error::Fatal`], and the underlying error is
+//! available from [`RemoteInstance::take_error`].
+//!
+//! A [`RemoteImport`] implements [`Fmi3Model`] by creating `RemoteInstance`s, so that code generic
+//! over the model, such as a simulation loop, can run the FMU out of process.
+//!
+//! Scheduled Execution, FMU states and intermediate update callbacks are not supported.
+//!
+//! ```rust,no_run
+//! use fmi::{fmi3::{import::Fmi3Import, remote::{RemoteInstance, RemoteOptions}}, import, ME};
+//! use fmi::traits::FmiInstance;
+//!
+//! let import: Fmi3Import = import::from_path("path/to/model.fmu").unwrap();
+//! let options = RemoteOptions {
+//!     timeout: Some(std::time::Duration::from_secs(10)),
+//!     ..Default::default()
+//! };
+//! let mut inst = RemoteInstance::<ME>::new(&import, "inst1", false, true, &options).unwrap();
+//! assert_eq!(inst.get_version(), "3.0");
+//! ```
+
+use std::{
+    ffi::CString,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    CS, Error, EventFlags, InterfaceType, ME,
+    fmi3::{
+        CoSimulation, Common, Fmi3Error, Fmi3Model, Fmi3Res, Fmi3Status, GetSet, LogMessage,
+        LogSinkFn, ModelExchange, Unsupported, VariableDependency, binding, import::Fmi3Import,
+        logger, schema,
+    },
+    traits::{FmiEventHandler, FmiImport, FmiInstance, FmiModelExchange, FmiStatus, InstanceTag},
+};
+
+use protocol::{
+    PROTOCOL_VERSION, Payload, Request, Response, ValueKind, Values, read_message, write_message,
+};
+
+mod protocol;
+mod worker;
+
+pub use worker::serve;
+
+/// Environment variable overriding the default path of the worker binary
+pub const WORKER_ENV: &str = "FMI_WORKER";
+
+/// How long a dropped instance waits for its worker to exit before killing it
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+/// Options for starting the worker process of a [`RemoteInstance`]
+#[derive(Debug, Clone)]
+pub struct RemoteOptions {
+    /// Path of the `fmi-worker` binary
+    ///
+    /// Defaults to the value of the `FMI_WORKER` environment variable, then to `fmi-worker` next
+    /// to the current executable, and finally to `fmi-worker` on the `PATH`.
+    pub worker: PathBuf,
+    /// How long to wait for the reply to a single call before killing the worker
+    ///
+    /// `None` waits indefinitely.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for RemoteOptions {
+    fn default() -> Self {
+        Self {
+            worker: default_worker_path(),
+            timeout: None,
+        }
+    }
+}
+
+fn default_worker_path() -> PathBuf {
+    if let Some(path) = std::env::var_os(WORKER_ENV) {
+        return path.into();
+    }
+    let file_name = format!("fmi-worker{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&file_name)))
+        .filter(|path| path.is_file())
+        .unwrap_or_else(|| file_name.into())
+}
+
+/// The worker process and the pipes to it
+struct WorkerProcess {
+    child: Child,
+    /// Requests to the worker, closed to make it exit
+    requests: Option<BufWriter<ChildStdin>>,
+    /// Responses read from the worker by a background thread, so that they can be awaited with a
+    /// timeout
+    responses: Receiver<std::io::Result<Response>>,
+    timeout: Option<Duration>,
+    /// Set once the worker crashed, timed out or broke the protocol
+    failed: bool,
+}
+
+impl WorkerProcess {
+    fn spawn(options: &RemoteOptions) -> Result<Self, Error> {
+        log::debug!("Starting FMU worker {:?}", options.worker);
+        let mut child = Command::new(&options.worker)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()
+            .map_err(|e| Error::RemoteWorker(format!("Cannot start {:?}: {e}", options.worker)))?;
+
+        let requests = child.stdin.take().map(BufWriter::new);
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let (sender, responses) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("fmi-worker-reader".to_owned())
+            .spawn(move || {
+                // Stops at the end of the output or the first invalid response
+                while let Some(response) = read_message::<Response>(&mut stdout).transpose() {
+                    let invalid = response.is_err();
+                    if sender.send(response).is_err() || invalid {
+                        break;
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            child,
+            requests,
+            responses,
+            timeout: options.timeout,
+            failed: false,
+        })
+    }
+
+    /// Mark the worker as failed and return the error describing it.
+    fn fail(&mut self, error: Error) -> Error {
+        self.failed = true;
+        if let Err(e) = self.child.kill() {
+            log::trace!("Killing FMU worker: {e}");
+        }
+        error
+    }
+
+    fn send(&mut self, request: &Request) -> Result<(), Error> {
+        if self.failed {
+            return Err(Error::RemoteWorker(
+                "Worker is no longer running".to_owned(),
+            ));
+        }
+        let requests = self.requests.as_mut().expect("open until dropped");
+        match write_message(requests, request).and_then(|_| requests.flush()) {
+            Ok(()) => Ok(()),
+            // A closed pipe means the worker has exited, which is reported by `receive`
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+            Err(e) => Err(self.fail(e.into())),
+        }
+    }
+
+    fn receive(&mut self) -> Result<Response, Error> {
+        let received = match self.timeout {
+            Some(timeout) => self.responses.recv_timeout(timeout),
+            None => self.responses.recv().map_err(RecvTimeoutError::from),
+        };
+        match received {
+            Ok(Ok(response)) => Ok(response),
+            Ok(Err(e)) => Err(self.fail(Error::RemoteWorker(format!("Invalid response: {e}")))),
+            Err(RecvTimeoutError::Timeout) => {
+                let timeout = self.timeout.unwrap_or_default();
+                Err(self.fail(Error::RemoteTimeout(timeout)))
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                self.failed = true;
+                let status = self
+                    .child
+                    .wait()
+                    .map(|status| status.to_string())
+                    .unwrap_or_else(|e| e.to_string());
+                Err(Error::RemoteWorker(format!(
+                    "Worker exited unexpectedly ({status})"
+                )))
+            }
+        }
+    }
+}
+
+impl Drop for WorkerProcess {
+    fn drop(&mut self) {
+        // Closing the requests makes the worker free the instance and exit
+        drop(self.requests.take());
+        let deadline = Instant::now() + SHUTDOWN_GRACE;
+        while Instant::now() < deadline {
+            match self.child.try_wait() {
+                Ok(Some(_)) => return,
+                Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+                Err(_) => break,
+            }
+        }
+        log::warn!("FMU worker did not exit, killing it");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// An FMI 3.0 instance running in a separate worker process.
+///
+/// See the [module documentation](self).
+pub struct RemoteInstance<Tag> {
+    worker: WorkerProcess,
+    /// Instance name
+    name: String,
+    /// FMI version reported by the FMU at instantiation
+    version: String,
+    log_sink: Option<LogSinkFn>,
+    /// The cause of the last failure reported by a trait method as an [`Fmi3Error`]
+    error: Option<Error>,
+    _tag: std::marker::PhantomData<Tag>,
+}
+
+impl<Tag> std::fmt::Debug for RemoteInstance<Tag> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteInstance")
+            .field("name", &self.name)
+            .field("worker", &self.worker.child.id())
+            .field("failed", &self.worker.failed)
+            .finish()
+    }
+}
+
+impl RemoteInstance<ME> {
+    /// Start a worker process and create a Model Exchange instance of `import` in it.
+    ///
+    /// The worker loads the FMU from the extracted directory of `import`, which must outlive the
+    /// instance.
+    pub fn new(
+        import: &Fmi3Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        options: &RemoteOptions,
+    ) -> Result<Self, Error> {
+        if import.model_description().model_exchange.is_none() {
+            return Err(Error::UnsupportedFmuType("ModelExchange".to_owned()));
+        }
+        Self::spawn(
+            instance_name,
+            Request::InstantiateModelExchange {
+                fmu_dir: fmu_dir(import)?,
+                instance_name: instance_name.to_owned(),
+                visible,
+                logging_on,
+            },
+            options,
+        )
+    }
+}
+
+impl RemoteInstance<CS> {
+    /// Start a worker process and create a Co-Simulation instance of `import` in it.
+    ///
+    /// The worker loads the FMU from the extracted directory of `import`, which must outlive the
+    /// instance.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        import: &Fmi3Import,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        event_mode_used: bool,
+        early_return_allowed: bool,
+        required_intermediate_variables: &[binding::fmi3ValueReference],
+        options: &RemoteOptions,
+    ) -> Result<Self, Error> {
+        if import.model_description().co_simulation.is_none() {
+            return Err(Error::UnsupportedFmuType("CoSimulation".to_owned()));
+        }
+        Self::spawn(
+            instance_name,
+            Request::InstantiateCoSimulation {
+                fmu_dir: fmu_dir(import)?,
+                instance_name: instance_name.to_owned(),
+                visible,
+                logging_on,
+                event_mode_used,
+                early_return_allowed,
+                required_intermediate_variables: required_intermediate_variables.to_vec(),
+            },
+            options,
+        )
+    }
+}
+
+/// An FMU whose instances each run in their own worker process, see the
+/// [module documentation](self).
+#[derive(Debug)]
+pub struct RemoteImport {
+    import: Fmi3Import,
+    options: RemoteOptions,
+}
+
+impl RemoteImport {
+    /// Create instances of `import` in worker processes started with `options`.
+    pub fn new(import: Fmi3Import, options: RemoteOptions) -> Self {
+        Self { import, options }
+    }
+
+    /// The import the workers load the FMU from
+    pub fn import(&self) -> &Fmi3Import {
+        &self.import
+    }
+
+    /// The model description of the FMU.
+    pub fn model_description(&self) -> &schema::Fmi3ModelDescription {
+        self.import.model_description()
+    }
+}
+
+impl Fmi3Model for RemoteImport {
+    type InstanceME = RemoteInstance<ME>;
+    type InstanceCS = RemoteInstance<CS>;
+    type InstanceSE = Unsupported;
+
+    fn instantiate_me(
+        &self,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+    ) -> Result<Self::InstanceME, Error> {
+        RemoteInstance::<ME>::new(
+            &self.import,
+            instance_name,
+            visible,
+            logging_on,
+            &self.options,
+        )
+    }
+
+    fn instantiate_cs(
+        &self,
+        instance_name: &str,
+        visible: bool,
+        logging_on: bool,
+        event_mode_used: bool,
+        early_return_allowed: bool,
+        required_intermediate_variables: &[binding::fmi3ValueReference],
+    ) -> Result<Self::InstanceCS, Error> {
+        RemoteInstance::<CS>::new(
+            &self.import,
+            instance_name,
+            visible,
+            logging_on,
+            event_mode_used,
+            early_return_allowed,
+            required_intermediate_variables,
+            &self.options,
+        )
+    }
+}
+
+/// The absolute path of the extracted FMU, as sent to the worker
+fn fmu_dir(import: &Fmi3Import) -> Result<String, Error> {
+    let path = std::path::absolute(import.archive_path())?;
+    path.into_os_string()
+        .into_string()
+        .map_err(|path| Error::RemoteWorker(format!("FMU path {path:?} is not valid UTF-8")))
+}
+
+impl<Tag> RemoteInstance<Tag> {
+    fn spawn(
+        instance_name: &str,
+        instantiate: Request,
+        options: &RemoteOptions,
+    ) -> Result<Self, Error> {
+        let mut instance = Self {
+            worker: WorkerProcess::spawn(options)?,
+            name: instance_name.to_owned(),
+            version: String::new(),
+            log_sink: None,
+            error: None,
+            _tag: std::marker::PhantomData,
+        };
+        instance.request(&Request::Handshake {
+            protocol: PROTOCOL_VERSION,
+        })?;
+        instance.request(&instantiate)?;
+        match instance.request(&Request::GetVersion)? {
+            (_, Payload::Version { version }) => instance.version = version,
+            _ => return Err(instance.worker.fail(unexpected_response())),
+        }
+        Ok(instance)
+    }
+
+    /// Route the log messages of this instance to `sink` instead of the `log` facade.
+    ///
+    /// Messages are forwarded from the worker along with the reply to the call that logged them.
+    pub fn set_log_sink<F>(&mut self, sink: F)
+    where
+        F: Fn(&LogMessage<'_>) + Send + Sync + 'static,
+    {
+        self.log_sink = Some(Box::new(sink));
+    }
+
+    /// Route the log messages of this instance back to the `log` facade.
+    pub fn reset_log_sink(&mut self) {
+        self.log_sink = None;
+    }
+
+    /// Take the error behind the last [`Fmi3Error`] returned by a trait method that did not come
+    /// from the FMU itself, such as a crash or timeout of the worker.
+    pub fn take_error(&mut self) -> Option<Error> {
+        self.error.take()
+    }
+
+    /// Whether the worker process is still serving the instance
+    pub fn is_running(&self) -> bool {
+        !self.worker.failed
+    }
+
+    /// Process id of the worker process
+    pub fn worker_id(&self) -> u32 {
+        self.worker.child.id()
+    }
+
+    /// Send `request` to the worker and wait for its reply, forwarding messages logged meanwhile.
+    fn request(&mut self, request: &Request) -> Result<(Fmi3Res, Payload), Error> {
+        self.worker.send(request)?;
+        loop {
+            match self.worker.receive()? {
+                Response::Log {
+                    status,
+                    category,
+                    message,
+                } => self.log(status, &category, &message),
+                Response::Done { status, payload } => {
+                    let status = match status {
+                        binding::fmi3Status_fmi3OK..=binding::fmi3Status_fmi3Fatal => {
+                            Fmi3Status::from(status)
+                        }
+                        _ => return Err(self.worker.fail(unexpected_response())),
+                    };
+                    return status.ok().map(|res| (res, payload)).map_err(Error::from);
+                }
+                Response::Failed { message } => return Err(Error::RemoteWorker(message)),
+            }
+        }
+    }
+
+    /// [`Self::request`] for the trait methods, which can only report an [`Fmi3Error`].
+    fn call(&mut self, request: &Request) -> Result<(Fmi3Res, Payload), Fmi3Error> {
+        self.request(request).map_err(|err| self.report(err))
+    }
+
+    /// Convert `err` to the [`Fmi3Error`] reported by the trait methods, keeping it for
+    /// [`Self::take_error`].
+    fn report(&mut self, err: Error) -> Fmi3Error {
+        match err {
+            Error::Fmi3Error(err) => err,
+            err => {
+                log::error!("Remote instance '{}': {err}", self.name);
+                self.error = Some(err);
+                if self.worker.failed {
+                    Fmi3Error::Fatal
+                } else {
+                    Fmi3Error::Error
+                }
+            }
+        }
+    }
+
+    /// Report a reply that doesn't match the request.
+    fn unexpected<T>(&mut self) -> Result<T, Fmi3Error> {
+        let err = self.worker.fail(unexpected_response());
+        Err(self.report(err))
+    }
+
+    fn log(&self, status: binding::fmi3Status, category: &str, message: &str) {
+        let status = Fmi3Status::from(status);
+        match &self.log_sink {
+            Some(sink) => sink(&LogMessage {
+                instance_name: &self.name,
+                status,
+                category,
+                message,
+            }),
+            None => logger::log_to_facade(&status, category, message),
+        }
+    }
+
+    fn call_empty(&mut self, request: &Request) -> Result<Fmi3Res, Fmi3Error> {
+        self.call(request).map(|(res, _)| res)
+    }
+
+    fn call_count(&mut self, request: &Request) -> Result<usize, Fmi3Error> {
+        match self.call(request)? {
+            (_, Payload::Count { count }) => Ok(count),
+            _ => self.unexpected(),
+        }
+    }
+
+    /// Call a function that returns `len` `f64` values into `values`.
+    fn call_float64s(
+        &mut self,
+        request: &Request,
+        values: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match self.call(request)? {
+            (
+                res,
+                Payload::Values {
+                    values: Values::Float64 { values: received },
+                },
+            ) if received.len() == values.len() => {
+                values.copy_from_slice(&received);
+                Ok(res)
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn derivative(&mut self, request: &Request, sensitivity: &mut [f64]) -> Result<Fmi3Res, Error> {
+        match self.request(request)? {
+            (
+                res,
+                Payload::Values {
+                    values: Values::Float64 { values },
+                },
+            ) if values.len() == sensitivity.len() => {
+                sensitivity.copy_from_slice(&values);
+                Ok(res)
+            }
+            _ => Err(self.worker.fail(unexpected_response())),
+        }
+    }
+}
+
+fn unexpected_response() -> Error {
+    Error::RemoteWorker("Unexpected response".to_owned())
+}
+
+macro_rules! remote_getter_setter {
+    ($ty:ty, $get:ident, $set:ident, $variant:ident) => {
+        fn $get(
+            &mut self,
+            vrs: &[binding::fmi3ValueReference],
+            values: &mut [$ty],
+        ) -> Result<Fmi3Res, Fmi3Error> {
+            let request = Request::GetValues {
+                kind: ValueKind::$variant,
+                vrs: vrs.to_vec(),
+                len: values.len(),
+            };
+            match self.call(&request)? {
+                (
+                    res,
+                    Payload::Values {
+                        values: Values::$variant { values: received },
+                    },
+                ) if received.len() == values.len() => {
+                    values.copy_from_slice(&received);
+                    Ok(res)
+                }
+                _ => self.unexpected(),
+            }
+        }
+
+        fn $set(
+            &mut self,
+            vrs: &[binding::fmi3ValueReference],
+            values: &[$ty],
+        ) -> Result<Fmi3Res, Fmi3Error> {
+            self.call_empty(&Request::SetValues {
+                vrs: vrs.to_vec(),
+                values: Values::$variant {
+                    values: values.to_vec(),
+                },
+            })
+        }
+    };
+}
+
+impl<Tag> GetSet for RemoteInstance<Tag> {
+    type ValueRef = binding::fmi3ValueReference;
+
+    remote_getter_setter!(bool, get_boolean, set_boolean, Boolean);
+    remote_getter_setter!(f32, get_float32, set_float32, Float32);
+    remote_getter_setter!(f64, get_float64, set_float64, Float64);
+    remote_getter_setter!(i8, get_int8, set_int8, Int8);
+    remote_getter_setter!(i16, get_int16, set_int16, Int16);
+    remote_getter_setter!(i32, get_int32, set_int32, Int32);
+    remote_getter_setter!(i64, get_int64, set_int64, Int64);
+    remote_getter_setter!(u8, get_uint8, set_uint8, UInt8);
+    remote_getter_setter!(u16, get_uint16, set_uint16, UInt16);
+    remote_getter_setter!(u32, get_uint32, set_uint32, UInt32);
+    remote_getter_setter!(u64, get_uint64, set_uint64, UInt64);
+    remote_getter_setter!(binding::fmi3Clock, get_clock, set_clock, Clock);
+
+    fn get_string(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [CString],
+    ) -> Result<(), Fmi3Error> {
+        let request = Request::GetValues {
+            kind: ValueKind::String,
+            vrs: vrs.to_vec(),
+            len: values.len(),
+        };
+        match self.call(&request)? {
+            (
+                _,
+                Payload::Values {
+                    values: Values::String { values: received },
+                },
+            ) if received.len() == values.len() => {
+                for (value, bytes) in values.iter_mut().zip(received) {
+                    *value = match CString::new(bytes) {
+                        Ok(value) => value,
+                        Err(_) => return self.unexpected(),
+                    };
+                }
+                Ok(())
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn set_string(&mut self, vrs: &[Self::ValueRef], values: &[CString]) -> Result<(), Fmi3Error> {
+        self.call_empty(&Request::SetValues {
+            vrs: vrs.to_vec(),
+            values: Values::String {
+                values: values.iter().map(|v| v.as_bytes().to_vec()).collect(),
+            },
+        })
+        .map(|_| ())
+    }
+
+    fn get_binary(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [&mut [u8]],
+    ) -> Result<Vec<usize>, Fmi3Error> {
+        let request = Request::GetBinary {
+            vrs: vrs.to_vec(),
+            capacities: values.iter().map(|v| v.len()).collect(),
+        };
+        match self.call(&request)? {
+            (_, Payload::Binary { values: received })
+                if received.len() == values.len()
+                    && received
+                        .iter()
+                        .zip(values.iter())
+                        .all(|(r, v)| r.len() <= v.len()) =>
+            {
+                Ok(values
+                    .iter_mut()
+                    .zip(received)
+                    .map(|(value, bytes)| {
+                        value[..bytes.len()].copy_from_slice(&bytes);
+                        bytes.len()
+                    })
+                    .collect())
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn set_binary(&mut self, vrs: &[Self::ValueRef], values: &[&[u8]]) -> Result<(), Fmi3Error> {
+        self.call_empty(&Request::SetValues {
+            vrs: vrs.to_vec(),
+            values: Values::Binary {
+                values: values.iter().map(|v| v.to_vec()).collect(),
+            },
+        })
+        .map(|_| ())
+    }
+}
+
+impl<Tag> Common for RemoteInstance<Tag> {
+    fn get_version(&self) -> &str {
+        &self.version
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::SetDebugLogging {
+            logging_on,
+            categories: categories.iter().map(|c| c.to_string()).collect(),
+        })
+    }
+
+    fn enter_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::EnterConfigurationMode)
+    }
+
+    fn exit_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::ExitConfigurationMode)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::EnterInitializationMode {
+            tolerance,
+            start_time,
+            stop_time,
+        })
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::ExitInitializationMode)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::EnterEventMode)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::Terminate)
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::Reset)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match self.call(&Request::UpdateDiscreteStates)? {
+            (
+                res,
+                Payload::EventFlags {
+                    discrete_states_need_update,
+                    terminate_simulation,
+                    nominals_of_continuous_states_changed,
+                    values_of_continuous_states_changed,
+                    next_event_time,
+                },
+            ) => {
+                *event_flags = EventFlags {
+                    discrete_states_need_update,
+                    terminate_simulation,
+                    nominals_of_continuous_states_changed,
+                    values_of_continuous_states_changed,
+                    next_event_time,
+                };
+                Ok(res)
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn get_number_of_variable_dependencies(
+        &mut self,
+        vr: Self::ValueRef,
+    ) -> Result<usize, Fmi3Error> {
+        self.call_count(&Request::GetNumberOfVariableDependencies { vr })
+    }
+
+    fn get_variable_dependencies(
+        &mut self,
+        dependent: Self::ValueRef,
+    ) -> Result<Vec<VariableDependency<Self::ValueRef>>, Fmi3Error> {
+        match self.call(&Request::GetVariableDependencies { dependent })? {
+            (
+                _,
+                Payload::Dependencies {
+                    dependent_element_indices,
+                    independents,
+                    independent_element_indices,
+                    dependency_kinds,
+                },
+            ) => Ok(dependent_element_indices
+                .into_iter()
+                .zip(independents)
+                .zip(independent_element_indices)
+                .zip(dependency_kinds)
+                .map(
+                    |(
+                        ((dependent_element_index, independent), independent_element_index),
+                        dependency_kind,
+                    )| {
+                        VariableDependency {
+                            dependent_element_index,
+                            independent,
+                            independent_element_index,
+                            dependency_kind,
+                        }
+                    },
+                )
+                .collect()),
+            _ => self.unexpected(),
+        }
+    }
+
+    fn get_directional_derivative(
+        &mut self,
+        unknowns: &[Self::ValueRef],
+        knowns: &[Self::ValueRef],
+        seed: &[f64],
+        sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        let request = Request::GetDirectionalDerivative {
+            unknowns: unknowns.to_vec(),
+            knowns: knowns.to_vec(),
+            seed: seed.to_vec(),
+            len: sensitivity.len(),
+        };
+        self.derivative(&request, sensitivity)
+    }
+
+    fn get_adjoint_derivative(
+        &mut self,
+        unknowns: &[Self::ValueRef],
+        knowns: &[Self::ValueRef],
+        seed: &[f64],
+        sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        let request = Request::GetAdjointDerivative {
+            unknowns: unknowns.to_vec(),
+            knowns: knowns.to_vec(),
+            seed: seed.to_vec(),
+            len: sensitivity.len(),
+        };
+        self.derivative(&request, sensitivity)
+    }
+}
+
+impl ModelExchange for RemoteInstance<ME> {
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::EnterContinuousTimeMode)
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match self.call(&Request::CompletedIntegratorStep {
+            no_set_fmu_state_prior,
+        })? {
+            (
+                res,
+                Payload::IntegratorStep {
+                    enter_event_mode: enter,
+                    terminate_simulation: terminate,
+                },
+            ) => {
+                *enter_event_mode = enter;
+                *terminate_simulation = terminate;
+                Ok(res)
+            }
+            _ => self.unexpected(),
+        }
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::SetTime { time })
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::SetContinuousStates {
+            states: states.to_vec(),
+        })
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        let request = Request::GetContinuousStates {
+            len: continuous_states.len(),
+        };
+        self.call_float64s(&request, continuous_states)
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        let request = Request::GetContinuousStateDerivatives {
+            len: derivatives.len(),
+        };
+        self.call_float64s(&request, derivatives)
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        let request = Request::GetNominalsOfContinuousStates {
+            len: nominals.len(),
+        };
+        self.call_float64s(&request, nominals)
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        let request = Request::GetEventIndicators {
+            len: event_indicators.len(),
+        };
+        match self.call(&request) {
+            Ok((_, Payload::EventIndicators { computed, values }))
+                if values.len() == event_indicators.len() =>
+            {
+                event_indicators.copy_from_slice(&values);
+                Ok(computed)
+            }
+            // Same as for a local instance
+            Err(Fmi3Error::Discard) => Ok(false),
+            Err(err) => Err(err),
+            Ok(_) => self.unexpected(),
+        }
+    }
+
+    fn get_number_of_event_indicators(&mut self) -> Result<usize, Fmi3Error> {
+        self.call_count(&Request::GetNumberOfEventIndicators)
+    }
+
+    fn get_number_of_continuous_states(&mut self) -> Result<usize, Fmi3Error> {
+        self.call_count(&Request::GetNumberOfContinuousStates)
+    }
+}
+
+impl CoSimulation for RemoteInstance<CS> {
+    fn enter_step_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        self.call_empty(&Request::EnterStepMode)
+    }
+
+    fn do_step(
+        &mut self,
+        current_communication_point: f64,
+        communication_step_size: f64,
+        no_set_fmu_state_prior_to_current_point: bool,
+        event_handling_needed: &mut bool,
+        terminate_simulation: &mut bool,
+        early_return: &mut bool,
+        last_successful_time: &mut f64,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        let request = Request::DoStep {
+            current_communication_point,
+            communication_step_size,
+            no_set_fmu_state_prior_to_current_point,
+        };
+        match self.call(&request)? {
+            (
+                res,
+                Payload::Step {
+                    event_handling_needed: event_handling,
+                    terminate_simulation: terminate,
+                    early_return: returned_early,
+                    last_successful_time: last_time,
+                },
+            ) => {
+                *event_handling_needed = event_handling;
+                *terminate_simulation = terminate;
+                *early_return = returned_early;
+                *last_successful_time = last_time;
+                Ok(res)
+            }
+            _ => self.unexpected(),
+        }
+    }
+}
+
+impl<Tag: InstanceTag> FmiInstance for RemoteInstance<Tag> {
+    type ModelDescription = schema::Fmi3ModelDescription;
+    type ValueRef = <Fmi3Import as FmiImport>::ValueRef;
+    type Status = Fmi3Status;
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_version(&self) -> &str {
+        Common::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        Tag::TYPE
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::set_debug_logging(self, logging_on, categories)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_initialization_mode(self, tolerance, start_time, stop_time)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::exit_initialization_mode(self)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::terminate(self)
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::reset(self)
+    }
+}
+
+impl FmiModelExchange for RemoteInstance<ME> {
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::enter_continuous_time_mode(self)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::completed_integrator_step(
+            self,
+            no_set_fmu_state_prior,
+            enter_event_mode,
+            terminate_simulation,
+        )
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::set_time(self, time)
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_continuous_states(self, continuous_states)
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::set_continuous_states(self, states)
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_continuous_state_derivatives(self, derivatives)
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_nominals_of_continuous_states(self, nominals)
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        ModelExchange::get_event_indicators(self, event_indicators)
+    }
+}
+
+impl FmiEventHandler for RemoteInstance<ME> {
+    #[inline]
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    #[inline]
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+}
+
+impl FmiEventHandler for RemoteInstance<CS> {
+    #[inline]
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    #[inline]
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+}
@@ -0,0 +1,430 @@
+//! Messages exchanged between a [`super::RemoteInstance`] and its worker process.
+//!
+//! Each message is framed as a little-endian `u32` length followed by the encoded message. Both
+//! sides are always built from the same crate version, so the encoding is only versioned through
+//! the initial [`Request::Handshake`].
+
+use std::io::{self, Read, Write};
+
+/// Version of the protocol, checked by the worker before anything else is exchanged
+pub(crate) const PROTOCOL_VERSION: u32 = 1;
+
+/// Binary encoding of a message or one of its fields.
+pub(crate) trait Wire: Sized {
+    fn encode(&self, buf: &mut Vec<u8>);
+    fn decode(buf: &mut &[u8]) -> io::Result<Self>;
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Split the next `n` bytes off `buf`.
+fn take<'a>(buf: &mut &'a [u8], n: usize) -> io::Result<&'a [u8]> {
+    if buf.len() < n {
+        return Err(invalid_data("truncated message"));
+    }
+    let (head, tail) = buf.split_at(n);
+    *buf = tail;
+    Ok(head)
+}
+
+macro_rules! impl_wire_number {
+    ($($ty:ty),*) => {
+        $(
+            impl Wire for $ty {
+                fn encode(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+                    let bytes = take(buf, std::mem::size_of::<$ty>())?;
+                    Ok(<$ty>::from_le_bytes(bytes.try_into().expect("sized by take")))
+                }
+            }
+        )*
+    };
+}
+
+impl_wire_number!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl Wire for bool {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        u8::from(*self).encode(buf);
+    }
+
+    fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+        match u8::decode(buf)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool")),
+        }
+    }
+}
+
+impl Wire for usize {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        (*self as u64).encode(buf);
+    }
+
+    fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+        usize::try_from(u64::decode(buf)?).map_err(|_| invalid_data("length out of range"))
+    }
+}
+
+impl Wire for String {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        buf.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+        let len = usize::decode(buf)?;
+        let bytes = take(buf, len)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data("invalid UTF-8 string"))
+    }
+}
+
+impl<T: Wire> Wire for Vec<T> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.len().encode(buf);
+        for item in self {
+            item.encode(buf);
+        }
+    }
+
+    fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+        let len = usize::decode(buf)?;
+        // Every item takes at least one byte, so a corrupt length can't allocate unbounded memory
+        let mut items = Vec::with_capacity(len.min(buf.len()));
+        for _ in 0..len {
+            items.push(T::decode(buf)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: Wire> Wire for Option<T> {
+    fn encode(&self, buf: &mut Vec<u8>) {
+        self.is_some().encode(buf);
+        if let Some(value) = self {
+            value.encode(buf);
+        }
+    }
+
+    fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+        if bool::decode(buf)? {
+            T::decode(buf).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Define an enum whose variants are encoded as a `u8` tag, in declaration order, followed by
+/// their fields.
+macro_rules! wire_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $(
+                $(#[$variant_meta:meta])*
+                $variant:ident $({ $($field:ident : $ty:ty),* $(,)? })?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $(
+                $(#[$variant_meta])*
+                $variant $({ $($field: $ty),* })?
+            ),*
+        }
+
+        impl Wire for $name {
+            #[allow(unused_assignments)]
+            fn encode(&self, buf: &mut Vec<u8>) {
+                let mut tag = 0u8;
+                $(
+                    if let $name::$variant $({ $($field),* })? = self {
+                        tag.encode(buf);
+                        $($($field.encode(buf);)*)?
+                        return;
+                    }
+                    tag += 1;
+                )*
+            }
+
+            #[allow(unused_assignments)]
+            fn decode(buf: &mut &[u8]) -> io::Result<Self> {
+                let tag = u8::decode(buf)?;
+                let mut next = 0u8;
+                $(
+                    if tag == next {
+                        return Ok($name::$variant $({ $($field: Wire::decode(buf)?),* })?);
+                    }
+                    next += 1;
+                )*
+                Err(invalid_data(concat!("invalid ", stringify!($name), " tag")))
+            }
+        }
+    };
+}
+
+wire_enum! {
+    /// The type of the values requested with [`Request::GetValues`]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub(crate) enum ValueKind {
+        Boolean,
+        Float32,
+        Float64,
+        Int8,
+        Int16,
+        Int32,
+        Int64,
+        UInt8,
+        UInt16,
+        UInt32,
+        UInt64,
+        String,
+        Clock,
+    }
+}
+
+wire_enum! {
+    /// Variable values, with strings as their bytes without the nul terminator
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum Values {
+        Boolean { values: Vec<bool> },
+        Float32 { values: Vec<f32> },
+        Float64 { values: Vec<f64> },
+        Int8 { values: Vec<i8> },
+        Int16 { values: Vec<i16> },
+        Int32 { values: Vec<i32> },
+        Int64 { values: Vec<i64> },
+        UInt8 { values: Vec<u8> },
+        UInt16 { values: Vec<u16> },
+        UInt32 { values: Vec<u32> },
+        UInt64 { values: Vec<u64> },
+        String { values: Vec<Vec<u8>> },
+        Binary { values: Vec<Vec<u8>> },
+        Clock { values: Vec<bool> },
+    }
+}
+
+wire_enum! {
+    /// A call from the parent process to the worker
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum Request {
+        /// Must be the first request, answered with [`Response::Failed`] on a version mismatch
+        Handshake { protocol: u32 },
+        InstantiateModelExchange {
+            fmu_dir: String,
+            instance_name: String,
+            visible: bool,
+            logging_on: bool,
+        },
+        InstantiateCoSimulation {
+            fmu_dir: String,
+            instance_name: String,
+            visible: bool,
+            logging_on: bool,
+            event_mode_used: bool,
+            early_return_allowed: bool,
+            required_intermediate_variables: Vec<u32>,
+        },
+        GetVersion,
+        SetDebugLogging { logging_on: bool, categories: Vec<String> },
+        EnterConfigurationMode,
+        ExitConfigurationMode,
+        EnterInitializationMode {
+            tolerance: Option<f64>,
+            start_time: f64,
+            stop_time: Option<f64>,
+        },
+        ExitInitializationMode,
+        EnterEventMode,
+        Terminate,
+        Reset,
+        UpdateDiscreteStates,
+        GetNumberOfVariableDependencies { vr: u32 },
+        GetVariableDependencies { dependent: u32 },
+        GetDirectionalDerivative {
+            unknowns: Vec<u32>,
+            knowns: Vec<u32>,
+            seed: Vec<f64>,
+            len: usize,
+        },
+        GetAdjointDerivative {
+            unknowns: Vec<u32>,
+            knowns: Vec<u32>,
+            seed: Vec<f64>,
+            len: usize,
+        },
+        GetValues { kind: ValueKind, vrs: Vec<u32>, len: usize },
+        /// Get binary values into buffers of the given capacities
+        GetBinary { vrs: Vec<u32>, capacities: Vec<usize> },
+        SetValues { vrs: Vec<u32>, values: Values },
+        EnterContinuousTimeMode,
+        CompletedIntegratorStep { no_set_fmu_state_prior: bool },
+        SetTime { time: f64 },
+        SetContinuousStates { states: Vec<f64> },
+        GetContinuousStates { len: usize },
+        GetContinuousStateDerivatives { len: usize },
+        GetNominalsOfContinuousStates { len: usize },
+        GetEventIndicators { len: usize },
+        GetNumberOfEventIndicators,
+        GetNumberOfContinuousStates,
+        EnterStepMode,
+        DoStep {
+            current_communication_point: f64,
+            communication_step_size: f64,
+            no_set_fmu_state_prior_to_current_point: bool,
+        },
+    }
+}
+
+wire_enum! {
+    /// The outputs of a call that reached the FMU
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum Payload {
+        Empty,
+        Version { version: String },
+        Count { count: usize },
+        Values { values: Values },
+        /// Binary values, truncated to their actual sizes
+        Binary { values: Vec<Vec<u8>> },
+        Dependencies {
+            dependent_element_indices: Vec<usize>,
+            independents: Vec<u32>,
+            independent_element_indices: Vec<usize>,
+            dependency_kinds: Vec<u32>,
+        },
+        EventFlags {
+            discrete_states_need_update: bool,
+            terminate_simulation: bool,
+            nominals_of_continuous_states_changed: bool,
+            values_of_continuous_states_changed: bool,
+            next_event_time: Option<f64>,
+        },
+        IntegratorStep { enter_event_mode: bool, terminate_simulation: bool },
+        EventIndicators { computed: bool, values: Vec<f64> },
+        Step {
+            event_handling_needed: bool,
+            terminate_simulation: bool,
+            early_return: bool,
+            last_successful_time: f64,
+        },
+    }
+}
+
+wire_enum! {
+    /// A message from the worker to the parent process
+    #[derive(Debug, Clone, PartialEq)]
+    pub(crate) enum Response {
+        /// A message logged by the FMU while the current request was served
+        Log { status: u32, category: String, message: String },
+        /// The `fmi3Status` of the call, with its outputs if it succeeded
+        Done { status: u32, payload: Payload },
+        /// The request could not be passed on to the FMU
+        Failed { message: String },
+    }
+}
+
+/// Write one framed message.
+pub(crate) fn write_message(writer: &mut impl Write, message: &impl Wire) -> io::Result<()> {
+    let mut buf = vec![0; 4];
+    message.encode(&mut buf);
+    let len = u32::try_from(buf.len() - 4).map_err(|_| invalid_data("message too long"))?;
+    buf[..4].copy_from_slice(&len.to_le_bytes());
+    writer.write_all(&buf)
+}
+
+/// Read one framed message, or `None` if the stream ended between messages.
+pub(crate) fn read_message<T: Wire>(reader: &mut impl Read) -> io::Result<Option<T>> {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut buf = vec![0; u32::from_le_bytes(len) as usize];
+    reader.read_exact(&mut buf)?;
+    let mut bytes = buf.as_slice();
+    let message = T::decode(&mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(invalid_data("trailing bytes after message"));
+    }
+    Ok(Some(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let requests = [
+            Request::Handshake {
+                protocol: PROTOCOL_VERSION,
+            },
+            Request::GetVersion,
+            Request::EnterInitializationMode {
+                tolerance: None,
+                start_time: 0.5,
+                stop_time: Some(10.0),
+            },
+            Request::SetValues {
+                vrs: vec![1, 2],
+                values: Values::String {
+                    values: vec![b"a".to_vec(), Vec::new()],
+                },
+            },
+            Request::GetValues {
+                kind: ValueKind::Clock,
+                vrs: vec![7],
+                len: 1,
+            },
+        ];
+        let mut stream = Vec::new();
+        for request in &requests {
+            write_message(&mut stream, request).unwrap();
+        }
+        let mut reader = stream.as_slice();
+        for request in &requests {
+            assert_eq!(
+                read_message::<Request>(&mut reader).unwrap().as_ref(),
+                Some(request)
+            );
+        }
+        assert_eq!(read_message::<Request>(&mut reader).unwrap(), None);
+
+        let response = Response::Done {
+            status: 1,
+            payload: Payload::EventFlags {
+                discrete_states_need_update: true,
+                terminate_simulation: false,
+                nominals_of_continuous_states_changed: false,
+                values_of_continuous_states_changed: true,
+                next_event_time: Some(1.5),
+            },
+        };
+        let mut stream = Vec::new();
+        write_message(&mut stream, &response).unwrap();
+        assert_eq!(
+            read_message::<Response>(&mut stream.as_slice()).unwrap(),
+            Some(response)
+        );
+    }
+
+    #[test]
+    fn test_invalid_message() {
+        let mut stream = Vec::new();
+        write_message(&mut stream, &Request::GetVersion).unwrap();
+        // Corrupt the tag
+        stream[4] = u8::MAX;
+        assert!(read_message::<Request>(&mut stream.as_slice()).is_err());
+        // Truncated frame
+        assert!(read_message::<Request>(&mut &stream[..4]).is_err());
+    }
+}
@@ -0,0 +1,478 @@
+//! The worker side of a [`super::RemoteInstance`], serving the calls of the parent process.
+
+use std::{
+    ffi::CString,
+    io::{BufReader, BufWriter, Read, Write},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use crate::{
+    CS, Error, EventFlags, ME,
+    fmi3::{
+        CoSimulation, Common, Fmi3Error, Fmi3Model, Fmi3Res, Fmi3Status, GetSet, ModelExchange,
+        binding, import::Fmi3Import, instance::Instance,
+    },
+};
+
+use super::protocol::{
+    PROTOCOL_VERSION, Payload, Request, Response, ValueKind, Values, read_message, write_message,
+};
+
+/// Serve the requests of a [`super::RemoteInstance`] read from `input` until it is closed.
+///
+/// This is the main loop of the `fmi-worker` binary. The FMU must not write to `output`, so it
+/// should not be stdout if the FMU prints there.
+pub fn serve(input: impl Read, output: impl Write) -> Result<(), Error> {
+    let mut input = BufReader::new(input);
+    let mut output = BufWriter::new(output);
+    let mut worker = Worker::default();
+
+    while let Some(request) = read_message::<Request>(&mut input)? {
+        let response = worker.handle(request);
+        let logs = std::mem::take(&mut *worker.logs.lock().unwrap_or_else(PoisonError::into_inner));
+        for log in &logs {
+            write_message(&mut output, log)?;
+        }
+        write_message(&mut output, &response)?;
+        output.flush()?;
+    }
+    log::debug!("Parent process closed the connection, exiting");
+    Ok(())
+}
+
+/// The instance served by the worker
+enum Served {
+    ModelExchange(Instance<ME>),
+    CoSimulation(Instance<CS>),
+}
+
+#[derive(Default)]
+struct Worker {
+    handshake_done: bool,
+    instance: Option<Served>,
+    /// Messages logged by the FMU since the last response
+    logs: Arc<Mutex<Vec<Response>>>,
+}
+
+/// The response to a call that reached the FMU.
+fn reply(result: Result<Fmi3Res, Fmi3Error>, payload: impl FnOnce() -> Payload) -> Response {
+    match result {
+        Ok(res) => Response::Done {
+            status: Fmi3Status::from(res).into(),
+            payload: payload(),
+        },
+        Err(err) => Response::Done {
+            status: Fmi3Status::from(err).into(),
+            payload: Payload::Empty,
+        },
+    }
+}
+
+/// The response to a call that could fail either in the FMU or before reaching it.
+fn reply_or_fail(result: Result<Fmi3Res, Error>, payload: impl FnOnce() -> Payload) -> Response {
+    match result {
+        Ok(res) => reply(Ok(res), payload),
+        Err(Error::Fmi3Error(err)) => reply(Err(err), payload),
+        Err(err) => Response::Failed {
+            message: err.to_string(),
+        },
+    }
+}
+
+fn failed(message: impl Into<String>) -> Response {
+    Response::Failed {
+        message: message.into(),
+    }
+}
+
+macro_rules! get_values {
+    ($inst:expr, $vrs:expr, $len:expr, $get:ident, $variant:ident, $default:expr) => {{
+        let mut values = vec![$default; $len];
+        reply($inst.$get(&$vrs, &mut values), move || Payload::Values {
+            values: Values::$variant { values },
+        })
+    }};
+}
+
+impl Worker {
+    fn handle(&mut self, request: Request) -> Response {
+        if !self.handshake_done {
+            return match request {
+                Request::Handshake { protocol } if protocol == PROTOCOL_VERSION => {
+                    self.handshake_done = true;
+                    reply(Ok(Fmi3Res::OK), || Payload::Empty)
+                }
+                Request::Handshake { protocol } => failed(format!(
+                    "protocol version {protocol} does not match the worker version {PROTOCOL_VERSION}"
+                )),
+                _ => failed("expected a handshake"),
+            };
+        }
+
+        match request {
+            Request::Handshake { .. } => failed("unexpected handshake"),
+            Request::InstantiateModelExchange {
+                fmu_dir,
+                instance_name,
+                visible,
+                logging_on,
+            } => self.instantiate(&fmu_dir, |import| {
+                import
+                    .instantiate_me(&instance_name, visible, logging_on)
+                    .map(Served::ModelExchange)
+            }),
+            Request::InstantiateCoSimulation {
+                fmu_dir,
+                instance_name,
+                visible,
+                logging_on,
+                event_mode_used,
+                early_return_allowed,
+                required_intermediate_variables,
+            } => self.instantiate(&fmu_dir, |import| {
+                import
+                    .instantiate_cs(
+                        &instance_name,
+                        visible,
+                        logging_on,
+                        event_mode_used,
+                        early_return_allowed,
+                        &required_intermediate_variables,
+                    )
+                    .map(Served::CoSimulation)
+            }),
+            request => match self.instance.as_mut() {
+                Some(Served::ModelExchange(inst)) => handle_model_exchange(inst, request),
+                Some(Served::CoSimulation(inst)) => handle_co_simulation(inst, request),
+                None => failed("no instance has been created"),
+            },
+        }
+    }
+
+    fn instantiate(
+        &mut self,
+        fmu_dir: &str,
+        instantiate: impl FnOnce(&Fmi3Import) -> Result<Served, Error>,
+    ) -> Response {
+        if self.instance.is_some() {
+            return failed("the worker already serves an instance");
+        }
+        let served =
+            crate::import::from_dir::<Fmi3Import>(fmu_dir).and_then(|import| instantiate(&import));
+        match served {
+            Ok(mut served) => {
+                let logs = self.logs.clone();
+                let sink = move |msg: &crate::fmi3::LogMessage<'_>| {
+                    logs.lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(Response::Log {
+                            status: msg.status.0,
+                            category: msg.category.to_owned(),
+                            message: msg.message.to_owned(),
+                        });
+                };
+                match &mut served {
+                    Served::ModelExchange(inst) => inst.set_log_sink(sink),
+                    Served::CoSimulation(inst) => inst.set_log_sink(sink),
+                }
+                self.instance = Some(served);
+                reply(Ok(Fmi3Res::OK), || Payload::Empty)
+            }
+            Err(err) => failed(err.to_string()),
+        }
+    }
+}
+
+fn handle_model_exchange(inst: &mut Instance<ME>, request: Request) -> Response {
+    match request {
+        Request::EnterContinuousTimeMode => {
+            reply(inst.enter_continuous_time_mode(), || Payload::Empty)
+        }
+        Request::CompletedIntegratorStep {
+            no_set_fmu_state_prior,
+        } => {
+            let mut enter_event_mode = false;
+            let mut terminate_simulation = false;
+            let result = inst.completed_integrator_step(
+                no_set_fmu_state_prior,
+                &mut enter_event_mode,
+                &mut terminate_simulation,
+            );
+            reply(result, || Payload::IntegratorStep {
+                enter_event_mode,
+                terminate_simulation,
+            })
+        }
+        Request::SetTime { time } => reply(inst.set_time(time), || Payload::Empty),
+        Request::SetContinuousStates { states } => {
+            reply(inst.set_continuous_states(&states), || Payload::Empty)
+        }
+        Request::GetContinuousStates { len } => {
+            let mut values = vec![0.0; len];
+            let result = inst.get_continuous_states(&mut values);
+            reply(result, || Payload::Values {
+                values: Values::Float64 { values },
+            })
+        }
+        Request::GetContinuousStateDerivatives { len } => {
+            let mut values = vec![0.0; len];
+            let result = inst.get_continuous_state_derivatives(&mut values);
+            reply(result, || Payload::Values {
+                values: Values::Float64 { values },
+            })
+        }
+        Request::GetNominalsOfContinuousStates { len } => {
+            let mut values = vec![0.0; len];
+            let result = inst.get_nominals_of_continuous_states(&mut values);
+            reply(result, || Payload::Values {
+                values: Values::Float64 { values },
+            })
+        }
+        Request::GetEventIndicators { len } => {
+            let mut values = vec![0.0; len];
+            match ModelExchange::get_event_indicators(inst, &mut values) {
+                Ok(computed) => reply(Ok(Fmi3Res::OK), || Payload::EventIndicators {
+                    computed,
+                    values,
+                }),
+                Err(err) => reply(Err(err), || Payload::Empty),
+            }
+        }
+        Request::GetNumberOfEventIndicators => {
+            let result = inst.get_number_of_event_indicators();
+            reply_count(result)
+        }
+        Request::GetNumberOfContinuousStates => {
+            let result = inst.get_number_of_continuous_states();
+            reply_count(result)
+        }
+        request => handle_common(inst, request),
+    }
+}
+
+fn handle_co_simulation(inst: &mut Instance<CS>, request: Request) -> Response {
+    match request {
+        Request::EnterStepMode => reply(inst.enter_step_mode(), || Payload::Empty),
+        Request::DoStep {
+            current_communication_point,
+            communication_step_size,
+            no_set_fmu_state_prior_to_current_point,
+        } => {
+            let mut event_handling_needed = false;
+            let mut terminate_simulation = false;
+            let mut early_return = false;
+            let mut last_successful_time = 0.0;
+            let result = inst.do_step(
+                current_communication_point,
+                communication_step_size,
+                no_set_fmu_state_prior_to_current_point,
+                &mut event_handling_needed,
+                &mut terminate_simulation,
+                &mut early_return,
+                &mut last_successful_time,
+            );
+            reply(result, || Payload::Step {
+                event_handling_needed,
+                terminate_simulation,
+                early_return,
+                last_successful_time,
+            })
+        }
+        request => handle_common(inst, request),
+    }
+}
+
+fn reply_count(result: Result<usize, Fmi3Error>) -> Response {
+    match result {
+        Ok(count) => reply(Ok(Fmi3Res::OK), || Payload::Count { count }),
+        Err(err) => reply(Err(err), || Payload::Empty),
+    }
+}
+
+fn handle_common<Inst>(inst: &mut Inst, request: Request) -> Response
+where
+    Inst: Common + GetSet<ValueRef = binding::fmi3ValueReference>,
+{
+    match request {
+        Request::GetVersion => {
+            let version = Common::get_version(inst).to_owned();
+            reply(Ok(Fmi3Res::OK), || Payload::Version { version })
+        }
+        Request::SetDebugLogging {
+            logging_on,
+            categories,
+        } => {
+            let categories = categories.iter().map(String::as_str).collect::<Vec<_>>();
+            reply(
+                Common::set_debug_logging(inst, logging_on, &categories),
+                || Payload::Empty,
+            )
+        }
+        Request::EnterConfigurationMode => {
+            reply(inst.enter_configuration_mode(), || Payload::Empty)
+        }
+        Request::ExitConfigurationMode => reply(inst.exit_configuration_mode(), || Payload::Empty),
+        Request::EnterInitializationMode {
+            tolerance,
+            start_time,
+            stop_time,
+        } => reply(
+            Common::enter_initialization_mode(inst, tolerance, start_time, stop_time),
+            || Payload::Empty,
+        ),
+        Request::ExitInitializationMode => {
+            reply(Common::exit_initialization_mode(inst), || Payload::Empty)
+        }
+        Request::EnterEventMode => reply(Common::enter_event_mode(inst), || Payload::Empty),
+        Request::Terminate => reply(Common::terminate(inst), || Payload::Empty),
+        Request::Reset => reply(Common::reset(inst), || Payload::Empty),
+        Request::UpdateDiscreteStates => {
+            let mut flags = EventFlags::default();
+            let result = Common::update_discrete_states(inst, &mut flags);
+            reply(result, || Payload::EventFlags {
+                discrete_states_need_update: flags.discrete_states_need_update,
+                terminate_simulation: flags.terminate_simulation,
+                nominals_of_continuous_states_changed: flags.nominals_of_continuous_states_changed,
+                values_of_continuous_states_changed: flags.values_of_continuous_states_changed,
+                next_event_time: flags.next_event_time,
+            })
+        }
+        Request::GetNumberOfVariableDependencies { vr } => {
+            reply_count(inst.get_number_of_variable_dependencies(vr))
+        }
+        Request::GetVariableDependencies { dependent } => {
+            match inst.get_variable_dependencies(dependent) {
+                Ok(dependencies) => reply(Ok(Fmi3Res::OK), || Payload::Dependencies {
+                    dependent_element_indices: dependencies
+                        .iter()
+                        .map(|d| d.dependent_element_index)
+                        .collect(),
+                    independents: dependencies.iter().map(|d| d.independent).collect(),
+                    independent_element_indices: dependencies
+                        .iter()
+                        .map(|d| d.independent_element_index)
+                        .collect(),
+                    dependency_kinds: dependencies.iter().map(|d| d.dependency_kind).collect(),
+                }),
+                Err(err) => reply(Err(err), || Payload::Empty),
+            }
+        }
+        Request::GetDirectionalDerivative {
+            unknowns,
+            knowns,
+            seed,
+            len,
+        } => {
+            let mut sensitivity = vec![0.0; len];
+            let result =
+                inst.get_directional_derivative(&unknowns, &knowns, &seed, &mut sensitivity);
+            reply_or_fail(result, || Payload::Values {
+                values: Values::Float64 {
+                    values: sensitivity,
+                },
+            })
+        }
+        Request::GetAdjointDerivative {
+            unknowns,
+            knowns,
+            seed,
+            len,
+        } => {
+            let mut sensitivity = vec![0.0; len];
+            let result = inst.get_adjoint_derivative(&unknowns, &knowns, &seed, &mut sensitivity);
+            reply_or_fail(result, || Payload::Values {
+                values: Values::Float64 {
+                    values: sensitivity,
+                },
+            })
+        }
+        Request::GetValues { kind, vrs, len } => match kind {
+            ValueKind::Boolean => get_values!(inst, vrs, len, get_boolean, Boolean, false),
+            ValueKind::Float32 => get_values!(inst, vrs, len, get_float32, Float32, 0.0),
+            ValueKind::Float64 => get_values!(inst, vrs, len, get_float64, Float64, 0.0),
+            ValueKind::Int8 => get_values!(inst, vrs, len, get_int8, Int8, 0),
+            ValueKind::Int16 => get_values!(inst, vrs, len, get_int16, Int16, 0),
+            ValueKind::Int32 => get_values!(inst, vrs, len, get_int32, Int32, 0),
+            ValueKind::Int64 => get_values!(inst, vrs, len, get_int64, Int64, 0),
+            ValueKind::UInt8 => get_values!(inst, vrs, len, get_uint8, UInt8, 0),
+            ValueKind::UInt16 => get_values!(inst, vrs, len, get_uint16, UInt16, 0),
+            ValueKind::UInt32 => get_values!(inst, vrs, len, get_uint32, UInt32, 0),
+            ValueKind::UInt64 => get_values!(inst, vrs, len, get_uint64, UInt64, 0),
+            ValueKind::Clock => get_values!(inst, vrs, len, get_clock, Clock, false),
+            ValueKind::String => {
+                let mut values = vec![CString::default(); len];
+                let result = inst.get_string(&vrs, &mut values).map(|_| Fmi3Res::OK);
+                reply(result, || Payload::Values {
+                    values: Values::String {
+                        values: values.into_iter().map(CString::into_bytes).collect(),
+                    },
+                })
+            }
+        },
+        Request::GetBinary { vrs, capacities } => {
+            let mut buffers = capacities
+                .iter()
+                .map(|capacity| vec![0u8; *capacity])
+                .collect::<Vec<_>>();
+            let mut slices = buffers
+                .iter_mut()
+                .map(Vec::as_mut_slice)
+                .collect::<Vec<_>>();
+            match inst.get_binary(&vrs, &mut slices) {
+                Ok(sizes) => reply(Ok(Fmi3Res::OK), || Payload::Binary {
+                    values: buffers
+                        .into_iter()
+                        .zip(sizes)
+                        .map(|(mut buffer, size)| {
+                            buffer.truncate(size);
+                            buffer
+                        })
+                        .collect(),
+                }),
+                Err(err) => reply(Err(err), || Payload::Empty),
+            }
+        }
+        Request::SetValues { vrs, values } => set_values(inst, &vrs, values),
+        _ => failed("request is not supported by the interface type of the instance"),
+    }
+}
+
+fn set_values<Inst>(
+    inst: &mut Inst,
+    vrs: &[binding::fmi3ValueReference],
+    values: Values,
+) -> Response
+where
+    Inst: GetSet<ValueRef = binding::fmi3ValueReference>,
+{
+    let result = match values {
+        Values::Boolean { values } => inst.set_boolean(vrs, &values),
+        Values::Float32 { values } => inst.set_float32(vrs, &values),
+        Values::Float64 { values } => inst.set_float64(vrs, &values),
+        Values::Int8 { values } => inst.set_int8(vrs, &values),
+        Values::Int16 { values } => inst.set_int16(vrs, &values),
+        Values::Int32 { values } => inst.set_int32(vrs, &values),
+        Values::Int64 { values } => inst.set_int64(vrs, &values),
+        Values::UInt8 { values } => inst.set_uint8(vrs, &values),
+        Values::UInt16 { values } => inst.set_uint16(vrs, &values),
+        Values::UInt32 { values } => inst.set_uint32(vrs, &values),
+        Values::UInt64 { values } => inst.set_uint64(vrs, &values),
+        Values::Clock { values } => inst.set_clock(vrs, &values),
+        Values::String { values } => {
+            let values = match values
+                .into_iter()
+                .map(CString::new)
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(values) => values,
+                Err(_) => return failed("string value contains a nul byte"),
+            };
+            inst.set_string(vrs, &values).map(|_| Fmi3Res::OK)
+        }
+        Values::Binary { values } => {
+            let values = values.iter().map(Vec::as_slice).collect::<Vec<_>>();
+            inst.set_binary(vrs, &values).map(|_| Fmi3Res::OK)
+        }
+    };
+    reply(result, || Payload::Empty)
+}
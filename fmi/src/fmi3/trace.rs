@@ -0,0 +1,1072 @@
+//! Recording and replay of FMI 3.0 call traces, see [`crate::trace`].
+//!
+//! FMU state functions and `fmi3GetVersion` are not recorded.
+
+use std::{ffi::CString, io::Write};
+
+use serde_json::json;
+
+use super::{
+    Fmi3Error, Fmi3Res, Fmi3Status, binding,
+    traits::{CoSimulation, Common, GetSet, ModelExchange, ScheduledExecution},
+};
+use crate::{
+    Error, EventFlags, InterfaceType,
+    trace::{
+        Mismatch, TraceCall, TraceWriter, arg, compare_calls, event_flags_value, output_len,
+        status, traced_call, value_status,
+    },
+    traits::{FmiEventHandler, FmiInstance, FmiModelExchange},
+};
+
+/// An FMI 3.0 instance that records every FMI call made through it to a trace.
+///
+/// The wrapper implements the same traits as the wrapped instance, so it can be used in its
+/// place:
+///
+/// ```rust,no_run
+/// # use fmi::{fmi3::{import::Fmi3Import, trace::TracedInstance, Fmi3Model}, import};
+/// let import: Fmi3Import = import::from_path("path/to/model.fmu").unwrap();
+/// let instance = import
+///     .instantiate_cs("inst1", true, true, false, false, &[])
+///     .unwrap();
+/// let trace = std::io::BufWriter::new(std::fs::File::create("inst1.jsonl").unwrap());
+/// let mut instance = TracedInstance::new(instance, trace).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TracedInstance<I> {
+    inner: I,
+    writer: TraceWriter,
+}
+
+impl<I: FmiInstance> TracedInstance<I> {
+    /// Wrap `inner`, writing its trace to `writer`.
+    pub fn new(inner: I, writer: impl Write + Send + 'static) -> Result<Self, Error> {
+        let interface = format!("{:?}", inner.interface_type());
+        let writer = TraceWriter::new(writer, "3.0", &interface, inner.name())?;
+        Ok(Self { inner, writer })
+    }
+}
+
+impl<I> TracedInstance<I> {
+    /// The wrapped instance. Calls made through it are not recorded.
+    pub fn inner(&self) -> &I {
+        &self.inner
+    }
+
+    /// The wrapped instance. Calls made through it are not recorded.
+    pub fn inner_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Stop recording and return the wrapped instance.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    /// Flush the trace.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()
+    }
+}
+
+fn value_ref<VR: Into<binding::fmi3ValueReference>>(vr: VR) -> u32 {
+    vr.into()
+}
+
+fn value_refs<VR: Copy + Into<binding::fmi3ValueReference>>(vrs: &[VR]) -> Vec<u32> {
+    vrs.iter().map(|&vr| vr.into()).collect()
+}
+
+fn strings(values: &[CString]) -> Vec<String> {
+    values
+        .iter()
+        .map(|s| s.to_string_lossy().into_owned())
+        .collect()
+}
+
+macro_rules! traced_getter_setter {
+    ($name:ident, $ty:ty, $fmi:literal) => {
+        paste::paste! {
+            fn [<get_ $name>](
+                &mut self,
+                vrs: &[Self::ValueRef],
+                values: &mut [$ty],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                traced_call!(
+                    self.writer,
+                    concat!("fmi3Get", $fmi),
+                    { "vrs": value_refs(vrs) },
+                    self.inner.[<get_ $name>](vrs, values),
+                    |result| { "values": values },
+                    status
+                )
+            }
+
+            fn [<set_ $name>](
+                &mut self,
+                vrs: &[Self::ValueRef],
+                values: &[$ty],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                traced_call!(
+                    self.writer,
+                    concat!("fmi3Set", $fmi),
+                    { "vrs": value_refs(vrs), "values": values },
+                    self.inner.[<set_ $name>](vrs, values),
+                    |result| {},
+                    status
+                )
+            }
+        }
+    };
+}
+
+impl<I: GetSet> GetSet for TracedInstance<I> {
+    type ValueRef = I::ValueRef;
+
+    traced_getter_setter!(boolean, bool, "Boolean");
+    traced_getter_setter!(float32, f32, "Float32");
+    traced_getter_setter!(float64, f64, "Float64");
+    traced_getter_setter!(int8, i8, "Int8");
+    traced_getter_setter!(int16, i16, "Int16");
+    traced_getter_setter!(int32, i32, "Int32");
+    traced_getter_setter!(int64, i64, "Int64");
+    traced_getter_setter!(uint8, u8, "UInt8");
+    traced_getter_setter!(uint16, u16, "UInt16");
+    traced_getter_setter!(uint32, u32, "UInt32");
+    traced_getter_setter!(uint64, u64, "UInt64");
+
+    fn get_string(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [CString],
+    ) -> Result<(), Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetString",
+            { "vrs": value_refs(vrs) },
+            self.inner.get_string(vrs, values),
+            |result| { "values": strings(values) },
+            value_status
+        )
+    }
+
+    fn set_string(&mut self, vrs: &[Self::ValueRef], values: &[CString]) -> Result<(), Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3SetString",
+            { "vrs": value_refs(vrs), "values": strings(values) },
+            self.inner.set_string(vrs, values),
+            |result| {},
+            value_status
+        )
+    }
+
+    fn get_binary(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [&mut [u8]],
+    ) -> Result<Vec<usize>, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetBinary",
+            { "vrs": value_refs(vrs) },
+            self.inner.get_binary(vrs, values),
+            |result| {
+                "values": result.as_ref().map_or(Vec::new(), |sizes| {
+                    values.iter().zip(sizes).map(|(value, &size)| &value[..size]).collect()
+                })
+            },
+            value_status
+        )
+    }
+
+    fn set_binary(&mut self, vrs: &[Self::ValueRef], values: &[&[u8]]) -> Result<(), Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3SetBinary",
+            { "vrs": value_refs(vrs), "values": values },
+            self.inner.set_binary(vrs, values),
+            |result| {},
+            value_status
+        )
+    }
+
+    fn get_clock(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &mut [binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetClock",
+            { "vrs": value_refs(vrs) },
+            self.inner.get_clock(vrs, values),
+            |result| { "values": values },
+            status
+        )
+    }
+
+    fn set_clock(
+        &mut self,
+        vrs: &[Self::ValueRef],
+        values: &[binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3SetClock",
+            { "vrs": value_refs(vrs), "values": values },
+            self.inner.set_clock(vrs, values),
+            |result| {},
+            status
+        )
+    }
+}
+
+impl<I: Common> Common for TracedInstance<I> {
+    fn get_version(&self) -> &str {
+        self.inner.get_version()
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3SetDebugLogging",
+            { "logging_on": logging_on, "categories": categories },
+            self.inner.set_debug_logging(logging_on, categories),
+            |result| {},
+            status
+        )
+    }
+
+    fn enter_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3EnterConfigurationMode",
+            {},
+            self.inner.enter_configuration_mode(),
+            |result| {},
+            status
+        )
+    }
+
+    fn exit_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3ExitConfigurationMode",
+            {},
+            self.inner.exit_configuration_mode(),
+            |result| {},
+            status
+        )
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3EnterInitializationMode",
+            { "tolerance": tolerance, "start_time": start_time, "stop_time": stop_time },
+            self.inner
+                .enter_initialization_mode(tolerance, start_time, stop_time),
+            |result| {},
+            status
+        )
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3ExitInitializationMode",
+            {},
+            self.inner.exit_initialization_mode(),
+            |result| {},
+            status
+        )
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3EnterEventMode",
+            {},
+            self.inner.enter_event_mode(),
+            |result| {},
+            status
+        )
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3Terminate",
+            {},
+            self.inner.terminate(),
+            |result| {},
+            status
+        )
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3Reset",
+            {},
+            self.inner.reset(),
+            |result| {},
+            status
+        )
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3UpdateDiscreteStates",
+            {},
+            self.inner.update_discrete_states(event_flags),
+            |result| { "event_flags": event_flags_value(event_flags) },
+            status
+        )
+    }
+
+    fn get_number_of_variable_dependencies(
+        &mut self,
+        vr: Self::ValueRef,
+    ) -> Result<usize, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetNumberOfVariableDependencies",
+            { "vr": value_ref(vr) },
+            self.inner.get_number_of_variable_dependencies(vr),
+            |result| { "value": result.as_ref().ok() },
+            value_status
+        )
+    }
+
+    fn get_variable_dependencies(
+        &mut self,
+        dependent: Self::ValueRef,
+    ) -> Result<Vec<super::traits::VariableDependency<Self::ValueRef>>, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetVariableDependencies",
+            { "dependent": value_ref(dependent) },
+            self.inner.get_variable_dependencies(dependent),
+            |result| {
+                "dependencies": result.as_ref().map_or(Vec::new(), |dependencies| {
+                    dependencies
+                        .iter()
+                        .map(|d| json!([
+                            d.dependent_element_index,
+                            value_ref(d.independent),
+                            d.independent_element_index,
+                            d.dependency_kind,
+                        ]))
+                        .collect()
+                })
+            },
+            value_status
+        )
+    }
+
+    fn get_directional_derivative(
+        &mut self,
+        unknowns: &[Self::ValueRef],
+        knowns: &[Self::ValueRef],
+        seed: &[f64],
+        sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetDirectionalDerivative",
+            { "unknowns": value_refs(unknowns), "knowns": value_refs(knowns), "seed": seed },
+            self.inner
+                .get_directional_derivative(unknowns, knowns, seed, sensitivity),
+            |result| { "sensitivity": sensitivity },
+            status
+        )
+    }
+
+    fn get_adjoint_derivative(
+        &mut self,
+        unknowns: &[Self::ValueRef],
+        knowns: &[Self::ValueRef],
+        seed: &[f64],
+        sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetAdjointDerivative",
+            { "unknowns": value_refs(unknowns), "knowns": value_refs(knowns), "seed": seed },
+            self.inner
+                .get_adjoint_derivative(unknowns, knowns, seed, sensitivity),
+            |result| { "sensitivity": sensitivity },
+            status
+        )
+    }
+}
+
+impl<I: ModelExchange> ModelExchange for TracedInstance<I> {
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3EnterContinuousTimeMode",
+            {},
+            self.inner.enter_continuous_time_mode(),
+            |result| {},
+            status
+        )
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3CompletedIntegratorStep",
+            { "no_set_fmu_state_prior": no_set_fmu_state_prior },
+            self.inner.completed_integrator_step(
+                no_set_fmu_state_prior,
+                enter_event_mode,
+                terminate_simulation
+            ),
+            |result| {
+                "enter_event_mode": *enter_event_mode,
+                "terminate_simulation": *terminate_simulation
+            },
+            status
+        )
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3SetTime",
+            { "time": time },
+            self.inner.set_time(time),
+            |result| {},
+            status
+        )
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3SetContinuousStates",
+            { "states": states },
+            self.inner.set_continuous_states(states),
+            |result| {},
+            status
+        )
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetContinuousStates",
+            {},
+            self.inner.get_continuous_states(continuous_states),
+            |result| { "continuous_states": continuous_states },
+            status
+        )
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetContinuousStateDerivatives",
+            {},
+            self.inner.get_continuous_state_derivatives(derivatives),
+            |result| { "derivatives": derivatives },
+            status
+        )
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetNominalsOfContinuousStates",
+            {},
+            self.inner.get_nominals_of_continuous_states(nominals),
+            |result| { "nominals": nominals },
+            status
+        )
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetEventIndicators",
+            {},
+            self.inner.get_event_indicators(event_indicators),
+            |result| {
+                "event_indicators": event_indicators,
+                "computed": result.as_ref().ok()
+            },
+            value_status
+        )
+    }
+
+    fn get_number_of_event_indicators(&mut self) -> Result<usize, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetNumberOfEventIndicators",
+            {},
+            self.inner.get_number_of_event_indicators(),
+            |result| { "value": result.as_ref().ok() },
+            value_status
+        )
+    }
+
+    fn get_number_of_continuous_states(&mut self) -> Result<usize, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3GetNumberOfContinuousStates",
+            {},
+            self.inner.get_number_of_continuous_states(),
+            |result| { "value": result.as_ref().ok() },
+            value_status
+        )
+    }
+}
+
+impl<I: CoSimulation> CoSimulation for TracedInstance<I> {
+    fn enter_step_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3EnterStepMode",
+            {},
+            self.inner.enter_step_mode(),
+            |result| {},
+            status
+        )
+    }
+
+    fn do_step(
+        &mut self,
+        current_communication_point: f64,
+        communication_step_size: f64,
+        no_set_fmu_state_prior_to_current_point: bool,
+        event_handling_needed: &mut bool,
+        terminate_simulation: &mut bool,
+        early_return: &mut bool,
+        last_successful_time: &mut f64,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3DoStep",
+            {
+                "current_communication_point": current_communication_point,
+                "communication_step_size": communication_step_size,
+                "no_set_fmu_state_prior_to_current_point": no_set_fmu_state_prior_to_current_point
+            },
+            self.inner.do_step(
+                current_communication_point,
+                communication_step_size,
+                no_set_fmu_state_prior_to_current_point,
+                event_handling_needed,
+                terminate_simulation,
+                early_return,
+                last_successful_time,
+            ),
+            |result| {
+                "event_handling_needed": *event_handling_needed,
+                "terminate_simulation": *terminate_simulation,
+                "early_return": *early_return,
+                "last_successful_time": *last_successful_time
+            },
+            status
+        )
+    }
+}
+
+impl<I: ScheduledExecution> ScheduledExecution for TracedInstance<I> {
+    fn activate_model_partition(
+        &mut self,
+        clock_reference: Self::ValueRef,
+        activation_time: f64,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        traced_call!(
+            self.writer,
+            "fmi3ActivateModelPartition",
+            { "clock_reference": value_ref(clock_reference), "activation_time": activation_time },
+            self.inner
+                .activate_model_partition(clock_reference, activation_time),
+            |result| {},
+            status
+        )
+    }
+}
+
+impl<I> FmiInstance for TracedInstance<I>
+where
+    I: FmiInstance<Status = Fmi3Status> + Common,
+{
+    type ModelDescription = I::ModelDescription;
+    type ValueRef = <I as FmiInstance>::ValueRef;
+    type Status = Fmi3Status;
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn get_version(&self) -> &str {
+        Common::get_version(self)
+    }
+
+    fn interface_type(&self) -> InterfaceType {
+        self.inner.interface_type()
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        logging_on: bool,
+        categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::set_debug_logging(self, logging_on, categories)
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        tolerance: Option<f64>,
+        start_time: f64,
+        stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_initialization_mode(self, tolerance, start_time, stop_time)
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::exit_initialization_mode(self)
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::terminate(self)
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::reset(self)
+    }
+}
+
+impl<I> FmiModelExchange for TracedInstance<I>
+where
+    I: FmiInstance<Status = Fmi3Status> + ModelExchange,
+{
+    fn enter_continuous_time_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::enter_continuous_time_mode(self)
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+
+    fn completed_integrator_step(
+        &mut self,
+        no_set_fmu_state_prior: bool,
+        enter_event_mode: &mut bool,
+        terminate_simulation: &mut bool,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::completed_integrator_step(
+            self,
+            no_set_fmu_state_prior,
+            enter_event_mode,
+            terminate_simulation,
+        )
+    }
+
+    fn set_time(&mut self, time: f64) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::set_time(self, time)
+    }
+
+    fn get_continuous_states(
+        &mut self,
+        continuous_states: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_continuous_states(self, continuous_states)
+    }
+
+    fn set_continuous_states(&mut self, states: &[f64]) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::set_continuous_states(self, states)
+    }
+
+    fn get_continuous_state_derivatives(
+        &mut self,
+        derivatives: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_continuous_state_derivatives(self, derivatives)
+    }
+
+    fn get_nominals_of_continuous_states(
+        &mut self,
+        nominals: &mut [f64],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        ModelExchange::get_nominals_of_continuous_states(self, nominals)
+    }
+
+    fn get_event_indicators(&mut self, event_indicators: &mut [f64]) -> Result<bool, Fmi3Error> {
+        ModelExchange::get_event_indicators(self, event_indicators)
+    }
+}
+
+impl<I> FmiEventHandler for TracedInstance<I>
+where
+    I: FmiInstance<Status = Fmi3Status> + Common,
+{
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        Common::enter_event_mode(self)
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        Common::update_discrete_states(self, event_flags)
+    }
+}
+
+/// Re-issue the recorded `calls` against a Model Exchange `instance`, and compare the results.
+///
+/// See [`crate::trace::compare_calls`] for the meaning of `tolerance`.
+pub fn replay_me<I: ModelExchange>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+) -> Result<Vec<Mismatch>, Error> {
+    replay_with(instance, calls, tolerance, replay_model_exchange)
+}
+
+/// Re-issue the recorded `calls` against a Co-Simulation `instance`, and compare the results.
+///
+/// See [`crate::trace::compare_calls`] for the meaning of `tolerance`.
+pub fn replay_cs<I: CoSimulation>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+) -> Result<Vec<Mismatch>, Error> {
+    replay_with(instance, calls, tolerance, replay_co_simulation)
+}
+
+/// Re-issue the recorded `calls` against a Scheduled Execution `instance`, and compare the
+/// results.
+///
+/// See [`crate::trace::compare_calls`] for the meaning of `tolerance`.
+pub fn replay_se<I: ScheduledExecution>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+) -> Result<Vec<Mismatch>, Error> {
+    replay_with(instance, calls, tolerance, replay_scheduled_execution)
+}
+
+/// Re-issues a call, returns `false` for functions of another interface
+type ReplayFn<I> = fn(&mut TracedInstance<I>, &TraceCall) -> Result<bool, Error>;
+
+fn replay_with<I>(
+    instance: I,
+    calls: &[TraceCall],
+    tolerance: f64,
+    replay_call: ReplayFn<I>,
+) -> Result<Vec<Mismatch>, Error> {
+    let mut traced = TracedInstance {
+        inner: instance,
+        writer: TraceWriter::memory(),
+    };
+    let mut mismatches = Vec::new();
+    for call in calls {
+        if !replay_call(&mut traced, call)? {
+            return Err(Error::Trace(format!(
+                "call {}: `{}` can not be replayed on this interface",
+                call.seq, call.function
+            )));
+        }
+        let replayed = traced
+            .writer
+            .take_calls()
+            .pop()
+            .expect("the replayed call is recorded");
+        mismatches.extend(compare_calls(call, &replayed, tolerance));
+    }
+    Ok(mismatches)
+}
+
+fn arg_value_refs<VR: From<binding::fmi3ValueReference>>(
+    call: &TraceCall,
+    name: &str,
+) -> Result<Vec<VR>, Error> {
+    Ok(arg::<Vec<binding::fmi3ValueReference>>(call, name)?
+        .into_iter()
+        .map(VR::from)
+        .collect())
+}
+
+fn arg_strings(call: &TraceCall, name: &str) -> Result<Vec<CString>, Error> {
+    arg::<Vec<String>>(call, name)?
+        .into_iter()
+        .map(|s| CString::new(s).map_err(|e| Error::Trace(e.to_string())))
+        .collect()
+}
+
+/// Buffer for the output `name` of a replayed call, sized like the recorded output, or like the
+/// value references if the recorded call failed.
+fn output_buffer<T: Clone + Default>(call: &TraceCall, name: &str, vrs: usize) -> Vec<T> {
+    let len = match output_len(call, name) {
+        0 if call.outputs.is_empty() => vrs,
+        len => len,
+    };
+    vec![T::default(); len]
+}
+
+macro_rules! replay_getter_setter {
+    ($instance:ident, $call:ident, $($name:ident, $ty:ty, $fmi:literal;)*) => {
+        paste::paste! {
+            match $call.function.as_str() {
+                $(
+                    concat!("fmi3Get", $fmi) => {
+                        let vrs = arg_value_refs(&$call, "vrs")?;
+                        let mut values = output_buffer::<$ty>(&$call, "values", vrs.len());
+                        let _ = $instance.[<get_ $name>](&vrs, &mut values);
+                        return Ok(true);
+                    }
+                    concat!("fmi3Set", $fmi) => {
+                        let vrs = arg_value_refs(&$call, "vrs")?;
+                        let values: Vec<$ty> = arg(&$call, "values")?;
+                        let _ = $instance.[<set_ $name>](&vrs, &values);
+                        return Ok(true);
+                    }
+                )*
+                _ => {}
+            }
+        }
+    };
+}
+
+fn replay_common<I: Common>(
+    instance: &mut TracedInstance<I>,
+    call: &TraceCall,
+) -> Result<bool, Error> {
+    replay_getter_setter!(
+        instance, call,
+        boolean, bool, "Boolean";
+        float32, f32, "Float32";
+        float64, f64, "Float64";
+        int8, i8, "Int8";
+        int16, i16, "Int16";
+        int32, i32, "Int32";
+        int64, i64, "Int64";
+        uint8, u8, "UInt8";
+        uint16, u16, "UInt16";
+        uint32, u32, "UInt32";
+        uint64, u64, "UInt64";
+        clock, binding::fmi3Clock, "Clock";
+    );
+    match call.function.as_str() {
+        "fmi3GetString" => {
+            let vrs = arg_value_refs(call, "vrs")?;
+            let mut values = output_buffer::<CString>(call, "values", vrs.len());
+            let _ = instance.get_string(&vrs, &mut values);
+        }
+        "fmi3SetString" => {
+            let vrs = arg_value_refs(call, "vrs")?;
+            let values = arg_strings(call, "values")?;
+            let _ = instance.set_string(&vrs, &values);
+        }
+        "fmi3GetBinary" => {
+            let vrs = arg_value_refs(call, "vrs")?;
+            let mut buffers: Vec<Vec<u8>> = match call.outputs.get("values") {
+                Some(values) => serde_json::from_value::<Vec<Vec<u8>>>(values.clone())
+                    .map_err(|e| Error::Trace(e.to_string()))?,
+                None => vec![Vec::new(); vrs.len()],
+            };
+            let mut values: Vec<&mut [u8]> = buffers.iter_mut().map(Vec::as_mut_slice).collect();
+            let _ = instance.get_binary(&vrs, &mut values);
+        }
+        "fmi3SetBinary" => {
+            let vrs = arg_value_refs(call, "vrs")?;
+            let buffers: Vec<Vec<u8>> = arg(call, "values")?;
+            let values: Vec<&[u8]> = buffers.iter().map(Vec::as_slice).collect();
+            let _ = instance.set_binary(&vrs, &values);
+        }
+        "fmi3SetDebugLogging" => {
+            let categories: Vec<String> = arg(call, "categories")?;
+            let categories: Vec<&str> = categories.iter().map(String::as_str).collect();
+            let _ = instance.set_debug_logging(arg(call, "logging_on")?, &categories);
+        }
+        "fmi3EnterConfigurationMode" => {
+            let _ = instance.enter_configuration_mode();
+        }
+        "fmi3ExitConfigurationMode" => {
+            let _ = instance.exit_configuration_mode();
+        }
+        "fmi3EnterInitializationMode" => {
+            let _ = instance.enter_initialization_mode(
+                arg(call, "tolerance")?,
+                arg(call, "start_time")?,
+                arg(call, "stop_time")?,
+            );
+        }
+        "fmi3ExitInitializationMode" => {
+            let _ = instance.exit_initialization_mode();
+        }
+        "fmi3EnterEventMode" => {
+            let _ = Common::enter_event_mode(instance);
+        }
+        "fmi3Terminate" => {
+            let _ = instance.terminate();
+        }
+        "fmi3Reset" => {
+            let _ = instance.reset();
+        }
+        "fmi3UpdateDiscreteStates" => {
+            let _ = Common::update_discrete_states(instance, &mut EventFlags::default());
+        }
+        "fmi3GetNumberOfVariableDependencies" => {
+            let vr: binding::fmi3ValueReference = arg(call, "vr")?;
+            let _ = instance.get_number_of_variable_dependencies(vr.into());
+        }
+        "fmi3GetVariableDependencies" => {
+            let dependent: binding::fmi3ValueReference = arg(call, "dependent")?;
+            let _ = instance.get_variable_dependencies(dependent.into());
+        }
+        "fmi3GetDirectionalDerivative" | "fmi3GetAdjointDerivative" => {
+            let unknowns = arg_value_refs(call, "unknowns")?;
+            let knowns = arg_value_refs(call, "knowns")?;
+            let seed: Vec<f64> = arg(call, "seed")?;
+            let mut sensitivity = output_buffer::<f64>(call, "sensitivity", 0);
+            let _ = if call.function == "fmi3GetDirectionalDerivative" {
+                instance.get_directional_derivative(&unknowns, &knowns, &seed, &mut sensitivity)
+            } else {
+                instance.get_adjoint_derivative(&unknowns, &knowns, &seed, &mut sensitivity)
+            };
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn replay_model_exchange<I: ModelExchange>(
+    instance: &mut TracedInstance<I>,
+    call: &TraceCall,
+) -> Result<bool, Error> {
+    if replay_common(instance, call)? {
+        return Ok(true);
+    }
+    match call.function.as_str() {
+        "fmi3EnterContinuousTimeMode" => {
+            let _ = ModelExchange::enter_continuous_time_mode(instance);
+        }
+        "fmi3CompletedIntegratorStep" => {
+            let _ = ModelExchange::completed_integrator_step(
+                instance,
+                arg(call, "no_set_fmu_state_prior")?,
+                &mut false,
+                &mut false,
+            );
+        }
+        "fmi3SetTime" => {
+            let _ = ModelExchange::set_time(instance, arg(call, "time")?);
+        }
+        "fmi3SetContinuousStates" => {
+            let states: Vec<f64> = arg(call, "states")?;
+            let _ = ModelExchange::set_continuous_states(instance, &states);
+        }
+        "fmi3GetContinuousStates" => {
+            let mut values = output_buffer(call, "continuous_states", 0);
+            let _ = ModelExchange::get_continuous_states(instance, &mut values);
+        }
+        "fmi3GetContinuousStateDerivatives" => {
+            let mut values = output_buffer(call, "derivatives", 0);
+            let _ = ModelExchange::get_continuous_state_derivatives(instance, &mut values);
+        }
+        "fmi3GetNominalsOfContinuousStates" => {
+            let mut values = output_buffer(call, "nominals", 0);
+            let _ = ModelExchange::get_nominals_of_continuous_states(instance, &mut values);
+        }
+        "fmi3GetEventIndicators" => {
+            let mut values = output_buffer(call, "event_indicators", 0);
+            let _ = ModelExchange::get_event_indicators(instance, &mut values);
+        }
+        "fmi3GetNumberOfEventIndicators" => {
+            let _ = instance.get_number_of_event_indicators();
+        }
+        "fmi3GetNumberOfContinuousStates" => {
+            let _ = instance.get_number_of_continuous_states();
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn replay_co_simulation<I: CoSimulation>(
+    instance: &mut TracedInstance<I>,
+    call: &TraceCall,
+) -> Result<bool, Error> {
+    if replay_common(instance, call)? {
+        return Ok(true);
+    }
+    match call.function.as_str() {
+        "fmi3EnterStepMode" => {
+            let _ = instance.enter_step_mode();
+        }
+        "fmi3DoStep" => {
+            let _ = instance.do_step(
+                arg(call, "current_communication_point")?,
+                arg(call, "communication_step_size")?,
+                arg(call, "no_set_fmu_state_prior_to_current_point")?,
+                &mut false,
+                &mut false,
+                &mut false,
+                &mut 0.0,
+            );
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn replay_scheduled_execution<I: ScheduledExecution>(
+    instance: &mut TracedInstance<I>,
+    call: &TraceCall,
+) -> Result<bool, Error> {
+    if replay_common(instance, call)? {
+        return Ok(true);
+    }
+    match call.function.as_str() {
+        "fmi3ActivateModelPartition" => {
+            let clock_reference: binding::fmi3ValueReference = arg(call, "clock_reference")?;
+            let _ = instance
+                .activate_model_partition(clock_reference.into(), arg(call, "activation_time")?);
+        }
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
@@ -95,17 +95,6 @@ pub trait GetSet {
         unimplemented!()
     }
 
-    /// See [https://fmi-standard.org/docs/3.0.1/#fmi3GetFMUState]
-    #[cfg(false)]
-    fn get_fmu_state<Tag>(
-        &mut self,
-        state: Option<Fmu3State<'_, Tag>>,
-    ) -> Result<Fmu3State<'_, Tag>, Error>;
-
-    /// See [https://fmi-standard.org/docs/3.0.1/#fmi3SetFMUState]
-    #[cfg(false)]
-    fn set_fmu_state<Tag>(&mut self, state: &Fmu3State<'_, Tag>) -> Fmi3Status;
-
     fn get_clock(
         &mut self,
         vrs: &[Self::ValueRef],
@@ -239,6 +228,54 @@ pub trait Common: GetSet {
         &mut self,
         dependent: Self::ValueRef,
     ) -> Result<Vec<VariableDependency<Self::ValueRef>>, Fmi3Error>;
+
+    /// Compute the directional derivatives `sensitivity = J * seed` of the partial derivative
+    /// matrix `J` of the `unknowns` with respect to the `knowns`.
+    ///
+    /// Requires the capability flag `providesDirectionalDerivatives = true`.
+    ///
+    /// Arguments:
+    /// * `unknowns`: value references of the unknowns (for example, outputs or state derivatives).
+    /// * `knowns`: value references of the knowns (for example, inputs or states).
+    /// * `seed`: one value for each scalar element of the `knowns`.
+    /// * `sensitivity`: receives one value for each scalar element of the `unknowns`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetDirectionalDerivative>
+    fn get_directional_derivative(
+        &mut self,
+        _unknowns: &[Self::ValueRef],
+        _knowns: &[Self::ValueRef],
+        _seed: &[f64],
+        _sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        Err(Error::UnsupportedCapability(
+            "providesDirectionalDerivatives".to_string(),
+        ))
+    }
+
+    /// Compute the adjoint derivatives `sensitivity = seed^T * J` of the partial derivative
+    /// matrix `J` of the `unknowns` with respect to the `knowns`.
+    ///
+    /// Requires the capability flag `providesAdjointDerivatives = true`.
+    ///
+    /// Arguments:
+    /// * `unknowns`: value references of the unknowns (for example, outputs or state derivatives).
+    /// * `knowns`: value references of the knowns (for example, inputs or states).
+    /// * `seed`: one value for each scalar element of the `unknowns`.
+    /// * `sensitivity`: receives one value for each scalar element of the `knowns`.
+    ///
+    /// See <https://fmi-standard.org/docs/3.0.1/#fmi3GetAdjointDerivative>
+    fn get_adjoint_derivative(
+        &mut self,
+        _unknowns: &[Self::ValueRef],
+        _knowns: &[Self::ValueRef],
+        _seed: &[f64],
+        _sensitivity: &mut [f64],
+    ) -> Result<Fmi3Res, Error> {
+        Err(Error::UnsupportedCapability(
+            "providesAdjointDerivatives".to_string(),
+        ))
+    }
 }
 
 /// Interface for Model Exchange instances
@@ -463,3 +500,106 @@ pub trait Fmi3Model {
         ))
     }
 }
+
+/// The instance type of the interfaces an [`Fmi3Model`] does not support, which can not be
+/// created.
+#[derive(Debug)]
+pub enum Unsupported {}
+
+impl GetSet for Unsupported {
+    type ValueRef = binding::fmi3ValueReference;
+
+    fn get_clock(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &mut [binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn set_clock(
+        &mut self,
+        _vrs: &[Self::ValueRef],
+        _values: &[binding::fmi3Clock],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+}
+
+impl Common for Unsupported {
+    fn get_version(&self) -> &str {
+        match *self {}
+    }
+
+    fn set_debug_logging(
+        &mut self,
+        _logging_on: bool,
+        _categories: &[&str],
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn enter_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn exit_configuration_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn enter_initialization_mode(
+        &mut self,
+        _tolerance: Option<f64>,
+        _start_time: f64,
+        _stop_time: Option<f64>,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn exit_initialization_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn enter_event_mode(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn terminate(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn reset(&mut self) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn update_discrete_states(
+        &mut self,
+        _event_flags: &mut EventFlags,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+
+    fn get_number_of_variable_dependencies(
+        &mut self,
+        _vr: Self::ValueRef,
+    ) -> Result<usize, Fmi3Error> {
+        match *self {}
+    }
+
+    fn get_variable_dependencies(
+        &mut self,
+        _dependent: Self::ValueRef,
+    ) -> Result<Vec<VariableDependency<Self::ValueRef>>, Fmi3Error> {
+        match *self {}
+    }
+}
+
+impl ScheduledExecution for Unsupported {
+    fn activate_model_partition(
+        &mut self,
+        _clock_reference: Self::ValueRef,
+        _activation_time: f64,
+    ) -> Result<Fmi3Res, Fmi3Error> {
+        match *self {}
+    }
+}
@@ -0,0 +1,362 @@
+//! Typed, name-based access to FMI 3.0 variables.
+//!
+//! A [`Variable`] is resolved once from the [`schema::Fmi3ModelDescription`] and can then be used
+//! to get and set values on any instance of the FMU without further lookups:
+//!
+//! ```rust,no_run
+//! # use fmi::fmi3::{Fmi3Model, import::Fmi3Import};
+//! # fn example(import: &Fmi3Import) -> Result<(), fmi::Error> {
+//! let h = import.variable::<f64>("h")?;
+//! let mut inst = import.instantiate_me("inst", false, true)?;
+//! let height = h.get(&mut inst)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::{
+    Error,
+    fmi3::{Fmi3Error, Fmi3Res, GetSet, binding, schema},
+};
+
+use schema::{ArrayableVariableTrait, Dimension, VariableType};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Rust types that map onto an FMI 3.0 variable type.
+///
+/// This trait is sealed, it is implemented for `bool`, `f32`, `f64` and the fixed-size integers.
+pub trait Fmi3Type: Copy + Default + private::Sealed {
+    /// The FMI type a variable must be declared with to be accessed as `Self`
+    const VARIABLE_TYPE: VariableType;
+
+    #[doc(hidden)]
+    fn get_values<I: GetSet>(
+        instance: &mut I,
+        vrs: &[I::ValueRef],
+        values: &mut [Self],
+    ) -> Result<Fmi3Res, Fmi3Error>;
+
+    #[doc(hidden)]
+    fn set_values<I: GetSet>(
+        instance: &mut I,
+        vrs: &[I::ValueRef],
+        values: &[Self],
+    ) -> Result<Fmi3Res, Fmi3Error>;
+}
+
+macro_rules! impl_fmi3_type {
+    ($ty:ty, $variable_type:ident, $get:ident, $set:ident) => {
+        impl private::Sealed for $ty {}
+
+        impl Fmi3Type for $ty {
+            const VARIABLE_TYPE: VariableType = VariableType::$variable_type;
+
+            fn get_values<I: GetSet>(
+                instance: &mut I,
+                vrs: &[I::ValueRef],
+                values: &mut [Self],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                instance.$get(vrs, values)
+            }
+
+            fn set_values<I: GetSet>(
+                instance: &mut I,
+                vrs: &[I::ValueRef],
+                values: &[Self],
+            ) -> Result<Fmi3Res, Fmi3Error> {
+                instance.$set(vrs, values)
+            }
+        }
+    };
+}
+
+impl_fmi3_type!(bool, FmiBoolean, get_boolean, set_boolean);
+impl_fmi3_type!(f32, FmiFloat32, get_float32, set_float32);
+impl_fmi3_type!(f64, FmiFloat64, get_float64, set_float64);
+impl_fmi3_type!(i8, FmiInt8, get_int8, set_int8);
+impl_fmi3_type!(i16, FmiInt16, get_int16, set_int16);
+impl_fmi3_type!(i32, FmiInt32, get_int32, set_int32);
+impl_fmi3_type!(i64, FmiInt64, get_int64, set_int64);
+impl_fmi3_type!(u8, FmiUInt8, get_uint8, set_uint8);
+impl_fmi3_type!(u16, FmiUInt16, get_uint16, set_uint16);
+impl_fmi3_type!(u32, FmiUInt32, get_uint32, set_uint32);
+impl_fmi3_type!(u64, FmiUInt64, get_uint64, set_uint64);
+
+/// The name of the XML element declaring a variable of type `variable_type`.
+pub(crate) fn type_name(variable_type: VariableType) -> &'static str {
+    match variable_type {
+        VariableType::FmiFloat32 => "Float32",
+        VariableType::FmiFloat64 => "Float64",
+        VariableType::FmiInt8 => "Int8",
+        VariableType::FmiUInt8 => "UInt8",
+        VariableType::FmiInt16 => "Int16",
+        VariableType::FmiUInt16 => "UInt16",
+        VariableType::FmiInt32 => "Int32",
+        VariableType::FmiUInt32 => "UInt32",
+        VariableType::FmiInt64 => "Int64",
+        VariableType::FmiUInt64 => "UInt64",
+        VariableType::FmiBoolean => "Boolean",
+        VariableType::FmiString => "String",
+        VariableType::FmiBinary => "Binary",
+        VariableType::FmiClock => "Clock",
+    }
+}
+
+/// The declared dimensions of `variable`, empty for scalars and Clocks.
+pub(crate) fn variable_dimensions(variable: &schema::Variable) -> &[Dimension] {
+    match variable {
+        schema::Variable::Int8(v) => v.dimensions(),
+        schema::Variable::UInt8(v) => v.dimensions(),
+        schema::Variable::Int16(v) => v.dimensions(),
+        schema::Variable::UInt16(v) => v.dimensions(),
+        schema::Variable::Int32(v) => v.dimensions(),
+        schema::Variable::UInt32(v) => v.dimensions(),
+        schema::Variable::Int64(v) => v.dimensions(),
+        schema::Variable::UInt64(v) => v.dimensions(),
+        schema::Variable::Float32(v) => v.dimensions(),
+        schema::Variable::Float64(v) => v.dimensions(),
+        schema::Variable::Boolean(v) => v.dimensions(),
+        schema::Variable::String(v) => v.dimensions(),
+        schema::Variable::Binary(v) => v.dimensions(),
+        schema::Variable::Enumeration(v) => v.dimensions(),
+        schema::Variable::Clock(_) => &[],
+    }
+}
+
+/// Find the variable named `name` in `model_description`.
+pub(crate) fn find_variable<'a>(
+    model_description: &'a schema::Fmi3ModelDescription,
+    name: &str,
+) -> Result<&'a schema::Variable, Error> {
+    model_description
+        .model_variables
+        .variables
+        .iter()
+        .find(|variable| variable.as_abstract().name() == name)
+        .ok_or_else(|| Error::UnknownVariable {
+            name: name.to_owned(),
+        })
+}
+
+/// A model description with its variables indexed by name and value reference.
+///
+/// Built once per import and shared with its instances, so that looking up a variable does not
+/// scan the model variables.
+#[derive(Debug)]
+pub(crate) struct IndexedModelDescription {
+    model_description: schema::Fmi3ModelDescription,
+    /// Position in `model_variables.variables` of each variable name
+    by_name: HashMap<String, usize>,
+    /// Position in `model_variables.variables` of each value reference
+    by_value_reference: HashMap<binding::fmi3ValueReference, usize>,
+}
+
+impl IndexedModelDescription {
+    pub(crate) fn new(model_description: schema::Fmi3ModelDescription) -> Self {
+        let variables = &model_description.model_variables.variables;
+        let by_name = variables
+            .iter()
+            .enumerate()
+            .map(|(index, variable)| (variable.as_abstract().name().to_owned(), index))
+            .collect();
+        let by_value_reference = variables
+            .iter()
+            .enumerate()
+            .map(|(index, variable)| (variable.as_abstract().value_reference(), index))
+            .collect();
+        Self {
+            model_description,
+            by_name,
+            by_value_reference,
+        }
+    }
+
+    /// Find the variable named `name`.
+    pub(crate) fn variable(&self, name: &str) -> Result<&schema::Variable, Error> {
+        self.by_name
+            .get(name)
+            .map(|&index| &self.model_description.model_variables.variables[index])
+            .ok_or_else(|| Error::UnknownVariable {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Find the variable with the value reference `vr`.
+    pub(crate) fn variable_by_vr(
+        &self,
+        vr: binding::fmi3ValueReference,
+    ) -> Result<&schema::Variable, Error> {
+        self.by_value_reference
+            .get(&vr)
+            .map(|&index| &self.model_description.model_variables.variables[index])
+            .ok_or_else(|| Error::UnknownVariable {
+                name: format!("valueReference={vr}"),
+            })
+    }
+}
+
+impl std::ops::Deref for IndexedModelDescription {
+    type Target = schema::Fmi3ModelDescription;
+
+    fn deref(&self) -> &Self::Target {
+        &self.model_description
+    }
+}
+
+/// Resolve `dimensions` to sizes, reading the values of [`Dimension::Variable`] dimensions from
+/// `instance`.
+pub(crate) fn resolve_shape<I: GetSet>(
+    instance: &mut I,
+    dimensions: &[Dimension],
+) -> Result<Vec<usize>, Error> {
+    dimensions
+        .iter()
+        .map(|dim| match dim {
+            Dimension::Fixed(size) => Ok(*size as usize),
+            Dimension::Variable(vr) => {
+                let mut size = [0];
+                instance.get_uint64(&[(*vr).into()], &mut size)?;
+                Ok(size[0] as usize)
+            }
+        })
+        .collect()
+}
+
+/// A handle to a variable of the Rust type `T`, resolved from the model description.
+///
+/// Created with [`super::import::Fmi3Import::variable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Variable<T> {
+    name: String,
+    value_reference: binding::fmi3ValueReference,
+    dimensions: Vec<Dimension>,
+    _type: PhantomData<T>,
+}
+
+impl<T: Fmi3Type> Variable<T> {
+    /// Resolve the variable `name` in `model_description`.
+    ///
+    /// Fails with [`Error::VariableTypeMismatch`] when the variable is not declared with the FMI
+    /// type corresponding to `T`.
+    pub fn from_model_description(
+        model_description: &schema::Fmi3ModelDescription,
+        name: &str,
+    ) -> Result<Self, Error> {
+        Self::from_variable(find_variable(model_description, name)?)
+    }
+
+    /// Resolve the handle from the declaration of the variable.
+    pub(crate) fn from_variable(variable: &schema::Variable) -> Result<Self, Error> {
+        let abs = variable.as_abstract();
+        let name = abs.name();
+        if abs.data_type() != T::VARIABLE_TYPE {
+            return Err(Error::VariableTypeMismatch {
+                name: name.to_owned(),
+                declared: type_name(abs.data_type()).to_owned(),
+                requested: std::any::type_name::<T>().to_owned(),
+            });
+        }
+        Ok(Self {
+            name: name.to_owned(),
+            value_reference: abs.value_reference(),
+            dimensions: variable_dimensions(variable).to_vec(),
+            _type: PhantomData,
+        })
+    }
+
+    /// The name of the variable
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value reference of the variable
+    pub fn value_reference(&self) -> binding::fmi3ValueReference {
+        self.value_reference
+    }
+
+    /// The declared dimensions of the variable, empty for scalar variables.
+    pub fn dimensions(&self) -> &[Dimension] {
+        &self.dimensions
+    }
+
+    /// Whether the variable is an array
+    pub fn is_array(&self) -> bool {
+        !self.dimensions.is_empty()
+    }
+
+    /// The number of elements of the variable, if all of its dimensions are fixed.
+    pub fn fixed_len(&self) -> Option<usize> {
+        self.dimensions
+            .iter()
+            .map(|d| d.as_fixed().map(|size| size as usize))
+            .product()
+    }
+
+    fn check_scalar(&self) -> Result<(), Error> {
+        if self.is_array() {
+            Err(Error::InvalidVariable {
+                name: self.name.clone(),
+                reason: "array variables must be accessed with get_array/set_array".to_owned(),
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The size of each dimension of the variable, with [`Dimension::Variable`] dimensions
+    /// resolved from the current values of the structural parameters of `instance`.
+    pub fn shape<I: GetSet>(&self, instance: &mut I) -> Result<Vec<usize>, Error> {
+        resolve_shape(instance, &self.dimensions)
+    }
+
+    fn check_array_len<I: GetSet>(&self, instance: &mut I, found: usize) -> Result<(), Error> {
+        let expected = self.shape(instance)?.iter().product();
+        if expected == found {
+            Ok(())
+        } else {
+            Err(Error::BufferLength {
+                buffer: self.name.clone(),
+                expected,
+                found,
+            })
+        }
+    }
+
+    /// Get the value of a scalar variable.
+    pub fn get<I: GetSet>(&self, instance: &mut I) -> Result<T, Error> {
+        self.check_scalar()?;
+        let mut value = [T::default()];
+        T::get_values(instance, &[self.value_reference.into()], &mut value)?;
+        Ok(value[0])
+    }
+
+    /// Set the value of a scalar variable.
+    pub fn set<I: GetSet>(&self, instance: &mut I, value: T) -> Result<Fmi3Res, Error> {
+        self.check_scalar()?;
+        T::set_values(instance, &[self.value_reference.into()], &[value]).map_err(Error::from)
+    }
+
+    /// Get the values of an array variable, serialized in row-major order.
+    ///
+    /// For scalar variables, `values` must hold a single element.
+    pub fn get_array<I: GetSet>(
+        &self,
+        instance: &mut I,
+        values: &mut [T],
+    ) -> Result<Fmi3Res, Error> {
+        self.check_array_len(instance, values.len())?;
+        T::get_values(instance, &[self.value_reference.into()], values).map_err(Error::from)
+    }
+
+    /// Set the values of an array variable, serialized in row-major order.
+    ///
+    /// For scalar variables, `values` must hold a single element.
+    pub fn set_array<I: GetSet>(&self, instance: &mut I, values: &[T]) -> Result<Fmi3Res, Error> {
+        self.check_array_len(instance, values.len())?;
+        T::set_values(instance, &[self.value_reference.into()], values).map_err(Error::from)
+    }
+}
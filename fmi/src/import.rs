@@ -37,6 +37,11 @@
 //! // Check the FMI version without full extraction
 //! let model_desc = import::peek_descr_path("path/to/model.fmu")?;
 //! match model_desc.major_version()? {
+//!     #[cfg(feature = "fmi1")]
+//!     MajorVersion::FMI1 => {
+//!         let import: fmi::fmi1::import::Fmi1Import = import::from_path("path/to/model.fmu")?;
+//!         // Work with FMI 1.0 import...
+//!     }
 //!     MajorVersion::FMI2 => {
 //!         let import: fmi::fmi2::import::Fmi2Import = import::from_path("path/to/model.fmu")?;
 //!         // Work with FMI 2.0 import...
@@ -50,6 +55,35 @@
 //! # Ok::<(), fmi::Error>(())
 //! ```
 //!
+//! ### Pre-extracted FMUs and the Extraction Cache
+//!
+//! ```rust,no_run
+//! use fmi::{import, fmi3::import::Fmi3Import, traits::FmiImport};
+//!
+//! // Use an FMU that was already unpacked, the directory is left in place
+//! let import: Fmi3Import = import::from_dir("path/to/unpacked/model")?;
+//! assert!(!import.dir().is_owned());
+//! # Ok::<(), fmi::Error>(())
+//! ```
+//!
+//! With the `cache` feature, [`ExtractionCache`] reuses one extraction for all imports of the same
+//! archive.
+//!
+//! ### Source Code FMUs
+//!
+//! With the `build` feature, FMI 3.0 FMUs without a binary for the host platform can be compiled
+//! from their sources when the shared library is first loaded:
+//!
+//! ```rust,no_run
+//! # #[cfg(feature = "build")] {
+//! use fmi::{import, fmi3::import::Fmi3Import};
+//!
+//! let mut import: Fmi3Import = import::from_path("path/to/model.fmu")?;
+//! import.set_build_from_sources(true);
+//! # }
+//! # Ok::<(), fmi::Error>(())
+//! ```
+//!
 //! ### Working with In-Memory FMU Data
 //!
 //! ```rust,no_run
@@ -77,12 +111,13 @@
 //! ## Related Modules
 //!
 //! - [`crate::traits::FmiImport`] - Core import trait implemented by version-specific imports
+//! - `crate::fmi1::import` - FMI 1.0 specific import functionality (with the `fmi1` feature)
 //! - [`crate::fmi2::import`] - FMI 2.0 specific import functionality
 //! - [`crate::fmi3::import`] - FMI 3.0 specific import functionality
 
 use std::{
     io::{Read, Seek},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 use crate::{Error, traits::FmiImport};
@@ -97,6 +132,57 @@ use fmi_schema::{minimal::MinModelDescription as MinModel, traits::FmiModelDescr
 /// and platform-specific information.
 const MODEL_DESCRIPTION: &str = "modelDescription.xml";
 
+/// How the shared library of an FMU is loaded for each new instance.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LibraryMode {
+    /// All instances load the shared library of the extracted FMU and share its global state.
+    ///
+    /// FMUs that declare `canBeInstantiatedOnlyOncePerProcess` can only have a single live
+    /// instance in this mode, further instantiations fail with
+    /// [`Error::InstantiatedOncePerProcess`].
+    #[default]
+    Shared,
+    /// Each instance loads its own copy of the shared library, so that instances do not share any
+    /// global state. The copy is placed next to the original and removed with the instance.
+    ///
+    /// The copies are written into the FMU directory even if it is managed elsewhere, as with
+    /// [`from_dir`] or the extraction cache.
+    CopyPerInstance,
+}
+
+/// Directory holding the extracted contents of an FMU.
+///
+/// Tracks whether the import owns the directory, and thereby whether it is deleted when the import
+/// is dropped.
+#[derive(Debug)]
+pub enum FmuDir {
+    /// Temporary directory owned by the import, deleted on drop
+    Temp(tempfile::TempDir),
+    /// Directory managed elsewhere (pre-extracted or cached), left in place on drop
+    External(PathBuf),
+}
+
+impl FmuDir {
+    /// Path to the extracted FMU
+    pub fn path(&self) -> &Path {
+        match self {
+            FmuDir::Temp(dir) => dir.path(),
+            FmuDir::External(path) => path,
+        }
+    }
+
+    /// Whether the directory is owned by the import and deleted along with it
+    pub fn is_owned(&self) -> bool {
+        matches!(self, FmuDir::Temp(_))
+    }
+}
+
+impl From<tempfile::TempDir> for FmuDir {
+    fn from(dir: tempfile::TempDir) -> Self {
+        FmuDir::Temp(dir)
+    }
+}
+
 /// Quickly inspect an FMU's model description without full extraction.
 ///
 /// This function opens an FMU file and reads only the `modelDescription.xml` file
@@ -349,5 +435,120 @@ pub fn new<R: Read + Seek, Imp: FmiImport>(reader: R) -> Result<Imp, Error> {
     let descr_file_path = temp_dir.path().join(MODEL_DESCRIPTION);
     let descr_xml = std::fs::read_to_string(descr_file_path)?;
 
-    Imp::new(temp_dir, &descr_xml)
+    Imp::new(temp_dir.into(), &descr_xml)
+}
+
+/// Import an FMU that has already been extracted to `dir`.
+///
+/// The directory must contain the `modelDescription.xml` at its root. It is not deleted when the
+/// import is dropped, and is only modified when a shared library is written into `binaries/`:
+/// copies of the library with [`LibraryMode::CopyPerInstance`], or a library built from the
+/// sources of an FMI 3.0 FMU.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use fmi::{import, fmi2::import::Fmi2Import};
+///
+/// let import: Fmi2Import = import::from_dir("path/to/unpacked/model")?;
+/// # Ok::<(), fmi::Error>(())
+/// ```
+///
+/// # See Also
+///
+/// - [`from_path`] for importing an FMU archive
+pub fn from_dir<Imp: FmiImport>(dir: impl AsRef<Path>) -> Result<Imp, Error> {
+    let dir = dir.as_ref();
+    let descr_file_path = dir.join(MODEL_DESCRIPTION);
+    if !descr_file_path.is_file() {
+        return Err(Error::ArchiveStructure(format!(
+            "{MODEL_DESCRIPTION} not found in {}",
+            dir.display()
+        )));
+    }
+    log::debug!("Importing extracted FMU from {dir:?}");
+    let descr_xml = std::fs::read_to_string(descr_file_path)?;
+    Imp::new(FmuDir::External(dir.to_path_buf()), &descr_xml)
+}
+
+/// On-disk cache of extracted FMUs, keyed by the SHA-256 hash of the archive.
+///
+/// Repeated imports of the same archive share one extraction under [`ExtractionCache::root`].
+/// Cached extractions are never deleted by the imports; use [`ExtractionCache::clear`] to remove
+/// them.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use fmi::{import::ExtractionCache, fmi3::import::Fmi3Import};
+///
+/// let cache = ExtractionCache::new(std::env::temp_dir().join("fmi-rs-cache"));
+/// let import: Fmi3Import = cache.import_path("path/to/model.fmu")?;
+/// # Ok::<(), fmi::Error>(())
+/// ```
+#[cfg(feature = "cache")]
+#[derive(Debug, Clone)]
+pub struct ExtractionCache {
+    root: PathBuf,
+}
+
+#[cfg(feature = "cache")]
+impl ExtractionCache {
+    /// Create a cache that stores extractions below `root`. The directory is created on demand.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// The directory holding the cached extractions
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Import the FMU archive at `path`, extracting it only if it is not cached yet.
+    pub fn import_path<Imp: FmiImport>(&self, path: impl AsRef<Path>) -> Result<Imp, Error> {
+        let file = std::fs::File::open(path.as_ref())?;
+        log::debug!("Opening FMU file {:?}", path.as_ref());
+        self.import(file)
+    }
+
+    /// Import the FMU archive read from `reader`, extracting it only if it is not cached yet.
+    pub fn import<R: Read + Seek, Imp: FmiImport>(&self, mut reader: R) -> Result<Imp, Error> {
+        use sha2::Digest;
+
+        let mut hasher = sha2::Sha256::new();
+        std::io::copy(&mut reader, &mut hasher)?;
+        let dir = self.root.join(format!("{:x}", hasher.finalize()));
+
+        if !dir.join(MODEL_DESCRIPTION).is_file() {
+            reader.rewind()?;
+            std::fs::create_dir_all(&self.root)?;
+            // Extract next to the final location and move it into place, so that concurrent
+            // imports never see a partial extraction.
+            let staging = tempfile::Builder::new()
+                .prefix(".extract")
+                .tempdir_in(&self.root)?;
+            log::debug!("Extracting into {staging:?}");
+            zip::ZipArchive::new(reader)?.extract(&staging)?;
+            match std::fs::rename(staging.path(), &dir) {
+                Ok(()) => {
+                    let _ = staging.keep();
+                }
+                // Another import cached the same archive in the meantime
+                Err(_) if dir.join(MODEL_DESCRIPTION).is_file() => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        from_dir(dir)
+    }
+
+    /// Remove all cached extractions.
+    ///
+    /// Imports created from this cache must not be used afterwards.
+    pub fn clear(&self) -> Result<(), Error> {
+        if self.root.exists() {
+            std::fs::remove_dir_all(&self.root)?;
+        }
+        Ok(())
+    }
 }
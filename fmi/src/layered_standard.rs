@@ -0,0 +1,139 @@
+//! Layered standards in the `extra/` directory of an FMU.
+//!
+//! Each layered standard places its files in `extra/<reverse-domain-name>/`, next to a manifest
+//! [`LayeredStandardManifest`]. Directories of `extra/` without a manifest hold tool-specific
+//! files and are not reported. Neither are directories whose manifest can not be parsed, they are
+//! logged as warnings instead. See
+//! [`FmiImport::layered_standards`](crate::traits::FmiImport::layered_standards).
+//!
+//! ```rust,no_run
+//! use fmi::{fmi3::import::Fmi3Import, import, traits::FmiImport};
+//!
+//! let import: Fmi3Import = import::from_path("path/to/model.fmu")?;
+//! if let Some(bus) = import.layered_standard("org.fmi-standard.fmi-ls-bus")? {
+//!     println!("LS-BUS {} in {}", bus.manifest.version, bus.path.display());
+//! }
+//! # Ok::<(), fmi::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+pub use fmi_schema::layered_standard::{LayeredStandardManifest, MANIFEST};
+
+use crate::Error;
+
+/// A layered standard of an extracted FMU
+#[derive(Debug, PartialEq)]
+pub struct LayeredStandard {
+    /// The parsed `fmi-ls-manifest.xml`
+    pub manifest: LayeredStandardManifest,
+    /// The directory of the layered standard in `extra/`
+    pub path: PathBuf,
+}
+
+impl LayeredStandard {
+    /// The files of the layered standard, relative to its directory and including the manifest
+    pub fn files(&self) -> Result<Vec<PathBuf>, Error> {
+        let mut files = Vec::new();
+        list_files(&self.path, Path::new(""), &mut files)?;
+        files.sort();
+        Ok(files)
+    }
+}
+
+fn list_files(dir: &Path, prefix: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = prefix.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            list_files(&entry.path(), &name, files)?;
+        } else {
+            files.push(name);
+        }
+    }
+    Ok(())
+}
+
+/// The layered standards in the `extra` directory, ordered by the names of their directories.
+/// Empty if there is no `extra` directory.
+///
+/// A malformed manifest is logged and skipped, so that it does not hide the other layered
+/// standards. Errors reading the directory are returned.
+pub fn read_layered_standards(extra: &Path) -> Result<Vec<LayeredStandard>, Error> {
+    if !extra.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut standards = Vec::new();
+    for entry in std::fs::read_dir(extra)? {
+        let path = entry?.path();
+        let manifest = path.join(MANIFEST);
+        if !manifest.is_file() {
+            continue;
+        }
+        match fmi_schema::deserialize(&std::fs::read_to_string(&manifest)?) {
+            Ok(manifest) => standards.push(LayeredStandard { manifest, path }),
+            Err(e) => log::warn!("Skipping malformed {}: {e}", manifest.display()),
+        }
+    }
+    standards.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(standards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_layered_standards() {
+        let dir = tempfile::tempdir().unwrap();
+        let extra = dir.path().join("extra");
+        assert_eq!(read_layered_standards(&extra).unwrap(), vec![]);
+
+        let bus = extra.join("org.fmi-standard.fmi-ls-bus");
+        std::fs::create_dir_all(bus.join("network")).unwrap();
+        std::fs::write(
+            bus.join(MANIFEST),
+            r#"<fmiLayeredStandardManifest xmlns:fmi-ls="http://fmi-standard.org/fmi-ls-manifest"
+    fmi-ls:fmi-ls-name="org.fmi-standard.fmi-ls-bus" fmi-ls:fmi-ls-version="1.0.0"
+    fmi-ls:fmi-ls-description="Network communication" isBusSimulationFMU="false"/>"#,
+        )
+        .unwrap();
+        std::fs::write(bus.join("network").join("can.dbc"), "").unwrap();
+        std::fs::create_dir_all(extra.join("com.example.tool")).unwrap();
+
+        let standards = read_layered_standards(&extra).unwrap();
+        assert_eq!(
+            standards,
+            vec![LayeredStandard {
+                manifest: LayeredStandardManifest {
+                    name: "org.fmi-standard.fmi-ls-bus".to_owned(),
+                    version: "1.0.0".to_owned(),
+                    description: Some("Network communication".to_owned()),
+                },
+                path: bus.clone(),
+            }]
+        );
+        assert_eq!(
+            standards[0].files().unwrap(),
+            vec![
+                PathBuf::from(MANIFEST),
+                Path::new("network").join("can.dbc")
+            ]
+        );
+
+        // A malformed manifest does not hide the other layered standards
+        let xcp = extra.join("org.fmi-standard.fmi-ls-xcp");
+        std::fs::create_dir_all(&xcp).unwrap();
+        std::fs::write(xcp.join(MANIFEST), "<fmiLayeredStandardManifest/>").unwrap();
+        let names = |standards: Vec<LayeredStandard>| {
+            standards
+                .into_iter()
+                .map(|standard| standard.manifest.name)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(read_layered_standards(&extra).unwrap()),
+            vec!["org.fmi-standard.fmi-ls-bus"]
+        );
+    }
+}
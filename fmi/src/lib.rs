@@ -1,5 +1,6 @@
 //! The `fmi` crate implements a Rust interface to FMUs (Functional Mockup Units) that follow FMI
-//! Standard. This version of the library supports FMI 2.0 and 3.0. See <http://www.fmi-standard.org/>
+//! Standard. This version of the library supports FMI 2.0 and 3.0, and FMI 1.0 with the `fmi1`
+//! feature. See <http://www.fmi-standard.org/>
 //!
 //! ## Examples
 //!
@@ -40,6 +41,12 @@
 //! let model_desc = import::peek_descr_path("path/to/model.fmu").unwrap();
 //! let version = model_desc.major_version().unwrap();
 //! match version {
+//!     #[cfg(feature = "fmi1")]
+//!     MajorVersion::FMI1 => {
+//!         // Load as FMI 1.0
+//!         let import: fmi::fmi1::import::Fmi1Import = import::from_path("path/to/model.fmu").unwrap();
+//!         // ... use import
+//!     }
 //!     MajorVersion::FMI2 => {
 //!         // Load as FMI 2.0
 //!         let import: fmi::fmi2::import::Fmi2Import = import::from_path("path/to/model.fmu").unwrap();
@@ -62,12 +69,20 @@ pub use fmi_schema as schema;
 use schema::MajorVersion;
 
 mod event_flags;
+#[cfg(feature = "fmi1")]
+pub mod fmi1;
 #[cfg(feature = "fmi2")]
 pub mod fmi2;
 #[cfg(feature = "fmi3")]
 pub mod fmi3;
 pub mod import;
+pub mod layered_standard;
+mod library;
+#[cfg(feature = "trace")]
+pub mod trace;
 pub mod traits;
+#[cfg(any(feature = "fmi2", feature = "fmi3"))]
+pub mod validation;
 
 pub use event_flags::EventFlags;
 
@@ -96,9 +111,54 @@ pub enum Error {
     #[error("Unsupported Interface type: {0}")]
     UnsupportedInterface(String),
 
+    #[error("Capability {0} not supported by this FMU")]
+    UnsupportedCapability(String),
+
+    #[error("Variable `{name}` is declared as {declared}, but was accessed as `{requested}`")]
+    VariableTypeMismatch {
+        name: String,
+        declared: String,
+        requested: String,
+    },
+
+    #[error(
+        "FMU `{0}` can only be instantiated once per process, consider LibraryMode::CopyPerInstance"
+    )]
+    InstantiatedOncePerProcess(String),
+
+    #[error("Invalid use of variable `{name}`: {reason}")]
+    InvalidVariable { name: String, reason: String },
+
+    #[error("Length of buffer `{buffer}` is {found}, expected {expected}")]
+    BufferLength {
+        buffer: String,
+        expected: usize,
+        found: usize,
+    },
+
     #[error("FMI version of loaded API ({found}) doesn't match expected ({expected})")]
     FmiVersionMismatch { found: String, expected: String },
 
+    #[cfg(feature = "remote")]
+    #[error("Remote FMU worker failed: {0}")]
+    RemoteWorker(String),
+
+    #[cfg(feature = "remote")]
+    #[error("Remote FMU worker did not respond within {0:?}")]
+    RemoteTimeout(std::time::Duration),
+
+    #[cfg(feature = "trace")]
+    #[error("Invalid FMI trace: {0}")]
+    Trace(String),
+
+    #[cfg(feature = "build")]
+    #[error("Failed to build FMU from source code: {0}")]
+    Build(String),
+
+    #[cfg(feature = "mock")]
+    #[error("Invalid step size {0}, expected a positive, finite number")]
+    InvalidStepSize(f64),
+
     #[error("FMU archive structure is not as expected: {0}")]
     ArchiveStructure(String),
 
@@ -120,6 +180,10 @@ pub enum Error {
         source: libloading::Error,
     },
 
+    #[cfg(feature = "fmi1")]
+    #[error(transparent)]
+    Fmi1Error(#[from] fmi1::Fmi1Error),
+
     #[cfg(feature = "fmi2")]
     #[error(transparent)]
     Fmi2Error(#[from] fmi2::Fmi2Error),
@@ -0,0 +1,147 @@
+//! Loading of FMU shared libraries for new instances.
+
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicUsize, Ordering},
+    },
+};
+
+use crate::{Error, import::LibraryMode};
+
+/// Shared libraries of FMUs with `canBeInstantiatedOnlyOncePerProcess` that have a live instance.
+static LIVE_LIBRARIES: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Distinguishes the per-instance copies of shared libraries within this process.
+static COPY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// The shared library an instance is loaded from.
+///
+/// Held by the instance for its whole lifetime: it keeps a library that can only be instantiated
+/// once per process registered, and removes a per-instance copy once the instance is dropped. It
+/// must therefore be dropped after the bindings of the instance.
+#[derive(Debug)]
+pub(crate) struct LibraryLease {
+    path: PathBuf,
+    registered: bool,
+    copied: bool,
+}
+
+impl LibraryLease {
+    /// Prepare the shared library at `lib_path` of the FMU `model_identifier` for a new instance.
+    ///
+    /// Fails if `only_once_per_process` is set and the library already has a live instance, unless
+    /// `mode` gives each instance its own copy of the library.
+    pub(crate) fn acquire(
+        lib_path: &Path,
+        model_identifier: &str,
+        mode: LibraryMode,
+        only_once_per_process: bool,
+    ) -> Result<Self, Error> {
+        match mode {
+            LibraryMode::CopyPerInstance => {
+                let copy_path = unique_copy_path(lib_path);
+                log::debug!("Copying shared library {lib_path:?} to {copy_path:?}");
+                std::fs::copy(lib_path, &copy_path)?;
+                Ok(Self {
+                    path: copy_path,
+                    registered: false,
+                    copied: true,
+                })
+            }
+            LibraryMode::Shared if only_once_per_process => {
+                let path = std::path::absolute(lib_path)?;
+                let newly_inserted = LIVE_LIBRARIES
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(path.clone());
+                if !newly_inserted {
+                    return Err(Error::InstantiatedOncePerProcess(
+                        model_identifier.to_owned(),
+                    ));
+                }
+                Ok(Self {
+                    path,
+                    registered: true,
+                    copied: false,
+                })
+            }
+            LibraryMode::Shared => Ok(Self {
+                path: lib_path.to_owned(),
+                registered: false,
+                copied: false,
+            }),
+        }
+    }
+
+    /// Path of the shared library to load
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for LibraryLease {
+    fn drop(&mut self) {
+        if self.registered {
+            LIVE_LIBRARIES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(&self.path);
+        }
+        if self.copied {
+            log::trace!("Removing shared library copy {:?}", self.path);
+            if let Err(e) = std::fs::remove_file(&self.path) {
+                log::warn!("Error removing shared library copy {:?}: {e}", self.path);
+            }
+        }
+    }
+}
+
+/// A path next to `lib_path` that no other instance in this process uses.
+///
+/// The copy stays in the same directory, since FMUs may locate their files relative to the
+/// shared library. This includes directories that the import does not own, as documented on
+/// [`LibraryMode::CopyPerInstance`].
+fn unique_copy_path(lib_path: &Path) -> PathBuf {
+    let stem = lib_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let n = COPY_COUNTER.fetch_add(1, Ordering::Relaxed);
+    lib_path.with_file_name(format!(
+        "{stem}.{}-{n}{}",
+        std::process::id(),
+        std::env::consts::DLL_SUFFIX
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_once_per_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib_path = dir.path().join("model.so");
+        std::fs::write(&lib_path, b"").unwrap();
+
+        let lease = LibraryLease::acquire(&lib_path, "model", LibraryMode::Shared, true).unwrap();
+        assert!(matches!(
+            LibraryLease::acquire(&lib_path, "model", LibraryMode::Shared, true),
+            Err(Error::InstantiatedOncePerProcess(_))
+        ));
+        // Without the flag, and with separate copies, any number of instances can be created
+        let shared = LibraryLease::acquire(&lib_path, "model", LibraryMode::Shared, false).unwrap();
+        let copy =
+            LibraryLease::acquire(&lib_path, "model", LibraryMode::CopyPerInstance, true).unwrap();
+        assert_ne!(copy.path(), lib_path);
+        assert!(copy.path().exists());
+
+        let copy_path = copy.path().to_owned();
+        drop((copy, shared, lease));
+        assert!(!copy_path.exists());
+        LibraryLease::acquire(&lib_path, "model", LibraryMode::Shared, true).unwrap();
+    }
+}
@@ -0,0 +1,472 @@
+//! Recording and replay of FMI API call traces.
+//!
+//! A trace is a JSON lines file. The first line is a [`TraceHeader`] describing the traced
+//! instance, every following line is a [`TraceCall`] holding the FMI function, its arguments, the
+//! values it returned, its status and timestamps:
+//!
+//! ```text
+//! {"type":"header","fmi_version":"3.0","interface":"CoSimulation","instance_name":"inst1",...}
+//! {"type":"call","seq":0,"function":"fmi3EnterInitializationMode","args":{...},"outputs":{},"status":"OK","time":0.0001,"duration":0.00002}
+//! ```
+//!
+//! Traces are recorded by wrapping an instance in [`crate::fmi2::trace::TracedInstance`] or
+//! [`crate::fmi3::trace::TracedInstance`], and replayed against an FMU with the `replay_*`
+//! functions of the same module (or the `fmi-replay` tool of `fmi-sim`), which re-issue the calls and report
+//! every [`Mismatch`] between the recorded and the replayed results.
+//!
+//! Non-finite floating point values are not representable in JSON, and are recorded as `null`.
+
+use std::{
+    fmt::{Debug, Display},
+    io::{BufRead, Write},
+    time::{Duration, Instant, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{Error, EventFlags};
+
+/// Description of the traced instance, the first record of a trace.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceHeader {
+    /// FMI version of the instance, `2.0` or `3.0`
+    pub fmi_version: String,
+    /// Interface type of the instance, for example `CoSimulation`
+    pub interface: String,
+    /// Name the instance was created with
+    pub instance_name: String,
+    /// Version of this crate that recorded the trace
+    pub recorder_version: String,
+    /// Wall-clock start of the trace, in seconds since the Unix epoch
+    pub created: f64,
+}
+
+/// A single recorded FMI call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceCall {
+    /// Position of the call in the trace, starting at 0
+    pub seq: u64,
+    /// Name of the FMI function, for example `fmi3GetFloat64`
+    pub function: String,
+    /// Input arguments of the call
+    #[serde(default)]
+    pub args: Map<String, Value>,
+    /// Values returned through output arguments, only recorded for successful calls
+    #[serde(default)]
+    pub outputs: Map<String, Value>,
+    /// Status of the call, `OK`, `Warning`, `Pending`, or the error
+    pub status: String,
+    /// Start of the call, in seconds since the start of the trace
+    pub time: f64,
+    /// Duration of the call, in seconds
+    pub duration: f64,
+}
+
+/// A line of a trace file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TraceRecord {
+    Header(TraceHeader),
+    Call(TraceCall),
+}
+
+enum Sink {
+    Writer(Box<dyn Write + Send>),
+    Memory(Vec<TraceCall>),
+}
+
+/// Records FMI calls to a trace.
+pub struct TraceWriter {
+    sink: Sink,
+    seq: u64,
+    start: Instant,
+    /// Set after the first write error, which is logged once
+    failed: bool,
+}
+
+impl Debug for TraceWriter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TraceWriter")
+            .field("seq", &self.seq)
+            .field("failed", &self.failed)
+            .finish_non_exhaustive()
+    }
+}
+
+impl TraceWriter {
+    /// Start a trace written to `writer`, beginning with a header for the given instance.
+    ///
+    /// The writer should be buffered. Write errors do not affect the traced calls, they are logged
+    /// and stop the recording.
+    pub fn new(
+        mut writer: impl Write + Send + 'static,
+        fmi_version: &str,
+        interface: &str,
+        instance_name: &str,
+    ) -> Result<Self, Error> {
+        let header = TraceHeader {
+            fmi_version: fmi_version.to_owned(),
+            interface: interface.to_owned(),
+            instance_name: instance_name.to_owned(),
+            recorder_version: crate::built_info::PKG_VERSION.to_owned(),
+            created: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or(Duration::ZERO)
+                .as_secs_f64(),
+        };
+        write_record(&mut writer, &TraceRecord::Header(header))?;
+        Ok(Self {
+            sink: Sink::Writer(Box::new(writer)),
+            seq: 0,
+            start: Instant::now(),
+            failed: false,
+        })
+    }
+
+    /// Record calls in memory, to be retrieved with [`Self::take_calls`].
+    pub(crate) fn memory() -> Self {
+        Self {
+            sink: Sink::Memory(Vec::new()),
+            seq: 0,
+            start: Instant::now(),
+            failed: false,
+        }
+    }
+
+    /// Take the calls recorded in memory.
+    pub(crate) fn take_calls(&mut self) -> Vec<TraceCall> {
+        match &mut self.sink {
+            Sink::Memory(calls) => std::mem::take(calls),
+            Sink::Writer(_) => Vec::new(),
+        }
+    }
+
+    /// Record a call that started at `started`.
+    pub(crate) fn record(
+        &mut self,
+        function: &str,
+        started: Instant,
+        args: Value,
+        outputs: Value,
+        status: String,
+    ) {
+        let call = TraceCall {
+            seq: self.seq,
+            function: function.to_owned(),
+            args: into_map(args),
+            outputs: into_map(outputs),
+            status,
+            time: started.duration_since(self.start).as_secs_f64(),
+            duration: started.elapsed().as_secs_f64(),
+        };
+        self.seq += 1;
+        match &mut self.sink {
+            Sink::Memory(calls) => calls.push(call),
+            Sink::Writer(_) if self.failed => {}
+            Sink::Writer(writer) => {
+                if let Err(e) = write_record(writer, &TraceRecord::Call(call)) {
+                    log::error!("Error writing FMI trace, recording stopped: {e}");
+                    self.failed = true;
+                }
+            }
+        }
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        match &mut self.sink {
+            Sink::Writer(writer) => writer.flush().map_err(Error::from),
+            Sink::Memory(_) => Ok(()),
+        }
+    }
+}
+
+impl Drop for TraceWriter {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+fn write_record(writer: &mut impl Write, record: &TraceRecord) -> Result<(), Error> {
+    serde_json::to_writer(&mut *writer, record).map_err(|e| Error::Trace(e.to_string()))?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+fn into_map(value: Value) -> Map<String, Value> {
+    match value {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        value => Map::from_iter([("value".to_owned(), value)]),
+    }
+}
+
+/// Status of a call returning an FMI status, such as [`crate::fmi3::Fmi3Res`].
+pub(crate) fn status<T: Debug, E: Display>(result: &Result<T, E>) -> String {
+    match result {
+        Ok(res) => format!("{res:?}"),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Status of a call returning a value instead of an FMI status.
+pub(crate) fn value_status<T, E: Display>(result: &Result<T, E>) -> String {
+    match result {
+        Ok(_) => "OK".to_owned(),
+        Err(e) => e.to_string(),
+    }
+}
+
+/// Outputs of `update_discrete_states` and `new_discrete_states`
+pub(crate) fn event_flags_value(event_flags: &EventFlags) -> Value {
+    serde_json::json!({
+        "discrete_states_need_update": event_flags.discrete_states_need_update,
+        "terminate_simulation": event_flags.terminate_simulation,
+        "nominals_of_continuous_states_changed": event_flags.nominals_of_continuous_states_changed,
+        "values_of_continuous_states_changed": event_flags.values_of_continuous_states_changed,
+        "next_event_time": event_flags.next_event_time,
+    })
+}
+
+/// Read a trace written by a [`TraceWriter`].
+pub fn read_trace(reader: impl BufRead) -> Result<(TraceHeader, Vec<TraceCall>), Error> {
+    let mut header = None;
+    let mut calls = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .map_err(|e| Error::Trace(format!("line {}: {e}", number + 1)))?;
+        match (record, &header) {
+            (TraceRecord::Header(h), None) => header = Some(h),
+            (TraceRecord::Call(call), Some(_)) => calls.push(call),
+            (TraceRecord::Header(_), Some(_)) => {
+                return Err(Error::Trace(format!(
+                    "line {}: unexpected second header",
+                    number + 1
+                )));
+            }
+            (TraceRecord::Call(_), None) => {
+                return Err(Error::Trace(
+                    "trace does not start with a header".to_owned(),
+                ));
+            }
+        }
+    }
+    header
+        .map(|header| (header, calls))
+        .ok_or_else(|| Error::Trace("empty trace".to_owned()))
+}
+
+/// Decode the argument `name` of a recorded call.
+pub(crate) fn arg<T: serde::de::DeserializeOwned>(
+    call: &TraceCall,
+    name: &str,
+) -> Result<T, Error> {
+    let value = call.args.get(name).cloned().unwrap_or(Value::Null);
+    serde_json::from_value(value).map_err(|e| {
+        Error::Trace(format!(
+            "call {} ({}): invalid argument `{name}`: {e}",
+            call.seq, call.function
+        ))
+    })
+}
+
+/// Number of values the recorded call returned in the output `name`, used to size the buffers of
+/// the replayed call.
+pub(crate) fn output_len(call: &TraceCall, name: &str) -> usize {
+    call.outputs
+        .get(name)
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len)
+}
+
+/// A difference between a recorded call and its replay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// Position of the call in the trace
+    pub seq: u64,
+    /// Name of the FMI function
+    pub function: String,
+    /// The differing part of the call, `status` or the path of an output such as `values[3]`
+    pub path: String,
+    pub recorded: Value,
+    pub replayed: Value,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "#{} {}: {} recorded {}, replayed {}",
+            self.seq, self.function, self.path, self.recorded, self.replayed
+        )
+    }
+}
+
+/// Compare the status and outputs of a recorded call with its replay.
+///
+/// Numbers are considered equal if they differ by at most `tolerance`, relative to the larger
+/// magnitude of the two (or absolute, below a magnitude of 1).
+pub fn compare_calls(recorded: &TraceCall, replayed: &TraceCall, tolerance: f64) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let mut mismatch = |path: String, recorded_value: &Value, replayed_value: &Value| {
+        mismatches.push(Mismatch {
+            seq: recorded.seq,
+            function: recorded.function.clone(),
+            path,
+            recorded: recorded_value.clone(),
+            replayed: replayed_value.clone(),
+        })
+    };
+    if recorded.status != replayed.status {
+        mismatch(
+            "status".to_owned(),
+            &Value::from(recorded.status.as_str()),
+            &Value::from(replayed.status.as_str()),
+        );
+    }
+    for (name, value) in &recorded.outputs {
+        let other = replayed.outputs.get(name).unwrap_or(&Value::Null);
+        compare_values(name.clone(), value, other, tolerance, &mut mismatch);
+    }
+    for (name, value) in &replayed.outputs {
+        if !recorded.outputs.contains_key(name) {
+            mismatch(name.clone(), &Value::Null, value);
+        }
+    }
+    mismatches
+}
+
+fn compare_values(
+    path: String,
+    recorded: &Value,
+    replayed: &Value,
+    tolerance: f64,
+    mismatch: &mut impl FnMut(String, &Value, &Value),
+) {
+    match (recorded, replayed) {
+        (Value::Array(a), Value::Array(b)) if a.len() == b.len() => {
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                compare_values(format!("{path}[{i}]"), a, b, tolerance, mismatch);
+            }
+        }
+        (Value::Object(a), Value::Object(b)) if a.len() == b.len() => {
+            for (key, a) in a {
+                let b = b.get(key).unwrap_or(&Value::Null);
+                compare_values(format!("{path}.{key}"), a, b, tolerance, mismatch);
+            }
+        }
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (
+                a.as_f64().unwrap_or(f64::NAN),
+                b.as_f64().unwrap_or(f64::NAN),
+            );
+            if (a - b).abs() > tolerance * a.abs().max(b.abs()).max(1.0) {
+                mismatch(path, recorded, replayed);
+            }
+        }
+        (a, b) if a == b => {}
+        _ => mismatch(path, recorded, replayed),
+    }
+}
+
+/// Time the call `$function` of a traced instance, recording its arguments, and the outputs and
+/// status computed from its result.
+///
+/// `$status` is [`status`] for functions returning an FMI status, and [`value_status`] otherwise.
+macro_rules! traced_call {
+    ($writer:expr, $function:expr, $args:tt, $call:expr, |$result:ident| $outputs:tt, $status:path) => {{
+        let started = std::time::Instant::now();
+        let $result = $call;
+        let outputs = if $result.is_ok() {
+            serde_json::json!($outputs)
+        } else {
+            serde_json::Value::Null
+        };
+        $writer.record(
+            $function,
+            started,
+            serde_json::json!($args),
+            outputs,
+            $status(&$result),
+        );
+        $result
+    }};
+}
+
+pub(crate) use traced_call;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(status: &str, outputs: Value) -> TraceCall {
+        TraceCall {
+            seq: 3,
+            function: "fmi3GetFloat64".to_owned(),
+            args: Map::new(),
+            outputs: into_map(outputs),
+            status: status.to_owned(),
+            time: 0.0,
+            duration: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let buffer = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        struct Shared(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+        impl Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut writer =
+            TraceWriter::new(Shared(buffer.clone()), "3.0", "CoSimulation", "inst1").unwrap();
+        writer.record(
+            "fmi3GetFloat64",
+            Instant::now(),
+            serde_json::json!({"vrs": [1, 2]}),
+            serde_json::json!({"values": [1.5, f64::NAN]}),
+            value_status(&Ok::<_, Error>(())),
+        );
+        drop(writer);
+
+        let buffer = buffer.lock().unwrap();
+        let (header, calls) = read_trace(&buffer[..]).unwrap();
+        assert_eq!(header.instance_name, "inst1");
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].status, "OK");
+        assert_eq!(calls[0].args["vrs"], serde_json::json!([1, 2]));
+        assert_eq!(calls[0].outputs["values"], serde_json::json!([1.5, null]));
+    }
+
+    #[test]
+    fn test_compare() {
+        let recorded = call(
+            "OK",
+            serde_json::json!({"values": [1.0, 2.0], "flag": true}),
+        );
+
+        let replayed = call(
+            "OK",
+            serde_json::json!({"values": [1.0, 2.0 + 1e-9], "flag": true}),
+        );
+        assert!(compare_calls(&recorded, &replayed, 1e-6).is_empty());
+        assert_eq!(compare_calls(&recorded, &replayed, 0.0).len(), 1);
+
+        let replayed = call("Discard", serde_json::json!({}));
+        let mismatches = compare_calls(&recorded, &replayed, 0.0);
+        let paths: Vec<_> = mismatches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["status", "flag", "values"]);
+    }
+}
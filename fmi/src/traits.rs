@@ -5,7 +5,11 @@ use fmi_schema::{
     traits::{DefaultExperiment, FmiModelDescription},
 };
 
-use crate::{Error, EventFlags, InterfaceType};
+use crate::{
+    Error, EventFlags, InterfaceType,
+    import::{FmuDir, LibraryMode},
+    layered_standard::{LayeredStandard, read_layered_standards},
+};
 
 /// Generic FMI import trait
 pub trait FmiImport: Sized {
@@ -22,10 +26,15 @@ pub trait FmiImport: Sized {
     type ValueRef;
 
     /// Create a new FMI import from a directory containing the unzipped FMU
-    fn new(dir: tempfile::TempDir, schema_xml: &str) -> Result<Self, Error>;
+    fn new(dir: FmuDir, schema_xml: &str) -> Result<Self, Error>;
+
+    /// Return the directory of the extracted FMU
+    fn dir(&self) -> &FmuDir;
 
     /// Return the path to the extracted FMU
-    fn archive_path(&self) -> &std::path::Path;
+    fn archive_path(&self) -> &std::path::Path {
+        self.dir().path()
+    }
 
     /// Get the path to the shared library
     fn shared_lib_path(&self, model_identifier: &str) -> Result<std::path::PathBuf, Error>;
@@ -38,11 +47,40 @@ pub trait FmiImport: Sized {
     /// Return a canonical string representation of the resource path
     fn canonical_resource_path_string(&self) -> String;
 
+    /// Return the path to the `extra` directory of layered standards and tool-specific files
+    fn extra_path(&self) -> std::path::PathBuf {
+        self.archive_path().join("extra")
+    }
+
+    /// The layered standards of the FMU, identified by the manifests in the `extra` directory.
+    /// Malformed manifests are skipped, see [`read_layered_standards`].
+    fn layered_standards(&self) -> Result<Vec<LayeredStandard>, Error> {
+        read_layered_standards(&self.extra_path())
+    }
+
+    /// The layered standard named `name`, such as `org.fmi-standard.fmi-ls-bus`, if the FMU
+    /// supports it
+    fn layered_standard(&self, name: &str) -> Result<Option<LayeredStandard>, Error> {
+        Ok(self
+            .layered_standards()?
+            .into_iter()
+            .find(|standard| standard.manifest.name == name))
+    }
+
     /// Get a reference to the raw-schema model description
     fn model_description(&self) -> &Self::ModelDescription;
 
     /// Load the plugin shared library and return the raw bindings.
+    ///
+    /// This always loads the library of the extracted FMU, regardless of the
+    /// [`FmiImport::library_mode`] used for new instances.
     fn binding(&self, model_identifier: &str) -> Result<Self::Binding, Error>;
+
+    /// How the shared library is loaded for new instances
+    fn library_mode(&self) -> LibraryMode;
+
+    /// Set how the shared library is loaded for new instances
+    fn set_library_mode(&mut self, mode: LibraryMode);
 }
 
 /// FMI status trait
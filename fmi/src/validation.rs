@@ -0,0 +1,420 @@
+//! Compliance checks of FMU archives.
+//!
+//! [`validate_path`] checks the model description of an FMU with
+//! [`Validate`](fmi_schema::validation::Validate), and the layout of the archive: the platform
+//! folders in `binaries/`, the shared libraries of each declared interface type, and the source
+//! code in `sources/`. The terminals of FMI 3.0 FMUs are checked against the model description.
+//!
+//! ```rust,no_run
+//! use fmi::validation::{Severity, validate_path};
+//!
+//! let findings = validate_path("path/to/model.fmu")?;
+//! for finding in &findings {
+//!     println!("{finding}");
+//! }
+//! let valid = findings.iter().all(|f| f.severity < Severity::Error);
+//! # Ok::<(), fmi::Error>(())
+//! ```
+
+use std::{
+    collections::BTreeSet,
+    io::{Read, Seek},
+    path::Path,
+};
+
+pub use fmi_schema::validation::{Finding, Location, Severity, Validate};
+use fmi_schema::{MajorVersion, minimal::MinModelDescription, traits::FmiModelDescription};
+
+use crate::Error;
+
+const MODEL_DESCRIPTION: &str = "modelDescription.xml";
+const TERMINALS_AND_ICONS: &str = "terminalsAndIcons/terminalsAndIcons.xml";
+
+/// The platform folders of FMI 2.0
+#[cfg(feature = "fmi2")]
+const FMI2_PLATFORMS: &[&str] = &[
+    "win32", "win64", "linux32", "linux64", "darwin32", "darwin64",
+];
+
+/// The architectures and operating systems of the standard platform tuples of FMI 3.0
+#[cfg(feature = "fmi3")]
+const FMI3_ARCHITECTURES: &[&str] = &["x86", "x86_64", "aarch32", "aarch64", "ppc32", "ppc64"];
+#[cfg(feature = "fmi3")]
+const FMI3_SYSTEMS: &[&str] = &["windows", "linux", "darwin"];
+
+/// Check the FMU at `path`, either an FMU archive or the directory of an extracted FMU.
+///
+/// Violations of the standard are returned as findings. An error is only returned if the FMU can
+/// not be read.
+pub fn validate_path(path: impl AsRef<Path>) -> Result<Vec<Finding>, Error> {
+    let path = path.as_ref();
+    if path.is_dir() {
+        let mut files = Vec::new();
+        list_dir(path, "", &mut files)?;
+        let read = |name: &str| match path.join(name) {
+            file if file.is_file() => std::fs::read_to_string(file).map(Some),
+            _ => Ok(None),
+        };
+        let xml = read(MODEL_DESCRIPTION)?;
+        let terminals = read(TERMINALS_AND_ICONS)?;
+        Ok(validate_files(&files, xml.as_deref(), terminals.as_deref()))
+    } else {
+        validate(std::fs::File::open(path)?)
+    }
+}
+
+/// Check the FMU archive read from `reader`. See [`validate_path`].
+pub fn validate<R: Read + Seek>(reader: R) -> Result<Vec<Finding>, Error> {
+    let mut archive = zip::ZipArchive::new(reader)?;
+    let files = archive
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .map(str::to_owned)
+        .collect::<Vec<_>>();
+    let mut read = |name: &str| match archive.by_name(name) {
+        Ok(mut file) => {
+            let mut xml = String::new();
+            file.read_to_string(&mut xml)?;
+            Ok(Some(xml))
+        }
+        Err(zip::result::ZipError::FileNotFound) => Ok(None),
+        Err(e) => Err(Error::from(e)),
+    };
+    let xml = read(MODEL_DESCRIPTION)?;
+    let terminals = read(TERMINALS_AND_ICONS)?;
+    Ok(validate_files(&files, xml.as_deref(), terminals.as_deref()))
+}
+
+/// The files below `dir`, as `/`-separated paths relative to the root of the FMU
+fn list_dir(dir: &Path, prefix: &str, files: &mut Vec<String>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = format!("{prefix}{}", entry.file_name().to_string_lossy());
+        if entry.file_type()?.is_dir() {
+            list_dir(&entry.path(), &format!("{name}/"), files)?;
+        } else {
+            files.push(name);
+        }
+    }
+    Ok(())
+}
+
+/// Check the model description `xml`, the `terminals` of `terminalsAndIcons.xml` and the `files` of
+/// an FMU
+#[cfg_attr(not(feature = "fmi3"), allow(unused_variables))]
+fn validate_files(files: &[String], xml: Option<&str>, terminals: Option<&str>) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let Some(xml) = xml else {
+        findings.push(Finding::error(
+            Location::Archive(MODEL_DESCRIPTION.to_owned()),
+            "the model description is missing",
+        ));
+        return findings;
+    };
+    let parse_error =
+        |e: fmi_schema::Error| Finding::error(Location::ModelDescription, e.to_string());
+
+    let version = MinModelDescription::deserialize(xml).and_then(|md| md.major_version());
+    match version {
+        #[cfg(feature = "fmi2")]
+        Ok(MajorVersion::FMI2) => match fmi_schema::fmi2::Fmi2ModelDescription::deserialize(xml) {
+            Ok(md) => {
+                findings.extend(md.validate());
+                check_fmi2_archive(&md, files, &mut findings);
+            }
+            Err(e) => findings.push(parse_error(e)),
+        },
+        #[cfg(feature = "fmi3")]
+        Ok(MajorVersion::FMI3) => match fmi_schema::fmi3::Fmi3ModelDescription::deserialize(xml) {
+            Ok(md) => {
+                findings.extend(md.validate());
+                check_fmi3_archive(&md, files, &mut findings);
+                if let Some(terminals) = terminals {
+                    check_fmi3_terminals(&md, terminals, &mut findings);
+                }
+            }
+            Err(e) => findings.push(parse_error(e)),
+        },
+        Ok(version) => findings.push(Finding::warning(
+            Location::ModelDescription,
+            format!("{version:?} FMUs are not checked"),
+        )),
+        Err(e) => findings.push(parse_error(e)),
+    }
+    findings
+}
+
+#[cfg(feature = "fmi2")]
+fn check_fmi2_archive(
+    md: &fmi_schema::fmi2::Fmi2ModelDescription,
+    files: &[String],
+    findings: &mut Vec<Finding>,
+) {
+    use fmi_schema::traits::FmiInterfaceType;
+
+    let model_identifiers = [
+        md.model_exchange.as_ref().map(|me| me.model_identifier()),
+        md.co_simulation.as_ref().map(|cs| cs.model_identifier()),
+    ];
+    check_binaries(
+        files,
+        &model_identifiers.into_iter().flatten().collect(),
+        |platform| FMI2_PLATFORMS.contains(&platform),
+        findings,
+    );
+
+    let source_files = md
+        .model_exchange
+        .iter()
+        .flat_map(|me| me.source_files.iter())
+        .chain(
+            md.co_simulation
+                .iter()
+                .flat_map(|cs| cs.source_files.iter()),
+        )
+        .flat_map(|source_files| source_files.files.iter())
+        .map(|file| format!("sources/{}", file.name))
+        .collect::<BTreeSet<_>>();
+    for file in source_files {
+        if !files.contains(&file) {
+            findings.push(Finding::error(
+                Location::Archive(file),
+                "the file is listed in SourceFiles, but missing",
+            ));
+        }
+    }
+}
+
+#[cfg(feature = "fmi3")]
+fn check_fmi3_archive(
+    md: &fmi_schema::fmi3::Fmi3ModelDescription,
+    files: &[String],
+    findings: &mut Vec<Finding>,
+) {
+    use fmi_schema::traits::FmiInterfaceType;
+
+    let model_identifiers = [
+        md.model_exchange.as_ref().map(|me| me.model_identifier()),
+        md.co_simulation.as_ref().map(|cs| cs.model_identifier()),
+        md.scheduled_execution
+            .as_ref()
+            .map(|se| se.model_identifier()),
+    ];
+    check_binaries(
+        files,
+        &model_identifiers.into_iter().flatten().collect(),
+        |platform| match platform.split_once('-') {
+            Some((arch, os)) => FMI3_ARCHITECTURES.contains(&arch) && FMI3_SYSTEMS.contains(&os),
+            None => false,
+        },
+        findings,
+    );
+
+    let has_sources = files.iter().any(|file| file.starts_with("sources/"));
+    if has_sources
+        && !files
+            .iter()
+            .any(|file| file == "sources/buildDescription.xml")
+    {
+        findings.push(Finding::warning(
+            Location::Archive("sources".to_owned()),
+            "the source code has no buildDescription.xml",
+        ));
+    }
+}
+
+#[cfg(feature = "fmi3")]
+fn check_fmi3_terminals(
+    md: &fmi_schema::fmi3::Fmi3ModelDescription,
+    terminals: &str,
+    findings: &mut Vec<Finding>,
+) {
+    match fmi_schema::deserialize::<fmi_schema::fmi3::Fmi3TerminalsAndIcons>(terminals) {
+        Ok(terminals) => {
+            findings.extend(fmi_schema::validation::validate_terminals(md, &terminals))
+        }
+        Err(e) => findings.push(Finding::error(
+            Location::Archive(TERMINALS_AND_ICONS.to_owned()),
+            e.to_string(),
+        )),
+    }
+}
+
+/// Each known platform folder has a shared library for each of the `model_identifiers`, and the
+/// FMU has either binaries or source code
+fn check_binaries(
+    files: &[String],
+    model_identifiers: &BTreeSet<&str>,
+    is_platform: impl Fn(&str) -> bool,
+    findings: &mut Vec<Finding>,
+) {
+    let platforms = files
+        .iter()
+        .filter_map(|file| file.strip_prefix("binaries/")?.split_once('/'))
+        .map(|(platform, _)| platform)
+        .collect::<BTreeSet<_>>();
+
+    let mut has_binaries = false;
+    for platform in platforms {
+        if !is_platform(platform) {
+            findings.push(Finding::warning(
+                Location::Archive(format!("binaries/{platform}")),
+                "unknown platform folder",
+            ));
+            continue;
+        }
+        has_binaries = true;
+        let suffix = if platform.contains("darwin") {
+            "dylib"
+        } else if platform.contains("win") {
+            "dll"
+        } else {
+            "so"
+        };
+        for model_identifier in model_identifiers {
+            let library = format!("binaries/{platform}/{model_identifier}.{suffix}");
+            if !files.contains(&library) {
+                findings.push(Finding::error(
+                    Location::Archive(library),
+                    "the shared library is missing",
+                ));
+            }
+        }
+    }
+
+    let has_sources = files.iter().any(|file| file.starts_with("sources/"));
+    if !has_binaries && !has_sources {
+        findings.push(Finding::error(
+            Location::Archive("binaries".to_owned()),
+            "the FMU has neither binaries for a known platform nor source code",
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "fmi3"))]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    const XML: &str = r#"<fmiModelDescription fmiVersion="3.0" modelName="Feedthrough" instantiationToken="{}">
+    <CoSimulation modelIdentifier="Feedthrough"/>
+    <ModelExchange modelIdentifier="Feedthrough"/>
+    <ModelVariables>
+        <Float64 name="time" valueReference="0" causality="independent" variability="continuous"/>
+        <Float64 name="u" valueReference="1" causality="input" start="0"/>
+        <Float64 name="y" valueReference="2" causality="output"/>
+    </ModelVariables>
+    <ModelStructure>
+        <Output valueReference="2" dependencies="1"/>
+    </ModelStructure>
+</fmiModelDescription>"#;
+
+    fn write_fmu(dir: &Path, files: &[(&str, &str)]) {
+        for (name, contents) in files {
+            let path = dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, contents).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        write_fmu(
+            dir.path(),
+            &[
+                (MODEL_DESCRIPTION, XML),
+                ("binaries/x86_64-linux/Feedthrough.so", ""),
+                ("binaries/x86_64-windows/Feedthrough.dll", ""),
+            ],
+        );
+        assert_eq!(validate_path(dir.path()).unwrap(), vec![]);
+
+        write_fmu(
+            dir.path(),
+            &[(
+                TERMINALS_AND_ICONS,
+                r#"<fmiTerminalsAndIcons fmiVersion="3.0">
+    <Terminals>
+        <Terminal name="port" matchingRule="plug">
+            <TerminalMemberVariable variableName="u" memberName="u" variableKind="signal"/>
+            <TerminalMemberVariable variableName="x" memberName="x" variableKind="signal"/>
+        </Terminal>
+    </Terminals>
+</fmiTerminalsAndIcons>"#,
+            )],
+        );
+        assert_eq!(
+            validate_path(dir.path()).unwrap(),
+            vec![Finding::error(
+                Location::Terminal("port".to_owned()),
+                "variable 'x' is not defined"
+            )]
+        );
+        std::fs::remove_file(dir.path().join(TERMINALS_AND_ICONS)).unwrap();
+
+        write_fmu(
+            dir.path(),
+            &[
+                ("binaries/aarch64-darwin/Other.dylib", ""),
+                ("binaries/os2/Feedthrough.dll", ""),
+                ("sources/all.c", ""),
+            ],
+        );
+        assert_eq!(
+            validate_path(dir.path()).unwrap(),
+            vec![
+                Finding::error(
+                    Location::Archive("binaries/aarch64-darwin/Feedthrough.dylib".to_owned()),
+                    "the shared library is missing"
+                ),
+                Finding::warning(
+                    Location::Archive("binaries/os2".to_owned()),
+                    "unknown platform folder"
+                ),
+                Finding::warning(
+                    Location::Archive("sources".to_owned()),
+                    "the source code has no buildDescription.xml"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_archive() {
+        let file = tempfile::tempfile().unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file(MODEL_DESCRIPTION, options).unwrap();
+        zip.write_all(XML.replace(r#"causality="output""#, "").as_bytes())
+            .unwrap();
+        zip.add_directory("binaries/", options).unwrap();
+        let file = zip.finish().unwrap();
+
+        assert_eq!(
+            validate(file).unwrap(),
+            vec![
+                Finding::error(
+                    Location::ModelStructure("Output[1]".to_owned()),
+                    "variable 'y' is not an output"
+                ),
+                Finding::error(
+                    Location::Archive("binaries".to_owned()),
+                    "the FMU has neither binaries for a known platform nor source code"
+                ),
+            ]
+        );
+
+        let empty = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()))
+            .finish()
+            .unwrap();
+        assert_eq!(
+            validate(empty).unwrap(),
+            vec![Finding::error(
+                Location::Archive(MODEL_DESCRIPTION.to_owned()),
+                "the model description is missing"
+            )]
+        );
+    }
+}
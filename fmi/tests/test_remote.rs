@@ -0,0 +1,110 @@
+//! Test that a crashing or hanging FMU only takes down its `fmi-worker` process.
+//!
+//! Unlike the other instance tests in the `tests/` directory of the workspace, this test lives in
+//! the `fmi` package, because Cargo only builds the `fmi-worker` binary and sets
+//! `CARGO_BIN_EXE_fmi-worker` for the integration tests of the package that defines it.
+#![cfg(all(feature = "remote", feature = "build"))]
+
+use std::time::Duration;
+
+use fmi::{
+    CS, Error,
+    fmi3::{
+        CoSimulation, Common, Fmi3Error,
+        remote::{RemoteInstance, RemoteOptions},
+    },
+};
+
+#[path = "../../tests/common/mod.rs"]
+mod common;
+
+/// `fmi3DoStep` aborts the process, `fmi3Terminate` never returns
+const FAILING_FUNCTIONS: &str = r#"
+fmi3Status fmi3DoStep(fmi3Instance instance, fmi3Float64 currentCommunicationPoint,
+    fmi3Float64 communicationStepSize, fmi3Boolean noSetFMUStatePriorToCurrentPoint,
+    fmi3Boolean* eventHandlingNeeded, fmi3Boolean* terminateSimulation, fmi3Boolean* earlyReturn,
+    fmi3Float64* lastSuccessfulTime) {
+    abort();
+}
+
+fmi3Status fmi3Terminate(fmi3Instance instance) {
+    for (;;) {}
+}
+"#;
+
+fn options(timeout: Duration) -> RemoteOptions {
+    RemoteOptions {
+        worker: env!("CARGO_BIN_EXE_fmi-worker").into(),
+        timeout: Some(timeout),
+    }
+}
+
+/// Start a worker with an initialized Co-Simulation instance of `import`
+fn instantiate(import: &fmi::fmi3::import::Fmi3Import, timeout: Duration) -> RemoteInstance<CS> {
+    let mut inst = RemoteInstance::<CS>::new(
+        import,
+        "inst1",
+        false,
+        false,
+        false,
+        false,
+        &[],
+        &options(timeout),
+    )
+    .unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+    inst
+}
+
+#[test]
+fn test_worker_abort() {
+    let (_dir, import) = common::build_fmu(FAILING_FUNCTIONS);
+    let mut inst = instantiate(&import, Duration::from_secs(10));
+
+    let (mut event, mut terminate, mut early_return, mut time) = (false, false, false, 0.0);
+    let result = inst.do_step(
+        0.0,
+        0.1,
+        true,
+        &mut event,
+        &mut terminate,
+        &mut early_return,
+        &mut time,
+    );
+    assert!(matches!(result, Err(Fmi3Error::Fatal)));
+    assert!(matches!(inst.take_error(), Some(Error::RemoteWorker(_))));
+    assert!(!inst.is_running());
+
+    // Later calls fail without a worker
+    assert!(matches!(inst.enter_event_mode(), Err(Fmi3Error::Fatal)));
+    assert!(matches!(inst.take_error(), Some(Error::RemoteWorker(_))));
+}
+
+#[test]
+fn test_worker_hang() {
+    let (_dir, import) = common::build_fmu(FAILING_FUNCTIONS);
+    let timeout = Duration::from_millis(500);
+    let mut inst = instantiate(&import, timeout);
+
+    assert!(matches!(
+        Common::terminate(&mut inst),
+        Err(Fmi3Error::Fatal)
+    ));
+    assert!(matches!(inst.take_error(), Some(Error::RemoteTimeout(t)) if t == timeout));
+    assert!(!inst.is_running());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_worker_killed() {
+    let (_dir, import) = common::build_fmu(FAILING_FUNCTIONS);
+    let mut inst = instantiate(&import, Duration::from_secs(10));
+
+    let pid = inst.worker_id() as libc::pid_t;
+    assert_eq!(unsafe { libc::kill(pid, libc::SIGKILL) }, 0);
+
+    assert!(matches!(inst.enter_event_mode(), Err(Fmi3Error::Fatal)));
+    assert!(matches!(inst.take_error(), Some(Error::RemoteWorker(_))));
+    assert!(!inst.is_running());
+}
@@ -0,0 +1,86 @@
+//! FMI 3.0 FMUs compiled from a few lines of C with the `build` feature.
+//!
+//! The FMUs implement Model Exchange and Co-Simulation with the model identifier `Tiny`. Only the
+//! functions given to [`build_fmu`] are defined, on top of [`MODEL_C`].
+
+use fmi::fmi3::{build::build_shared_library, import::Fmi3Import};
+
+const MODEL_DESCRIPTION_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<fmiModelDescription fmiVersion="3.0" modelName="Tiny" instantiationToken="{tiny}">
+    <ModelExchange modelIdentifier="Tiny"/>
+    <CoSimulation modelIdentifier="Tiny" canGetAndSetFMUState="true" canSerializeFMUState="true"/>
+    <ModelVariables>
+        <Float64 name="time" valueReference="0" causality="independent" variability="continuous"/>
+        <Binary name="data" valueReference="1" causality="output" variability="discrete"/>
+    </ModelVariables>
+    <ModelStructure/>
+</fmiModelDescription>"#;
+
+const BUILD_DESCRIPTION_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<fmiBuildDescription fmiVersion="3.0">
+    <BuildConfiguration modelIdentifier="Tiny">
+        <SourceFileSet language="C99">
+            <SourceFile name="model.c"/>
+        </SourceFileSet>
+    </BuildConfiguration>
+</fmiBuildDescription>"#;
+
+/// Instantiation and initialization of the FMUs, all instances share one dummy pointer
+pub const MODEL_C: &str = r#"
+#include <stdlib.h>
+#include "fmi3Functions.h"
+
+static int instance;
+
+const char* fmi3GetVersion(void) {
+    return fmi3Version;
+}
+
+fmi3Instance fmi3InstantiateModelExchange(fmi3String instanceName, fmi3String instantiationToken,
+    fmi3String resourcePath, fmi3Boolean visible, fmi3Boolean loggingOn,
+    fmi3InstanceEnvironment instanceEnvironment, fmi3LogMessageCallback logMessage) {
+    return &instance;
+}
+
+fmi3Instance fmi3InstantiateCoSimulation(fmi3String instanceName, fmi3String instantiationToken,
+    fmi3String resourcePath, fmi3Boolean visible, fmi3Boolean loggingOn, fmi3Boolean eventModeUsed,
+    fmi3Boolean earlyReturnAllowed, const fmi3ValueReference requiredIntermediateVariables[],
+    size_t nRequiredIntermediateVariables, fmi3InstanceEnvironment instanceEnvironment,
+    fmi3LogMessageCallback logMessage, fmi3IntermediateUpdateCallback intermediateUpdate) {
+    return &instance;
+}
+
+void fmi3FreeInstance(fmi3Instance instance) {}
+
+fmi3Status fmi3EnterInitializationMode(fmi3Instance instance, fmi3Boolean toleranceDefined,
+    fmi3Float64 tolerance, fmi3Float64 startTime, fmi3Boolean stopTimeDefined,
+    fmi3Float64 stopTime) {
+    return fmi3OK;
+}
+
+fmi3Status fmi3ExitInitializationMode(fmi3Instance instance) {
+    return fmi3OK;
+}
+"#;
+
+/// Build an FMU from [`MODEL_C`] followed by `functions` in a temporary directory, and import it.
+pub fn build_fmu(functions: &str) -> (tempfile::TempDir, Fmi3Import) {
+    let fmu_dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        fmu_dir.path().join("modelDescription.xml"),
+        MODEL_DESCRIPTION_XML,
+    )
+    .unwrap();
+    let sources_dir = fmu_dir.path().join("sources");
+    std::fs::create_dir(&sources_dir).unwrap();
+    std::fs::write(
+        sources_dir.join("buildDescription.xml"),
+        BUILD_DESCRIPTION_XML,
+    )
+    .unwrap();
+    std::fs::write(sources_dir.join("model.c"), format!("{MODEL_C}{functions}")).unwrap();
+
+    build_shared_library(fmu_dir.path(), "Tiny").unwrap();
+    let import = fmi::import::from_dir(fmu_dir.path()).unwrap();
+    (fmu_dir, import)
+}
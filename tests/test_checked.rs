@@ -0,0 +1,137 @@
+//! Test that checked instances reject calls without reaching the FMU.
+
+use fmi::fmi3::{CoSimulation, Common, Fmi3Error, Fmi3Model, ModelExchange, instance::State};
+
+mod common;
+
+/// The calls rejected by the tests abort the process if they reach the FMU
+const ABORTING_FUNCTIONS: &str = r#"
+fmi3Status fmi3SetTime(fmi3Instance instance, fmi3Float64 time) {
+    abort();
+}
+
+fmi3Status fmi3DoStep(fmi3Instance instance, fmi3Float64 currentCommunicationPoint,
+    fmi3Float64 communicationStepSize, fmi3Boolean noSetFMUStatePriorToCurrentPoint,
+    fmi3Boolean* eventHandlingNeeded, fmi3Boolean* terminateSimulation, fmi3Boolean* earlyReturn,
+    fmi3Float64* lastSuccessfulTime) {
+    abort();
+}
+
+fmi3Status fmi3GetBinary(fmi3Instance instance, const fmi3ValueReference valueReferences[],
+    size_t nValueReferences, size_t valueSizes[], fmi3Binary values[], size_t nValues) {
+    abort();
+}
+"#;
+
+#[test]
+fn test_do_step_before_initialization() {
+    let (_dir, import) = common::build_fmu(ABORTING_FUNCTIONS);
+    let mut inst = import
+        .instantiate_cs("inst1", false, false, false, false, &[])
+        .unwrap();
+    inst.set_checked(true);
+
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    assert_eq!(inst.state(), State::InitializationMode);
+
+    let (mut event, mut terminate, mut early_return, mut time) = (false, false, false, 0.0);
+    let result = inst.do_step(
+        0.0,
+        0.1,
+        true,
+        &mut event,
+        &mut terminate,
+        &mut early_return,
+        &mut time,
+    );
+    assert!(matches!(result, Err(Fmi3Error::IllegalCall(_))));
+    assert_eq!(inst.state(), State::InitializationMode);
+}
+
+#[test]
+fn test_set_time_in_event_mode() {
+    let (_dir, import) = common::build_fmu(ABORTING_FUNCTIONS);
+    let mut inst = import.instantiate_me("inst1", false, false).unwrap();
+    inst.set_checked(true);
+
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+    assert_eq!(inst.state(), State::EventMode);
+
+    let result = inst.set_time(0.5);
+    assert!(matches!(result, Err(Fmi3Error::IllegalCall(_))));
+    assert_eq!(inst.state(), State::EventMode);
+}
+
+#[test]
+fn test_get_binary_array_before_initialization() {
+    let (_dir, import) = common::build_fmu(ABORTING_FUNCTIONS);
+    let mut inst = import
+        .instantiate_cs("inst1", false, false, false, false, &[])
+        .unwrap();
+    inst.set_checked(true);
+    assert_eq!(inst.state(), State::Instantiated);
+
+    let result = inst.get_array::<Vec<u8>>(1);
+    assert!(matches!(
+        result,
+        Err(fmi::Error::Fmi3Error(Fmi3Error::IllegalCall(_)))
+    ));
+    assert_eq!(inst.state(), State::Instantiated);
+}
+
+/// FMU states that the FMU fails to serialize, free and deserialize
+const FAILING_STATE_FUNCTIONS: &str = r#"
+static int state;
+
+fmi3Status fmi3GetFMUState(fmi3Instance instance, fmi3FMUState* FMUState) {
+    *FMUState = &state;
+    return fmi3OK;
+}
+
+fmi3Status fmi3SerializedFMUStateSize(fmi3Instance instance, fmi3FMUState FMUState,
+    size_t* size) {
+    return fmi3Error;
+}
+
+fmi3Status fmi3FreeFMUState(fmi3Instance instance, fmi3FMUState* FMUState) {
+    return fmi3Error;
+}
+
+fmi3Status fmi3DeserializeFMUState(fmi3Instance instance, const fmi3Byte serializedState[],
+    size_t size, fmi3FMUState* FMUState) {
+    return fmi3Fatal;
+}
+"#;
+
+#[test]
+fn test_fmu_state_errors() {
+    let (_dir, import) = common::build_fmu(FAILING_STATE_FUNCTIONS);
+    let mut inst = import
+        .instantiate_cs("inst1", false, false, false, false, &[])
+        .unwrap();
+    inst.set_checked(true);
+
+    let state = inst.get_fmu_state().unwrap();
+    assert!(matches!(
+        inst.serialize_fmu_state(&state),
+        Err(fmi::Error::Fmi3Error(Fmi3Error::Error))
+    ));
+    assert_eq!(inst.state(), State::Error);
+    assert!(matches!(
+        inst.free_fmu_state(state),
+        Err(fmi::Error::Fmi3Error(Fmi3Error::Error))
+    ));
+    assert_eq!(inst.state(), State::Error);
+
+    assert!(matches!(
+        inst.deserialize_fmu_state(&[]),
+        Err(fmi::Error::Fmi3Error(Fmi3Error::Fatal))
+    ));
+    assert_eq!(inst.state(), State::Fatal);
+    // No calls are allowed after a fatal error
+    assert!(matches!(
+        inst.get_fmu_state(),
+        Err(fmi::Error::Fmi3Error(Fmi3Error::IllegalCall(_)))
+    ));
+}
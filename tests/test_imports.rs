@@ -75,3 +75,42 @@ fn test_fmi3_imports() {
         }
     }
 }
+
+#[test]
+fn test_extraction_cache() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let fmu_file = ref_fmus
+        .extract_reference_fmu("BouncingBall", fmi::schema::MajorVersion::FMI3)
+        .unwrap();
+
+    let cache_root = std::env::temp_dir().join(format!("fmi-rs-cache-test-{}", std::process::id()));
+    let cache = fmi::import::ExtractionCache::new(&cache_root);
+    let import1: fmi::fmi3::import::Fmi3Import = cache.import_path(fmu_file.path()).unwrap();
+    let import2: fmi::fmi3::import::Fmi3Import = cache.import_path(fmu_file.path()).unwrap();
+    assert_eq!(import1.archive_path(), import2.archive_path());
+    assert!(!import1.dir().is_owned());
+
+    // An extracted FMU can be imported directly
+    let import3: fmi::fmi3::import::Fmi3Import =
+        fmi::import::from_dir(import1.archive_path()).unwrap();
+    assert_eq!(import3.model_description().model_name, "BouncingBall");
+    assert!(!import3.dir().is_owned());
+
+    drop((import1, import2, import3));
+    assert!(cache_root.exists());
+    cache.clear().unwrap();
+    assert!(!cache_root.exists());
+}
+
+#[test]
+fn test_copy_library_per_instance() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let mut import: fmi::fmi3::import::Fmi3Import =
+        ref_fmus.get_reference_fmu("BouncingBall").unwrap();
+    import.set_library_mode(fmi::import::LibraryMode::CopyPerInstance);
+
+    let inst1 = import.instantiate_me("inst1", false, true).unwrap();
+    let inst2 = import.instantiate_me("inst2", false, true).unwrap();
+    assert_eq!(inst1.get_version(), "3.0");
+    assert_eq!(inst2.get_version(), "3.0");
+}
@@ -0,0 +1,98 @@
+//! Test the FMI1.0 instance API.
+
+use fmi::{
+    InterfaceType,
+    traits::{FmiImport as _, FmiInstance as _, FmiModelExchange as _},
+};
+use fmi_test_data::ReferenceFmus;
+
+extern crate fmi;
+extern crate fmi_test_data;
+
+#[test]
+fn test_instance_me() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import = ref_fmus
+        .get_reference_fmu_fmi1("Dahlquist", InterfaceType::ModelExchange)
+        .unwrap();
+    let inst1 = import.instantiate_me("inst1", true, true);
+
+    if cfg!(target_os = "macos") {
+        // FMI1 Reference FMUs are not built for MacOS
+        assert!(inst1.is_err());
+    } else {
+        let mut inst1 = inst1.expect("instantiate_me");
+        assert_eq!(inst1.get_version(), "1.0");
+        // An FMI 1.0 FMU implements a single interface type
+        assert!(import.instantiate_cs("inst2", true, true).is_err());
+
+        inst1
+            .enter_initialization_mode(None, 0.0, None)
+            .expect("enter_initialization_mode");
+        inst1
+            .exit_initialization_mode()
+            .expect("exit_initialization_mode");
+
+        // der(x) = -k * x, with x = 1 and k = 1
+        let mut x = [0.0];
+        inst1.get_continuous_states(&mut x).unwrap();
+        assert_eq!(x, [1.0]);
+        let mut der_x = [0.0];
+        inst1.get_continuous_state_derivatives(&mut der_x).unwrap();
+        assert_eq!(der_x, [-1.0]);
+
+        inst1.set_time(0.5).expect("set_time");
+        inst1.set_continuous_states(&[0.5]).unwrap();
+        inst1.get_continuous_state_derivatives(&mut der_x).unwrap();
+        assert_eq!(der_x, [-0.5]);
+
+        inst1.terminate().expect("terminate");
+    }
+}
+
+#[test]
+fn test_instance_cs() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import = ref_fmus
+        .get_reference_fmu_fmi1("Dahlquist", InterfaceType::CoSimulation)
+        .unwrap();
+    let inst1 = import.instantiate_cs("inst1", true, true);
+
+    if cfg!(target_os = "macos") {
+        // FMI1 Reference FMUs are not built for MacOS
+        assert!(inst1.is_err());
+    } else {
+        let mut inst1 = inst1.expect("instantiate_cs");
+        assert_eq!(inst1.get_version(), "1.0");
+        assert!(import.instantiate_me("inst2", true, true).is_err());
+
+        let k = import
+            .model_description()
+            .model_variable_by_name("k")
+            .unwrap();
+        inst1
+            .set_real(&[k.value_reference], &[2.0f64])
+            .expect("set k parameter");
+
+        inst1
+            .enter_initialization_mode(None, 0.0, None)
+            .expect("enter_initialization_mode");
+        inst1
+            .exit_initialization_mode()
+            .expect("exit_initialization_mode");
+
+        let x = import
+            .model_description()
+            .model_variable_by_name("x")
+            .unwrap();
+        let mut value = [0.0];
+        inst1.get_real(&[x.value_reference], &mut value).unwrap();
+        assert_eq!(value, [1.0]);
+
+        inst1.do_step(0.0, 0.125, true).expect("do_step");
+        inst1.get_real(&[x.value_reference], &mut value).unwrap();
+        assert_eq!(value, [0.8]);
+
+        inst1.terminate().expect("terminate");
+    }
+}
@@ -109,3 +109,40 @@ fn test_instance_cs() {
         assert_eq!(x, [0.8]);
     }
 }
+
+/// Test that log messages are routed to the log sink of the instance
+#[test]
+fn test_instance_log_sink() {
+    use std::sync::{Arc, Mutex};
+
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi2Import = ref_fmus.get_reference_fmu("Dahlquist").unwrap();
+    let inst1 = import.instantiate_me("inst1", true, true);
+
+    if cfg!(target_os = "macos") {
+        // FMI2 Reference FMUs are not built for MacOS
+        assert!(inst1.is_err());
+    } else {
+        let mut inst1 = inst1.expect("instantiate_me");
+
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        inst1.set_log_sink({
+            let messages = messages.clone();
+            move |msg| {
+                messages.lock().unwrap().push((
+                    msg.instance_name.to_owned(),
+                    msg.category.to_owned(),
+                    msg.message.to_owned(),
+                ));
+            }
+        });
+
+        // Getting an unknown value reference makes the FMU log an error
+        let mut value = [0.0];
+        assert!(inst1.get_real(&[9999], &mut value).is_err());
+
+        let messages = messages.lock().unwrap();
+        assert!(!messages.is_empty());
+        assert!(messages.iter().all(|(name, _, _)| name == "inst1"));
+    }
+}
@@ -35,9 +35,9 @@ fn test_instance_dahlquist() {
         .ok()
         .unwrap();
     inst1.exit_initialization_mode().ok().unwrap();
-    inst1.set_time(1234.0).ok().unwrap();
 
     inst1.enter_continuous_time_mode().ok().unwrap();
+    inst1.set_time(1234.0).ok().unwrap();
 
     let states = (0..import
         .model_description()
@@ -143,3 +143,419 @@ fn test_instance_feedthrough_binary() {
     // compare my_binary to the new value
     assert_eq!(&my_binary[..values_sizes[0]], b"New Binary Value");
 }
+
+/// Test getting, restoring and (de)serializing the FMU state with the `BouncingBall` FMU
+#[test]
+fn test_instance_fmu_state() {
+    use fmi::fmi3::CoSimulation;
+
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("BouncingBall").unwrap();
+    let mut inst1 = import
+        .instantiate_cs("inst1", true, true, false, false, &[])
+        .unwrap();
+    let h = import
+        .model_description()
+        .model_variables
+        .find_by_name("h")
+        .unwrap()
+        .value_reference();
+
+    inst1
+        .enter_initialization_mode(None, 0.0, None)
+        .ok()
+        .unwrap();
+    inst1.exit_initialization_mode().ok().unwrap();
+
+    let mut h0 = [0.0];
+    inst1.get_float64(&[h], &mut h0).unwrap();
+    let state = inst1.get_fmu_state().unwrap();
+
+    let (mut event, mut terminate, mut early_return, mut last_time) = (false, false, false, 0.0);
+    inst1
+        .do_step(
+            0.0,
+            0.1,
+            false,
+            &mut event,
+            &mut terminate,
+            &mut early_return,
+            &mut last_time,
+        )
+        .unwrap();
+    let mut h1 = [0.0];
+    inst1.get_float64(&[h], &mut h1).unwrap();
+    assert_ne!(h0, h1);
+
+    inst1.set_fmu_state(&state).unwrap();
+    let mut h_restored = [0.0];
+    inst1.get_float64(&[h], &mut h_restored).unwrap();
+    assert_eq!(h0, h_restored);
+
+    let bytes = inst1.serialize_fmu_state(&state).unwrap();
+    assert!(!bytes.is_empty());
+    let state2 = inst1.deserialize_fmu_state(&bytes).unwrap();
+    inst1.set_fmu_state(&state2).unwrap();
+    inst1.free_fmu_state(state).unwrap();
+    assert!(inst1.set_fmu_state(&state2).is_ok());
+
+    // Handles don't borrow their instance, so they can outlive it. Such a handle is rejected by
+    // any other instance, even one reusing the address of its owner.
+    drop(inst1);
+    let mut inst2 = import
+        .instantiate_cs("inst2", true, true, false, false, &[])
+        .unwrap();
+    assert!(inst2.set_fmu_state(&state2).is_err());
+}
+
+/// Test directional and adjoint derivatives with the `VanDerPol` FMU, where `der(x0) = x1`
+#[test]
+fn test_instance_derivatives() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("VanDerPol").unwrap();
+    let mut inst1 = import.instantiate_me("inst1", true, true).unwrap();
+    let vr = |name: &str| {
+        import
+            .model_description()
+            .model_variables
+            .find_by_name(name)
+            .unwrap()
+            .value_reference()
+    };
+    let (der_x0, x1) = (vr("der(x0)"), vr("x1"));
+
+    inst1
+        .enter_initialization_mode(None, 0.0, None)
+        .ok()
+        .unwrap();
+    inst1.exit_initialization_mode().ok().unwrap();
+
+    let mut sensitivity = [0.0];
+    inst1
+        .get_directional_derivative(&[der_x0], &[x1], &[1.0], &mut sensitivity)
+        .unwrap();
+    assert_eq!(sensitivity, [1.0]);
+
+    let mut sensitivity = [0.0];
+    inst1
+        .get_adjoint_derivative(&[der_x0], &[x1], &[1.0], &mut sensitivity)
+        .unwrap();
+    assert_eq!(sensitivity, [1.0]);
+
+    // Seed buffer does not match the number of knowns
+    assert!(matches!(
+        inst1.get_directional_derivative(&[der_x0], &[x1], &[1.0, 2.0], &mut sensitivity),
+        Err(fmi::Error::BufferLength { .. })
+    ));
+}
+
+/// Test the Clock metadata and interval checks with the `Clocks` FMU
+#[test]
+fn test_instance_clocks() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("Clocks").unwrap();
+    let mut inst1 = import.instantiate_se("inst1", true, true).unwrap();
+
+    // `time` is not a Clock
+    assert!(matches!(
+        inst1.clock_variable(0),
+        Err(fmi::Error::UnknownVariable { .. })
+    ));
+
+    let clocks = import.model_description().model_variables.clock();
+    assert!(!clocks.is_empty());
+    for clock in clocks {
+        let variability = inst1
+            .clock_interval_variability(clock.value_reference)
+            .unwrap();
+        if clock.interval_variability.is_some() {
+            assert_eq!(variability, clock.interval_variability);
+        }
+
+        // Triggered Clocks have no interval
+        if variability.is_some_and(|iv| iv.is_triggered()) {
+            let mut intervals = [0.0];
+            assert!(matches!(
+                inst1.get_interval_decimal(&[clock.value_reference], &mut intervals),
+                Err(fmi::Error::InvalidVariable { .. })
+            ));
+        }
+    }
+}
+
+/// Do one step of the `BouncingBall` FMU with `h` among the `required` intermediate variables, and
+/// return the time of each intermediate update with the result of getting `h` in it.
+fn intermediate_updates(required: bool) -> Vec<(f64, Result<f64, fmi::Error>)> {
+    use fmi::fmi3::{CoSimulation, instance::InstanceCS};
+    use std::{cell::RefCell, rc::Rc};
+
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("BouncingBall").unwrap();
+    let h = import
+        .model_description()
+        .model_variables
+        .find_by_name("h")
+        .unwrap()
+        .value_reference();
+    let required = if required { vec![h] } else { vec![] };
+
+    let updates = Rc::new(RefCell::new(Vec::new()));
+    let mut inst1 = InstanceCS::new_with_intermediate_update(
+        &import,
+        "inst1",
+        true,
+        true,
+        false,
+        true,
+        &required,
+        {
+            let updates = updates.clone();
+            move |update| {
+                let mut value = [0.0];
+                let get = update.get_float64(&[h], &mut value).map(|_| value[0]);
+                updates
+                    .borrow_mut()
+                    .push((update.intermediate_update_time(), get));
+            }
+        },
+    )
+    .unwrap();
+
+    inst1
+        .enter_initialization_mode(None, 0.0, None)
+        .ok()
+        .unwrap();
+    inst1.exit_initialization_mode().ok().unwrap();
+
+    let (mut event, mut terminate, mut early_return, mut last_time) = (false, false, false, 0.0);
+    inst1
+        .do_step(
+            0.0,
+            0.1,
+            false,
+            &mut event,
+            &mut terminate,
+            &mut early_return,
+            &mut last_time,
+        )
+        .unwrap();
+
+    drop(inst1);
+    Rc::try_unwrap(updates).unwrap().into_inner()
+}
+
+/// Test that the intermediate update closure only sees the required intermediate variables
+#[test]
+fn test_instance_intermediate_update() {
+    // `h` is not in requiredIntermediateVariables
+    let updates = intermediate_updates(false);
+    assert!(!updates.is_empty());
+    for (time, get) in &updates {
+        assert!((0.0..=0.1).contains(time));
+        assert!(matches!(get, Err(fmi::Error::InvalidVariable { .. })));
+    }
+
+    // The ball falls from h = 1 during the step
+    let updates = intermediate_updates(true);
+    assert!(!updates.is_empty());
+    for (time, get) in &updates {
+        assert!((0.0..=0.1).contains(time));
+        let h = get.as_ref().unwrap();
+        assert!((0.9..=1.0).contains(h), "h = {h} at t = {time}");
+    }
+}
+
+/// Run the `Clocks` FMU in Scheduled Execution, activating the partitions of all input Clocks
+#[test]
+fn test_instance_scheduled_execution() {
+    use fmi::{
+        fmi3::{
+            ScheduledExecution,
+            instance::{InstanceSE, PreemptionLock},
+        },
+        schema::fmi3::Causality,
+    };
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    // Count the calls of the preemption callbacks around the default lock
+    static LOCKS: AtomicUsize = AtomicUsize::new(0);
+    static UNLOCKS: AtomicUsize = AtomicUsize::new(0);
+    unsafe extern "C" fn lock() {
+        LOCKS.fetch_add(1, Ordering::Relaxed);
+        unsafe { (PreemptionLock::default().lock)() };
+    }
+    unsafe extern "C" fn unlock() {
+        UNLOCKS.fetch_add(1, Ordering::Relaxed);
+        unsafe { (PreemptionLock::default().unlock)() };
+    }
+
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("Clocks").unwrap();
+    let clocks = import.model_description().model_variables.clock();
+    let clock_vrs = |causality| {
+        clocks
+            .iter()
+            .filter(|clock| clock.causality == Some(causality))
+            .map(|clock| clock.value_reference)
+            .collect::<Vec<_>>()
+    };
+    let (input_clocks, output_clocks) = (clock_vrs(Causality::Input), clock_vrs(Causality::Output));
+    assert!(!input_clocks.is_empty());
+
+    let clock_updates = Arc::new(AtomicUsize::new(0));
+    let clock_update = {
+        let clock_updates = clock_updates.clone();
+        move || {
+            clock_updates.fetch_add(1, Ordering::Relaxed);
+        }
+    };
+    let mut inst1 = InstanceSE::new_with_preemption_lock(
+        &import,
+        "inst1",
+        true,
+        true,
+        Some(Box::new(clock_update)),
+        PreemptionLock { lock, unlock },
+    )
+    .unwrap();
+
+    inst1
+        .enter_initialization_mode(None, 0.0, None)
+        .ok()
+        .unwrap();
+    inst1.exit_initialization_mode().ok().unwrap();
+
+    let mut ticks = Vec::new();
+    for time in 0..10 {
+        for clock in &input_clocks {
+            inst1
+                .activate_model_partition(*clock, time as f64)
+                .ok()
+                .unwrap();
+            ticks.extend(inst1.output_clock_ticks().unwrap());
+        }
+    }
+
+    assert!(!ticks.is_empty());
+    assert!(ticks.iter().all(|vr| output_clocks.contains(vr)));
+    assert!(clock_updates.load(Ordering::Relaxed) > 0);
+
+    inst1.terminate().ok().unwrap();
+    assert_eq!(
+        LOCKS.load(Ordering::Relaxed),
+        UNLOCKS.load(Ordering::Relaxed)
+    );
+}
+
+/// Test that log messages are routed to the log sink of the instance
+#[test]
+fn test_instance_log_sink() {
+    use std::sync::{Arc, Mutex};
+
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("BouncingBall").unwrap();
+    let mut inst1 = import.instantiate_me("inst1", true, true).unwrap();
+
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    inst1.set_log_sink({
+        let messages = messages.clone();
+        move |msg| {
+            messages.lock().unwrap().push((
+                msg.instance_name.to_owned(),
+                msg.category.to_owned(),
+                msg.message.to_owned(),
+            ));
+        }
+    });
+
+    // Getting an unknown value reference makes the FMU log an error
+    let mut value = [0.0];
+    assert!(inst1.get_float64(&[9999], &mut value).is_err());
+
+    let messages = messages.lock().unwrap();
+    assert!(!messages.is_empty());
+    assert!(messages.iter().all(|(name, _, _)| name == "inst1"));
+}
+
+/// Test the typed variable handles with the `BouncingBall` FMU
+#[test]
+fn test_instance_typed_variable() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("BouncingBall").unwrap();
+
+    let h = import.variable::<f64>("h").unwrap();
+    assert!(!h.is_array());
+    assert!(matches!(
+        import.variable::<i32>("h"),
+        Err(fmi::Error::VariableTypeMismatch { .. })
+    ));
+    assert!(matches!(
+        import.variable::<f64>("no_such_variable"),
+        Err(fmi::Error::UnknownVariable { .. })
+    ));
+
+    let mut inst1 = import.instantiate_me("inst1", true, true).unwrap();
+    inst1
+        .enter_initialization_mode(None, 0.0, None)
+        .ok()
+        .unwrap();
+    assert_eq!(h.get(&mut inst1).unwrap(), 1.0);
+    h.set(&mut inst1, 2.0).unwrap();
+    assert_eq!(h.get(&mut inst1).unwrap(), 2.0);
+
+    let mut values = [0.0; 2];
+    assert!(matches!(
+        h.get_array(&mut inst1, &mut values),
+        Err(fmi::Error::BufferLength { .. })
+    ));
+}
+
+/// Test the shape-aware array interface with the `StateSpace` FMU, whose matrices are sized by
+/// structural parameters
+#[test]
+fn test_instance_arrays() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi3Import = ref_fmus.get_reference_fmu("StateSpace").unwrap();
+    let model_variables = &import.model_description().model_variables;
+    let vr_of = |name: &str| {
+        model_variables
+            .find_by_name(name)
+            .unwrap()
+            .value_reference()
+    };
+    let (vr_n, vr_a, vr_x0) = (vr_of("n"), vr_of("A"), vr_of("x0"));
+
+    let mut inst1 = import
+        .instantiate_cs("inst1", true, true, false, false, &[])
+        .unwrap();
+    let n = inst1.get_array::<u64>(vr_n).unwrap().values()[0] as usize;
+
+    let a = inst1.get_array::<f64>(vr_a).unwrap();
+    assert_eq!(a.shape(), &[n, n]);
+    assert_eq!(inst1.variable_shape(vr_x0).unwrap(), vec![n]);
+    assert_eq!(
+        inst1.get_variable_dimensions(&[vr_a, vr_x0]).unwrap(),
+        n * n + n
+    );
+    assert!(matches!(
+        inst1.get_array::<i32>(vr_a),
+        Err(fmi::Error::VariableTypeMismatch { .. })
+    ));
+
+    inst1
+        .enter_initialization_mode(None, 0.0, None)
+        .ok()
+        .unwrap();
+    let x0 = fmi::fmi3::instance::Array::new(vec![n], (0..n).map(|i| i as f64).collect()).unwrap();
+    inst1.set_array(vr_x0, &x0).unwrap();
+    assert_eq!(inst1.get_array::<f64>(vr_x0).unwrap(), x0);
+
+    let wrong_shape = fmi::fmi3::instance::Array::new(vec![n + 1], vec![0.0; n + 1]).unwrap();
+    assert!(matches!(
+        inst1.set_array(vr_x0, &wrong_shape),
+        Err(fmi::Error::InvalidVariable { .. })
+    ));
+}
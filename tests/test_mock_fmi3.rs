@@ -0,0 +1,275 @@
+//! Test the in-process mock FMUs of FMI3.0.
+
+use fmi::{
+    EventFlags,
+    fmi3::mock::{MockCoSimulation, MockImport, OdeInstance, OdeModel},
+    fmi3::{CoSimulation as _, Common as _, Fmi3Model as _, GetSet as _, ModelExchange as _},
+};
+
+extern crate fmi;
+
+/// der(x) = -k * x
+fn dahlquist() -> OdeModel {
+    OdeModel::new("Dahlquist")
+        .state("x", 1.0)
+        .parameter("k", 1.0)
+        .derivatives(|_time, values, derivatives| derivatives[0] = -values[3] * values[1])
+}
+
+/// A ball falling from h = 1, bouncing with a coefficient of restitution of 0.7.
+fn bouncing_ball() -> OdeModel {
+    OdeModel::new("BouncingBall")
+        .state("h", 1.0)
+        .state("v", 0.0)
+        .parameter("g", -9.81)
+        .parameter("e", 0.7)
+        .derivatives(|_time, values, derivatives| {
+            derivatives[0] = values[3];
+            derivatives[1] = values[5];
+        })
+        .event_indicators(1, |_time, values, indicators| indicators[0] = values[1])
+        .event_update(|_time, values, event_flags| {
+            if values[1] <= 0.0 && values[3] < 0.0 {
+                values[1] = 0.0;
+                values[3] = -values[3] * values[6];
+                event_flags.values_of_continuous_states_changed = true;
+            }
+        })
+}
+
+#[test]
+fn test_model_description() {
+    let model = bouncing_ball();
+    let md = model.model_description();
+    assert_eq!(md.model_name, "BouncingBall");
+    assert!(md.model_exchange.is_some());
+    assert!(md.co_simulation.is_some());
+    assert_eq!(md.model_structure.continuous_state_derivatives().count(), 2);
+    assert_eq!(md.model_structure.event_indicators().count(), 1);
+    assert_eq!(model.value_reference("time"), Some(0));
+    assert_eq!(model.value_reference("der(h)"), Some(2));
+    assert_eq!(model.value_reference("e"), Some(6));
+    assert_eq!(model.value_reference("nope"), None);
+}
+
+#[test]
+fn test_instance_me() {
+    let import = dahlquist().import();
+    let mut inst = import.instantiate_me("inst1", false, true).unwrap();
+    assert_eq!(inst.get_version(), "3.0");
+
+    inst.set_float64(&[3], &[2.0]).unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+
+    let mut event_flags = EventFlags::default();
+    inst.update_discrete_states(&mut event_flags).unwrap();
+    assert!(!event_flags.discrete_states_need_update);
+    assert!(!event_flags.terminate_simulation);
+    inst.enter_continuous_time_mode().unwrap();
+
+    let (mut states, mut derivatives) = ([0.0], [0.0]);
+    inst.get_continuous_states(&mut states).unwrap();
+    assert_eq!(states, [1.0]);
+    inst.get_continuous_state_derivatives(&mut derivatives)
+        .unwrap();
+    assert_eq!(derivatives, [-2.0]);
+
+    inst.set_time(0.5).unwrap();
+    inst.set_continuous_states(&[0.25]).unwrap();
+    inst.get_continuous_state_derivatives(&mut derivatives)
+        .unwrap();
+    assert_eq!(derivatives, [-0.5]);
+
+    let mut time = [0.0];
+    inst.get_float64(&[0], &mut time).unwrap();
+    assert_eq!(time, [0.5]);
+
+    assert!(inst.set_float64(&[0], &[1.0]).is_err());
+    assert!(inst.get_boolean(&[1], &mut [false]).is_err());
+    inst.terminate().unwrap();
+}
+
+#[test]
+fn test_instance_cs() {
+    let model = dahlquist();
+    let x = model.value_reference("x").unwrap();
+    let import = model.import().with_step_size(1e-4).unwrap();
+    assert!(import.instantiate_se("inst1", false, true).is_err());
+
+    let mut inst = import
+        .instantiate_cs("inst1", false, true, false, false, &[])
+        .unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+
+    let mut values = [0.0];
+    let (mut event, mut terminate, mut early_return, mut last_time) = (false, false, false, 0.0);
+    for step in 0..10 {
+        let time = step as f64 * 0.1;
+        inst.do_step(
+            time,
+            0.1,
+            true,
+            &mut event,
+            &mut terminate,
+            &mut early_return,
+            &mut last_time,
+        )
+        .unwrap();
+        assert!(!early_return);
+        assert!((last_time - (time + 0.1)).abs() < 1e-12);
+
+        inst.get_float64(&[x], &mut values).unwrap();
+        assert!((values[0] - (-last_time).exp()).abs() < 1e-4);
+    }
+}
+
+/// Step sizes that would never advance the integration are rejected
+#[test]
+fn test_invalid_step_size() {
+    let import = dahlquist().import();
+    for step_size in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+        assert!(matches!(
+            dahlquist().import().with_step_size(step_size),
+            Err(fmi::Error::InvalidStepSize(_))
+        ));
+        let inner = import.instantiate_me("inst1", false, true).unwrap();
+        assert!(matches!(
+            MockCoSimulation::new(inner, step_size, false),
+            Err(fmi::Error::InvalidStepSize(_))
+        ));
+    }
+
+    let mut model_description = dahlquist().model_description();
+    model_description.default_experiment = Some(fmi::fmi3::schema::DefaultExperiment {
+        step_size: Some(0.0),
+        ..Default::default()
+    });
+    assert!(matches!(
+        MockImport::<OdeInstance>::new(model_description, |_, _| unreachable!()),
+        Err(fmi::Error::InvalidStepSize(_))
+    ));
+}
+
+#[test]
+fn test_state_events() {
+    let model = bouncing_ball();
+    let h = model.value_reference("h").unwrap();
+    let import = model.import();
+
+    let mut inst = import
+        .instantiate_cs("inst1", false, true, false, false, &[])
+        .unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+
+    // The ball hits the ground at t = sqrt(2 / 9.81) ~ 0.4515
+    let (mut event, mut terminate, mut early_return, mut last_time) = (false, false, false, 0.0);
+    let mut values = [0.0, 0.0];
+    let mut min_height = f64::INFINITY;
+    for step in 0..100 {
+        inst.do_step(
+            step as f64 * 0.01,
+            0.01,
+            true,
+            &mut event,
+            &mut terminate,
+            &mut early_return,
+            &mut last_time,
+        )
+        .unwrap();
+        inst.get_float64(&[h, h + 2], &mut values).unwrap();
+        min_height = min_height.min(values[0]);
+    }
+    // After the first bounce the ball moves upwards again, and never falls through the floor
+    assert!(min_height > -1e-2, "min height {min_height}");
+    assert!(values[0] > 0.0);
+
+    // With event mode, the instance returns at the first bounce
+    let mut inst = import
+        .instantiate_cs("inst2", false, true, true, true, &[])
+        .unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+    inst.enter_step_mode().unwrap();
+    inst.do_step(
+        0.0,
+        1.0,
+        true,
+        &mut event,
+        &mut terminate,
+        &mut early_return,
+        &mut last_time,
+    )
+    .unwrap();
+    assert!(event);
+    assert!(early_return);
+    assert!((last_time - (2.0 / 9.81f64).sqrt()).abs() < 1e-2);
+}
+
+#[test]
+fn test_fmi_export_model() {
+    use fmi::fmi3::{Fmi3Error, Fmi3Res, schema::AbstractVariableTrait as _};
+    use fmi_export::{
+        FmuModel,
+        fmi3::{DefaultLoggingCategory, ModelContext, ModelInstance, UserModel},
+    };
+
+    #[derive(FmuModel, Default, Debug)]
+    #[model()]
+    struct Dahlquist {
+        #[variable(causality = Output, variability = Continuous, state, start = 1.0, initial = Exact)]
+        x: f64,
+        #[variable(causality = Local, variability = Continuous, derivative = x, initial = Calculated)]
+        der_x: f64,
+        #[variable(causality = Parameter, variability = Fixed, start = 1.0, initial = Exact)]
+        k: f64,
+    }
+
+    impl UserModel for Dahlquist {
+        type LoggingCategory = DefaultLoggingCategory;
+
+        fn calculate_values(
+            &mut self,
+            _context: &ModelContext<Self>,
+        ) -> Result<Fmi3Res, Fmi3Error> {
+            self.der_x = -self.k * self.x;
+            Ok(Fmi3Res::OK)
+        }
+    }
+
+    let import = ModelInstance::<Dahlquist>::mock_import()
+        .unwrap()
+        .with_step_size(1e-4)
+        .unwrap();
+    let x = import
+        .model_description()
+        .model_variables
+        .float64()
+        .into_iter()
+        .find(|var| var.name() == "x")
+        .map(|var| var.value_reference())
+        .unwrap();
+
+    let mut inst = import
+        .instantiate_cs("inst1", false, false, false, false, &[])
+        .unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+    let (mut event, mut terminate, mut early_return, mut last_time) = (false, false, false, 0.0);
+    inst.do_step(
+        0.0,
+        1.0,
+        true,
+        &mut event,
+        &mut terminate,
+        &mut early_return,
+        &mut last_time,
+    )
+    .unwrap();
+
+    let mut values = [0.0];
+    inst.get_float64(&[x], &mut values).unwrap();
+    assert!((values[0] - (-1.0f64).exp()).abs() < 1e-4);
+}
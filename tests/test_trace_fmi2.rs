@@ -0,0 +1,74 @@
+//! Test recording and replaying traces of FMI2.0 calls.
+
+use std::{fs::File, io::BufReader};
+
+use fmi::{
+    fmi2::{
+        import::Fmi2Import,
+        instance::{CoSimulation as _, Common as _},
+        trace::{TracedInstance, replay_cs},
+    },
+    trace::read_trace,
+    traits::FmiImport as _,
+};
+use fmi_test_data::ReferenceFmus;
+use tempfile::NamedTempFile;
+
+extern crate fmi;
+extern crate fmi_test_data;
+
+#[test]
+fn test_replay_cs() {
+    let mut ref_fmus = ReferenceFmus::new().unwrap();
+    let import: Fmi2Import = ref_fmus.get_reference_fmu("Dahlquist").unwrap();
+    let inst1 = import.instantiate_cs("inst1", false, true);
+
+    if cfg!(target_os = "macos") {
+        // FMI2 Reference FMUs are not built for MacOS
+        assert!(inst1.is_err());
+        return;
+    }
+
+    let x = import
+        .model_description()
+        .model_variable_by_name("x")
+        .unwrap()
+        .value_reference;
+
+    let trace = NamedTempFile::new().unwrap();
+    let mut inst1 =
+        TracedInstance::new(inst1.expect("instantiate_cs"), trace.reopen().unwrap()).unwrap();
+    inst1.setup_experiment(None, 0.0, None).unwrap();
+    inst1.enter_initialization_mode().unwrap();
+    inst1.exit_initialization_mode().unwrap();
+
+    let mut values = [0.0];
+    for step in 0..4 {
+        inst1.do_step(step as f64 * 0.125, 0.125, true).unwrap();
+        inst1.get_real(&[x], &mut values).unwrap();
+    }
+    inst1.terminate().unwrap();
+    drop(inst1);
+
+    let (header, mut calls) =
+        read_trace(BufReader::new(File::open(trace.path()).unwrap())).unwrap();
+    assert_eq!(header.fmi_version, "2.0");
+    assert_eq!(calls.len(), 12);
+
+    let inst2 = import.instantiate_cs("inst1", false, true).unwrap();
+    let mismatches = replay_cs(inst2, &calls, 1e-12).unwrap();
+    assert!(mismatches.is_empty(), "{mismatches:?}");
+
+    // A tampered trace no longer matches the replay
+    let last_get = calls.len() - 2;
+    assert_eq!(calls[last_get].function, "fmi2GetReal");
+    let recorded = calls[last_get].outputs["values"][0].as_f64().unwrap();
+    calls[last_get].outputs["values"][0] = (recorded + 0.1).into();
+
+    let inst3 = import.instantiate_cs("inst1", false, true).unwrap();
+    let mismatches = replay_cs(inst3, &calls, 1e-12).unwrap();
+    assert_eq!(mismatches.len(), 1, "{mismatches:?}");
+    assert_eq!(mismatches[0].seq, calls[last_get].seq);
+    assert_eq!(mismatches[0].path, "values[0]");
+    assert_eq!(mismatches[0].replayed, recorded);
+}
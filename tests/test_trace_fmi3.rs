@@ -0,0 +1,162 @@
+//! Test recording and replaying traces of FMI3.0 calls with the in-process mock FMUs.
+
+use std::{fs::File, io::BufReader};
+
+use fmi::{
+    EventFlags,
+    fmi3::{
+        CoSimulation as _, Common as _, Fmi3Model as _, GetSet as _, ModelExchange as _,
+        mock::OdeModel,
+        trace::{TracedInstance, replay_cs, replay_me},
+    },
+    trace::{TraceCall, read_trace},
+};
+use tempfile::NamedTempFile;
+
+extern crate fmi;
+
+/// der(x) = -k * x
+fn dahlquist() -> OdeModel {
+    OdeModel::new("Dahlquist")
+        .state("x", 1.0)
+        .parameter("k", 1.0)
+        .derivatives(|_time, values, derivatives| derivatives[0] = -values[3] * values[1])
+}
+
+fn read_calls(trace: &NamedTempFile) -> Vec<TraceCall> {
+    let (header, calls) = read_trace(BufReader::new(File::open(trace.path()).unwrap())).unwrap();
+    assert_eq!(header.fmi_version, "3.0");
+    assert_eq!(header.instance_name, "inst1");
+    calls
+}
+
+/// Record a short Co-Simulation run of the Dahlquist model.
+fn record_cs() -> Vec<TraceCall> {
+    let model = dahlquist();
+    let (x, k) = (
+        model.value_reference("x").unwrap(),
+        model.value_reference("k").unwrap(),
+    );
+    let import = model.import().with_step_size(1e-3).unwrap();
+    let inst = import
+        .instantiate_cs("inst1", false, true, false, false, &[])
+        .unwrap();
+
+    let trace = NamedTempFile::new().unwrap();
+    let mut inst = TracedInstance::new(inst, trace.reopen().unwrap()).unwrap();
+    inst.set_float64(&[k], &[2.0]).unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+
+    let mut values = [0.0];
+    let (mut event, mut terminate, mut early_return, mut last_time) = (false, false, false, 0.0);
+    for step in 0..5 {
+        inst.do_step(
+            step as f64 * 0.1,
+            0.1,
+            true,
+            &mut event,
+            &mut terminate,
+            &mut early_return,
+            &mut last_time,
+        )
+        .unwrap();
+        inst.get_float64(&[x], &mut values).unwrap();
+    }
+    inst.terminate().unwrap();
+    drop(inst);
+
+    read_calls(&trace)
+}
+
+fn replay_dahlquist_cs(calls: &[TraceCall]) -> Vec<fmi::trace::Mismatch> {
+    let import = dahlquist().import().with_step_size(1e-3).unwrap();
+    let inst = import
+        .instantiate_cs("inst1", false, true, false, false, &[])
+        .unwrap();
+    replay_cs(inst, calls, 1e-12).unwrap()
+}
+
+#[test]
+fn test_replay_cs() {
+    let calls = record_cs();
+    assert_eq!(calls.len(), 14);
+    assert!(calls.iter().all(|call| call.status == "OK"));
+    assert_eq!(calls[13].function, "fmi3Terminate");
+
+    let mismatches = replay_dahlquist_cs(&calls);
+    assert!(mismatches.is_empty(), "{mismatches:?}");
+}
+
+#[test]
+fn test_replay_cs_tampered() {
+    let mut calls = record_cs();
+    let last_get = calls
+        .iter()
+        .rposition(|call| call.function == "fmi3GetFloat64")
+        .unwrap();
+    let recorded = calls[last_get].outputs["values"][0].as_f64().unwrap();
+    assert!((recorded - (-1.0_f64).exp()).abs() < 1e-3);
+    calls[last_get].outputs["values"][0] = (recorded + 0.1).into();
+
+    let mismatches = replay_dahlquist_cs(&calls);
+    assert_eq!(mismatches.len(), 1, "{mismatches:?}");
+    let mismatch = &mismatches[0];
+    assert_eq!(mismatch.seq, calls[last_get].seq);
+    assert_eq!(mismatch.function, "fmi3GetFloat64");
+    assert_eq!(mismatch.path, "values[0]");
+    assert_eq!(mismatch.recorded, recorded + 0.1);
+    assert_eq!(mismatch.replayed, recorded);
+}
+
+#[test]
+fn test_replay_me() {
+    let import = dahlquist().import();
+    let inst = import.instantiate_me("inst1", false, true).unwrap();
+
+    let trace = NamedTempFile::new().unwrap();
+    let mut inst = TracedInstance::new(inst, trace.reopen().unwrap()).unwrap();
+    inst.enter_initialization_mode(None, 0.0, None).unwrap();
+    inst.exit_initialization_mode().unwrap();
+    inst.update_discrete_states(&mut EventFlags::default())
+        .unwrap();
+    inst.enter_continuous_time_mode().unwrap();
+
+    let (mut states, mut derivatives) = ([0.0], [0.0]);
+    inst.get_continuous_states(&mut states).unwrap();
+    for step in 1..=3 {
+        inst.get_continuous_state_derivatives(&mut derivatives)
+            .unwrap();
+        inst.set_time(step as f64 * 0.1).unwrap();
+        inst.set_continuous_states(&[states[0] + 0.1 * derivatives[0]])
+            .unwrap();
+        inst.get_continuous_states(&mut states).unwrap();
+        inst.completed_integrator_step(true, &mut false, &mut false)
+            .unwrap();
+    }
+    inst.terminate().unwrap();
+    drop(inst);
+
+    let mut calls = read_calls(&trace);
+    assert_eq!(calls.len(), 21);
+    let inst = import.instantiate_me("inst1", false, true).unwrap();
+    let mismatches = replay_me(inst, &calls, 1e-12).unwrap();
+    assert!(mismatches.is_empty(), "{mismatches:?}");
+
+    // A step of the recorded run that the replay does not follow
+    let first_set = calls
+        .iter()
+        .position(|call| call.function == "fmi3SetContinuousStates")
+        .unwrap();
+    calls[first_set].args["states"][0] = 0.5.into();
+    let inst = import.instantiate_me("inst1", false, true).unwrap();
+    let mismatches = replay_me(inst, &calls, 1e-12).unwrap();
+    assert!(!mismatches.is_empty());
+    assert!(
+        mismatches
+            .iter()
+            .all(|mismatch| mismatch.seq > calls[first_set].seq)
+    );
+    assert_eq!(mismatches[0].function, "fmi3GetContinuousStates");
+    assert_eq!(mismatches[0].path, "continuous_states[0]");
+}